@@ -93,44 +93,66 @@ impl Client {
         &self,
         subject: &str,
     ) -> Result<(Subject, Vec<Subject>), GetBySubjectError> {
+        let (primary, dependencies) = self
+            .get_subject_and_references_by_version(subject, "latest".to_owned())
+            .await?;
+        Ok((primary, dependencies.into_iter().map(|(_, s)| s).collect()))
+    }
+
+    /// Like [`Client::get_subject_and_references`], but additionally returns
+    /// the name under which each dependency is referenced.
+    ///
+    /// For Protobuf schemas, the reference name is the path that the
+    /// referencing schema uses to import the dependency.
+    pub async fn get_subject_and_named_references(
+        &self,
+        subject: &str,
+    ) -> Result<(Subject, Vec<(String, Subject)>), GetBySubjectError> {
         self.get_subject_and_references_by_version(subject, "latest".to_owned())
             .await
     }
 
-    /// Gets a subject and all other subjects referenced by that subject (recursively)
+    /// Gets a subject and all other subjects referenced by that subject (recursively),
+    /// along with the name under which each of the latter is referenced.
     ///
     /// The dependencies are returned in alphabetical order by subject name.
     async fn get_subject_and_references_by_version(
         &self,
         subject: &str,
         version: String,
-    ) -> Result<(Subject, Vec<Subject>), GetBySubjectError> {
+    ) -> Result<(Subject, Vec<(String, Subject)>), GetBySubjectError> {
         let mut subjects = vec![];
         let mut seen = HashSet::new();
-        let mut subjects_queue = vec![(subject.to_owned(), version)];
-        while let Some((subject, version)) = subjects_queue.pop() {
+        let mut subjects_queue = vec![(subject.to_owned(), subject.to_owned(), version)];
+        while let Some((name, subject, version)) = subjects_queue.pop() {
+            if seen.contains(&subject) {
+                continue;
+            }
             let req = self.make_request(Method::GET, &["subjects", &subject, "versions", &version]);
             let res: GetBySubjectResponse = send_request(req).await?;
-            subjects.push(Subject {
-                schema: Schema {
-                    id: res.id,
-                    raw: res.schema,
+            subjects.push((
+                name,
+                Subject {
+                    schema: Schema {
+                        id: res.id,
+                        raw: res.schema,
+                    },
+                    version: res.version,
+                    name: res.subject.clone(),
                 },
-                version: res.version,
-                name: res.subject.clone(),
-            });
+            ));
             seen.insert(res.subject);
             subjects_queue.extend(
                 res.references
                     .into_iter()
                     .filter(|r| !seen.contains(&r.subject))
-                    .map(|r| (r.subject, r.version.to_string())),
+                    .map(|r| (r.name, r.subject, r.version.to_string())),
             );
         }
         assert!(subjects.len() > 0, "Request should error if no subjects");
 
-        let primary = subjects.remove(0);
-        subjects.sort_by(|a, b| a.name.cmp(&b.name));
+        let (_, primary) = subjects.remove(0);
+        subjects.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
         Ok((primary, subjects))
     }
 
@@ -202,11 +224,13 @@ impl Client {
         // for more info.
         match res.as_slice() {
             [first, ..] => {
-                self.get_subject_and_references_by_version(
-                    &first.subject,
-                    first.version.to_string(),
-                )
-                .await
+                let (primary, dependencies) = self
+                    .get_subject_and_references_by_version(
+                        &first.subject,
+                        first.version.to_string(),
+                    )
+                    .await?;
+                Ok((primary, dependencies.into_iter().map(|(_, s)| s).collect()))
             }
             _ => Err(GetBySubjectError::SubjectNotFound),
         }
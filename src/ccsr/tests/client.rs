@@ -225,6 +225,17 @@ async fn test_client_subject_and_references() -> Result<(), anyhow::Error> {
     assert_eq!(schema0_subject, dependency_subjects[0].name);
     assert_eq!(schema1_subject, dependency_subjects[1].name);
 
+    // The named lookup additionally reports the reference names.
+    let (primary_subject, dependency_subjects) = client
+        .get_subject_and_named_references(&schema2_subject)
+        .await?;
+    assert_eq!(schema2_subject, primary_subject.name);
+    assert_eq!(2, dependency_subjects.len());
+    assert_eq!(schema0_subject, dependency_subjects[0].0);
+    assert_eq!(schema0_subject, dependency_subjects[0].1.name);
+    assert_eq!(schema1_subject, dependency_subjects[1].0);
+    assert_eq!(schema1_subject, dependency_subjects[1].1.name);
+
     // Also do the by-id lookup
     let (primary_subject, dependency_subjects) =
         client.get_subject_and_references_by_id(schema2_id).await?;
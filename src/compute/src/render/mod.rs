@@ -103,19 +103,22 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::rc::Rc;
 
-use differential_dataflow::AsCollection;
+use differential_dataflow::operators::iterate::Variable;
+use differential_dataflow::{AsCollection, Collection};
 use timely::communication::Allocate;
 use timely::dataflow::operators::to_stream::ToStream;
 use timely::dataflow::scopes::Child;
 use timely::dataflow::Scope;
-use timely::progress::Timestamp;
+use timely::order::Product;
+use timely::progress::{Antichain, Timestamp};
 use timely::worker::Worker as TimelyWorker;
 
 use mz_dataflow_types::client::controller::storage::CollectionMetadata;
+use mz_dataflow_types::plan::AvailableCollections;
 use mz_dataflow_types::*;
-use mz_expr::Id;
+use mz_expr::{permutation_for_arrangement, Id, MapFilterProject};
 use mz_ore::collections::CollectionExt as IteratorExt;
-use mz_repr::{GlobalId, Row};
+use mz_repr::{Diff, GlobalId, Row};
 use mz_storage::source::persist_source;
 
 use crate::arrangement::manager::TraceBundle;
@@ -145,6 +148,15 @@ pub fn build_compute_dataflow<A: Allocate>(
     let name = format!("Dataflow: {}", &dataflow.debug_name);
 
     timely_worker.dataflow_core(&name, worker_logging, Box::new(()), |_, scope| {
+        if dataflow
+            .objects_to_build
+            .iter()
+            .any(|object| object.plan.is_recursive())
+        {
+            build_recursive_compute_dataflow(scope, compute_state, dataflow, &name);
+            return;
+        }
+
         // The scope.clone() occurs to allow import in the region.
         // We build a region here to establish a pattern of a scope inside the dataflow,
         // so that other similar uses (e.g. with iterative scopes) do not require weird
@@ -157,28 +169,9 @@ pub fn build_compute_dataflow<A: Allocate>(
             let mut tokens = BTreeMap::new();
 
             // Import declared sources into the rendering context.
+            let as_of = dataflow.as_of.as_ref().unwrap();
             for (source_id, source) in dataflow.source_imports.iter() {
-                let (ok_stream, err_stream, token) = persist_source::persist_source(
-                    region,
-                    source.storage_metadata.clone(),
-                    dataflow.as_of.clone().unwrap(),
-                );
-
-                let (mut ok, mut err) = (ok_stream.as_collection(), err_stream.as_collection());
-
-                // We do not trust `replay` to correctly advance times.
-                use timely::dataflow::operators::Map;
-                let as_of_frontier1 = dataflow.as_of.clone().unwrap();
-                ok = ok
-                    .inner
-                    .map_in_place(move |(_, time, _)| time.advance_by(as_of_frontier1.borrow()))
-                    .as_collection();
-
-                let as_of_frontier2 = dataflow.as_of.clone().unwrap();
-                err = err
-                    .inner
-                    .map_in_place(move |(_, time, _)| time.advance_by(as_of_frontier2.borrow()))
-                    .as_collection();
+                let (ok, err, token) = import_source(region, &source.storage_metadata, as_of);
 
                 // Associate collection bundle with the source identifier.
                 context.insert_id(
@@ -230,6 +223,155 @@ pub fn build_compute_dataflow<A: Allocate>(
     })
 }
 
+/// Assemble the "compute" side of a dataflow that contains recursive bindings.
+///
+/// The declared objects are built in an iterative scope, in which recursive
+/// bindings are rendered as loops. Arrangements cannot leave that scope, so the
+/// collections to export leave it unarranged, and are arranged and exported
+/// from a region as in [`build_compute_dataflow`].
+fn build_recursive_compute_dataflow<G>(
+    scope: &mut G,
+    compute_state: &mut ComputeState,
+    mut dataflow: DataflowDescription<mz_dataflow_types::plan::Plan, CollectionMetadata>,
+    name: &str,
+) where
+    G: Scope<Timestamp = mz_repr::Timestamp>,
+{
+    let mut tokens = BTreeMap::new();
+
+    // Determine indexes and sinks to export, and the arity of the collections
+    // they export.
+    let indexes = dataflow
+        .index_exports
+        .iter()
+        .map(|(idx_id, (idx, typ))| {
+            (
+                *idx_id,
+                dataflow.depends_on(idx.on_id),
+                idx.clone(),
+                typ.arity(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let sinks = dataflow
+        .sink_exports
+        .iter()
+        .map(|(sink_id, sink)| (*sink_id, dataflow.depends_on(sink.from), sink.clone()))
+        .collect::<Vec<_>>();
+    let exports = indexes
+        .iter()
+        .map(|(idx_id, _, _, arity)| (*idx_id, *arity))
+        .chain(
+            sinks
+                .iter()
+                .map(|(_, _, sink)| (sink.from, sink.from_desc.arity())),
+        )
+        .collect::<BTreeMap<_, _>>();
+
+    let collections = scope.clone().iterative::<u64, _, _>(|inner| {
+        let mut context =
+            crate::render::context::Context::for_dataflow(&dataflow, scope.addr().into_element());
+
+        // Import declared sources into the rendering context.
+        let as_of = dataflow.as_of.as_ref().unwrap();
+        for (source_id, source) in dataflow.source_imports.iter() {
+            let (ok, err, token) = import_source(scope, &source.storage_metadata, as_of);
+            context.insert_id(
+                mz_expr::Id::Global(*source_id),
+                crate::render::CollectionBundle::from_collections(
+                    ok.enter(inner),
+                    err.enter(inner),
+                ),
+            );
+            tokens.insert(*source_id, token);
+        }
+
+        // Import declared indexes into the rendering context.
+        for (idx_id, idx) in &dataflow.index_imports {
+            context.import_index(compute_state, &mut tokens, scope, inner, *idx_id, &idx.0);
+        }
+
+        // Build declared objects.
+        for object in std::mem::take(&mut dataflow.objects_to_build) {
+            context.build_recursive_object(inner, object);
+        }
+
+        exports
+            .iter()
+            .map(|(id, arity)| {
+                let (oks, errs) = context.unarranged_collection(*id, *arity);
+                (*id, oks.leave(), errs.leave())
+            })
+            .collect::<Vec<_>>()
+    });
+
+    scope.clone().region_named(name, |region| {
+        let mut context =
+            crate::render::context::Context::for_dataflow(&dataflow, scope.addr().into_element());
+        for (id, oks, errs) in collections {
+            context.insert_id(
+                Id::Global(id),
+                CollectionBundle::from_collections(oks.enter(region), errs.enter(region)),
+            );
+        }
+
+        // Export declared indexes, after arranging their collections.
+        for (idx_id, imports, idx, arity) in indexes {
+            let bundle = context
+                .remove_id(Id::Global(idx_id))
+                .expect("Index collection not exported from iterative scope");
+            let (permutation, thinning) = permutation_for_arrangement(&idx.key, arity);
+            let bundle = bundle.ensure_collections(
+                AvailableCollections::new_arranged(vec![(idx.key.clone(), permutation, thinning)]),
+                None,
+                MapFilterProject::new(arity),
+            );
+            context.insert_id(Id::Global(idx_id), bundle);
+            context.export_index(compute_state, &mut tokens, imports, idx_id, &idx);
+        }
+
+        // Export declared sinks.
+        for (sink_id, imports, sink) in sinks {
+            context.export_sink(compute_state, &mut tokens, imports, sink_id, &sink);
+        }
+    });
+}
+
+/// Imports a source into `scope`, with the times of its updates advanced to
+/// `as_of`.
+///
+/// Returns the collections of the source's updates and errors, and a token
+/// that keeps the source running.
+fn import_source<G>(
+    scope: &G,
+    storage_metadata: &CollectionMetadata,
+    as_of: &Antichain<mz_repr::Timestamp>,
+) -> (
+    Collection<G, Row, Diff>,
+    Collection<G, DataflowError, Diff>,
+    Rc<dyn std::any::Any>,
+)
+where
+    G: Scope<Timestamp = mz_repr::Timestamp>,
+{
+    let (ok_stream, err_stream, token) =
+        persist_source::persist_source(scope, storage_metadata.clone(), as_of.clone());
+
+    // We do not trust `replay` to correctly advance times.
+    use timely::dataflow::operators::Map;
+    let as_of_frontier1 = as_of.clone();
+    let ok = ok_stream
+        .map_in_place(move |(_, time, _)| time.advance_by(as_of_frontier1.borrow()))
+        .as_collection();
+
+    let as_of_frontier2 = as_of.clone();
+    let err = err_stream
+        .map_in_place(move |(_, time, _)| time.advance_by(as_of_frontier2.borrow()))
+        .as_collection();
+
+    (ok, err, token)
+}
+
 // This implementation block allows child timestamps to vary from parent timestamps,
 // but requires the parent timestamp to be `repr::Timestamp`.
 impl<'g, G, T> Context<Child<'g, G, T>, Row>
@@ -291,6 +433,95 @@ where
         let bundle = self.render_plan(object.plan, scope, scope.index());
         self.insert_id(Id::Global(object.id), bundle);
     }
+
+    /// Returns the unarranged collections bound to `id`, whose unthinned arity
+    /// is `arity`.
+    fn unarranged_collection(
+        &self,
+        id: GlobalId,
+        arity: usize,
+    ) -> (Collection<G, Row, Diff>, Collection<G, DataflowError, Diff>) {
+        let bundle = self
+            .lookup_id(Id::Global(id))
+            .unwrap_or_else(|| panic!("Collection {} not found at render time", id));
+        if let Some(collection) = &bundle.collection {
+            collection.clone()
+        } else {
+            let (key, _arrangement) = bundle
+                .arranged
+                .iter()
+                .next()
+                .expect("Invariant violated: at least one collection must be present.");
+            let (permutation, thinning) = permutation_for_arrangement(key, arity);
+            let mut mfp = MapFilterProject::new(arity);
+            mfp.permute(permutation, thinning.len() + key.len());
+            bundle.as_collection_core(mfp, Some((key.clone(), None)))
+        }
+    }
+}
+
+// This implementation block requires an iterative scope, in which recursive
+// bindings can be rendered as loops.
+impl<G> Context<G, Row>
+where
+    G: Scope<Timestamp = Product<mz_repr::Timestamp, u64>>,
+{
+    /// Builds an object whose plan may contain recursive bindings, which
+    /// [`plan::Plan::lift_bindings`] has lifted to the root of the plan.
+    pub(crate) fn build_recursive_object(&mut self, scope: &mut G, object: BuildDesc<plan::Plan>) {
+        let bundle = self.render_recursive_plan(object.plan, scope, scope.index());
+        self.insert_id(Id::Global(object.id), bundle);
+    }
+
+    /// Renders the bindings at the root of `plan`, and the remainder of the
+    /// plan with [`Context::render_plan`].
+    fn render_recursive_plan(
+        &mut self,
+        plan: plan::Plan,
+        scope: &mut G,
+        worker_index: usize,
+    ) -> CollectionBundle<G, Row> {
+        match plan {
+            Plan::Let { id, value, body } => {
+                let value = self.render_recursive_plan(*value, scope, worker_index);
+                let prebound = self.insert_id(Id::Local(id), value);
+                assert!(prebound.is_none());
+
+                let body = self.render_recursive_plan(*body, scope, worker_index);
+                self.remove_id(Id::Local(id));
+                body
+            }
+            Plan::LetRec { id, value, body } => {
+                // Bind `id` to variables that start out empty, and that are fed
+                // the results of `value` in each subsequent iteration.
+                let step = Product::new(0, 1);
+                let oks_variable = Variable::new(scope, step.clone());
+                let errs_variable = Variable::new(scope, step);
+                let prebound = self.insert_id(
+                    Id::Local(id),
+                    CollectionBundle::from_collections(
+                        (*oks_variable).clone(),
+                        (*errs_variable).clone(),
+                    ),
+                );
+                assert!(prebound.is_none());
+
+                let value = self.render_recursive_plan(*value, scope, worker_index);
+                let (oks, errs) = value.as_specific_collection(None);
+                let oks = oks.consolidate();
+                let errs = errs.consolidate();
+                oks_variable.set(&oks);
+                errs_variable.set(&errs);
+
+                // The body observes the fixed point, rather than the variables.
+                self.update_id(Id::Local(id), CollectionBundle::from_collections(oks, errs));
+                let body = self.render_recursive_plan(*body, scope, worker_index);
+                self.remove_id(Id::Local(id));
+                body
+            }
+            plan => self.render_plan(plan, scope, worker_index),
+        }
+    }
 }
 
 // This implementation block requires the scopes have the same timestamp as the trace manager.
@@ -443,6 +674,9 @@ where
                 self.remove_id(Id::Local(id));
                 body
             }
+            Plan::LetRec { .. } => {
+                unreachable!("recursive bindings are lifted to the root of their plan")
+            }
             Plan::Mfp {
                 input,
                 mfp,
@@ -571,3 +805,21 @@ impl RenderTimestamp for mz_repr::Timestamp {
         self.saturating_sub(1)
     }
 }
+
+impl RenderTimestamp for Product<mz_repr::Timestamp, u64> {
+    fn system_time(&mut self) -> &mut mz_repr::Timestamp {
+        &mut self.outer
+    }
+    fn system_delay(delay: mz_repr::Timestamp) -> <Self as Timestamp>::Summary {
+        Product::new(delay, 0)
+    }
+    fn event_time(&mut self) -> &mut mz_repr::Timestamp {
+        &mut self.outer
+    }
+    fn event_delay(delay: mz_repr::Timestamp) -> <Self as Timestamp>::Summary {
+        Product::new(delay, 0)
+    }
+    fn step_back(&self) -> Self {
+        Product::new(self.outer.saturating_sub(1), self.inner)
+    }
+}
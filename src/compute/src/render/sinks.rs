@@ -15,8 +15,10 @@ use std::collections::BTreeSet;
 use std::rc::Rc;
 
 use differential_dataflow::operators::arrange::arrangement::ArrangeByKey;
-use differential_dataflow::{Collection, Hashable};
+use differential_dataflow::{AsCollection, Collection, Hashable};
+use timely::dataflow::operators::Map;
 use timely::dataflow::Scope;
+use tracing::error;
 
 use mz_dataflow_types::sinks::*;
 use mz_expr::{permutation_for_arrangement, MapFilterProject};
@@ -72,7 +74,7 @@ where
             collection
         };
 
-        let collection = apply_sink_envelope(sink, &sink_render, collection);
+        let collection = apply_sink_envelope(sink_id, sink, &sink_render, collection);
 
         // TODO(benesch): errors should stream out through the sink,
         // if we figure out a protocol for that.
@@ -92,6 +94,7 @@ where
 
 #[allow(clippy::borrowed_box)]
 fn apply_sink_envelope<G>(
+    sink_id: GlobalId,
     sink: &SinkDesc,
     sink_render: &Box<dyn SinkRender<G>>,
     collection: Collection<G, Row, Diff>,
@@ -158,6 +161,11 @@ where
     //   It then renders those as Avro.
     // * Upsert" does the same, except at the last step, it renders the diff pair in upsert format.
     //   (As part of doing so, it asserts that there are not multiple conflicting values at the same timestamp)
+    // * "AppendOnly" passes inserts through unchanged. The coordinator only permits it for
+    //   inputs that never retract, so any retraction that shows up here is dropped. Only the
+    //   first one is logged, so that a misbehaving input cannot flood the logs.
+    // * "CdcV2" passes updates through with their original times and diffs, which the sink
+    //   encodes along with progress statements. It does not support keys.
     let collection = match sink.envelope {
         Some(SinkEnvelope::Debezium) => {
             let combined = combine_at_timestamp(keyed.arrange_by_key().stream);
@@ -194,6 +202,37 @@ where
             });
             collection
         }
+        Some(SinkEnvelope::AppendOnly) => {
+            // if there is no user-specified key, remove the synthetic
+            // distribution key again
+            let user_key_indices = sink_render.get_key_indices();
+            let keyed = if user_key_indices.is_some() {
+                keyed
+            } else {
+                keyed.map(|(_key, value)| (None, value))
+            };
+
+            let mut logged_retraction = false;
+            keyed
+                .inner
+                .flat_map(move |((key, value), time, diff)| {
+                    if diff < 0 {
+                        if !logged_retraction {
+                            error!(
+                                "append-only sink {} received a retraction at time {}; \
+                                 dropping it and any further retractions",
+                                sink_id, time
+                            );
+                            logged_retraction = true;
+                        }
+                        None
+                    } else {
+                        Some(((key, Some(value)), time, diff))
+                    }
+                })
+                .as_collection()
+        }
+        Some(SinkEnvelope::CdcV2) => keyed.map(|(_key, value)| (None, Some(value))),
         Some(SinkEnvelope::DifferentialRow) | None => keyed.map(|(key, value)| (key, Some(value))),
     };
 
@@ -231,5 +270,7 @@ where
         SinkConnector::Kafka(connector) => Box::new(connector.clone()),
         SinkConnector::Tail(connector) => Box::new(connector.clone()),
         SinkConnector::Persist(connector) => Box::new(connector.clone()),
+        SinkConnector::S3(connector) => Box::new(connector.clone()),
+        SinkConnector::Postgres(connector) => Box::new(connector.clone()),
     }
 }
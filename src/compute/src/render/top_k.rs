@@ -73,7 +73,7 @@ where
                         ),
                     );
                     let thinned = ok_input.concat(&retractions.negate());
-                    let result = build_topk(thinned, group_key, order_key, 0, limit, arity);
+                    let result = build_topk(thinned, group_key, order_key, 0, limit, false, arity);
                     retractions.set(&ok_input.concat(&result.negate()));
                     result
                 }
@@ -82,8 +82,11 @@ where
                     order_key,
                     offset,
                     limit,
+                    with_ties,
                     arity,
-                }) => build_topk(ok_input, group_key, order_key, offset, limit, arity),
+                }) => build_topk(
+                    ok_input, group_key, order_key, offset, limit, with_ties, arity,
+                ),
             };
             // Extract the results from the region.
             ok_result.leave_region()
@@ -98,6 +101,7 @@ where
             order_key: Vec<mz_expr::ColumnOrder>,
             offset: usize,
             limit: Option<usize>,
+            with_ties: bool,
             arity: usize,
         ) -> Collection<G, Row, Diff>
         where
@@ -133,12 +137,18 @@ where
                     // here we do not apply `offset`, but instead restrict ourself with a limit
                     // that includes the offset. We cannot apply `offset` until we perform the
                     // final, complete reduction.
+                    //
+                    // If ties are retained, each stage retains the records tying with its own
+                    // last record. This is a superset of the records tying with the last record
+                    // of the complete reduction, as any stage that cuts off such a record must
+                    // itself end on a record that ties with it.
                     collection = build_topk_stage(
                         collection,
                         order_key.clone(),
                         1u64 << log_modulus,
                         0,
                         Some(offset + limit),
+                        with_ties,
                         arity,
                     );
                 }
@@ -147,7 +157,7 @@ where
             // We do a final step, both to make sure that we complete the reduction, and to correctly
             // apply `offset` to the final group, as we have not yet been applying it to the partially
             // formed groups.
-            build_topk_stage(collection, order_key, 1u64, offset, limit, arity)
+            build_topk_stage(collection, order_key, 1u64, offset, limit, with_ties, arity)
                 .map(|((_key, _hash), row)| row)
         }
 
@@ -165,6 +175,7 @@ where
             modulus: u64,
             offset: usize,
             limit: Option<usize>,
+            with_ties: bool,
             arity: usize,
         ) -> Collection<G, ((Row, u64), Row), Diff>
         where
@@ -194,10 +205,11 @@ where
 
                         // The order in which we should produce rows.
                         let mut indexes = (0..source.len()).collect::<Vec<_>>();
+                        // We decode the datums once, into a common buffer for efficiency.
+                        // Each row should contain `arity` columns; we should check that.
+                        let mut buffer = Vec::new();
                         if !order_key.is_empty() {
-                            // We decode the datums once, into a common buffer for efficiency.
-                            // Each row should contain `arity` columns; we should check that.
-                            let mut buffer = Vec::with_capacity(arity * source.len());
+                            buffer.reserve(arity * source.len());
                             for (index, row) in source.iter().enumerate() {
                                 buffer.extend(row.0.iter());
                                 assert_eq!(buffer.len(), arity * (index + 1));
@@ -213,9 +225,21 @@ where
                                 })
                             });
                         }
+                        // Determines whether two rows are equal on `order_key`. Without an
+                        // order key, all rows tie with each other.
+                        let ties = |left: usize, right: usize| {
+                            order_key.is_empty() || {
+                                let left = &buffer[left * arity..][..arity];
+                                let right = &buffer[right * arity..][..arity];
+                                mz_expr::compare_columns(&order_key, left, right, || {
+                                    std::cmp::Ordering::Equal
+                                }) == std::cmp::Ordering::Equal
+                            }
+                        };
 
                         // We now need to lay out the data in order of `buffer`, but respecting
                         // the `offset` and `limit` constraints.
+                        let mut last_produced = None;
                         for index in indexes.into_iter() {
                             let (row, mut diff) = source[index];
                             if diff > 0 {
@@ -226,16 +250,29 @@ where
                                     offset -= to_skip;
                                     diff -= Diff::try_from(to_skip).unwrap();
                                 }
-                                // We should produce at most `limit` records.
+                                // We should produce at most `limit` records, except for those
+                                // that tie with the last produced record if `with_ties` is set.
                                 if let Some(limit) = &mut limit {
-                                    diff = std::cmp::min(diff, Diff::try_from(*limit).unwrap());
-                                    *limit -= diff as usize;
+                                    if *limit > 0 {
+                                        let within_limit =
+                                            std::cmp::min(diff, Diff::try_from(*limit).unwrap());
+                                        *limit -= within_limit as usize;
+                                        // Copies beyond the limit tie with those within it.
+                                        if !with_ties {
+                                            diff = within_limit;
+                                        }
+                                    } else if !with_ties
+                                        || !last_produced.map_or(false, |last| ties(last, index))
+                                    {
+                                        diff = 0;
+                                    }
                                 }
                                 // Output the indicated number of rows.
                                 if diff > 0 {
                                     // Emit retractions for the elements actually part of
                                     // the set of TopK elements.
                                     target.push((row.clone(), -diff));
+                                    last_produced = Some(index);
                                 }
                             }
                         }
@@ -10,7 +10,7 @@
 use std::any::Any;
 use std::cell::{Cell, RefCell};
 use std::cmp;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::future::Future;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -18,10 +18,11 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{anyhow, bail, Context};
+use differential_dataflow::consolidation::consolidate_updates;
 use differential_dataflow::{AsCollection, Collection, Hashable};
 use futures::{StreamExt, TryFutureExt};
 use itertools::Itertools;
-use mz_interchange::json::JsonEncoder;
+use mz_interchange::json::{self, JsonEncoder};
 use prometheus::core::AtomicU64;
 use rdkafka::client::ClientContext;
 use rdkafka::config::ClientConfig;
@@ -35,7 +36,7 @@ use timely::dataflow::channels::pact::Exchange;
 use timely::dataflow::channels::pact::Pipeline;
 use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
 use timely::dataflow::operators::generic::{InputHandle, OutputHandle};
-use timely::dataflow::operators::{Capability, Map};
+use timely::dataflow::operators::{Capability, Map, Operator};
 use timely::dataflow::{Scope, Stream};
 use timely::progress::frontier::AntichainRef;
 use timely::progress::{Antichain, Timestamp as _};
@@ -45,13 +46,14 @@ use tracing::{debug, error, info};
 
 use mz_avro::types::Value;
 use mz_dataflow_types::sinks::{
-    KafkaSinkConnector, KafkaSinkConsistencyConnector, PublishedSchemaInfo, SinkAsOf, SinkDesc,
-    SinkEnvelope,
+    KafkaSinkConnector, KafkaSinkConsistencyConnector, KafkaSinkEncoding, PublishedSchemaInfo,
+    SinkAsOf, SinkDesc, SinkEnvelope,
 };
 use mz_interchange::avro::{
-    self, get_debezium_transaction_schema, AvroEncoder, AvroSchemaGenerator,
+    self, cdc_v2, get_debezium_transaction_schema, AvroEncoder, AvroSchemaGenerator,
 };
 use mz_interchange::encode::Encode;
+use mz_interchange::protobuf::{ProtobufEncoder, ProtobufSchemaGenerator};
 use mz_kafka_util::client::{create_new_client_config, MzClientContext};
 use mz_ore::cast::CastFrom;
 use mz_ore::collections::CollectionExt;
@@ -65,6 +67,9 @@ use mz_timely_util::operators_async_ext::OperatorBuilderExt;
 use super::KafkaBaseMetrics;
 use crate::render::sinks::SinkRender;
 
+/// The maximum number of updates encoded in a single CDCv2 update message.
+const CDCV2_MAX_BATCH_SIZE: usize = 1024;
+
 impl<G> SinkRender<G> for KafkaSinkConnector
 where
     G: Scope<Timestamp = Timestamp>,
@@ -351,7 +356,8 @@ impl KafkaTxProducer {
 #[derive(Debug, Clone)]
 struct KafkaConsistencyInitState {
     topic: String,
-    schema_id: i32,
+    /// The ID of the Avro consistency schema, or `None` for JSON.
+    schema_id: Option<i32>,
     consistency_client_config: rdkafka::ClientConfig,
 }
 
@@ -368,10 +374,23 @@ impl KafkaConsistencyInitState {
 #[derive(Debug, Clone)]
 struct KafkaConsistencyRunningState {
     topic: String,
-    schema_id: i32,
+    /// The ID of the Avro consistency schema, or `None` for JSON.
+    schema_id: Option<i32>,
     gate_ts: Rc<Cell<Option<Timestamp>>>,
 }
 
+/// State for writing CDCv2 progress statements to the sink topic.
+#[derive(Debug)]
+struct CdcV2ProgressState {
+    encoder: cdc_v2::Encoder,
+    schema_id: i32,
+    /// The lower bound of the next progress statement.
+    lower: Antichain<Timestamp>,
+    /// The number of updates written at each time that is not yet covered
+    /// by a progress statement.
+    counts: BTreeMap<Timestamp, usize>,
+}
+
 #[derive(Debug, Clone)]
 enum KafkaSinkStateEnum {
     // Initialize ourselves as a transactional producer with Kafka
@@ -411,6 +430,9 @@ struct KafkaSinkState {
     retry_manager: Arc<Mutex<KafkaSinkSendRetryManager>>,
     sink_state: KafkaSinkStateEnum,
 
+    /// Progress tracking state, if this sink writes the CDCv2 format.
+    cdcv2_progress: Option<CdcV2ProgressState>,
+
     /// Timestamp of the latest `END` record that was written out to Kafka.
     latest_progress_ts: Timestamp,
 
@@ -428,6 +450,8 @@ struct KafkaSinkState {
 impl KafkaSinkState {
     fn new(
         connector: KafkaSinkConnector,
+        envelope: Option<SinkEnvelope>,
+        as_of: &SinkAsOf,
         sink_name: String,
         sink_id: &GlobalId,
         worker_id: String,
@@ -463,6 +487,27 @@ impl KafkaSinkState {
             timeout: Duration::from_secs(5),
         };
 
+        let cdcv2_progress = match (envelope, &connector.published_schema_info) {
+            (
+                Some(SinkEnvelope::CdcV2),
+                Some(PublishedSchemaInfo {
+                    value_schema_id, ..
+                }),
+            ) => Some(CdcV2ProgressState {
+                encoder: cdc_v2::Encoder::new(connector.value_desc.clone()),
+                schema_id: *value_schema_id,
+                // The sink only writes updates beyond its `as_of`, so the
+                // first progress statement starts where the sink resumes.
+                lower: if as_of.strict {
+                    as_of.frontier.iter().map(|t| t + 1).collect()
+                } else {
+                    as_of.frontier.clone()
+                },
+                counts: BTreeMap::new(),
+            }),
+            _ => None,
+        };
+
         let sink_state = KafkaSinkStateEnum::Init(connector.consistency.map(
             |KafkaSinkConsistencyConnector { topic, schema_id }| KafkaConsistencyInitState {
                 topic,
@@ -484,6 +529,7 @@ impl KafkaSinkState {
             ready_rows: VecDeque::new(),
             retry_manager,
             sink_state,
+            cdcv2_progress,
             latest_progress_ts: Timestamp::minimum(),
             write_frontier,
         }
@@ -746,6 +792,7 @@ impl KafkaSinkState {
         // always be called on background thread
         fn get_latest_ts(
             consistency_topic: &str,
+            schema_id: Option<i32>,
             config: &ClientConfig,
             timeout: Duration,
         ) -> Result<Option<Timestamp>, anyhow::Error> {
@@ -814,7 +861,8 @@ impl KafkaSinkState {
                 debug_assert!(offset >= latest_offset.unwrap_or(0));
                 latest_offset = Some(offset);
 
-                if let Some(ts) = maybe_decode_consistency_end_record(&message, consistency_topic)?
+                if let Some(ts) =
+                    maybe_decode_consistency_end_record(&message, consistency_topic, schema_id)?
                 {
                     if ts >= latest_ts.unwrap_or(0) {
                         latest_ts = Some(ts);
@@ -838,7 +886,34 @@ impl KafkaSinkState {
         fn maybe_decode_consistency_end_record(
             bytes: &[u8],
             consistency_topic: &str,
+            schema_id: Option<i32>,
         ) -> Result<Option<Timestamp>, anyhow::Error> {
+            let id_and_status = match schema_id {
+                Some(_) => decode_avro_consistency_record(bytes)?,
+                None => json::decode_debezium_transaction(bytes)
+                    .context("Failed to decode consistency topic message")?,
+            };
+
+            match id_and_status {
+                Some((id, status)) if status == "END" => {
+                    if let Ok(ts) = id.parse::<u64>() {
+                        Ok(Some(ts))
+                    } else {
+                        bail!(
+                            "Malformed consistency record, failed to parse timestamp {} in topic {}",
+                            id,
+                            consistency_topic
+                        );
+                    }
+                }
+                _ => Ok(None),
+            }
+        }
+
+        // Decodes the transaction ID and status of an Avro-encoded consistency record.
+        fn decode_avro_consistency_record(
+            bytes: &[u8],
+        ) -> Result<Option<(String, String)>, anyhow::Error> {
             // The first 5 bytes are reserved for the schema id/schema registry information
             let mut bytes = bytes.get(5..).ok_or_else(|| {
                 anyhow!("Malformed consistency topic message.  Shorter than 5 bytes.")
@@ -847,21 +922,11 @@ impl KafkaSinkState {
             let record = mz_avro::from_avro_datum(get_debezium_transaction_schema(), &mut bytes)
                 .context("Failed to decode consistency topic message")?;
 
-            if let Value::Record(ref r) = record {
-                let m: HashMap<String, Value> = r.clone().into_iter().collect();
-                let status = m.get("status");
-                let id = m.get("id");
-                match (status, id) {
-                    (Some(Value::String(status)), Some(Value::String(id))) if status == "END" => {
-                        if let Ok(ts) = id.parse::<u64>() {
-                            Ok(Some(ts))
-                        } else {
-                            bail!(
-                        "Malformed consistency record, failed to parse timestamp {} in topic {}",
-                        id,
-                        consistency_topic
-                    );
-                        }
+            if let Value::Record(r) = record {
+                let m: HashMap<String, Value> = r.into_iter().collect();
+                match (m.get("id"), m.get("status")) {
+                    (Some(Value::String(id)), Some(Value::String(status))) => {
+                        Ok(Some((id.clone(), status.clone())))
                     }
                     _ => Ok(None),
                 }
@@ -872,8 +937,8 @@ impl KafkaSinkState {
 
         if let KafkaSinkStateEnum::Init(Some(KafkaConsistencyInitState {
             ref topic,
+            schema_id,
             ref consistency_client_config,
-            ..
         })) = self.sink_state
         {
             // Only actually used for retriable errors.
@@ -887,6 +952,7 @@ impl KafkaSinkState {
                         move || {
                             get_latest_ts(
                                 &topic,
+                                schema_id,
                                 &consistency_client_config,
                                 Duration::from_secs(10),
                             )
@@ -907,13 +973,21 @@ impl KafkaSinkState {
         message_count: Option<i64>,
         consistency: &KafkaConsistencyRunningState,
     ) -> KafkaResult<()> {
-        let encoded = avro::encode_debezium_transaction_unchecked(
-            consistency.schema_id,
-            &self.topic_prefix,
-            transaction_id,
-            status,
-            message_count,
-        );
+        let encoded = match consistency.schema_id {
+            Some(schema_id) => avro::encode_debezium_transaction_unchecked(
+                schema_id,
+                &self.topic_prefix,
+                transaction_id,
+                status,
+                message_count,
+            ),
+            None => json::encode_debezium_transaction(
+                &self.topic_prefix,
+                transaction_id,
+                status,
+                message_count,
+            ),
+        };
 
         let record = BaseRecord::to(&consistency.topic)
             .payload(&encoded)
@@ -1014,6 +1088,50 @@ impl KafkaSinkState {
 
         Ok(progress_emitted)
     }
+
+    /// Writes a CDCv2 progress statement to the sink topic if the given input
+    /// frontier, held back by any rows that have not yet been written, has
+    /// advanced beyond the upper bound of the last progress statement.
+    ///
+    /// The progress statement reports the number of updates written at each
+    /// time between the previous and the new upper bound. This is a no-op for
+    /// sinks that do not use the CDCv2 envelope.
+    async fn maybe_emit_cdcv2_progress<'a>(
+        &mut self,
+        input_frontier: AntichainRef<'a, Timestamp>,
+    ) -> KafkaResult<bool> {
+        let upper = input_frontier
+            .iter()
+            .cloned()
+            .chain(self.pending_rows.keys().cloned())
+            .chain(self.ready_rows.iter().map(|(ts, _)| *ts))
+            .min();
+        let upper = match upper {
+            Some(upper) => Antichain::from_elem(upper),
+            None => Antichain::new(),
+        };
+
+        let progress = match &mut self.cdcv2_progress {
+            Some(progress) if progress.lower != upper => progress,
+            _ => return Ok(false),
+        };
+        let counts = match upper.as_option() {
+            Some(upper) => {
+                let remaining = progress.counts.split_off(upper);
+                std::mem::replace(&mut progress.counts, remaining)
+            }
+            None => std::mem::take(&mut progress.counts),
+        };
+        let counts = counts.into_iter().collect::<Vec<_>>();
+        let encoder = &progress.encoder;
+        let value = encoder.encode_progress(progress.lower.elements(), upper.elements(), &counts);
+        let encoded = encoder.encode_unchecked(progress.schema_id, &value);
+        progress.lower = upper;
+
+        let record = BaseRecord::<(), _>::to(&self.topic).payload(&encoded);
+        self.send(record).await?;
+        Ok(true)
+    }
 }
 
 #[derive(Debug)]
@@ -1049,11 +1167,61 @@ where
         .map(|(desc, _indices)| desc.clone());
     let value_desc = connector.value_desc.clone();
 
-    let encoded_stream = match connector.published_schema_info {
-        Some(PublishedSchemaInfo {
-            key_schema_id,
-            value_schema_id,
-        }) => {
+    let encoded_stream = match (
+        envelope,
+        connector.encoding,
+        &connector.published_schema_info,
+    ) {
+        (
+            Some(SinkEnvelope::CdcV2),
+            _,
+            Some(PublishedSchemaInfo {
+                value_schema_id, ..
+            }),
+        ) => {
+            let encoder = cdc_v2::Encoder::new(value_desc);
+            encode_cdcv2_stream(
+                stream,
+                as_of.clone(),
+                encoder,
+                *value_schema_id,
+                name.clone(),
+            )
+        }
+        (_, KafkaSinkEncoding::Protobuf, published_schema_info) => {
+            let (key_schema_id, value_schema_id) = match published_schema_info {
+                Some(PublishedSchemaInfo {
+                    key_schema_id,
+                    value_schema_id,
+                }) => (*key_schema_id, Some(*value_schema_id)),
+                None => (None, None),
+            };
+            let schema_generator = ProtobufSchemaGenerator::new(
+                key_desc,
+                value_desc,
+                matches!(envelope, Some(SinkEnvelope::Debezium)),
+                connector.consistency.is_some(),
+            )
+            .expect("Protobuf schema validated during planning");
+            let encoder = ProtobufEncoder::new(schema_generator, key_schema_id, value_schema_id);
+            encode_stream(
+                stream,
+                as_of.clone(),
+                Rc::clone(&shared_gate_ts),
+                encoder,
+                connector.fuel,
+                name.clone(),
+            )
+        }
+        (
+            _,
+            _,
+            Some(PublishedSchemaInfo {
+                key_schema_id,
+                value_schema_id,
+            }),
+        ) => {
+            let (key_schema_id, value_schema_id) = (*key_schema_id, *value_schema_id);
             let schema_generator = AvroSchemaGenerator::new(
                 None,
                 None,
@@ -1072,7 +1240,7 @@ where
                 name.clone(),
             )
         }
-        None => {
+        (_, _, None) => {
             let encoder = JsonEncoder::new(
                 key_desc,
                 value_desc,
@@ -1095,6 +1263,7 @@ where
         id,
         name,
         connector,
+        envelope,
         as_of,
         shared_gate_ts,
         write_frontier,
@@ -1119,6 +1288,7 @@ pub fn produce_to_kafka<G>(
     id: GlobalId,
     name: String,
     connector: KafkaSinkConnector,
+    envelope: Option<SinkEnvelope>,
     as_of: SinkAsOf,
     shared_gate_ts: Rc<Cell<Option<Timestamp>>>,
     write_frontier: Rc<RefCell<Antichain<Timestamp>>>,
@@ -1136,6 +1306,8 @@ where
 
     let mut s = KafkaSinkState::new(
         connector,
+        envelope,
+        &as_of,
         name,
         &id,
         scope.index().to_string(),
@@ -1247,12 +1419,19 @@ where
                     };
                     let diff = diff as usize;
 
+                    // The diff of a CDCv2 message is the number of updates in
+                    // the batch that it encodes, and the message itself is
+                    // written exactly once.
+                    let count = match &mut s.cdcv2_progress {
+                        Some(progress) => {
+                            *progress.counts.entry(time).or_default() += diff;
+                            1
+                        }
+                        None => diff,
+                    };
+
                     let rows = s.pending_rows.entry(time).or_default();
-                    rows.push(EncodedRow {
-                        key,
-                        value,
-                        count: diff,
-                    });
+                    rows.push(EncodedRow { key, value, count });
                     s.metrics.rows_queued.inc();
                 }
             });
@@ -1357,6 +1536,15 @@ where
             // updates. Only on worker receives all the updates and we don't want
             // the other workers to also emit END records.
             if is_active_worker {
+                match s.maybe_emit_cdcv2_progress(frontier.borrow()).await {
+                    Ok(true) => bail_err!(s.flush().await),
+                    Ok(false) => {}
+                    Err(e) => {
+                        debug!("Error writing out CDCv2 progress statement: {}", e);
+                        s.activator.activate();
+                        return true;
+                    }
+                }
                 match s.maybe_emit_progress(frontier.borrow()).await {
                     Ok(progress_emitted) => {
                         if progress_emitted {
@@ -1393,6 +1581,68 @@ where
     Rc::new(KafkaSinkToken { shutdown_flag })
 }
 
+/// Encodes a stream of `(Option<Row>, Option<Row>)` updates as batches of CDCv2 updates.
+///
+/// Updates are stashed until the input frontier has passed their time. All updates at a time
+/// are then consolidated and encoded into messages of at most [`CDCV2_MAX_BATCH_SIZE`] updates
+/// each. The diff of each message is the number of updates that it contains, which
+/// [`produce_to_kafka`] uses to count updates for CDCv2 progress statements.
+///
+/// Updates that are not beyond the given [`SinkAsOf`] will be discarded without encoding them.
+fn encode_cdcv2_stream<G>(
+    input_stream: &Stream<G, ((Option<Row>, Option<Row>), Timestamp, Diff)>,
+    as_of: SinkAsOf,
+    encoder: cdc_v2::Encoder,
+    schema_id: i32,
+    name_prefix: String,
+) -> Stream<G, ((Option<Vec<u8>>, Option<Vec<u8>>), Timestamp, Diff)>
+where
+    G: Scope<Timestamp = Timestamp>,
+{
+    let name = format!("{}-cdcv2_encode", name_prefix);
+
+    let mut stash: BTreeMap<Timestamp, (Capability<Timestamp>, Vec<(Row, Timestamp, Diff)>)> =
+        BTreeMap::new();
+    let mut vector = Vec::new();
+    input_stream.unary_frontier(Pipeline, &name, move |_, _| {
+        move |input, output| {
+            input.for_each(|cap, data| {
+                data.swap(&mut vector);
+                for ((_key, value), time, diff) in vector.drain(..) {
+                    let should_emit = if as_of.strict {
+                        as_of.frontier.less_than(&time)
+                    } else {
+                        as_of.frontier.less_equal(&time)
+                    };
+                    if !should_emit {
+                        continue;
+                    }
+                    let value = value.expect("CDCv2 updates always have a value");
+                    let (_cap, updates) = stash
+                        .entry(time)
+                        .or_insert_with(|| (cap.delayed(&time), Vec::new()));
+                    updates.push((value, time, diff));
+                }
+            });
+
+            while let Some(time) = stash.keys().next().copied() {
+                if input.frontier().less_equal(&time) {
+                    break;
+                }
+                let (cap, mut updates) = stash.remove(&time).expect("known to exist");
+                consolidate_updates(&mut updates);
+                let mut session = output.session(&cap);
+                for batch in updates.chunks(CDCV2_MAX_BATCH_SIZE) {
+                    let value = encoder.encode_updates(batch);
+                    let encoded = encoder.encode_unchecked(schema_id, &value);
+                    let count = Diff::try_from(batch.len()).expect("batch size fits in a diff");
+                    session.give(((None, Some(encoded)), time, count));
+                }
+            }
+        }
+    })
+}
+
 /// Encodes a stream of `(Option<Row>, Option<Row>)` updates using the specified encoder.
 ///
 /// This operator will only encode `fuel` number of updates per invocation. If necessary, it will
@@ -10,6 +10,8 @@
 mod kafka;
 mod metrics;
 mod persist_sink;
+mod postgres;
+mod s3;
 mod tail;
 
 pub(crate) use metrics::KafkaBaseMetrics;
@@ -0,0 +1,510 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use bytes::BytesMut;
+use differential_dataflow::{Collection, Hashable};
+use futures::StreamExt;
+use itertools::Itertools;
+use timely::dataflow::channels::pact::Exchange;
+use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
+use timely::dataflow::Scope;
+use timely::progress::Antichain;
+use timely::progress::Timestamp as TimelyTimestamp;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::Client;
+use tracing::{error, warn};
+
+use mz_dataflow_types::sinks::{PostgresSinkConnector, SinkDesc};
+use mz_ore::retry::Retry;
+use mz_postgres_util::{escape_identifier, SINK_PROGRESS_TABLE};
+use mz_repr::{ColumnType, Diff, GlobalId, Row, Timestamp};
+use mz_timely_util::operators_async_ext::OperatorBuilderExt;
+
+use crate::render::sinks::SinkRender;
+
+/// The maximum number of parameters of a statement that Postgres accepts.
+const MAX_STATEMENT_PARAMS: usize = u16::MAX as usize;
+
+/// The maximum number of rows that a single statement upserts or deletes.
+const MAX_BATCH_ROWS: usize = 1000;
+
+impl<G> SinkRender<G> for PostgresSinkConnector
+where
+    G: Scope<Timestamp = Timestamp>,
+{
+    fn uses_keys(&self) -> bool {
+        true
+    }
+
+    fn get_key_indices(&self) -> Option<&[usize]> {
+        Some(&self.key_indices)
+    }
+
+    fn get_relation_key_indices(&self) -> Option<&[usize]> {
+        self.relation_key_indices.as_deref()
+    }
+
+    fn render_continuous_sink(
+        &self,
+        compute_state: &mut crate::compute_state::ComputeState,
+        sink: &SinkDesc,
+        sink_id: GlobalId,
+        sinked_collection: Collection<G, (Option<Row>, Option<Row>), Diff>,
+    ) -> Option<Rc<dyn Any>>
+    where
+        G: Scope<Timestamp = Timestamp>,
+    {
+        let scope = sinked_collection.scope();
+        let operator_name = format!("postgres_sink({})", sink_id);
+        let mut postgres_op = OperatorBuilder::new(operator_name, scope.clone());
+
+        // Exactly one worker applies the updates, so that all updates at a
+        // timestamp are applied in a single transaction.
+        let hashed_id = sink_id.hashed();
+        let active_write_worker = (hashed_id as usize) % scope.peers() == scope.index();
+
+        let mut input =
+            postgres_op.new_input(&sinked_collection.inner, Exchange::new(move |_| hashed_id));
+
+        let as_of = sink.as_of.clone();
+        let mut state = PostgresSinkState::new(self.clone(), sink_id);
+
+        let token = Rc::new(());
+        let token_weak = Rc::downgrade(&token);
+
+        // Only the active_write_worker will ever produce data so all other workers have
+        // an empty frontier.
+        let shared_frontier = Rc::new(RefCell::new(if active_write_worker {
+            Antichain::from_elem(TimelyTimestamp::minimum())
+        } else {
+            Antichain::new()
+        }));
+
+        compute_state
+            .sink_write_frontiers
+            .insert(sink_id, Rc::clone(&shared_frontier));
+
+        postgres_op.build_async(
+            scope,
+            move |_capabilities, frontiers, scheduler| async move {
+                let mut buffer = Vec::new();
+                // Keyed by the update's key, so that each key is updated at
+                // most once per timestamp.
+                let mut pending: BTreeMap<Timestamp, BTreeMap<Row, Option<Row>>> = BTreeMap::new();
+
+                // Updates at timestamps that a previous incarnation of the
+                // sink has already applied are skipped.
+                let gate_ts = if active_write_worker {
+                    state.latest_ts().await
+                } else {
+                    None
+                };
+
+                // If the input was compacted beyond the sink's progress, the
+                // updates in between are no longer available. A snapshot at the
+                // `as_of` replaces the contents of the upstream table, which
+                // also removes the rows deleted in the meantime. Without a
+                // snapshot, the sink cannot catch up.
+                let mut replace_at = None;
+                if let (Some(gate_ts), Some(as_of_ts)) = (gate_ts, as_of.frontier.as_option()) {
+                    if *as_of_ts > gate_ts {
+                        if as_of.strict {
+                            error!(
+                                "postgres sink {} cannot resume: its input has been compacted \
+                                 to {}, beyond the last applied timestamp {}. Shutting down sink.",
+                                sink_id, as_of_ts, gate_ts
+                            );
+                            // Indicate that the sink is closed to everyone
+                            // else who might be tracking its write frontier.
+                            shared_frontier.borrow_mut().clear();
+                            return;
+                        }
+                        replace_at = Some(*as_of_ts);
+                    }
+                }
+
+                while scheduler.notified().await {
+                    let frontier = frontiers.borrow()[0].clone();
+
+                    if !active_write_worker || token_weak.upgrade().is_none() {
+                        return;
+                    }
+
+                    input.for_each(|_cap, data| {
+                        data.swap(&mut buffer);
+                        for ((key, value), time, diff) in buffer.drain(..) {
+                            let should_emit = if as_of.strict {
+                                as_of.frontier.less_than(&time)
+                            } else {
+                                as_of.frontier.less_equal(&time)
+                            };
+                            let previously_applied = Some(time) <= gate_ts;
+                            if !should_emit || previously_applied {
+                                continue;
+                            }
+                            assert!(diff > 0, "can't sink negative multiplicities");
+                            let key = key.expect("Postgres sinks always have a key");
+                            pending.entry(time).or_default().insert(key, value);
+                        }
+                    });
+
+                    // The replacing snapshot must be applied even if it is
+                    // empty.
+                    if let Some(time) = replace_at {
+                        if !frontier.less_equal(&time) {
+                            pending.entry(time).or_default();
+                        }
+                    }
+
+                    let closed: Vec<_> = pending
+                        .keys()
+                        .copied()
+                        .take_while(|time| !frontier.less_equal(time))
+                        .collect();
+                    for time in closed {
+                        let updates = pending.remove(&time).expect("known to exist");
+                        let replace = replace_at == Some(time);
+                        if let Err(e) = state.apply(time, &updates, replace).await {
+                            error!(
+                                "unable to apply updates at {} to postgres sink {}: {:#}. \
+                                 Shutting down sink.",
+                                time, sink_id, e
+                            );
+                            // Indicate that the sink is closed to everyone
+                            // else who might be tracking its write frontier.
+                            shared_frontier.borrow_mut().clear();
+                            return;
+                        }
+                        if replace {
+                            replace_at = None;
+                        }
+                    }
+
+                    let mut shared_frontier = shared_frontier.borrow_mut();
+                    shared_frontier.clear();
+                    shared_frontier.extend(frontier.iter().cloned());
+                }
+            },
+        );
+
+        Some(token)
+    }
+}
+
+struct PostgresSinkState {
+    connector: PostgresSinkConnector,
+    sink_id: GlobalId,
+    /// The key under which the sink's progress is recorded in the progress
+    /// table, which is the sink's ID.
+    progress_key: String,
+    /// The qualified name of the upstream table.
+    table: String,
+    /// The names of the upstream table's columns.
+    columns: Vec<String>,
+    /// The names of the upstream table's key columns, separated by commas.
+    key_columns: String,
+    /// The action of upserts whose key already exists.
+    on_conflict: String,
+    delete_all_sql: String,
+    create_progress_table_sql: String,
+    progress_sql: String,
+    latest_ts_sql: String,
+    client: Option<Client>,
+}
+
+impl PostgresSinkState {
+    fn new(connector: PostgresSinkConnector, sink_id: GlobalId) -> Self {
+        let table = format!(
+            "{}.{}",
+            escape_identifier(&connector.namespace),
+            escape_identifier(&connector.table)
+        );
+        let progress_table = format!(
+            "{}.{}",
+            escape_identifier(&connector.namespace),
+            SINK_PROGRESS_TABLE
+        );
+        let columns: Vec<_> = connector
+            .value_desc
+            .iter_names()
+            .map(|name| escape_identifier(name.as_str()))
+            .collect();
+        let key_columns = connector
+            .key_indices
+            .iter()
+            .map(|i| &columns[*i])
+            .join(", ");
+        let updates = columns
+            .iter()
+            .enumerate()
+            .filter(|(i, _column)| !connector.key_indices.contains(i))
+            .map(|(_i, column)| format!("{} = EXCLUDED.{}", column, column))
+            .join(", ");
+        let on_conflict = if updates.is_empty() {
+            "NOTHING".into()
+        } else {
+            format!("UPDATE SET {}", updates)
+        };
+        let delete_all_sql = format!("DELETE FROM {}", table);
+        let create_progress_table_sql = format!(
+            "CREATE TABLE IF NOT EXISTS {} (sink_id text PRIMARY KEY, ts bigint NOT NULL)",
+            progress_table
+        );
+        let progress_sql = format!(
+            "INSERT INTO {} (sink_id, ts) VALUES ($1, $2) \
+             ON CONFLICT (sink_id) DO UPDATE SET ts = EXCLUDED.ts",
+            progress_table
+        );
+        let latest_ts_sql = format!("SELECT ts FROM {} WHERE sink_id = $1", progress_table);
+
+        PostgresSinkState {
+            progress_key: sink_id.to_string(),
+            connector,
+            sink_id,
+            table,
+            columns,
+            key_columns,
+            on_conflict,
+            delete_all_sql,
+            create_progress_table_sql,
+            progress_sql,
+            latest_ts_sql,
+            client: None,
+        }
+    }
+
+    /// Returns a connection to the upstream database, reconnecting if the
+    /// previous connection was lost.
+    async fn client(&mut self) -> Result<&mut Client, anyhow::Error> {
+        if !matches!(&self.client, Some(client) if !client.is_closed()) {
+            self.client = Some(mz_postgres_util::connect(&self.connector.conn).await?);
+        }
+        Ok(self.client.as_mut().expect("connected above"))
+    }
+
+    /// Creates the progress table if it does not exist, and returns the latest
+    /// timestamp that the sink has applied to the upstream table, if any,
+    /// retrying indefinitely on failure.
+    async fn latest_ts(&mut self) -> Option<Timestamp> {
+        let tries = Retry::default()
+            .clamp_backoff(Duration::from_secs(60))
+            .into_retry_stream();
+        tokio::pin!(tries);
+        while tries.next().await.is_some() {
+            match self.try_latest_ts().await {
+                Ok(ts) => return ts,
+                Err(e) => warn!(
+                    "error reading progress of postgres sink {}, retrying: {:#}",
+                    self.sink_id, e
+                ),
+            }
+        }
+        unreachable!("retries forever")
+    }
+
+    async fn try_latest_ts(&mut self) -> Result<Option<Timestamp>, anyhow::Error> {
+        let create_sql = self.create_progress_table_sql.clone();
+        let sql = self.latest_ts_sql.clone();
+        let progress_key = self.progress_key.clone();
+        let client = self.client().await?;
+        client.batch_execute(&create_sql).await?;
+        match client.query_opt(&sql, &[&progress_key]).await? {
+            Some(row) => Ok(Some(u64::try_from(row.get::<_, i64>("ts"))?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns a statement that upserts `rows` rows, whose columns are passed
+    /// as consecutive parameters.
+    fn upsert_sql(&self, rows: usize) -> String {
+        let arity = self.columns.len();
+        format!(
+            "INSERT INTO {} ({}) VALUES {} ON CONFLICT ({}) DO {}",
+            self.table,
+            self.columns.iter().join(", "),
+            (0..rows)
+                .map(|row| format!(
+                    "({})",
+                    (0..arity)
+                        .map(|column| self.param(row * arity + column, column))
+                        .join(", ")
+                ))
+                .join(", "),
+            self.key_columns,
+            self.on_conflict,
+        )
+    }
+
+    /// Returns a statement that deletes the rows with `rows` keys, whose
+    /// columns are passed as consecutive parameters.
+    fn delete_sql(&self, rows: usize) -> String {
+        let key_indices = &self.connector.key_indices;
+        format!(
+            "DELETE FROM {} WHERE ({}) IN (VALUES {})",
+            self.table,
+            self.key_columns,
+            (0..rows)
+                .map(|row| format!(
+                    "({})",
+                    key_indices
+                        .iter()
+                        .enumerate()
+                        .map(|(i, column)| self.param(row * key_indices.len() + i, *column))
+                        .join(", ")
+                ))
+                .join(", "),
+        )
+    }
+
+    /// Returns the `i`th parameter of a statement, which is passed in its text
+    /// format and cast to the type of the upstream `column`.
+    fn param(&self, i: usize, column: usize) -> String {
+        format!("${}::text::{}", i + 1, self.connector.column_types[column])
+    }
+
+    /// Applies the updates at `time` to the upstream table in a single
+    /// transaction that also records `time` as the sink's progress. If
+    /// `replace` is set, the updates are a snapshot that replaces the table's
+    /// existing rows.
+    ///
+    /// Transient failures are retried indefinitely. Errors that would recur on
+    /// every retry, like constraint violations, are returned.
+    async fn apply(
+        &mut self,
+        time: Timestamp,
+        updates: &BTreeMap<Row, Option<Row>>,
+        replace: bool,
+    ) -> Result<(), anyhow::Error> {
+        let tries = Retry::default()
+            .clamp_backoff(Duration::from_secs(60))
+            .into_retry_stream();
+        tokio::pin!(tries);
+        while tries.next().await.is_some() {
+            match self.try_apply(time, updates, replace).await {
+                Ok(()) => return Ok(()),
+                Err(e) if is_transient(&e) => warn!(
+                    "error applying updates at {} to postgres sink {}, retrying: {:#}",
+                    time, self.sink_id, e
+                ),
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!("retries forever")
+    }
+
+    async fn try_apply(
+        &mut self,
+        time: Timestamp,
+        updates: &BTreeMap<Row, Option<Row>>,
+        replace: bool,
+    ) -> Result<(), anyhow::Error> {
+        let value_types: Vec<_> = self.connector.value_desc.iter_types().cloned().collect();
+        let key_types: Vec<_> = self
+            .connector
+            .key_indices
+            .iter()
+            .map(|i| value_types[*i].clone())
+            .collect();
+
+        // Each key is updated at most once, so the deletes and upserts can be
+        // applied in batches of many rows, in any order.
+        let mut statements = Vec::new();
+        let mut buf = BytesMut::new();
+        let (upserts, deletes): (Vec<_>, Vec<_>) =
+            updates.iter().partition(|(_key, value)| value.is_some());
+        for batch in deletes.chunks(batch_rows(key_types.len())) {
+            let params: Vec<_> = batch
+                .iter()
+                .flat_map(|(key, _value)| encode_params(key, &key_types, &mut buf))
+                .collect();
+            statements.push((self.delete_sql(batch.len()), params));
+        }
+        for batch in upserts.chunks(batch_rows(value_types.len())) {
+            let params: Vec<_> = batch
+                .iter()
+                .flat_map(|(_key, value)| {
+                    let value = value.as_ref().expect("partitioned above");
+                    encode_params(value, &value_types, &mut buf)
+                })
+                .collect();
+            statements.push((self.upsert_sql(batch.len()), params));
+        }
+        let delete_all_sql = self.delete_all_sql.clone();
+        let progress_sql = self.progress_sql.clone();
+        let progress_key = self.progress_key.clone();
+
+        let client = self.client().await?;
+        let txn = client.transaction().await?;
+        if replace {
+            txn.execute(&delete_all_sql, &[]).await?;
+        }
+        for (sql, params) in &statements {
+            txn.execute(sql.as_str(), &as_sql_params(params)).await?;
+        }
+        let ts = i64::try_from(time)?;
+        txn.execute(&progress_sql, &[&progress_key, &ts]).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+}
+
+/// Returns the number of rows with `arity` columns that a single statement
+/// upserts or deletes. Postgres limits the number of parameters of a statement.
+fn batch_rows(arity: usize) -> usize {
+    (MAX_STATEMENT_PARAMS / arity.max(1)).clamp(1, MAX_BATCH_ROWS)
+}
+
+/// Reports whether applying updates might succeed after failing with `e`.
+///
+/// Errors that Postgres reports with an SQLSTATE outside of the classes below,
+/// like constraint violations or invalid input values, recur on every retry.
+/// Errors without an SQLSTATE, like lost connections, are assumed to be
+/// transient.
+fn is_transient(e: &anyhow::Error) -> bool {
+    match e
+        .downcast_ref::<tokio_postgres::Error>()
+        .and_then(|e| e.as_db_error())
+    {
+        // Connection exceptions, transaction rollbacks, insufficient resources,
+        // objects in use, operator interventions, and system and internal
+        // errors.
+        Some(db_error) => matches!(
+            &db_error.code().code()[..2],
+            "08" | "40" | "53" | "55" | "57" | "58" | "XX"
+        ),
+        None => true,
+    }
+}
+
+/// Encodes the datums of `row` in their text format, with `None` for nulls.
+fn encode_params(row: &Row, types: &[ColumnType], buf: &mut BytesMut) -> Vec<Option<String>> {
+    row.iter()
+        .zip(types)
+        .map(|(datum, typ)| {
+            mz_pgrepr::Value::from_datum(datum, &typ.scalar_type).map(|value| {
+                buf.clear();
+                value.encode_text(buf);
+                String::from_utf8(buf.to_vec()).expect("text encoding is valid UTF-8")
+            })
+        })
+        .collect()
+}
+
+fn as_sql_params(params: &[Option<String>]) -> Vec<&(dyn ToSql + Sync)> {
+    params
+        .iter()
+        .map(|param| param as &(dyn ToSql + Sync))
+        .collect()
+}
@@ -0,0 +1,290 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use aws_sdk_s3::types::ByteStream;
+use chrono::NaiveDateTime;
+use differential_dataflow::consolidation::consolidate_updates;
+use differential_dataflow::{Collection, Hashable};
+use timely::dataflow::channels::pact::Exchange;
+use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
+use timely::dataflow::Scope;
+use timely::progress::Antichain;
+use timely::progress::Timestamp as TimelyTimestamp;
+use timely::PartialOrder;
+use tracing::{error, warn};
+
+use mz_dataflow_types::sinks::{S3SinkConnector, S3SinkFormat, SinkDesc};
+use mz_interchange::csv::CsvEncoder;
+use mz_interchange::parquet::ParquetEncoder;
+use mz_ore::retry::Retry;
+use mz_repr::{Diff, GlobalId, Row, Timestamp};
+use mz_timely_util::operators_async_ext::OperatorBuilderExt;
+
+use crate::render::sinks::SinkRender;
+
+impl<G> SinkRender<G> for S3SinkConnector
+where
+    G: Scope<Timestamp = Timestamp>,
+{
+    fn uses_keys(&self) -> bool {
+        false
+    }
+
+    fn get_key_indices(&self) -> Option<&[usize]> {
+        None
+    }
+
+    fn get_relation_key_indices(&self) -> Option<&[usize]> {
+        None
+    }
+
+    fn render_continuous_sink(
+        &self,
+        compute_state: &mut crate::compute_state::ComputeState,
+        _sink: &SinkDesc,
+        sink_id: GlobalId,
+        sinked_collection: Collection<G, (Option<Row>, Option<Row>), Diff>,
+    ) -> Option<Rc<dyn Any>>
+    where
+        G: Scope<Timestamp = Timestamp>,
+    {
+        let scope = sinked_collection.scope();
+        let operator_name = format!("s3_sink({})", sink_id);
+        let mut s3_op = OperatorBuilder::new(operator_name, scope.clone());
+
+        // Exactly one worker writes the sink's objects, so that each batch of
+        // updates is written as a single data file and described by a single
+        // manifest.
+        let hashed_id = sink_id.hashed();
+        let active_write_worker = (hashed_id as usize) % scope.peers() == scope.index();
+
+        let mut input =
+            s3_op.new_input(&sinked_collection.inner, Exchange::new(move |_| hashed_id));
+
+        let connector = self.clone();
+        let aws_external_id_prefix = compute_state
+            .connector_context
+            .aws_external_id_prefix
+            .clone();
+
+        let token = Rc::new(());
+        let token_weak = Rc::downgrade(&token);
+
+        // Only the active_write_worker will ever produce data so all other workers have
+        // an empty frontier.
+        let shared_frontier = Rc::new(RefCell::new(if active_write_worker {
+            Antichain::from_elem(TimelyTimestamp::minimum())
+        } else {
+            Antichain::new()
+        }));
+
+        compute_state
+            .sink_write_frontiers
+            .insert(sink_id, Rc::clone(&shared_frontier));
+
+        s3_op.build_async(
+            scope,
+            move |_capabilities, frontiers, scheduler| async move {
+                let mut buffer = Vec::new();
+                let mut stash: Vec<(Row, Timestamp, Diff)> = Vec::new();
+                let mut lower = Antichain::from_elem(Timestamp::minimum());
+
+                let encoder = Encoder::new(&connector);
+                let config = connector
+                    .aws
+                    .load(aws_external_id_prefix.as_ref(), Some(&sink_id))
+                    .await;
+                let client = aws_sdk_s3::Client::new(&config);
+
+                while scheduler.notified().await {
+                    let frontier = frontiers.borrow()[0].clone();
+
+                    if !active_write_worker || token_weak.upgrade().is_none() {
+                        return;
+                    }
+
+                    input.for_each(|_cap, data| {
+                        data.swap(&mut buffer);
+                        for ((_key, value), time, diff) in buffer.drain(..) {
+                            let value = value.expect("S3 sinks always have a value");
+                            stash.push((value, time, diff));
+                        }
+                    });
+
+                    if !PartialOrder::less_than(&lower, &frontier) {
+                        continue;
+                    }
+
+                    let (mut updates, rest): (Vec<_>, Vec<_>) = stash
+                        .drain(..)
+                        .partition(|(_row, time, _diff)| !frontier.less_equal(time));
+                    stash = rest;
+                    consolidate_updates(&mut updates);
+
+                    // Batches are only written when they contain updates. The
+                    // manifests of consecutive batches need not have adjacent
+                    // bounds; no updates occurred in the gaps between them.
+                    if !updates.is_empty() {
+                        let batch = Batch {
+                            lower: *lower.as_option().expect("lower is never empty"),
+                            // An empty frontier means that the sinked
+                            // collection is complete.
+                            upper: frontier.as_option().copied().unwrap_or(Timestamp::MAX),
+                        };
+                        if let Err(e) = batch.write(&client, &connector, &encoder, updates).await {
+                            error!(
+                                "unable to write batch to S3 sink {}: {:#}. Shutting down sink.",
+                                sink_id, e
+                            );
+                            // Indicate that the sink is closed to everyone
+                            // else who might be tracking its write frontier.
+                            shared_frontier.borrow_mut().clear();
+                            return;
+                        }
+                    }
+
+                    lower = frontier;
+                    let mut shared_frontier = shared_frontier.borrow_mut();
+                    shared_frontier.clear();
+                    shared_frontier.extend(lower.iter().cloned());
+                }
+            },
+        );
+
+        Some(token)
+    }
+}
+
+enum Encoder {
+    Csv(CsvEncoder),
+    Parquet(ParquetEncoder),
+}
+
+impl Encoder {
+    fn new(connector: &S3SinkConnector) -> Self {
+        let desc = connector.value_desc.clone();
+        match connector.format {
+            S3SinkFormat::Csv { delimiter } => Encoder::Csv(CsvEncoder::new(desc, delimiter)),
+            S3SinkFormat::Parquet => Encoder::Parquet(ParquetEncoder::new(desc)),
+        }
+    }
+
+    fn encode_updates(&self, updates: &[(Row, Timestamp, Diff)]) -> Vec<u8> {
+        match self {
+            Encoder::Csv(encoder) => encoder.encode_updates(updates),
+            Encoder::Parquet(encoder) => encoder
+                .encode_updates(updates)
+                .expect("encoding updates as Parquet cannot fail"),
+        }
+    }
+}
+
+/// A batch of the updates with times in `[lower, upper)`.
+struct Batch {
+    lower: Timestamp,
+    upper: Timestamp,
+}
+
+impl Batch {
+    /// Writes the batch's data files, followed by the manifest that announces
+    /// them.
+    ///
+    /// The updates are partitioned by the hour of their timestamp, with one
+    /// data file per partition. Readers discover data files only through
+    /// manifests, so a reader never observes a partially written batch. All
+    /// objects are named after the batch's bounds, which makes the writes
+    /// idempotent and lets readers list manifests in time order.
+    async fn write(
+        &self,
+        client: &aws_sdk_s3::Client,
+        connector: &S3SinkConnector,
+        encoder: &Encoder,
+        updates: Vec<(Row, Timestamp, Diff)>,
+    ) -> Result<(), anyhow::Error> {
+        let mut partitions: BTreeMap<String, Vec<_>> = BTreeMap::new();
+        for update in updates {
+            partitions
+                .entry(partition_path(update.1))
+                .or_default()
+                .push(update);
+        }
+
+        let mut files = vec![];
+        for (partition, updates) in partitions {
+            let data_key = format!(
+                "{}data/{}/{:020}-{:020}.{}",
+                connector.prefix,
+                partition,
+                self.lower,
+                self.upper,
+                connector.format.extension()
+            );
+            let data = encoder.encode_updates(&updates);
+            put_object(client, &connector.bucket, &data_key, data).await?;
+            files.push(serde_json::json!({"key": data_key, "records": updates.len()}));
+        }
+
+        let manifest_key = format!("{}manifests/{:020}.json", connector.prefix, self.upper);
+        let manifest = serde_json::json!({
+            "lower": self.lower,
+            "upper": self.upper,
+            "files": files,
+        });
+        put_object(
+            client,
+            &connector.bucket,
+            &manifest_key,
+            manifest.to_string().into_bytes(),
+        )
+        .await
+    }
+}
+
+/// Returns the Hive-style partition, e.g. `date=2022-05-26/hour=13`, of the
+/// hour that contains `time`, which is in milliseconds since the Unix epoch.
+fn partition_path(time: Timestamp) -> String {
+    let secs = i64::try_from(time / 1000).unwrap_or(i64::MAX);
+    let datetime =
+        NaiveDateTime::from_timestamp_opt(secs, 0).unwrap_or(chrono::naive::MAX_DATETIME);
+    datetime.format("date=%Y-%m-%d/hour=%H").to_string()
+}
+
+/// Writes an object, retrying transient failures for up to five minutes.
+async fn put_object(
+    client: &aws_sdk_s3::Client,
+    bucket: &str,
+    key: &str,
+    body: Vec<u8>,
+) -> Result<(), anyhow::Error> {
+    Retry::default()
+        .clamp_backoff(Duration::from_secs(60))
+        .max_duration(Duration::from_secs(5 * 60))
+        .retry_async(|_| async {
+            let result = client
+                .put_object()
+                .bucket(bucket)
+                .key(key)
+                .body(ByteStream::from(body.clone()))
+                .send()
+                .await;
+            if let Err(e) = &result {
+                warn!("error writing s3://{}/{}, retrying: {}", bucket, key, e);
+            }
+            result
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("writing s3://{}/{}: {}", bucket, key, e))?;
+    Ok(())
+}
@@ -9,7 +9,7 @@
 
 //! Persistent metadata storage for the coordinator.
 
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -33,12 +33,12 @@ use mz_dataflow_types::sinks::{SinkConnector, SinkConnectorBuilder, SinkEnvelope
 use mz_dataflow_types::sources::{
     ConnectorInner, ExternalSourceConnector, SourceConnector, Timeline,
 };
-use mz_expr::{ExprHumanizer, MirScalarExpr, OptimizedMirRelationExpr};
+use mz_expr::{EvalError, ExprHumanizer, MirScalarExpr, OptimizedMirRelationExpr};
 use mz_ore::collections::CollectionExt;
 use mz_ore::metrics::MetricsRegistry;
 use mz_ore::now::{to_datetime, EpochMillis, NowFn};
 use mz_pgrepr::oid::FIRST_USER_OID;
-use mz_repr::{GlobalId, RelationDesc, ScalarType};
+use mz_repr::{Datum, GlobalId, RelationDesc, Row, RowArena, ScalarType};
 use mz_sql::ast::display::AstDisplay;
 use mz_sql::ast::Expr;
 use mz_sql::catalog::{
@@ -233,10 +233,12 @@ impl CatalogState {
     pub fn uses_tables(&self, id: GlobalId) -> bool {
         match self.get_entry(&id).item() {
             CatalogItem::Table(_) => true,
+            // Webhook sources are written like tables, and are read like them
+            // to observe every acknowledged request.
+            CatalogItem::Source(source) => source.is_webhook(),
             item @ CatalogItem::View(_) => item.uses().iter().any(|id| self.uses_tables(*id)),
             CatalogItem::Index(idx) => self.uses_tables(idx.on),
-            CatalogItem::Source(_)
-            | CatalogItem::Func(_)
+            CatalogItem::Func(_)
             | CatalogItem::Sink(_)
             | CatalogItem::Type(_)
             | CatalogItem::Secret(_)
@@ -661,6 +663,7 @@ impl CatalogState {
                 SourceConnector::External { connector, .. } => match &connector {
                     ExternalSourceConnector::PubNub(_) => Volatile,
                     ExternalSourceConnector::Kinesis(_) => Volatile,
+                    ExternalSourceConnector::Webhook(_) => Volatile,
                     _ => Unknown,
                 },
                 SourceConnector::Local { .. } | SourceConnector::Log => Volatile,
@@ -967,6 +970,8 @@ pub struct Table {
     pub desc: RelationDesc,
     #[serde(skip)]
     pub defaults: Vec<Expr<Aug>>,
+    pub checks: Vec<(String, MirScalarExpr)>,
+    pub foreign_keys: Vec<(GlobalId, Vec<(usize, usize)>)>,
     pub conn_id: Option<u32>,
     pub depends_on: Vec<GlobalId>,
 }
@@ -977,6 +982,19 @@ impl Table {
     pub fn timeline(&self) -> Timeline {
         Timeline::EpochMilliseconds
     }
+
+    /// Returns the name of the first check constraint that `row` violates, if
+    /// any, i.e., that evaluates to `false` on `row`.
+    pub fn violated_check(&self, row: &Row) -> Result<Option<&str>, EvalError> {
+        let datums = row.unpack();
+        let arena = RowArena::new();
+        for (name, expr) in &self.checks {
+            if expr.eval(&datums, &arena)? == Datum::False {
+                return Ok(Some(name));
+            }
+        }
+        Ok(None)
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -991,6 +1009,18 @@ impl Source {
     pub fn requires_single_materialization(&self) -> bool {
         self.connector.requires_single_materialization()
     }
+
+    /// Reports whether this is a webhook source, whose data the coordinator
+    /// appends at the timestamps of table writes.
+    pub fn is_webhook(&self) -> bool {
+        matches!(
+            self.connector,
+            SourceConnector::External {
+                connector: ExternalSourceConnector::Webhook(_),
+                ..
+            }
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -1241,6 +1271,31 @@ impl CatalogItem {
         }
     }
 
+    /// Returns a clone of `self`, which must be a table, without the foreign
+    /// keys that reference any of the tables identified by `parents`, or
+    /// errors if the table's definition cannot be parsed.
+    fn drop_foreign_keys(&self, parents: &BTreeSet<GlobalId>) -> Result<CatalogItem, Error> {
+        let mut table = match self {
+            CatalogItem::Table(table) => table.clone(),
+            _ => unreachable!("only tables have foreign keys"),
+        };
+        let parent_ids = parents.iter().map(|id| id.to_string()).collect();
+        let mut create_stmt = mz_sql::parse::parse(&table.create_sql)
+            .map_err(|e| {
+                Error::new(ErrorKind::Corruption {
+                    detail: format!("failed to parse table definition: {}", e),
+                })
+            })?
+            .into_element();
+        mz_sql::ast::transform::create_stmt_drop_foreign_keys(&mut create_stmt, &parent_ids);
+        table.create_sql = create_stmt.to_ast_string_stable();
+        table
+            .foreign_keys
+            .retain(|(parent, _)| !parents.contains(parent));
+        table.depends_on.retain(|id| !parents.contains(id));
+        Ok(CatalogItem::Table(table))
+    }
+
     pub fn requires_single_materialization(&self) -> bool {
         if let CatalogItem::Source(Source {
             connector: SourceConnector::External { ref connector, .. },
@@ -1315,6 +1370,11 @@ impl CatalogEntry {
         matches!(self.item(), CatalogItem::Table(_))
     }
 
+    /// Reports whether this catalog entry is a webhook source.
+    pub fn is_webhook_source(&self) -> bool {
+        matches!(self.item(), CatalogItem::Source(source) if source.is_webhook())
+    }
+
     /// Collects the identifiers of the dataflows that this dataflow depends
     /// upon.
     pub fn uses(&self) -> &[GlobalId] {
@@ -1553,6 +1613,8 @@ impl<S: Append> Catalog<S> {
                             create_sql: "TODO".to_string(),
                             desc: table.desc.clone(),
                             defaults: vec![Expr::null(); table.desc.arity()],
+                            checks: vec![],
+                            foreign_keys: vec![],
                             conn_id: None,
                             depends_on: vec![],
                         }),
@@ -2220,7 +2282,7 @@ impl<S: Append> Catalog<S> {
             .contains_key(item_name)
     }
 
-    pub fn drop_temp_item_ops(&mut self, conn_id: u32) -> Vec<Op> {
+    pub fn drop_temp_item_ops(&mut self, conn_id: u32) -> Result<Vec<Op>, Error> {
         let ids: Vec<GlobalId> = self.state.temporary_schemas[&conn_id]
             .items
             .values()
@@ -2237,7 +2299,7 @@ impl<S: Append> Catalog<S> {
         Ok(())
     }
 
-    pub fn drop_database_ops(&mut self, id: Option<DatabaseId>) -> Vec<Op> {
+    pub fn drop_database_ops(&mut self, id: Option<DatabaseId>) -> Result<Vec<Op>, Error> {
         let mut ops = vec![];
         let mut seen = HashSet::new();
         if let Some(id) = id {
@@ -2251,10 +2313,14 @@ impl<S: Append> Catalog<S> {
             }
             ops.push(Op::DropDatabase { id });
         }
-        ops
+        Self::drop_foreign_keys_ops(&self.state.entry_by_id, &mut ops, &seen)?;
+        Ok(ops)
     }
 
-    pub fn drop_schema_ops(&mut self, id: Option<(DatabaseId, SchemaId)>) -> Vec<Op> {
+    pub fn drop_schema_ops(
+        &mut self,
+        id: Option<(DatabaseId, SchemaId)>,
+    ) -> Result<Vec<Op>, Error> {
         let mut ops = vec![];
         let mut seen = HashSet::new();
         if let Some((database_id, schema_id)) = id {
@@ -2266,16 +2332,18 @@ impl<S: Append> Catalog<S> {
                 schema_id,
             })
         }
-        ops
+        Self::drop_foreign_keys_ops(&self.state.entry_by_id, &mut ops, &seen)?;
+        Ok(ops)
     }
 
-    pub fn drop_items_ops(&mut self, ids: &[GlobalId]) -> Vec<Op> {
+    pub fn drop_items_ops(&mut self, ids: &[GlobalId]) -> Result<Vec<Op>, Error> {
         let mut ops = vec![];
         let mut seen = HashSet::new();
         for &id in ids {
             Self::drop_item_cascade(id, &self.state.entry_by_id, &mut ops, &mut seen);
         }
-        ops
+        Self::drop_foreign_keys_ops(&self.state.entry_by_id, &mut ops, &seen)?;
+        Ok(ops)
     }
 
     fn drop_schema_items(
@@ -2298,12 +2366,49 @@ impl<S: Append> Catalog<S> {
         if !seen.contains(&id) {
             seen.insert(id);
             for &u in &by_id[&id].used_by {
-                Self::drop_item_cascade(u, by_id, ops, seen)
+                // Dropping a table drops the foreign keys that reference it,
+                // but not the tables that declare them.
+                if !Self::references_foreign_table(&by_id[&u], id) {
+                    Self::drop_item_cascade(u, by_id, ops, seen)
+                }
             }
             ops.push(Op::DropItem(id));
         }
     }
 
+    /// Reports whether `entry` is a table with a foreign key that references
+    /// the table identified by `parent`.
+    fn references_foreign_table(entry: &CatalogEntry, parent: GlobalId) -> bool {
+        match entry.item() {
+            CatalogItem::Table(table) => table.foreign_keys.iter().any(|(id, _)| *id == parent),
+            _ => false,
+        }
+    }
+
+    /// Adds the ops that drop the foreign keys that reference the items in
+    /// `dropped` from the tables that are not dropped themselves.
+    fn drop_foreign_keys_ops(
+        by_id: &BTreeMap<GlobalId, CatalogEntry>,
+        ops: &mut Vec<Op>,
+        dropped: &HashSet<GlobalId>,
+    ) -> Result<(), Error> {
+        let mut parents_by_child: BTreeMap<GlobalId, BTreeSet<GlobalId>> = BTreeMap::new();
+        for &id in dropped {
+            for &u in &by_id[&id].used_by {
+                if !dropped.contains(&u) && Self::references_foreign_table(&by_id[&u], id) {
+                    parents_by_child.entry(u).or_default().insert(id);
+                }
+            }
+        }
+        for (child, parents) in parents_by_child {
+            ops.push(Op::UpdateItem {
+                id: child,
+                to_item: by_id[&child].item().drop_foreign_keys(&parents)?,
+            });
+        }
+        Ok(())
+    }
+
     /// Gets GlobalIds of temporary items to be created, checks for name collisions
     /// within a connection id.
     fn temporary_ids(
@@ -2621,6 +2726,19 @@ impl<S: Append> Catalog<S> {
                     builtin_table_updates.extend(self.state.pack_item_update(id, -1));
                     vec![Action::DropItem(id)]
                 }
+                Op::UpdateItem { id, to_item } => {
+                    let entry = self.get_entry(&id);
+                    if !entry.item().is_temporary() {
+                        let serialized_item = self.serialize_item(&to_item);
+                        tx.update_item(id, &entry.name().item, &serialized_item)?;
+                    }
+                    builtin_table_updates.extend(self.state.pack_item_update(id, -1));
+                    vec![Action::UpdateItem {
+                        id,
+                        to_name: entry.name().clone(),
+                        to_item,
+                    }]
+                }
                 Op::RenameItem {
                     id,
                     to_name,
@@ -2945,7 +3063,14 @@ impl<S: Append> Catalog<S> {
                         state.resolve_full_name(&old_entry.name, old_entry.conn_id()),
                         id
                     );
-                    assert_eq!(old_entry.uses(), to_item.uses());
+                    // The new definition may only lose dependencies that an
+                    // earlier action of this transaction dropped, which keeps
+                    // `used_by` of the remaining items in sync.
+                    assert!(to_item.uses().iter().all(|u| old_entry.uses().contains(u)));
+                    assert!(old_entry
+                        .uses()
+                        .iter()
+                        .all(|u| to_item.uses().contains(u) || !state.entry_by_id.contains_key(u)));
                     let conn_id = old_entry.item().conn_id().unwrap_or(SYSTEM_CONN_ID);
                     let schema = &mut state.get_schema_mut(
                         &old_entry.name().qualifiers.database_spec,
@@ -3036,6 +3161,8 @@ impl<S: Append> Catalog<S> {
                 create_sql: table.create_sql,
                 desc: table.desc,
                 defaults: table.defaults,
+                checks: table.checks,
+                foreign_keys: table.foreign_keys,
                 conn_id: None,
                 depends_on: table.depends_on,
             }),
@@ -3209,6 +3336,13 @@ pub enum Op {
         current_full_name: FullObjectName,
         to_name: String,
     },
+    /// Replaces the definition of the identified item, keeping its name. The
+    /// new definition must have the same dependencies as the old one, except
+    /// for items dropped by earlier ops of the same transaction.
+    UpdateItem {
+        id: GlobalId,
+        to_item: CatalogItem,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -3670,16 +3804,25 @@ impl mz_sql::catalog::CatalogItem for CatalogEntry {
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+    use std::time::Duration;
+
     use tempfile::TempDir;
 
+    use mz_build_info::DUMMY_BUILD_INFO;
+    use mz_ore::metrics::MetricsRegistry;
     use mz_ore::now::NOW_ZERO;
+    use mz_repr::{Datum, GlobalId};
     use mz_sql::names::{
         ObjectQualifiers, PartialObjectName, QualifiedObjectName, ResolvedDatabaseSpecifier,
         SchemaSpecifier,
     };
+    use mz_sql::DEFAULT_SCHEMA;
+    use mz_stash::Sqlite;
 
-    use crate::catalog::{Catalog, Op};
-    use crate::session::Session;
+    use crate::catalog::builtin::MZ_VIEW_FOREIGN_KEYS;
+    use crate::catalog::{storage, Catalog, CatalogItem, Config, Op, SYSTEM_CONN_ID};
+    use crate::session::{Session, DEFAULT_DATABASE_NAME};
 
     /// System sessions have an empty `search_path` so it's necessary to
     /// schema-qualify all referenced items.
@@ -3906,4 +4049,142 @@ mod tests {
 
         Ok(())
     }
+
+    /// Opens a debug catalog in experimental mode, which foreign keys require.
+    async fn open_experimental_sqlite(
+        data_dir_path: &Path,
+    ) -> Result<Catalog<Sqlite>, anyhow::Error> {
+        let stash = mz_stash::Sqlite::open(&data_dir_path.join("stash"))?;
+        let storage = storage::Connection::open(stash, Some(true)).await?;
+        let (catalog, _) = Catalog::open(Config {
+            storage,
+            experimental_mode: Some(true),
+            build_info: &DUMMY_BUILD_INFO,
+            timestamp_frequency: Duration::from_secs(1),
+            now: NOW_ZERO.clone(),
+            skip_migrations: true,
+            metrics_registry: &MetricsRegistry::new(),
+        })
+        .await?;
+        Ok(catalog)
+    }
+
+    /// Asserts that `uses` and `used_by` agree across all catalog entries.
+    fn assert_dependencies_consistent(catalog: &Catalog<Sqlite>) {
+        for entry in catalog.entries() {
+            for u in entry.uses() {
+                assert!(catalog.get_entry(u).used_by().contains(&entry.id()));
+            }
+            for u in entry.used_by() {
+                assert!(catalog.get_entry(u).uses().contains(&entry.id()));
+            }
+        }
+    }
+
+    /// Returns the number of rows that `mz_view_foreign_keys` holds for the
+    /// table `id`.
+    fn foreign_key_rows(catalog: &Catalog<Sqlite>, id: GlobalId) -> usize {
+        let table_id = catalog.resolve_builtin_table(&MZ_VIEW_FOREIGN_KEYS);
+        catalog
+            .state()
+            .pack_item_update(id, 1)
+            .into_iter()
+            .filter(|update| update.id == table_id)
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_drop_cascade_foreign_keys() -> Result<(), anyhow::Error> {
+        let data_dir = TempDir::new()?;
+        let mut catalog = open_experimental_sqlite(data_dir.path()).await?;
+        let database_id = catalog.resolve_database(DEFAULT_DATABASE_NAME)?.id.clone();
+        let database_spec = ResolvedDatabaseSpecifier::Id(database_id);
+        let schema_spec = catalog
+            .resolve_schema_in_database(&database_spec, DEFAULT_SCHEMA, SYSTEM_CONN_ID)?
+            .id
+            .clone();
+
+        let mut ids = vec![];
+        for (name, create_sql) in [
+            (
+                "parents",
+                "CREATE TABLE materialize.public.parents (id int PRIMARY KEY)",
+            ),
+            (
+                "children",
+                "CREATE TABLE materialize.public.children \
+                 (parent_id int REFERENCES materialize.public.parents (id))",
+            ),
+        ] {
+            let id = catalog.allocate_user_id().await?;
+            let oid = catalog.allocate_oid().await?;
+            let item = catalog.parse_item(create_sql.to_string(), None)?;
+            catalog
+                .transact(
+                    vec![Op::CreateItem {
+                        id,
+                        oid,
+                        name: QualifiedObjectName {
+                            qualifiers: ObjectQualifiers {
+                                database_spec: database_spec.clone(),
+                                schema_spec: schema_spec.clone(),
+                            },
+                            item: name.to_string(),
+                        },
+                        item,
+                    }],
+                    |_catalog| Ok(()),
+                )
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+        let (parent_id, child_id) = (ids[0], ids[1]);
+        assert_eq!(catalog.get_entry(&child_id).uses(), &[parent_id]);
+        assert_eq!(catalog.get_entry(&parent_id).used_by(), &[child_id]);
+        assert_eq!(foreign_key_rows(&catalog, child_id), 1);
+
+        // Dropping the parent retracts the child's foreign key, and leaves the
+        // child in place.
+        let ops = catalog.drop_items_ops(&[parent_id])?;
+        let (updates, ()) = catalog.transact(ops, |_catalog| Ok(())).await.unwrap();
+        let table_id = catalog.resolve_builtin_table(&MZ_VIEW_FOREIGN_KEYS);
+        let foreign_key_diff: i64 = updates
+            .iter()
+            .filter(|update| update.id == table_id)
+            .map(|update| {
+                assert_eq!(
+                    update.row.iter().next(),
+                    Some(Datum::String(&child_id.to_string()))
+                );
+                update.diff
+            })
+            .sum();
+        assert_eq!(foreign_key_diff, -1);
+
+        assert_foreign_key_dropped(&catalog, parent_id, child_id);
+
+        // The catalog agrees after a reload, which plans the child anew.
+        drop(catalog);
+        let catalog = open_experimental_sqlite(data_dir.path()).await?;
+        assert_foreign_key_dropped(&catalog, parent_id, child_id);
+
+        Ok(())
+    }
+
+    fn assert_foreign_key_dropped(
+        catalog: &Catalog<Sqlite>,
+        parent_id: GlobalId,
+        child_id: GlobalId,
+    ) {
+        assert!(catalog.try_get_entry(&parent_id).is_none());
+        let child = catalog.get_entry(&child_id);
+        assert!(child.uses().is_empty());
+        match child.item() {
+            CatalogItem::Table(table) => assert!(table.foreign_keys.is_empty()),
+            _ => panic!("{} is not a table", child_id),
+        }
+        assert_eq!(foreign_key_rows(catalog, child_id), 0);
+        assert_dependencies_consistent(catalog);
+    }
 }
@@ -23,11 +23,11 @@ use crate::catalog::builtin::{
     MZ_ARRAY_TYPES, MZ_BASE_TYPES, MZ_CLUSTERS, MZ_CLUSTER_REPLICAS, MZ_COLUMNS, MZ_CONNECTORS,
     MZ_DATABASES, MZ_FUNCTIONS, MZ_INDEXES, MZ_INDEX_COLUMNS, MZ_KAFKA_SINKS, MZ_LIST_TYPES,
     MZ_MAP_TYPES, MZ_PSEUDO_TYPES, MZ_ROLES, MZ_SCHEMAS, MZ_SECRETS, MZ_SINKS, MZ_SOURCES,
-    MZ_TABLES, MZ_TYPES, MZ_VIEWS,
+    MZ_TABLES, MZ_TYPES, MZ_VIEWS, MZ_VIEW_FOREIGN_KEYS,
 };
 use crate::catalog::{
     CatalogItem, CatalogState, Connector, Func, Index, Sink, SinkConnector, SinkConnectorState,
-    Source, Type, View, SYSTEM_CONN_ID,
+    Source, Table, Type, View, SYSTEM_CONN_ID,
 };
 
 /// An update to a built-in table.
@@ -155,7 +155,9 @@ impl CatalogState {
         let name = &entry.name().item;
         let mut updates = match entry.item() {
             CatalogItem::Index(index) => self.pack_index_update(id, oid, name, index, diff),
-            CatalogItem::Table(_) => self.pack_table_update(id, oid, schema_id, name, diff),
+            CatalogItem::Table(table) => {
+                self.pack_table_update(id, oid, schema_id, name, table, diff)
+            }
             CatalogItem::Source(source) => {
                 self.pack_source_update(id, oid, schema_id, name, source, diff)
             }
@@ -206,9 +208,10 @@ impl CatalogState {
         oid: u32,
         schema_id: &SchemaSpecifier,
         name: &str,
+        table: &Table,
         diff: Diff,
     ) -> Vec<BuiltinTableUpdate> {
-        vec![BuiltinTableUpdate {
+        let mut updates = vec![BuiltinTableUpdate {
             id: self.resolve_builtin_table(&MZ_TABLES),
             row: Row::pack_slice(&[
                 Datum::String(&id.to_string()),
@@ -217,7 +220,25 @@ impl CatalogState {
                 Datum::String(name),
             ]),
             diff,
-        }]
+        }];
+        let child_id = id.to_string();
+        for (index, (parent_id, pairs)) in table.foreign_keys.iter().enumerate() {
+            let parent_id = parent_id.to_string();
+            for (child_column, parent_column) in pairs {
+                updates.push(BuiltinTableUpdate {
+                    id: self.resolve_builtin_table(&MZ_VIEW_FOREIGN_KEYS),
+                    row: Row::pack_slice(&[
+                        Datum::String(&child_id),
+                        Datum::Int64(*child_column as i64),
+                        Datum::String(&parent_id),
+                        Datum::Int64(*parent_column as i64),
+                        Datum::Int64(index as i64),
+                    ]),
+                    diff,
+                });
+            }
+        }
+        updates
     }
 
     fn pack_source_update(
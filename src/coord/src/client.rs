@@ -7,10 +7,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::sync::Arc;
 use std::time::Instant;
 
+use bytes::Bytes;
 use tokio::sync::{mpsc, oneshot, watch};
 use uuid::Uuid;
 
@@ -28,6 +30,7 @@ use crate::command::{
 use crate::coord::PeekResponseUnary;
 use crate::error::CoordError;
 use crate::session::{EndTransactionAction, PreparedStatement, Session};
+use crate::webhook::WebhookError;
 
 /// A handle to a running coordinator.
 ///
@@ -113,6 +116,32 @@ impl Client {
         let response = self.system_execute(stmt).await?;
         Ok(response.results.into_element())
     }
+
+    /// Appends an HTTP request with the specified `headers` and `body` to the
+    /// webhook source `database.schema.name`.
+    ///
+    /// Header names must be lowercase.
+    pub async fn append_webhook(
+        &self,
+        database: String,
+        schema: String,
+        name: String,
+        headers: BTreeMap<String, String>,
+        body: Bytes,
+    ) -> Result<(), WebhookError> {
+        let (tx, rx) = oneshot::channel();
+        self.cmd_tx
+            .send(Command::AppendWebhook {
+                database,
+                schema,
+                name,
+                headers,
+                body,
+                tx,
+            })
+            .expect("coordinator unexpectedly gone");
+        rx.await.expect("coordinator unexpectedly canceled request")
+    }
 }
 
 /// A coordinator client that is bound to a connection.
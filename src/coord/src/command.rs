@@ -7,11 +7,13 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::BTreeMap;
 use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
+use bytes::Bytes;
 use derivative::Derivative;
 use serde::Serialize;
 use tokio::sync::oneshot;
@@ -26,6 +28,7 @@ use tokio::sync::watch;
 use crate::coord::PeekResponseUnary;
 use crate::error::CoordError;
 use crate::session::{EndTransactionAction, RowBatchStream, Session};
+use crate::webhook::WebhookError;
 
 #[derive(Debug)]
 pub enum Command {
@@ -102,6 +105,15 @@ pub enum Command {
     Terminate {
         session: Session,
     },
+
+    AppendWebhook {
+        database: String,
+        schema: String,
+        name: String,
+        headers: BTreeMap<String, String>,
+        body: Bytes,
+        tx: oneshot::Sender<Result<(), WebhookError>>,
+    },
 }
 
 #[derive(Debug)]
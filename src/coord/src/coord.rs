@@ -73,6 +73,7 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Context};
+use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use derivative::Derivative;
 use differential_dataflow::lattice::Lattice;
@@ -97,7 +98,9 @@ use mz_dataflow_types::client::{
     ComputeInstanceId, ConcreteComputeInstanceReplicaConfig, ControllerResponse,
     LinearizedTimestampBindingFeedback, ReplicaId,
 };
+use mz_dataflow_types::postgres_source::PostgresTable;
 use mz_dataflow_types::sinks::{SinkAsOf, SinkConnector, SinkDesc, TailSinkConnector};
+use mz_dataflow_types::sources::encoding::SourceDataEncoding;
 use mz_dataflow_types::sources::{
     ExternalSourceConnector, PostgresSourceConnector, SourceConnector, Timeline,
 };
@@ -123,26 +126,27 @@ use mz_repr::{
 use mz_secrets::{SecretOp, SecretsController, SecretsReader};
 use mz_sql::ast::display::AstDisplay;
 use mz_sql::ast::{
-    CreateIndexStatement, CreateSourceStatement, ExplainStage, FetchStatement, Ident, InsertSource,
-    ObjectType, Query, Raw, RawIdent, SetExpr, Statement,
+    CreateIndexStatement, CreateSourceConnector, CreateSourceStatement, ExplainStage,
+    FetchStatement, Ident, InsertSource, ObjectType, Query, Raw, RawIdent, SetExpr, Statement,
 };
 use mz_sql::catalog::{
     CatalogComputeInstance, CatalogError, CatalogItemType, CatalogTypeDetails, SessionCatalog as _,
 };
 use mz_sql::names::{
-    FullObjectName, QualifiedObjectName, ResolvedDatabaseSpecifier, SchemaSpecifier,
+    FullObjectName, PartialObjectName, QualifiedObjectName, ResolvedDatabaseSpecifier,
+    SchemaSpecifier,
 };
 use mz_sql::plan::{
     AlterIndexResetOptionsPlan, AlterIndexSetOptionsPlan, AlterItemRenamePlan, AlterSecretPlan,
-    CreateComputeInstancePlan, CreateComputeInstanceReplicaPlan, CreateConnectorPlan,
-    CreateDatabasePlan, CreateIndexPlan, CreateRolePlan, CreateSchemaPlan, CreateSecretPlan,
-    CreateSinkPlan, CreateSourcePlan, CreateTablePlan, CreateTypePlan, CreateViewPlan,
-    CreateViewsPlan, DropComputeInstanceReplicaPlan, DropComputeInstancesPlan, DropDatabasePlan,
-    DropItemsPlan, DropRolesPlan, DropSchemaPlan, ExecutePlan, ExplainPlan, FetchPlan,
-    HirRelationExpr, IndexOption, IndexOptionName, InsertPlan, MutationKind, OptimizerConfig,
-    Params, PeekPlan, Plan, QueryWhen, RaisePlan, ReadThenWritePlan, ReplicaConfig,
-    ResetVariablePlan, SendDiffsPlan, SetVariablePlan, ShowVariablePlan, StatementDesc, TailFrom,
-    TailPlan, View,
+    AlterSourceRefreshPlan, CreateComputeInstancePlan, CreateComputeInstanceReplicaPlan,
+    CreateConnectorPlan, CreateDatabasePlan, CreateIndexPlan, CreateRolePlan, CreateSchemaPlan,
+    CreateSecretPlan, CreateSinkPlan, CreateSourcePlan, CreateTablePlan, CreateTypePlan,
+    CreateViewPlan, CreateViewsPlan, DropComputeInstanceReplicaPlan, DropComputeInstancesPlan,
+    DropDatabasePlan, DropItemsPlan, DropRolesPlan, DropSchemaPlan, ExecutePlan, ExplainPlan,
+    FetchPlan, HirRelationExpr, IndexOption, IndexOptionName, InsertPlan, MutationKind,
+    OptimizerConfig, Params, PeekPlan, Plan, QueryWhen, RaisePlan, ReadThenWritePlan,
+    ReplicaConfig, ResetVariablePlan, SendDiffsPlan, SetVariablePlan, ShowVariablePlan,
+    StatementDesc, TailFrom, TailPlan, View,
 };
 use mz_sql_parser::ast::RawObjectName;
 use mz_transform::Optimizer;
@@ -150,7 +154,7 @@ use mz_transform::Optimizer;
 use crate::catalog::builtin::{BUILTINS, MZ_VIEW_FOREIGN_KEYS, MZ_VIEW_KEYS};
 use crate::catalog::{
     self, storage, BuiltinTableUpdate, Catalog, CatalogItem, CatalogState, ComputeInstance,
-    Connector, SinkConnectorState,
+    Connector, SinkConnectorState, SYSTEM_CONN_ID,
 };
 use crate::client::{Client, Handle};
 use crate::command::{
@@ -166,6 +170,7 @@ use crate::session::{
 use crate::sink_connector;
 use crate::tail::PendingTail;
 use crate::util::ClientTransmitter;
+use crate::webhook::{self, WebhookError};
 
 pub mod id_bundle;
 
@@ -178,6 +183,7 @@ pub enum Message {
     ControllerReady,
     CreateSourceStatementReady(CreateSourceStatementReady),
     SinkConnectorReady(SinkConnectorReady),
+    AlterSourceRefreshReady(AlterSourceRefreshReady),
     SendDiffs(SendDiffs),
     WriteLockGrant(tokio::sync::OwnedMutexGuard<()>),
     AdvanceLocalInputs,
@@ -227,6 +233,16 @@ pub struct SinkConnectorReady {
     pub compute_instance: ComputeInstanceId,
 }
 
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct AlterSourceRefreshReady {
+    pub session: Session,
+    #[derivative(Debug = "ignore")]
+    pub tx: ClientTransmitter<ExecuteResponse>,
+    pub id: GlobalId,
+    pub result: Result<Vec<PostgresTable>, CoordError>,
+}
+
 /// Configures a coordinator.
 pub struct Config<S> {
     pub dataflow_client: mz_dataflow_types::client::Controller,
@@ -779,6 +795,9 @@ impl<S: Append + 'static> Coordinator<S> {
                 Message::SinkConnectorReady(ready) => {
                     self.message_sink_connector_ready(ready).await
                 }
+                Message::AlterSourceRefreshReady(ready) => {
+                    self.message_alter_source_refresh_ready(ready).await
+                }
                 Message::WriteLockGrant(write_lock_guard) => {
                     // It's possible to have more incoming write lock grants
                     // than pending writes because of cancellations.
@@ -804,15 +823,16 @@ impl<S: Append + 'static> Coordinator<S> {
         }
     }
 
-    // Advance all local inputs (tables) to the current wall clock or at least
-    // a time greater than any previous table read (if wall clock has gone
-    // backward). This downgrades the capabilities of all tables, which means that
-    // all tables can no longer produce new data before this timestamp.
+    // Advance all local inputs (tables and webhook sources) to the current wall
+    // clock or at least a time greater than any previous table read (if wall
+    // clock has gone backward). This downgrades the capabilities of all tables,
+    // which means that all tables can no longer produce new data before this
+    // timestamp.
     async fn advance_local_inputs(&mut self, advance_to: mz_repr::Timestamp) {
         let appends = self
             .catalog
             .entries()
-            .filter(|e| e.is_table())
+            .filter(|e| e.is_table() || e.is_webhook_source())
             .map(|table| (table.id(), vec![], advance_to))
             .collect();
         self.dataflow_client
@@ -945,6 +965,25 @@ impl<S: Append + 'static> Coordinator<S> {
         }
     }
 
+    async fn message_alter_source_refresh_ready(
+        &mut self,
+        AlterSourceRefreshReady {
+            session,
+            tx,
+            id,
+            result,
+        }: AlterSourceRefreshReady,
+    ) {
+        let result = match result {
+            Ok(upstream_tables) => self.refresh_postgres_source(id, upstream_tables).await,
+            Err(e) => Err(e),
+        };
+        tx.send(
+            result.map(|()| ExecuteResponse::AlteredObject(ObjectType::Source)),
+            session,
+        );
+    }
+
     fn message_send_diffs(
         &mut self,
         SendDiffs {
@@ -1114,6 +1153,20 @@ impl<S: Append + 'static> Coordinator<S> {
                 self.handle_terminate(&mut session).await;
             }
 
+            Command::AppendWebhook {
+                database,
+                schema,
+                name,
+                headers,
+                body,
+                tx,
+            } => {
+                let result = self
+                    .append_webhook(database, schema, name, headers, body)
+                    .await;
+                let _ = tx.send(result);
+            }
+
             Command::StartTransaction {
                 implicit,
                 session,
@@ -1416,6 +1469,7 @@ impl<S: Append + 'static> Coordinator<S> {
                     // Statements below must by run singly (in Started).
                     Statement::AlterIndex(_)
                     | Statement::AlterSecret(_)
+                    | Statement::AlterSource(_)
                     | Statement::AlterObjectRename(_)
                     | Statement::CreateConnector(_)
                     | Statement::CreateDatabase(_)
@@ -1585,7 +1639,10 @@ impl<S: Append + 'static> Coordinator<S> {
     /// Removes all temporary items created by the specified connection, though
     /// not the temporary schema itself.
     async fn drop_temp_items(&mut self, conn_id: u32) {
-        let ops = self.catalog.drop_temp_item_ops(conn_id);
+        let ops = self
+            .catalog
+            .drop_temp_item_ops(conn_id)
+            .expect("unable to drop temporary items for conn_id");
         self.catalog_transact(ops, |_| Ok(()))
             .await
             .expect("unable to drop temporary items for conn_id");
@@ -1813,6 +1870,9 @@ impl<S: Append + 'static> Coordinator<S> {
                     session,
                 );
             }
+            Plan::AlterSourceRefresh(plan) => {
+                self.sequence_alter_source_refresh(session, plan, tx).await;
+            }
             Plan::DiscardTemp => {
                 self.drop_temp_items(session.conn_id()).await;
                 tx.send(Ok(ExecuteResponse::DiscardedTemp), session);
@@ -2167,6 +2227,8 @@ impl<S: Append + 'static> Coordinator<S> {
             create_sql: table.create_sql,
             desc: table.desc,
             defaults: table.defaults,
+            checks: table.checks,
+            foreign_keys: table.foreign_keys,
             conn_id,
             depends_on: table.depends_on,
         };
@@ -2454,7 +2516,7 @@ impl<S: Append + 'static> Coordinator<S> {
         let mut ops = vec![];
 
         if let Some(id) = replace {
-            ops.extend(self.catalog.drop_items_ops(&[id]));
+            ops.extend(self.catalog.drop_items_ops(&[id])?);
         }
         let view_id = self.catalog.allocate_user_id().await?;
         let view_oid = self.catalog.allocate_oid().await?;
@@ -2684,7 +2746,7 @@ impl<S: Append + 'static> Coordinator<S> {
         &mut self,
         plan: DropDatabasePlan,
     ) -> Result<ExecuteResponse, CoordError> {
-        let ops = self.catalog.drop_database_ops(plan.id);
+        let ops = self.catalog.drop_database_ops(plan.id)?;
         self.catalog_transact(ops, |_| Ok(())).await?;
         Ok(ExecuteResponse::DroppedDatabase)
     }
@@ -2693,7 +2755,7 @@ impl<S: Append + 'static> Coordinator<S> {
         &mut self,
         plan: DropSchemaPlan,
     ) -> Result<ExecuteResponse, CoordError> {
-        let ops = self.catalog.drop_schema_ops(plan.id);
+        let ops = self.catalog.drop_schema_ops(plan.id)?;
         self.catalog_transact(ops, |_| Ok(())).await?;
         Ok(ExecuteResponse::DroppedSchema)
     }
@@ -2727,7 +2789,7 @@ impl<S: Append + 'static> Coordinator<S> {
                 });
             }
             let ids_to_drop: Vec<GlobalId> = instance.indexes().iter().cloned().collect();
-            ops.extend(self.catalog.drop_items_ops(&ids_to_drop));
+            ops.extend(self.catalog.drop_items_ops(&ids_to_drop)?);
             ops.push(catalog::Op::DropComputeInstance { name });
         }
 
@@ -2788,7 +2850,7 @@ impl<S: Append + 'static> Coordinator<S> {
         &mut self,
         plan: DropItemsPlan,
     ) -> Result<ExecuteResponse, CoordError> {
-        let ops = self.catalog.drop_items_ops(&plan.items);
+        let ops = self.catalog.drop_items_ops(&plan.items)?;
         self.catalog_transact(ops, |_| Ok(())).await?;
         Ok(match plan.ty {
             ObjectType::Source => ExecuteResponse::DroppedSource,
@@ -3092,7 +3154,7 @@ impl<S: Append + 'static> Coordinator<S> {
             let mut unmaterialized = vec![];
             for id in &id_bundle.storage_ids {
                 let entry = catalog.get_entry(id);
-                if entry.is_table() {
+                if entry.is_table() || entry.is_webhook_source() {
                     continue;
                 }
                 let has_indexes = catalog
@@ -3957,6 +4019,25 @@ impl<S: Append + 'static> Coordinator<S> {
         session: &mut Session,
         mut plan: SendDiffsPlan,
     ) -> Result<ExecuteResponse, CoordError> {
+        // Rows added to a table must satisfy its check constraints.
+        if let Some(entry) = self.catalog.try_get_entry(&plan.id) {
+            if let CatalogItem::Table(table) = entry.item() {
+                if !table.checks.is_empty() {
+                    for (row, diff) in plan.updates.iter() {
+                        if *diff <= 0 {
+                            continue;
+                        }
+                        if let Some(constraint) = table.violated_check(row)? {
+                            return Err(CoordError::CheckViolation {
+                                relation: entry.name().item.clone(),
+                                constraint: constraint.to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
         let affected_rows = {
             let mut affected_rows = Diff::from(0);
             let mut all_positive_diffs = true;
@@ -4135,6 +4216,67 @@ impl<S: Append + 'static> Coordinator<S> {
         self.sequence_insert_constant(session, id, values.into_inner())
     }
 
+    /// Appends a request to the webhook source `database.schema.name`, after
+    /// verifying its signature if the source requires one.
+    ///
+    /// Like single-row inserts into a table, each request is written at its
+    /// own timestamp.
+    async fn append_webhook(
+        &mut self,
+        database: String,
+        schema: String,
+        name: String,
+        headers: BTreeMap<String, String>,
+        body: Bytes,
+    ) -> Result<(), WebhookError> {
+        let name = PartialObjectName {
+            database: Some(database),
+            schema: Some(schema),
+            item: name,
+        };
+        let entry = self
+            .catalog
+            .resolve_entry(None, &vec![], &name, SYSTEM_CONN_ID)
+            .ok()
+            .filter(|entry| entry.is_webhook_source())
+            .ok_or_else(|| WebhookError::UnknownSource(name.to_string()))?;
+        let (connector, encoding) = match entry.source_connector() {
+            Ok(SourceConnector::External {
+                connector: ExternalSourceConnector::Webhook(connector),
+                encoding: SourceDataEncoding::Single(encoding),
+                ..
+            }) => (connector, encoding),
+            _ => unreachable!("webhook sources have a single value encoding"),
+        };
+        if let Some(validation) = &connector.validation {
+            let secret = self
+                .secrets_reader
+                .read(validation.secret_id)
+                .map_err(|e| WebhookError::Internal(format!("reading secret: {:#}", e)))?;
+            webhook::validate(&secret, &validation.header, &headers, &body)?;
+        }
+        let row = webhook::pack_row(
+            encoding,
+            connector.include_headers.is_some(),
+            &headers,
+            &body,
+        )?;
+
+        let id = entry.id();
+        let (timestamp, advance_to) = self.get_and_step_local_write_ts();
+        let updates = vec![Update {
+            row,
+            diff: 1,
+            timestamp,
+        }];
+        self.dataflow_client
+            .storage_mut()
+            .append(vec![(id, updates, advance_to)])
+            .await
+            .unwrap();
+        Ok(())
+    }
+
     // ReadThenWrite is a plan whose writes depend on the results of a
     // read. This works by doing a Peek then queuing a SendDiffs. No writes
     // or read-then-writes can occur between the Peek and SendDiff otherwise a
@@ -4398,6 +4540,132 @@ impl<S: Append + 'static> Coordinator<S> {
         Ok(ExecuteResponse::AlteredObject(ObjectType::Secret))
     }
 
+    async fn sequence_alter_source_refresh(
+        &mut self,
+        session: Session,
+        plan: AlterSourceRefreshPlan,
+        tx: ClientTransmitter<ExecuteResponse>,
+    ) {
+        let AlterSourceRefreshPlan { id } = plan;
+        let (conn, publication) = match self.catalog.get_entry(&id).source_connector() {
+            Ok(SourceConnector::External {
+                connector: ExternalSourceConnector::Postgres(connector),
+                ..
+            }) => (connector.conn.clone(), connector.publication.clone()),
+            _ => unreachable!("only Postgres sources can be refreshed"),
+        };
+
+        // Fetching the shape of the upstream tables could take an arbitrarily
+        // long time, so it happens off the main coordinator thread.
+        let internal_cmd_tx = self.internal_cmd_tx.clone();
+        task::spawn(|| format!("alter_source_refresh:{id}"), async move {
+            let result = mz_postgres_util::publication_info(&conn, &publication)
+                .await
+                .map(|tables| tables.into_iter().map(PostgresTable::from).collect())
+                .map_err(CoordError::Unstructured);
+            internal_cmd_tx
+                .send(Message::AlterSourceRefreshReady(AlterSourceRefreshReady {
+                    session,
+                    tx,
+                    id,
+                    result,
+                }))
+                .expect("sending to internal_cmd_tx cannot fail");
+        });
+    }
+
+    /// Records the current shape of the upstream tables of the Postgres source
+    /// `id` in the catalog and restarts the source with it.
+    ///
+    /// Refreshing fails unless the tables of the source have only had columns
+    /// appended, which the running source survives by ignoring them. The
+    /// restarted source takes a new snapshot of the tables, including the new
+    /// columns, which replaces the contents ingested so far.
+    async fn refresh_postgres_source(
+        &mut self,
+        id: GlobalId,
+        upstream_tables: Vec<PostgresTable>,
+    ) -> Result<(), CoordError> {
+        // The source may have been dropped while the upstream tables were
+        // being fetched.
+        let mut source = match self.catalog.try_get_entry(&id).and_then(|e| e.source()) {
+            Some(source) => source.clone(),
+            None => coord_bail!("source was dropped while it was being refreshed"),
+        };
+        let connector = match &mut source.connector {
+            SourceConnector::External {
+                connector: ExternalSourceConnector::Postgres(connector),
+                ..
+            } => connector,
+            _ => unreachable!("only Postgres sources can be refreshed"),
+        };
+        for table in &connector.details.tables {
+            match upstream_tables
+                .iter()
+                .find(|upstream| upstream.relation_id == table.relation_id)
+            {
+                Some(upstream) if table.is_extended_by(upstream) => (),
+                Some(_) => coord_bail!(
+                    "table {}.{} has been altered in ways other than appending columns; \
+                     recreate the source to use its new schema",
+                    table.namespace,
+                    table.name
+                ),
+                None => coord_bail!(
+                    "table {}.{} is no longer in publication {}",
+                    table.namespace,
+                    table.name,
+                    connector.publication
+                ),
+            }
+        }
+        connector.details.tables = upstream_tables;
+
+        // The details are recorded in the `CREATE SOURCE` statement, from
+        // which the source is planned when the catalog is loaded.
+        let details = hex::encode(prost::Message::encode_to_vec(&connector.details));
+        let mut create_stmt = mz_sql::parse::parse(&source.create_sql)
+            .expect("create_sql cannot be invalid")
+            .into_element();
+        match &mut create_stmt {
+            Statement::CreateSource(CreateSourceStatement {
+                connector: CreateSourceConnector::Postgres { details: d, .. },
+                ..
+            }) => *d = Some(details),
+            _ => unreachable!("Postgres sources are created by CREATE SOURCE"),
+        }
+        source.create_sql = create_stmt.to_ast_string_stable();
+
+        // Everything that restarting the source depends on is checked before
+        // the transaction commits, so that the catalog is not updated unless
+        // the source can be restarted.
+        let source_description = self
+            .catalog_transact(
+                vec![catalog::Op::UpdateItem {
+                    id,
+                    to_item: CatalogItem::Source(source),
+                }],
+                |txn| {
+                    txn.dataflow_client
+                        .storage()
+                        .collection(id)
+                        .map_err(|e| CoordError::Unstructured(anyhow!(e)))?;
+                    match txn.catalog.source_description_for(id) {
+                        Some(source_description) => Ok(source_description),
+                        None => coord_bail!("source {} has no description", id),
+                    }
+                },
+            )
+            .await?;
+
+        self.dataflow_client
+            .storage_mut()
+            .alter_sources(vec![(id, source_description)])
+            .await
+            .map_err(|e| CoordError::Unstructured(anyhow!(e)))?;
+        Ok(())
+    }
+
     fn extract_secret(
         &mut self,
         session: &Session,
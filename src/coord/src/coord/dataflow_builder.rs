@@ -14,9 +14,11 @@
 //! and indicate which identifiers have arrangements available. This module
 //! isolates that logic from the rest of the somewhat complicated coordinator.
 
+use std::collections::HashSet;
+
 use mz_dataflow_types::client::controller::ComputeController;
 use mz_dataflow_types::client::ComputeInstanceId;
-use mz_dataflow_types::sinks::SinkDesc;
+use mz_dataflow_types::sinks::{SinkDesc, SinkEnvelope};
 use mz_dataflow_types::{BuildDesc, DataflowDesc, IndexDesc};
 use mz_expr::visit::Visit;
 use mz_expr::{
@@ -28,6 +30,7 @@ use mz_repr::adt::array::ArrayDimension;
 use mz_repr::adt::numeric::Numeric;
 use mz_repr::{Datum, GlobalId, Row};
 use mz_stash::Append;
+use mz_transform::monotonic::MonotonicFlag;
 
 use crate::catalog::{CatalogItem, CatalogState};
 use crate::coord::{CatalogTxn, Coordinator};
@@ -263,6 +266,15 @@ impl<'a> DataflowBuilder<'a, mz_repr::Timestamp> {
         id: GlobalId,
         sink_description: SinkDesc,
     ) -> Result<(), CoordError> {
+        if sink_description.envelope == Some(SinkEnvelope::AppendOnly)
+            && !self.is_monotonic(&sink_description.from)
+        {
+            let entry = self.catalog.get_entry(&sink_description.from);
+            let name = self
+                .catalog
+                .resolve_full_name(entry.name(), entry.conn_id());
+            return Err(CoordError::NonMonotonicSinkInput(name.to_string()));
+        }
         dataflow.set_as_of(sink_description.as_of.frontier.clone());
         self.import_into_dataflow(&sink_description.from, dataflow)?;
         for BuildDesc { plan, .. } in &mut dataflow.objects_to_build {
@@ -275,6 +287,35 @@ impl<'a> DataflowBuilder<'a, mz_repr::Timestamp> {
 
         Ok(())
     }
+
+    /// Reports whether the collection identified by `id` is known never to
+    /// retract rows.
+    ///
+    /// This is a conservative analysis: sources are monotonic if their
+    /// connector is append-only, views are monotonic if their optimized plan
+    /// preserves the monotonicity of their inputs, and everything else
+    /// (notably tables) is assumed to be non-monotonic.
+    fn is_monotonic(&self, id: &GlobalId) -> bool {
+        maybe_grow(|| match self.catalog.get_entry(id).item() {
+            CatalogItem::Source(source) => source.connector.append_only(),
+            CatalogItem::View(view) => {
+                let monotonic_ids = view
+                    .optimized_expr
+                    .depends_on()
+                    .into_iter()
+                    .filter(|id| self.is_monotonic(id))
+                    .collect::<HashSet<_>>();
+                MonotonicFlag::default()
+                    .apply(
+                        &mut view.optimized_expr.clone().into_inner(),
+                        &monotonic_ids,
+                        &mut HashSet::new(),
+                    )
+                    .unwrap_or(false)
+            }
+            _ => false,
+        })
+    }
 }
 
 /// Prepares a relation expression for dataflow execution by preparing all
@@ -36,6 +36,13 @@ pub enum CoordError {
     Catalog(catalog::Error),
     /// The cached plan or descriptor changed.
     ChangedPlan,
+    /// A row written to a table violated one of its check constraints.
+    CheckViolation {
+        /// The name of the table.
+        relation: String,
+        /// The name of the violated constraint.
+        constraint: String,
+    },
     /// The specified session parameter is constrained to a finite set of values.
     ConstrainedParameter {
         parameter: &'static (dyn Var + Send + Sync),
@@ -86,6 +93,8 @@ pub enum CoordError {
     ConstraintViolation(NotNullViolation),
     /// Target cluster has no replicas to service query.
     NoClusterReplicasAvailable(String),
+    /// An `ENVELOPE NONE` sink was created on an input that may retract rows.
+    NonMonotonicSinkInput(String),
     /// The named operation cannot be run in a transaction.
     OperationProhibitsTransaction(String),
     /// The named operation requires an active transaction.
@@ -199,6 +208,8 @@ impl CoordError {
             } => {
                 let source_name = match source_type {
                     RematerializedSourceType::Postgres => "Postgres",
+                    RematerializedSourceType::MySql => "MySQL",
+                    RematerializedSourceType::SqlServer => "SQL Server",
                     RematerializedSourceType::S3 => "S3 with SQS notification ",
                 };
                 Some(format!(
@@ -242,6 +253,8 @@ impl CoordError {
             CoordError::InvalidRematerialization { source_type, .. } => {
                 let doc_page = match source_type {
                     RematerializedSourceType::Postgres => "postgres",
+                    RematerializedSourceType::MySql => "mysql",
+                    RematerializedSourceType::SqlServer => "sql-server",
                     RematerializedSourceType::S3 => "text-s3",
                 };
                 Some(format!(
@@ -263,6 +276,11 @@ impl CoordError {
             CoordError::NoClusterReplicasAvailable(_) => {
                 Some("You can create cluster replicas using CREATE CLUSTER REPLICA".into())
             }
+            CoordError::NonMonotonicSinkInput(_) => Some(
+                "Use ENVELOPE DEBEZIUM or ENVELOPE UPSERT to sink relations that may \
+                 update or delete rows."
+                    .into(),
+            ),
             CoordError::UntargetedLogRead { .. } => Some(
                 "Use `SET cluster_replica = <replica-name>` to target a specific replica in the \
                  active cluster. Note that subsequent `SELECT` queries will only be answered by \
@@ -280,6 +298,15 @@ impl fmt::Display for CoordError {
                 f.write_str("unable to automatically determine a query timestamp")
             }
             CoordError::ChangedPlan => f.write_str("cached plan must not change result type"),
+            CoordError::CheckViolation {
+                relation,
+                constraint,
+            } => write!(
+                f,
+                "new row for relation {} violates check constraint {}",
+                relation.quoted(),
+                constraint.quoted()
+            ),
             CoordError::Catalog(e) => e.fmt(f),
             CoordError::ConstrainedParameter {
                 parameter, value, ..
@@ -348,6 +375,11 @@ impl fmt::Display for CoordError {
                     cluster.quoted()
                 )
             }
+            CoordError::NonMonotonicSinkInput(from) => write!(
+                f,
+                "ENVELOPE NONE sinks require an append-only input, but {} may update or delete rows",
+                from.quoted()
+            ),
             CoordError::OperationProhibitsTransaction(op) => {
                 write!(f, "{} cannot be run inside a transaction block", op)
             }
@@ -486,6 +518,8 @@ impl Error for CoordError {}
 #[derive(Debug)]
 pub enum RematerializedSourceType {
     Postgres,
+    MySql,
+    SqlServer,
     S3,
 }
 
@@ -500,6 +534,8 @@ impl RematerializedSourceType {
             SourceConnector::External { connector, .. } => match connector {
                 ExternalSourceConnector::S3(_) => RematerializedSourceType::S3,
                 ExternalSourceConnector::Postgres(_) => RematerializedSourceType::Postgres,
+                ExternalSourceConnector::MySql(_) => RematerializedSourceType::MySql,
+                ExternalSourceConnector::SqlServer(_) => RematerializedSourceType::SqlServer,
                 _ => unreachable!(),
             },
             _ => unreachable!(),
@@ -39,6 +39,7 @@ mod error;
 mod sink_connector;
 mod tail;
 mod util;
+mod webhook;
 
 pub mod catalog;
 pub mod session;
@@ -47,3 +48,4 @@ pub use crate::client::{Client, ConnClient, Handle, SessionClient};
 pub use crate::command::{Canceled, ExecuteResponse, RowsFuture, StartupMessage, StartupResponse};
 pub use crate::coord::{serve, Config, PeekResponseUnary};
 pub use crate::error::CoordError;
+pub use crate::webhook::WebhookError;
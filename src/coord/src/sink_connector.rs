@@ -14,8 +14,10 @@ use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, ResourceSpecifier, Top
 
 use mz_dataflow_types::sinks::{
     KafkaSinkConnector, KafkaSinkConnectorBuilder, KafkaSinkConnectorRetention,
-    KafkaSinkConsistencyConnector, PersistSinkConnector, PersistSinkConnectorBuilder,
-    PublishedSchemaInfo, SinkConnector, SinkConnectorBuilder,
+    KafkaSinkConsistencyConnector, KafkaSinkEncoding, KafkaSinkFormat, PersistSinkConnector,
+    PersistSinkConnectorBuilder, PostgresSinkConnector, PostgresSinkConnectorBuilder,
+    PublishedSchemaInfo, S3SinkConnector, S3SinkConnectorBuilder, SinkConnector,
+    SinkConnectorBuilder,
 };
 use mz_dataflow_types::ConnectorContext;
 use mz_kafka_util::client::{create_new_client_config, MzClientContext};
@@ -32,6 +34,8 @@ pub async fn build(
     match builder {
         SinkConnectorBuilder::Kafka(k) => build_kafka(k, id, connector_context).await,
         SinkConnectorBuilder::Persist(p) => build_persist_sink(p, id),
+        SinkConnectorBuilder::S3(s3) => build_s3_sink(s3, id),
+        SinkConnectorBuilder::Postgres(p) => build_postgres_sink(p).await,
     }
 }
 
@@ -247,8 +251,8 @@ async fn build_kafka(
     )
     .await
     .context("error registering kafka topic for sink")?;
-    let published_schema_info = match builder.format {
-        mz_dataflow_types::sinks::KafkaSinkFormat::Avro {
+    let (encoding, published_schema_info) = match builder.format {
+        KafkaSinkFormat::Avro {
             key_schema,
             value_schema,
             ccsr_config,
@@ -265,27 +269,49 @@ async fn build_kafka(
             )
             .await
             .context("error publishing kafka schemas for sink")?;
-            Some(PublishedSchemaInfo {
+            let published_schema_info = PublishedSchemaInfo {
                 key_schema_id,
                 value_schema_id,
-            })
+            };
+            (KafkaSinkEncoding::Avro, Some(published_schema_info))
+        }
+        KafkaSinkFormat::Json => (KafkaSinkEncoding::Json, None),
+        KafkaSinkFormat::Protobuf {
+            ccsr_config: None, ..
+        } => (KafkaSinkEncoding::Protobuf, None),
+        KafkaSinkFormat::Protobuf {
+            ccsr_config: Some(ccsr_config),
+            key_schema,
+            value_schema,
+        } => {
+            let ccsr = ccsr_config.build()?;
+            let (key_schema_id, value_schema_id) = publish_kafka_schemas(
+                &ccsr,
+                &topic,
+                key_schema.as_deref(),
+                Some(mz_ccsr::SchemaType::Protobuf),
+                &value_schema,
+                mz_ccsr::SchemaType::Protobuf,
+            )
+            .await
+            .context("error publishing kafka schemas for sink")?;
+            let published_schema_info = PublishedSchemaInfo {
+                key_schema_id,
+                value_schema_id,
+            };
+            (KafkaSinkEncoding::Protobuf, Some(published_schema_info))
         }
-        mz_dataflow_types::sinks::KafkaSinkFormat::Json => None,
     };
 
     let consistency = match builder.consistency_format {
-        Some(mz_dataflow_types::sinks::KafkaSinkFormat::Avro {
-            value_schema,
-            ccsr_config,
-            ..
-        }) => {
+        Some(consistency_format) => {
             let consistency_topic = maybe_append_nonce(
                 builder
                     .consistency_topic_prefix
                     .as_ref()
                     .expect("known to exist"),
             );
-            // create consistency topic/schema and retrieve schema id
+            // create consistency topic
             register_kafka_topic(
                 &client,
                 &consistency_topic,
@@ -297,25 +323,38 @@ async fn build_kafka(
             .await
             .context("error registering kafka consistency topic for sink")?;
 
-            let ccsr = ccsr_config.build()?;
-            let (_, consistency_schema_id) = publish_kafka_schemas(
-                &ccsr,
-                &consistency_topic,
-                None,
-                None,
-                &value_schema,
-                mz_ccsr::SchemaType::Avro,
-            )
-            .await
-            .context("error publishing kafka consistency schemas for sink")?;
+            // publish the consistency schema and retrieve its id, if any
+            let schema_id = match consistency_format {
+                KafkaSinkFormat::Avro {
+                    value_schema,
+                    ccsr_config,
+                    ..
+                } => {
+                    let ccsr = ccsr_config.build()?;
+                    let (_, consistency_schema_id) = publish_kafka_schemas(
+                        &ccsr,
+                        &consistency_topic,
+                        None,
+                        None,
+                        &value_schema,
+                        mz_ccsr::SchemaType::Avro,
+                    )
+                    .await
+                    .context("error publishing kafka consistency schemas for sink")?;
+                    Some(consistency_schema_id)
+                }
+                KafkaSinkFormat::Json => None,
+                KafkaSinkFormat::Protobuf { .. } => {
+                    unreachable!("consistency topics are never encoded as Protobuf")
+                }
+            };
 
             Some(KafkaSinkConsistencyConnector {
                 topic: consistency_topic,
-                schema_id: consistency_schema_id,
+                schema_id,
             })
         }
-        Some(other) => unreachable!("non-Avro consistency format for Kafka sink {:#?}", &other),
-        _ => None,
+        None => None,
     };
 
     Ok(SinkConnector::Kafka(KafkaSinkConnector {
@@ -325,6 +364,7 @@ async fn build_kafka(
         relation_key_indices: builder.relation_key_indices,
         key_desc_and_indices: builder.key_desc_and_indices,
         value_desc: builder.value_desc,
+        encoding,
         published_schema_info,
         consistency,
         exactly_once: builder.reuse_topic,
@@ -345,3 +385,82 @@ fn build_persist_sink(
         value_desc: builder.value_desc,
     }))
 }
+
+fn build_s3_sink(
+    builder: S3SinkConnectorBuilder,
+    id: GlobalId,
+) -> Result<SinkConnector, CoordError> {
+    // Like the topics of Kafka sinks, each incarnation of the sink writes to a
+    // new location, so that readers never observe a mix of two snapshots.
+    let mut prefix = builder.prefix;
+    if !prefix.is_empty() && !prefix.ends_with('/') {
+        prefix.push('/');
+    }
+    prefix.push_str(&format!("{}-{}/", id, builder.prefix_suffix_nonce));
+    Ok(SinkConnector::S3(S3SinkConnector {
+        bucket: builder.bucket,
+        prefix,
+        aws: builder.aws,
+        format: builder.format,
+        value_desc: builder.value_desc,
+    }))
+}
+
+async fn build_postgres_sink(
+    builder: PostgresSinkConnectorBuilder,
+) -> Result<SinkConnector, CoordError> {
+    let table = mz_postgres_util::sink_table_info(&builder.conn, &builder.table)
+        .await
+        .with_context(|| format!("error reading upstream table {} for sink", builder.table))?;
+
+    let column_types = builder
+        .value_desc
+        .iter_names()
+        .map(|name| {
+            table
+                .columns
+                .iter()
+                .find(|(upstream_name, _type)| upstream_name == name.as_str())
+                .map(|(_name, typ)| typ.clone())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "upstream table {} has no column named {}",
+                        builder.table,
+                        name
+                    )
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Upserts rely on `ON CONFLICT`, which requires a unique index on exactly
+    // the key columns.
+    let column_names: Vec<_> = builder.value_desc.iter_names().collect();
+    let mut key_columns: Vec<_> = builder
+        .key_indices
+        .iter()
+        .map(|i| column_names[*i].as_str())
+        .collect();
+    key_columns.sort_unstable();
+    let has_unique_key = table.unique_keys.iter().any(|unique_key| {
+        let mut unique_key: Vec<_> = unique_key.iter().map(|c| c.as_str()).collect();
+        unique_key.sort_unstable();
+        unique_key == key_columns
+    });
+    if !has_unique_key {
+        coord_bail!(
+            "upstream table {} has no unique index on the sink key ({})",
+            builder.table,
+            key_columns.join(", ")
+        );
+    }
+
+    Ok(SinkConnector::Postgres(PostgresSinkConnector {
+        conn: builder.conn,
+        namespace: table.namespace,
+        table: table.name,
+        column_types,
+        key_indices: builder.key_indices,
+        relation_key_indices: builder.relation_key_indices,
+        value_desc: builder.value_desc,
+    }))
+}
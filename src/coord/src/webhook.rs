@@ -0,0 +1,96 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Ingestion of HTTP requests into webhook sources.
+
+use std::collections::BTreeMap;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use mz_dataflow_types::sources::encoding::DataEncoding;
+use mz_repr::adt::jsonb::JsonbPacker;
+use mz_repr::{Datum, Row};
+
+/// Errors that can occur when appending a request to a webhook source.
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    /// The named webhook source does not exist.
+    #[error("unknown webhook source {0}")]
+    UnknownSource(String),
+    /// The request's signature is missing or does not match its body.
+    #[error("missing or invalid signature in header {0}")]
+    InvalidSignature(String),
+    /// The request's body cannot be decoded in the format of the source.
+    #[error("invalid request body: {0}")]
+    InvalidBody(String),
+    /// Unexpected internal state was encountered.
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+/// Verifies that the `header` of a request holds the hex-encoded HMAC-SHA256
+/// of its `body`, keyed by `secret`.
+///
+/// The signature may be prefixed with `sha256=`, as is conventional for
+/// webhook providers.
+pub(crate) fn validate(
+    secret: &[u8],
+    header: &str,
+    headers: &BTreeMap<String, String>,
+    body: &[u8],
+) -> Result<(), WebhookError> {
+    let invalid = || WebhookError::InvalidSignature(header.into());
+    let signature = headers.get(header).ok_or_else(invalid)?.trim();
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let signature = hex::decode(signature).map_err(|_| invalid())?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC can take a key of any size");
+    mac.update(body);
+    mac.verify_slice(&signature).map_err(|_| invalid())
+}
+
+/// Decodes a request into a row of a webhook source whose values have the
+/// specified `encoding`.
+///
+/// If `include_headers` is set, the request's headers are appended to the
+/// row as a map.
+pub(crate) fn pack_row(
+    encoding: &DataEncoding,
+    include_headers: bool,
+    headers: &BTreeMap<String, String>,
+    body: &[u8],
+) -> Result<Row, WebhookError> {
+    let mut row = Row::default();
+    let mut packer = row.packer();
+    match encoding {
+        DataEncoding::Bytes => packer.push(Datum::Bytes(body)),
+        DataEncoding::Text => {
+            let text =
+                std::str::from_utf8(body).map_err(|e| WebhookError::InvalidBody(e.to_string()))?;
+            packer.push(Datum::String(text));
+        }
+        DataEncoding::Json(_) => JsonbPacker::new(&mut packer)
+            .pack_slice(body)
+            .map_err(|e| WebhookError::InvalidBody(format!("{:#}", e)))?,
+        _ => {
+            return Err(WebhookError::Internal(format!(
+                "webhook source with unexpected encoding {}",
+                encoding.op_name()
+            )))
+        }
+    }
+    if include_headers {
+        packer.push_dict(
+            headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), Datum::String(value))),
+        );
+    }
+    Ok(row)
+}
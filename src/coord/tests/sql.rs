@@ -92,6 +92,8 @@ async fn datadriven() {
                                         create_sql: "TODO".to_string(),
                                         desc: RelationDesc::empty(),
                                         defaults: vec![Expr::null(); 0],
+                                        checks: vec![],
+                                        foreign_keys: vec![],
                                         conn_id: None,
                                         depends_on: vec![],
                                     }),
@@ -22,6 +22,10 @@ fn main() {
         .extern_path(".mz_repr.proto", "::mz_repr::proto")
         .extern_path(".mz_repr.relation_and_scalar", "::mz_repr")
         .extern_path(".mz_repr.row", "::mz_repr")
+        .type_attribute(
+            ".mz_dataflow_types.mysql_source",
+            "#[derive(Eq, serde::Serialize, serde::Deserialize)]",
+        )
         .type_attribute(
             ".mz_dataflow_types.postgres_source",
             "#[derive(Eq, serde::Serialize, serde::Deserialize)]",
@@ -32,6 +36,7 @@ fn main() {
                 "dataflow-types/src/client/controller/storage.proto",
                 "dataflow-types/src/errors.proto",
                 "dataflow-types/src/logging.proto",
+                "dataflow-types/src/mysql_source.proto",
                 "dataflow-types/src/postgres_source.proto",
                 "dataflow-types/src/types/aws.proto",
                 "dataflow-types/src/types/sinks.proto",
@@ -321,6 +321,11 @@ pub struct CreateSourceCommand<T> {
 pub enum StorageCommand<T = mz_repr::Timestamp> {
     /// Create the enumerated sources, each associated with its identifier.
     CreateSources(Vec<CreateSourceCommand<T>>),
+    /// Restart the ingestion of the enumerated, already created sources with new descriptions.
+    ///
+    /// The sources keep writing to their existing collections. Each restarted ingestion
+    /// retracts the contents written by its predecessor along with its first updates.
+    AlterSources(Vec<CreateSourceCommand<T>>),
     /// Enable compaction in storage-managed collections.
     ///
     /// Each entry in the vector names a collection and provides a frontier after which
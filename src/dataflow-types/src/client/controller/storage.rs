@@ -83,6 +83,16 @@ pub trait StorageController: Debug + Send {
         mut bindings: Vec<(GlobalId, (SourceDesc, Antichain<Self::Timestamp>))>,
     ) -> Result<(), StorageError>;
 
+    /// Replaces the descriptions of existing sources and restarts their ingestion.
+    ///
+    /// The sources keep their collections, read capabilities and write frontiers. Their
+    /// contents are replaced by the output of the new descriptions once the restarted
+    /// ingestion produces its first updates.
+    async fn alter_sources(
+        &mut self,
+        descriptions: Vec<(GlobalId, SourceDesc)>,
+    ) -> Result<(), StorageError>;
+
     /// Drops the read capability for the sources and allows their resources to be reclaimed.
     async fn drop_sources(&mut self, identifiers: Vec<GlobalId>) -> Result<(), StorageError>;
 
@@ -359,7 +369,9 @@ where
                 .persist_handles
                 .insert(id, PersistHandles { read, write });
 
-            let timestamp_shard_id = TypedCollection::new("timestamp-shard-id")
+            // The remap shards recorded under the previous name were keyed by `()` rather than by
+            // the position of each binding, and can no longer be opened.
+            let timestamp_shard_id = TypedCollection::new("remap-shard-id")
                 .insert_without_overwrite(&mut self.state.stash, &id, ShardId::new())
                 .await?;
 
@@ -389,6 +401,37 @@ where
         Ok(())
     }
 
+    async fn alter_sources(
+        &mut self,
+        descriptions: Vec<(GlobalId, SourceDesc)>,
+    ) -> Result<(), StorageError> {
+        self.validate_ids(descriptions.iter().map(|(id, _)| *id))?;
+
+        let mut dataflow_commands = vec![];
+        for (id, desc) in descriptions {
+            let collection = self.collection_mut(id)?;
+            collection.description.0 = desc.clone();
+            // The ingestion is restarted from the `since` it was created with, so that it
+            // observes all timestamp bindings minted by its predecessor.
+            let since = collection.description.1.clone();
+
+            dataflow_commands.push(CreateSourceCommand {
+                id,
+                desc,
+                since,
+                storage_metadata: self.collection_metadata(id)?,
+            });
+        }
+
+        self.state
+            .client
+            .send(StorageCommand::AlterSources(dataflow_commands))
+            .await
+            .expect("Storage command failed; unrecoverable");
+
+        Ok(())
+    }
+
     async fn drop_sources(&mut self, identifiers: Vec<GlobalId>) -> Result<(), StorageError> {
         self.validate_ids(identifiers.iter().cloned())?;
         let policies = identifiers
@@ -15,6 +15,7 @@
 
 pub mod client;
 pub mod logging;
+pub mod mysql_source;
 pub mod plan;
 pub mod postgres_source;
 pub mod reconciliation;
@@ -0,0 +1,233 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Generated protobuf code and companion impls.
+
+include!(concat!(
+    env!("OUT_DIR"),
+    "/mz_dataflow_types.mysql_source.rs"
+));
+
+use anyhow::bail;
+use proptest::prelude::{any, Arbitrary};
+use proptest::strategy::{BoxedStrategy, Strategy};
+
+use mz_mysql_util::{MySqlColumnInfo, MySqlTableInfo};
+
+impl From<MySqlColumnInfo> for MySqlColumn {
+    fn from(c: MySqlColumnInfo) -> MySqlColumn {
+        MySqlColumn {
+            name: c.name,
+            data_type: c.data_type,
+            column_type: c.column_type,
+            nullable: c.nullable,
+            primary_key: c.primary_key,
+        }
+    }
+}
+
+impl From<MySqlTableInfo> for MySqlTable {
+    fn from(t: MySqlTableInfo) -> MySqlTable {
+        MySqlTable {
+            database: t.database,
+            name: t.name,
+            columns: t.columns.into_iter().map(|c| c.into()).collect(),
+        }
+    }
+}
+
+impl MySqlColumn {
+    /// Reports whether the column has an unsigned numeric type.
+    pub fn is_unsigned(&self) -> bool {
+        self.column_type.ends_with(" unsigned") || self.column_type.contains(" unsigned ")
+    }
+
+    /// Returns the name of the type that views over a MySQL source cast the
+    /// text values of this column to.
+    ///
+    /// Unsigned integer types map to the narrowest signed type that holds
+    /// all of their values.
+    pub fn cast_type(&self) -> Result<String, anyhow::Error> {
+        let unsigned = self.is_unsigned();
+        let ty = match self.data_type.as_str() {
+            "tinyint" => "int2",
+            "smallint" if unsigned => "int4",
+            "smallint" => "int2",
+            "mediumint" | "year" => "int4",
+            "int" if unsigned => "int8",
+            "int" => "int4",
+            "bigint" if unsigned => "numeric(20,0)",
+            "bigint" | "bit" => "int8",
+            "decimal" => {
+                // The column type looks like `decimal(10,2)`. Precisions
+                // beyond what our numeric type can constrain are dropped.
+                match self.type_modifiers().as_slice() {
+                    [precision, scale] if *precision <= 39 => {
+                        return Ok(format!("pg_catalog.numeric({},{})", precision, scale))
+                    }
+                    _ => "numeric",
+                }
+            }
+            "float" => "float4",
+            "double" => "float8",
+            "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "enum"
+            | "set" => "text",
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => "bytea",
+            "date" => "date",
+            "datetime" => "timestamp",
+            "timestamp" => "timestamptz",
+            "time" => "interval",
+            "json" => "jsonb",
+            other => bail!("column {} has unsupported type {}", self.name, other),
+        };
+        Ok(format!("pg_catalog.{}", ty))
+    }
+
+    /// Returns the permitted values of an `enum` or `set` column, in the order
+    /// of their indices.
+    pub fn enum_values(&self) -> Vec<String> {
+        // The column type looks like `enum('a','b''c')`.
+        let mut values = vec![];
+        let mut chars = self
+            .column_type
+            .trim_start_matches(|c| c != '(')
+            .chars()
+            .peekable();
+        while let Some(c) = chars.next() {
+            if c != '\'' {
+                continue;
+            }
+            let mut value = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\'' if chars.peek() == Some(&'\'') => {
+                        chars.next();
+                        value.push('\'');
+                    }
+                    '\'' => break,
+                    c => value.push(c),
+                }
+            }
+            values.push(value);
+        }
+        values
+    }
+
+    /// Returns the numeric modifiers of the column's type, e.g. `[10, 2]` for
+    /// `decimal(10,2)`.
+    fn type_modifiers(&self) -> Vec<u32> {
+        match self
+            .column_type
+            .split_once('(')
+            .and_then(|(_, rest)| rest.split_once(')'))
+        {
+            Some((modifiers, _)) => modifiers
+                .split(',')
+                .filter_map(|m| m.trim().parse().ok())
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+impl Arbitrary for MySqlColumn {
+    type Strategy = BoxedStrategy<Self>;
+    type Parameters = ();
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<String>(),
+            any::<String>(),
+            any::<String>(),
+            any::<bool>(),
+            any::<bool>(),
+        )
+            .prop_map(
+                |(name, data_type, column_type, nullable, primary_key)| MySqlColumn {
+                    name,
+                    data_type,
+                    column_type,
+                    nullable,
+                    primary_key,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for MySqlTable {
+    type Strategy = BoxedStrategy<Self>;
+    type Parameters = ();
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), any::<String>(), any::<Vec<MySqlColumn>>())
+            .prop_map(|(database, name, columns)| MySqlTable {
+                database,
+                name,
+                columns,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for MySqlSourceDetails {
+    type Strategy = BoxedStrategy<Self>;
+    type Parameters = ();
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<Vec<MySqlTable>>()
+            .prop_map(|tables| MySqlSourceDetails { tables })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(data_type: &str, column_type: &str) -> MySqlColumn {
+        MySqlColumn {
+            name: "c".into(),
+            data_type: data_type.into(),
+            column_type: column_type.into(),
+            nullable: true,
+            primary_key: false,
+        }
+    }
+
+    #[test]
+    fn test_cast_type() {
+        for (data_type, column_type, expected) in [
+            ("tinyint", "tinyint unsigned", "pg_catalog.int2"),
+            ("smallint", "smallint", "pg_catalog.int2"),
+            ("smallint", "smallint unsigned", "pg_catalog.int4"),
+            ("int", "int unsigned zerofill", "pg_catalog.int8"),
+            ("bigint", "bigint unsigned", "pg_catalog.numeric(20,0)"),
+            ("decimal", "decimal(10,2)", "pg_catalog.numeric(10,2)"),
+            ("decimal", "decimal(65,30)", "pg_catalog.numeric"),
+            ("enum", "enum('a','b')", "pg_catalog.text"),
+            ("timestamp", "timestamp(3)", "pg_catalog.timestamptz"),
+        ] {
+            assert_eq!(
+                column(data_type, column_type).cast_type().unwrap(),
+                expected
+            );
+        }
+        assert!(column("geometry", "geometry").cast_type().is_err());
+    }
+
+    #[test]
+    fn test_enum_values() {
+        assert_eq!(
+            column("enum", "enum('a','b''c','(d)')").enum_values(),
+            vec!["a", "b'c", "(d)"]
+        );
+        assert_eq!(column("set", "set('x')").enum_values(), vec!["x"]);
+    }
+}
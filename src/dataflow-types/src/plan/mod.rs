@@ -215,6 +215,22 @@ pub enum Plan<T = mz_repr::Timestamp> {
         /// that reference `Id::Local(id)`.
         body: Box<Plan<T>>,
     },
+    /// Binds the fixed point of `value` to `id`, and then results in `body`
+    /// with that binding.
+    ///
+    /// Unlike `Let`, `value` may itself contain `Get` stages that reference
+    /// `Id::Local(id)`. The fixed point is computed by iteration, starting from
+    /// an empty collection, and is only available as an unarranged collection.
+    LetRec {
+        /// The local identifier to be used, available to `value` and `body` as
+        /// `Id::Local(id)`.
+        id: LocalId,
+        /// The collection that should be bound to `id`.
+        value: Box<Plan<T>>,
+        /// The collection that results, which is allowed to contain `Get` stages
+        /// that reference `Id::Local(id)`.
+        body: Box<Plan<T>>,
+    },
     /// Map, Filter, and Project operators.
     ///
     /// This stage contains work that we would ideally like to fuse to other plan
@@ -377,6 +393,14 @@ impl Arbitrary for Plan {
                         body: body.into(),
                     })
                     .boxed(),
+                //Plan::LetRec
+                (any::<LocalId>(), inner.clone(), inner.clone())
+                    .prop_map(|(id, value, body)| Plan::LetRec {
+                        id,
+                        value: value.into(),
+                        body: body.into(),
+                    })
+                    .boxed(),
                 //Plan::Mfp
                 (
                     inner.clone(),
@@ -530,6 +554,14 @@ impl RustType<ProtoPlan> for Plan {
                     body: Some(body.into_proto()),
                 }
                 .into()),
+                Plan::LetRec { id, value, body } => LetRec(
+                    ProtoPlanLet {
+                        id: Some(id.into_proto()),
+                        value: Some(value.into_proto()),
+                        body: Some(body.into_proto()),
+                    }
+                    .into(),
+                ),
                 Plan::Mfp {
                     input,
                     mfp,
@@ -661,6 +693,11 @@ impl RustType<ProtoPlan> for Plan {
                 value: proto.value.into_rust_if_some("ProtoPlanLet::value")?,
                 body: proto.body.into_rust_if_some("ProtoPlanLet::body")?,
             },
+            LetRec(proto) => Plan::LetRec {
+                id: proto.id.into_rust_if_some("ProtoPlanLet::id")?,
+                value: proto.value.into_rust_if_some("ProtoPlanLet::value")?,
+                body: proto.body.into_rust_if_some("ProtoPlanLet::body")?,
+            },
             Mfp(proto) => Plan::Mfp {
                 input: proto.input.into_rust_if_some("ProtoPlanMfp::input")?,
                 input_key_val: input_kv_try_into(proto.input_key_val)?,
@@ -1015,6 +1052,28 @@ impl<T: timely::progress::Timestamp> Plan<T> {
                     b_keys,
                 )
             }
+            MirRelationExpr::LetRec { id, value, body } => {
+                // The binding is only available as an unarranged collection,
+                // both to its own value and to the body.
+                let arity = value.arity();
+                let pre_existing =
+                    arrangements.insert(Id::Local(*id), AvailableCollections::new_raw());
+                assert!(pre_existing.is_none());
+                let (mut value, v_keys) = Plan::from_mir(value, arrangements, debug_info)?;
+                if !v_keys.raw {
+                    value = value.arrange_by(AvailableCollections::new_raw(), &v_keys, arity);
+                }
+                let (body, b_keys) = Plan::from_mir(body, arrangements, debug_info)?;
+                arrangements.remove(&Id::Local(*id));
+                (
+                    Plan::LetRec {
+                        id: id.clone(),
+                        value: Box::new(value),
+                        body: Box::new(body),
+                    },
+                    b_keys,
+                )
+            }
             MirRelationExpr::FlatMap { input, func, exprs } => {
                 let (input, keys) = Plan::from_mir(input, arrangements, debug_info)?;
                 // This stage can absorb arbitrary MFP instances.
@@ -1184,6 +1243,7 @@ This is not expected to cause incorrect results, but could indicate a performanc
                 order_key,
                 limit,
                 offset,
+                with_ties,
                 monotonic,
             } => {
                 let arity = input.arity();
@@ -1194,6 +1254,7 @@ This is not expected to cause incorrect results, but could indicate a performanc
                     order_key.clone(),
                     *offset,
                     *limit,
+                    *with_ties,
                     arity,
                     *monotonic,
                 );
@@ -1412,6 +1473,86 @@ This is not expected to cause incorrect results, but could indicate a performanc
         Ok((plan, keys))
     }
 
+    /// Indicates if this plan contains a recursive binding, i.e. a
+    /// [`Plan::LetRec`].
+    pub fn is_recursive(&self) -> bool {
+        match self {
+            Plan::Constant { .. } | Plan::Get { .. } => false,
+            Plan::LetRec { .. } => true,
+            Plan::Let { value, body, .. } => value.is_recursive() || body.is_recursive(),
+            Plan::Join { inputs, .. } | Plan::Union { inputs } => {
+                inputs.iter().any(|input| input.is_recursive())
+            }
+            Plan::Mfp { input, .. }
+            | Plan::FlatMap { input, .. }
+            | Plan::ArrangeBy { input, .. }
+            | Plan::Reduce { input, .. }
+            | Plan::TopK { input, .. }
+            | Plan::Negate { input }
+            | Plan::Threshold { input, .. } => input.is_recursive(),
+        }
+    }
+
+    /// Lifts the `Let` and `LetRec` bindings of the plan to its root, so that
+    /// the plan becomes a sequence of bindings followed by a body without them.
+    ///
+    /// Bindings within the value of a `LetRec` may refer to it, and so are
+    /// lifted to the root of that value instead. Identifiers of local bindings
+    /// are unique within a plan, so no binding is captured by another.
+    pub fn lift_bindings(mut self) -> Self {
+        let mut bindings = Vec::new();
+        self.lift_bindings_into(&mut bindings);
+        bindings
+            .into_iter()
+            .rev()
+            .fold(self, |body, (id, value, recursive)| {
+                let value = Box::new(value);
+                let body = Box::new(body);
+                if recursive {
+                    Plan::LetRec { id, value, body }
+                } else {
+                    Plan::Let { id, value, body }
+                }
+            })
+    }
+
+    /// Removes the bindings of the plan, pushing them onto `bindings` in an
+    /// order in which each binding precedes its uses.
+    fn lift_bindings_into(&mut self, bindings: &mut Vec<(LocalId, Plan<T>, bool)>) {
+        let placeholder = || Plan::Constant {
+            rows: Ok(Vec::new()),
+        };
+        match self {
+            Plan::Let { id, value, body } => {
+                value.lift_bindings_into(bindings);
+                bindings.push((*id, std::mem::replace(&mut **value, placeholder()), false));
+                let body = std::mem::replace(&mut **body, placeholder());
+                *self = body;
+                self.lift_bindings_into(bindings);
+            }
+            Plan::LetRec { id, value, body } => {
+                let value = std::mem::replace(&mut **value, placeholder()).lift_bindings();
+                bindings.push((*id, value, true));
+                let body = std::mem::replace(&mut **body, placeholder());
+                *self = body;
+                self.lift_bindings_into(bindings);
+            }
+            Plan::Constant { .. } | Plan::Get { .. } => (),
+            Plan::Join { inputs, .. } | Plan::Union { inputs } => {
+                for input in inputs {
+                    input.lift_bindings_into(bindings);
+                }
+            }
+            Plan::Mfp { input, .. }
+            | Plan::FlatMap { input, .. }
+            | Plan::ArrangeBy { input, .. }
+            | Plan::Reduce { input, .. }
+            | Plan::TopK { input, .. }
+            | Plan::Negate { input }
+            | Plan::Threshold { input, .. } => input.lift_bindings_into(bindings),
+        }
+    }
+
     /// Convert the dataflow description into one that uses render plans.
     pub fn finalize_dataflow(
         desc: DataflowDescription<OptimizedMirRelationExpr>,
@@ -1455,6 +1596,13 @@ This is not expected to cause incorrect results, but could indicate a performanc
                 },
             )?;
             arrangements.insert(Id::Global(build.id), keys);
+            // Recursive bindings are rendered in an iterative scope, which
+            // expects to find them at the root of the plan.
+            let plan = if plan.is_recursive() {
+                plan.lift_bindings()
+            } else {
+                plan
+            };
             objects_to_build.push(crate::BuildDesc { id: build.id, plan });
         }
 
@@ -1521,6 +1669,19 @@ This is not expected to cause incorrect results, but could indicate a performanc
                         })
                         .collect()
                 }
+                Plan::LetRec { value, body, id } => {
+                    let value_parts = value.partition_among(parts);
+                    let body_parts = body.partition_among(parts);
+                    value_parts
+                        .into_iter()
+                        .zip(body_parts)
+                        .map(|(value, body)| Plan::LetRec {
+                            value: Box::new(value),
+                            body: Box::new(body),
+                            id,
+                        })
+                        .collect()
+                }
                 Plan::Mfp {
                     input,
                     input_key_val,
@@ -1657,7 +1818,7 @@ impl<T> CollectionPlan for Plan<T> {
                 }
                 Id::Local(_) => (),
             },
-            Plan::Let { id: _, value, body } => {
+            Plan::Let { id: _, value, body } | Plan::LetRec { id: _, value, body } => {
                 value.depends_on_into(out);
                 body.depends_on_into(out);
             }
@@ -15,7 +15,8 @@
 //! The TopK variants can be distinguished as follows:
 //! * A [MonotonicTop1Plan] maintains a single row per key and is suitable for monotonic inputs.
 //! * A [MonotonicTopKPlan] maintains up to K rows per key and is suitable for monotonic inputs.
-//! * A [BasicTopKPlan] maintains up to K rows per key and can handle retractions.
+//! * A [BasicTopKPlan] maintains up to K rows per key, optionally along with the rows that tie
+//!   with the K-th row, and can handle retractions.
 
 #![allow(missing_docs)]
 
@@ -46,6 +47,7 @@ impl TopKPlan {
     /// * `order_key` - The columns specifying an ordering withing each group.
     /// * `offset` - The number of rows to skip at the top. Provide 0 to reveal all rows.
     /// * `limit` - An optional limit of how many rows should be revealed.
+    /// * `with_ties` - `true` if rows that tie with the last revealed row should be revealed too.
     /// * `arity` - The number of columns in the input and output.
     /// * `monotonic` - `true` if the input is monotonic.
     pub(crate) fn create_from(
@@ -53,15 +55,16 @@ impl TopKPlan {
         order_key: Vec<ColumnOrder>,
         offset: usize,
         limit: Option<usize>,
+        with_ties: bool,
         arity: usize,
         monotonic: bool,
     ) -> Self {
-        if monotonic && offset == 0 && limit == Some(1) {
+        if monotonic && offset == 0 && limit == Some(1) && !with_ties {
             TopKPlan::MonotonicTop1(MonotonicTop1Plan {
                 group_key,
                 order_key,
             })
-        } else if monotonic && offset == 0 {
+        } else if monotonic && offset == 0 && !with_ties {
             // For monotonic inputs, we are able to retract inputs that can no longer be produced
             // as outputs. Any inputs beyond `offset + limit` will never again be produced as
             // outputs, and can be removed. The simplest form of this is when `offset == 0` and
@@ -83,6 +86,7 @@ impl TopKPlan {
                 order_key,
                 offset,
                 limit,
+                with_ties,
                 arity,
             })
         }
@@ -202,6 +206,9 @@ pub struct BasicTopKPlan {
     ///
     /// This can be set to zero to have no effect.
     pub offset: usize,
+    /// Whether to also produce the records that tie with the last record
+    /// produced from each group, even beyond `limit`.
+    pub with_ties: bool,
     /// The number of columns in the input and output.
     pub arity: usize,
 }
@@ -213,6 +220,7 @@ impl RustType<ProtoBasicTopKPlan> for BasicTopKPlan {
             order_key: self.order_key.into_proto(),
             limit: self.limit.into_proto(),
             offset: self.offset.into_proto(),
+            with_ties: self.with_ties,
             arity: self.arity.into_proto(),
         }
     }
@@ -223,6 +231,7 @@ impl RustType<ProtoBasicTopKPlan> for BasicTopKPlan {
             order_key: proto.order_key.into_rust()?,
             limit: proto.limit.into_rust()?,
             offset: proto.offset.into_rust()?,
+            with_ties: proto.with_ties,
             arity: proto.arity.into_rust()?,
         })
     }
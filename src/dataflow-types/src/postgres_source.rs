@@ -18,6 +18,7 @@ use proptest::prelude::{any, Arbitrary};
 use proptest::strategy::{BoxedStrategy, Strategy};
 
 use mz_postgres_util::{PgColumn, TableInfo};
+use mz_sql_server_util::{SqlServerColumnInfo, SqlServerTableInfo};
 
 impl From<PgColumn> for PostgresColumn {
     fn from(c: PgColumn) -> PostgresColumn {
@@ -65,6 +66,57 @@ impl From<PostgresTable> for TableInfo {
     }
 }
 
+/// SQL Server tables are described by the Postgres type that each column's
+/// type maps to, so that their sources are laid out like Postgres sources.
+impl From<SqlServerColumnInfo> for PostgresColumn {
+    fn from(c: SqlServerColumnInfo) -> PostgresColumn {
+        let ty = mz_pgrepr::Type::from(&c.scalar_type());
+        PostgresColumn {
+            name: c.name,
+            type_oid: ty.oid().try_into().unwrap(),
+            type_mod: ty.typmod(),
+            nullable: c.nullable,
+            primary_key: c.primary_key,
+        }
+    }
+}
+
+impl From<SqlServerTableInfo> for PostgresTable {
+    fn from(t: SqlServerTableInfo) -> PostgresTable {
+        PostgresTable {
+            name: t.name,
+            namespace: t.schema,
+            relation_id: t.object_id,
+            columns: t.columns.into_iter().map(|c| c.into()).collect(),
+        }
+    }
+}
+
+impl PostgresTable {
+    /// Reports whether `upstream` describes this table after only additive
+    /// changes, i.e., whether it is the same table with zero or more columns
+    /// appended.
+    ///
+    /// Replication messages carry neither the nullability of columns nor
+    /// whether they are part of the primary key, so changes to either are
+    /// ignored.
+    pub fn is_extended_by(&self, upstream: &PostgresTable) -> bool {
+        self.relation_id == upstream.relation_id
+            && self.name == upstream.name
+            && self.namespace == upstream.namespace
+            && self.columns.len() <= upstream.columns.len()
+            && self
+                .columns
+                .iter()
+                .zip(&upstream.columns)
+                .all(|(column, upstream)| {
+                    column.name == upstream.name
+                        && column.type_oid == upstream.type_oid
+                        && column.type_mod == upstream.type_mod
+                })
+    }
+}
+
 impl Arbitrary for PostgresColumn {
     type Strategy = BoxedStrategy<Self>;
     type Parameters = ();
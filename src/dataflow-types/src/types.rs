@@ -1119,6 +1119,7 @@ pub mod sources {
     use mz_repr::{ColumnType, GlobalId, RelationDesc, RelationType, Row, ScalarType};
 
     use crate::aws::AwsConfig;
+    use crate::mysql_source::MySqlSourceDetails;
     use crate::postgres_source::PostgresSourceDetails;
     use crate::DataflowError;
 
@@ -1134,6 +1135,7 @@ pub mod sources {
         use proptest_derive::Arbitrary;
         use serde::{Deserialize, Serialize};
 
+        use mz_expr::MirScalarExpr;
         use mz_interchange::{avro, protobuf};
         use mz_repr::adt::regex::any_regex;
         use mz_repr::proto::{IntoRustIfSome, ProtoType, RustType, TryFromProtoError};
@@ -1229,7 +1231,10 @@ pub mod sources {
             Protobuf(ProtobufEncoding),
             Csv(CsvEncoding),
             Regex(RegexEncoding),
+            Json(JsonEncoding),
+            Parquet(ParquetEncoding),
             Postgres,
+            MySql,
             Bytes,
             Text,
             RowCodec(RelationDesc),
@@ -1244,7 +1249,10 @@ pub mod sources {
                         DataEncoding::Protobuf(e) => Kind::Protobuf(e.into_proto()),
                         DataEncoding::Csv(e) => Kind::Csv(e.into_proto()),
                         DataEncoding::Regex(e) => Kind::Regex(e.into_proto()),
+                        DataEncoding::Json(e) => Kind::Json(e.into_proto()),
+                        DataEncoding::Parquet(e) => Kind::Parquet(e.into_proto()),
                         DataEncoding::Postgres => Kind::Postgres(()),
+                        DataEncoding::MySql => Kind::Mysql(()),
                         DataEncoding::Bytes => Kind::Bytes(()),
                         DataEncoding::Text => Kind::Text(()),
                         DataEncoding::RowCodec(e) => Kind::RowCodec(e.into_proto()),
@@ -1262,7 +1270,10 @@ pub mod sources {
                     Kind::Protobuf(e) => DataEncoding::Protobuf(e.into_rust()?),
                     Kind::Csv(e) => DataEncoding::Csv(e.into_rust()?),
                     Kind::Regex(e) => DataEncoding::Regex(e.into_rust()?),
+                    Kind::Json(e) => DataEncoding::Json(e.into_rust()?),
+                    Kind::Parquet(e) => DataEncoding::Parquet(e.into_rust()?),
                     Kind::Postgres(()) => DataEncoding::Postgres,
+                    Kind::Mysql(()) => DataEncoding::MySql,
                     Kind::Bytes(()) => DataEncoding::Bytes,
                     Kind::Text(()) => DataEncoding::Text,
                     Kind::RowCodec(e) => DataEncoding::RowCodec(e.into_rust()?),
@@ -1340,6 +1351,21 @@ pub mod sources {
                                 desc.with_column(name, ScalarType::String.nullable(false))
                             }),
                     },
+                    DataEncoding::Json(JsonEncoding { columns }) => {
+                        if columns.is_empty() {
+                            RelationDesc::empty()
+                                .with_column("data", ScalarType::Jsonb.nullable(false))
+                        } else {
+                            columns.iter().fold(RelationDesc::empty(), |desc, column| {
+                                desc.with_column(column.name.as_str(), column.typ.clone())
+                            })
+                        }
+                    }
+                    DataEncoding::Parquet(ParquetEncoding { columns }) => {
+                        columns.iter().fold(RelationDesc::empty(), |desc, column| {
+                            desc.with_column(column.name.as_str(), column.typ.clone())
+                        })
+                    }
                     DataEncoding::Text => RelationDesc::empty()
                         .with_column("text", ScalarType::String.nullable(false)),
                     DataEncoding::Postgres => RelationDesc::empty()
@@ -1352,6 +1378,16 @@ pub mod sources {
                             }
                             .nullable(false),
                         ),
+                    DataEncoding::MySql => RelationDesc::empty()
+                        .with_column("table_id", ScalarType::Int32.nullable(false))
+                        .with_column(
+                            "row_data",
+                            ScalarType::List {
+                                element_type: Box::new(ScalarType::String),
+                                custom_id: None,
+                            }
+                            .nullable(false),
+                        ),
                     DataEncoding::RowCodec(desc) => desc.clone(),
                 })
             }
@@ -1363,8 +1399,11 @@ pub mod sources {
                     DataEncoding::Protobuf(_) => "Protobuf",
                     DataEncoding::Regex { .. } => "Regex",
                     DataEncoding::Csv(_) => "Csv",
+                    DataEncoding::Json(_) => "Json",
+                    DataEncoding::Parquet(_) => "Parquet",
                     DataEncoding::Text => "Text",
                     DataEncoding::Postgres => "Postgres",
+                    DataEncoding::MySql => "MySql",
                     DataEncoding::RowCodec(_) => "RowCodec",
                 }
             }
@@ -1422,6 +1461,112 @@ pub mod sources {
             }
         }
 
+        /// Encoding in JSON format.
+        #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+        pub struct JsonEncoding {
+            /// The top-level fields to project out of each JSON object.
+            ///
+            /// If empty, each document is decoded into a single `jsonb` column.
+            pub columns: Vec<JsonColumn>,
+        }
+
+        impl RustType<ProtoJsonEncoding> for JsonEncoding {
+            fn into_proto(self: &Self) -> ProtoJsonEncoding {
+                ProtoJsonEncoding {
+                    columns: self.columns.into_proto(),
+                }
+            }
+
+            fn from_proto(proto: ProtoJsonEncoding) -> Result<Self, TryFromProtoError> {
+                Ok(JsonEncoding {
+                    columns: proto.columns.into_rust()?,
+                })
+            }
+        }
+
+        /// A typed column projected out of a JSON object.
+        #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+        pub struct JsonColumn {
+            /// The name of the field, which is also the name of the column.
+            pub name: String,
+            /// The type of the column.
+            pub typ: ColumnType,
+            /// An expression that converts the field to `typ`.
+            ///
+            /// The expression is evaluated against two columns: the field as
+            /// `jsonb`, and the field as `text`, where JSON strings are
+            /// unquoted. Both are `NULL` if the field is absent or `null`.
+            pub cast: MirScalarExpr,
+        }
+
+        impl RustType<ProtoJsonColumn> for JsonColumn {
+            fn into_proto(self: &Self) -> ProtoJsonColumn {
+                ProtoJsonColumn {
+                    name: self.name.clone(),
+                    typ: Some(self.typ.into_proto()),
+                    cast: Some(self.cast.into_proto()),
+                }
+            }
+
+            fn from_proto(proto: ProtoJsonColumn) -> Result<Self, TryFromProtoError> {
+                Ok(JsonColumn {
+                    name: proto.name,
+                    typ: proto.typ.into_rust_if_some("ProtoJsonColumn::typ")?,
+                    cast: proto.cast.into_rust_if_some("ProtoJsonColumn::cast")?,
+                })
+            }
+        }
+
+        /// Encoding in Parquet format.
+        #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+        pub struct ParquetEncoding {
+            /// The columns to decode from each Parquet file.
+            pub columns: Vec<ParquetColumn>,
+        }
+
+        impl RustType<ProtoParquetEncoding> for ParquetEncoding {
+            fn into_proto(self: &Self) -> ProtoParquetEncoding {
+                ProtoParquetEncoding {
+                    columns: self.columns.into_proto(),
+                }
+            }
+
+            fn from_proto(proto: ProtoParquetEncoding) -> Result<Self, TryFromProtoError> {
+                Ok(ParquetEncoding {
+                    columns: proto.columns.into_rust()?,
+                })
+            }
+        }
+
+        /// A typed column decoded from a Parquet file.
+        #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+        pub struct ParquetColumn {
+            /// The name of the Parquet column, which is also the name of the
+            /// column.
+            pub name: String,
+            /// The type of the column.
+            ///
+            /// Values of files whose column has a different type are converted
+            /// to this type, if possible.
+            pub typ: ColumnType,
+        }
+
+        impl RustType<ProtoParquetColumn> for ParquetColumn {
+            fn into_proto(self: &Self) -> ProtoParquetColumn {
+                ProtoParquetColumn {
+                    name: self.name.clone(),
+                    typ: Some(self.typ.into_proto()),
+                }
+            }
+
+            fn from_proto(proto: ProtoParquetColumn) -> Result<Self, TryFromProtoError> {
+                Ok(ParquetColumn {
+                    name: proto.name,
+                    typ: proto.typ.into_rust_if_some("ProtoParquetColumn::typ")?,
+                })
+            }
+        }
+
         /// Arguments necessary to define how to decode from CSV format
         #[derive(Arbitrary, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
         pub struct CsvEncoding {
@@ -2555,8 +2700,11 @@ pub mod sources {
             UnplannedSourceEnvelope::Debezium(_) => true,
             _ => false,
         };
+        // CDCv2 messages carry their own timestamps, and the decoded
+        // collection has no offsets to report.
+        let is_cdcv2 = matches!(envelope, UnplannedSourceEnvelope::CdcV2);
 
-        !is_avro && !is_stateless_dbz
+        !is_avro && !is_stateless_dbz && !is_cdcv2
     }
 
     #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -2590,6 +2738,9 @@ pub mod sources {
     #[derive(Arbitrary, Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
     pub enum Compression {
         Gzip,
+        Zstd,
+        /// The Snappy framing format, as opposed to raw Snappy blocks
+        Snappy,
         None,
     }
 
@@ -2599,6 +2750,8 @@ pub mod sources {
             ProtoCompression {
                 kind: Some(match self {
                     Compression::Gzip => Kind::Gzip(()),
+                    Compression::Zstd => Kind::Zstd(()),
+                    Compression::Snappy => Kind::Snappy(()),
                     Compression::None => Kind::None(()),
                 }),
             }
@@ -2608,6 +2761,8 @@ pub mod sources {
             use proto_compression::Kind;
             Ok(match proto.kind {
                 Some(Kind::Gzip(())) => Compression::Gzip,
+                Some(Kind::Zstd(())) => Compression::Zstd,
+                Some(Kind::Snappy(())) => Compression::Snappy,
                 Some(Kind::None(())) => Compression::None,
                 None => {
                     return Err(TryFromProtoError::MissingField(
@@ -2778,8 +2933,8 @@ pub mod sources {
                 match connector {
                     // TODO(guswynn): does postgres count here as well?
                     ExternalSourceConnector::Kafka(_) => true,
-                    // Currently, the Kinesis connector assigns "offsets" by counting the message in the order it was received
-                    // and this order is not replayable across different reads of the same Kinesis stream.
+                    // The Kinesis connector assigns offsets by counting the messages in each shard, which
+                    // is only replayable while the stream still retains the shard's oldest messages.
                     ExternalSourceConnector::Kinesis(_) => false,
                     _ => false,
                 }
@@ -2796,9 +2951,12 @@ pub mod sources {
         // for sources like pg
         pub fn append_only(&self) -> bool {
             match self {
-                // Postgres can produce retractions (deletes)
+                // Postgres, MySQL, and SQL Server can produce retractions (deletes)
                 SourceConnector::External {
-                    connector: ExternalSourceConnector::Postgres(_),
+                    connector:
+                        ExternalSourceConnector::Postgres(_)
+                        | ExternalSourceConnector::MySql(_)
+                        | ExternalSourceConnector::SqlServer(_),
                     ..
                 } => false,
                 // Other sources the `None` envelope are append-only.
@@ -2818,7 +2976,8 @@ pub mod sources {
                         | ExternalSourceConnector::Kafka(_)
                         | ExternalSourceConnector::Kinesis(_)
                         | ExternalSourceConnector::PubNub(_)
-                        | ExternalSourceConnector::Persist(_),
+                        | ExternalSourceConnector::Persist(_)
+                        | ExternalSourceConnector::Webhook(_),
                     ..
                 } => false,
                 // Local sources (i.e., tables) also support retractions (deletes)
@@ -2891,8 +3050,11 @@ pub mod sources {
         Kinesis(KinesisSourceConnector),
         S3(S3SourceConnector),
         Postgres(PostgresSourceConnector),
+        MySql(MySqlSourceConnector),
+        SqlServer(SqlServerSourceConnector),
         PubNub(PubNubSourceConnector),
         Persist(PersistSourceConnector),
+        Webhook(WebhookSourceConnector),
     }
 
     impl RustType<ProtoExternalSourceConnector> for ExternalSourceConnector {
@@ -2908,10 +3070,17 @@ pub mod sources {
                     ExternalSourceConnector::Postgres(postgres) => {
                         Kind::Postgres(postgres.into_proto())
                     }
+                    ExternalSourceConnector::MySql(mysql) => Kind::Mysql(mysql.into_proto()),
+                    ExternalSourceConnector::SqlServer(sql_server) => {
+                        Kind::SqlServer(sql_server.into_proto())
+                    }
                     ExternalSourceConnector::PubNub(pubnub) => Kind::Pubnub(pubnub.into_proto()),
                     ExternalSourceConnector::Persist(persist) => {
                         Kind::Persist(persist.into_proto())
                     }
+                    ExternalSourceConnector::Webhook(webhook) => {
+                        Kind::Webhook(webhook.into_proto())
+                    }
                 }),
             }
         }
@@ -2928,8 +3097,13 @@ pub mod sources {
                 Kind::Postgres(postgres) => {
                     ExternalSourceConnector::Postgres(postgres.into_rust()?)
                 }
+                Kind::Mysql(mysql) => ExternalSourceConnector::MySql(mysql.into_rust()?),
+                Kind::SqlServer(sql_server) => {
+                    ExternalSourceConnector::SqlServer(sql_server.into_rust()?)
+                }
                 Kind::Pubnub(pubnub) => ExternalSourceConnector::PubNub(pubnub.into_rust()?),
                 Kind::Persist(persist) => ExternalSourceConnector::Persist(persist.into_rust()?),
+                Kind::Webhook(webhook) => ExternalSourceConnector::Webhook(webhook.into_rust()?),
             })
         }
     }
@@ -2982,7 +3156,7 @@ pub mod sources {
                                         (
                                             "value".into(),
                                             ColumnType {
-                                                nullable: false,
+                                                nullable: true,
                                                 scalar_type: ScalarType::Bytes,
                                             },
                                         ),
@@ -3014,8 +3188,23 @@ pub mod sources {
                     columns
                 }
                 Self::Postgres(_) => vec![],
+                Self::MySql(_) => vec![],
+                Self::SqlServer(_) => vec![],
                 Self::PubNub(_) => vec![],
                 Self::Persist(_) => vec![],
+                Self::Webhook(WebhookSourceConnector {
+                    include_headers, ..
+                }) => match include_headers {
+                    Some(include) => vec![(
+                        &include.name,
+                        ScalarType::Map {
+                            value_type: Box::new(ScalarType::String),
+                            custom_id: None,
+                        }
+                        .nullable(false),
+                    )],
+                    None => vec![],
+                },
             }
         }
 
@@ -3026,8 +3215,11 @@ pub mod sources {
                 ExternalSourceConnector::Kinesis(_) => Some("mz_offset"),
                 ExternalSourceConnector::S3(_) => Some("mz_record"),
                 ExternalSourceConnector::Postgres(_) => None,
+                ExternalSourceConnector::MySql(_) => None,
+                ExternalSourceConnector::SqlServer(_) => None,
                 ExternalSourceConnector::PubNub(_) => None,
                 ExternalSourceConnector::Persist(_) => None,
+                ExternalSourceConnector::Webhook(_) => None,
             }
         }
 
@@ -3070,7 +3262,16 @@ pub mod sources {
                         Vec::new()
                     }
                 }
+                ExternalSourceConnector::Webhook(WebhookSourceConnector {
+                    include_headers,
+                    ..
+                }) => match include_headers {
+                    Some(_) => vec![IncludedColumnSource::Headers],
+                    None => Vec::new(),
+                },
                 ExternalSourceConnector::Postgres(_)
+                | ExternalSourceConnector::MySql(_)
+                | ExternalSourceConnector::SqlServer(_)
                 | ExternalSourceConnector::PubNub(_)
                 | ExternalSourceConnector::Persist(_) => Vec::new(),
             }
@@ -3083,8 +3284,11 @@ pub mod sources {
                 ExternalSourceConnector::Kinesis(_) => "kinesis",
                 ExternalSourceConnector::S3(_) => "s3",
                 ExternalSourceConnector::Postgres(_) => "postgres",
+                ExternalSourceConnector::MySql(_) => "mysql",
+                ExternalSourceConnector::SqlServer(_) => "sql-server",
                 ExternalSourceConnector::PubNub(_) => "pubnub",
                 ExternalSourceConnector::Persist(_) => "persist",
+                ExternalSourceConnector::Webhook(_) => "webhook",
             }
         }
 
@@ -3101,20 +3305,26 @@ pub mod sources {
                 }) => Some(stream_name.as_str()),
                 ExternalSourceConnector::S3(_) => None,
                 ExternalSourceConnector::Postgres(_) => None,
+                ExternalSourceConnector::MySql(_) => None,
+                ExternalSourceConnector::SqlServer(_) => None,
                 ExternalSourceConnector::PubNub(_) => None,
                 ExternalSourceConnector::Persist(_) => None,
+                ExternalSourceConnector::Webhook(_) => None,
             }
         }
 
         pub fn requires_single_materialization(&self) -> bool {
             match self {
                 ExternalSourceConnector::S3(c) => c.requires_single_materialization(),
-                ExternalSourceConnector::Postgres(_) => true,
+                ExternalSourceConnector::Postgres(_)
+                | ExternalSourceConnector::MySql(_)
+                | ExternalSourceConnector::SqlServer(_) => true,
 
                 ExternalSourceConnector::Kafka(_)
                 | ExternalSourceConnector::Kinesis(_)
                 | ExternalSourceConnector::PubNub(_)
-                | ExternalSourceConnector::Persist(_) => false,
+                | ExternalSourceConnector::Persist(_)
+                | ExternalSourceConnector::Webhook(_) => false,
             }
         }
     }
@@ -3149,6 +3359,12 @@ pub mod sources {
         pub publication: String,
         pub slot_name: String,
         pub details: PostgresSourceDetails,
+        /// Whether truncating an upstream table retracts its contents, rather
+        /// than putting the source into an error state.
+        pub retract_on_truncate: bool,
+        /// Whether the rows of tables whose replica identity is their primary
+        /// key are tracked, so that updates and deletes to them can be decoded.
+        pub track_primary_keys: bool,
     }
 
     impl RustType<ProtoPostgresSourceConnector> for PostgresSourceConnector {
@@ -3158,6 +3374,8 @@ pub mod sources {
                 publication: self.publication.clone(),
                 slot_name: self.slot_name.clone(),
                 details: Some(self.details.clone()),
+                retract_on_truncate: self.retract_on_truncate,
+                track_primary_keys: self.track_primary_keys,
             }
         }
 
@@ -3170,6 +3388,63 @@ pub mod sources {
                 details: proto.details.ok_or_else(|| {
                     TryFromProtoError::missing_field("ProtoPostgresSourceConnector::details")
                 })?,
+                retract_on_truncate: proto.retract_on_truncate,
+                track_primary_keys: proto.track_primary_keys,
+            })
+        }
+    }
+
+    #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct MySqlSourceConnector {
+        pub conn: String,
+        /// The upstream database whose tables the source replicates.
+        pub database: String,
+        pub details: MySqlSourceDetails,
+    }
+
+    impl RustType<ProtoMySqlSourceConnector> for MySqlSourceConnector {
+        fn into_proto(&self) -> ProtoMySqlSourceConnector {
+            ProtoMySqlSourceConnector {
+                conn: self.conn.clone(),
+                database: self.database.clone(),
+                details: Some(self.details.clone()),
+            }
+        }
+
+        fn from_proto(proto: ProtoMySqlSourceConnector) -> Result<Self, TryFromProtoError> {
+            Ok(MySqlSourceConnector {
+                conn: proto.conn,
+                database: proto.database,
+                details: proto.details.ok_or_else(|| {
+                    TryFromProtoError::missing_field("ProtoMySqlSourceConnector::details")
+                })?,
+            })
+        }
+    }
+
+    #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct SqlServerSourceConnector {
+        /// The connection string, in ADO.NET format
+        pub conn: String,
+        /// The tables that the source replicates, described as if they were
+        /// Postgres tables, whose relation IDs are the tables' object IDs.
+        pub details: PostgresSourceDetails,
+    }
+
+    impl RustType<ProtoSqlServerSourceConnector> for SqlServerSourceConnector {
+        fn into_proto(&self) -> ProtoSqlServerSourceConnector {
+            ProtoSqlServerSourceConnector {
+                conn: self.conn.clone(),
+                details: Some(self.details.clone()),
+            }
+        }
+
+        fn from_proto(proto: ProtoSqlServerSourceConnector) -> Result<Self, TryFromProtoError> {
+            Ok(SqlServerSourceConnector {
+                conn: proto.conn,
+                details: proto.details.ok_or_else(|| {
+                    TryFromProtoError::missing_field("ProtoSqlServerSourceConnector::details")
+                })?,
             })
         }
     }
@@ -3196,6 +3471,61 @@ pub mod sources {
         }
     }
 
+    /// A source whose data is delivered by requests to an endpoint of the HTTP
+    /// server. The body of each request becomes a row of the source.
+    #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct WebhookSourceConnector {
+        /// If present, requests must be signed as described.
+        pub validation: Option<WebhookValidation>,
+        /// If present, include the request headers as an output column of the
+        /// source with the given name.
+        pub include_headers: Option<IncludedColumnPos>,
+    }
+
+    impl RustType<ProtoWebhookSourceConnector> for WebhookSourceConnector {
+        fn into_proto(&self) -> ProtoWebhookSourceConnector {
+            ProtoWebhookSourceConnector {
+                validation: self.validation.into_proto(),
+                include_headers: self.include_headers.into_proto(),
+            }
+        }
+
+        fn from_proto(proto: ProtoWebhookSourceConnector) -> Result<Self, TryFromProtoError> {
+            Ok(WebhookSourceConnector {
+                validation: proto.validation.into_rust()?,
+                include_headers: proto.include_headers.into_rust()?,
+            })
+        }
+    }
+
+    /// The validation of requests to a webhook source.
+    ///
+    /// The `header` of each request must hold the hex-encoded HMAC-SHA256 of
+    /// the request body, keyed by the contents of the secret `secret_id`.
+    #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct WebhookValidation {
+        pub secret_id: GlobalId,
+        pub header: String,
+    }
+
+    impl RustType<ProtoWebhookValidation> for WebhookValidation {
+        fn into_proto(&self) -> ProtoWebhookValidation {
+            ProtoWebhookValidation {
+                secret_id: Some(self.secret_id.into_proto()),
+                header: self.header.clone(),
+            }
+        }
+
+        fn from_proto(proto: ProtoWebhookValidation) -> Result<Self, TryFromProtoError> {
+            Ok(WebhookValidation {
+                secret_id: proto
+                    .secret_id
+                    .into_rust_if_some("ProtoWebhookValidation::secret_id")?,
+                header: proto.header,
+            })
+        }
+    }
+
     #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
     pub struct PersistSourceConnector {
         pub consensus_uri: String,
@@ -3307,6 +3637,9 @@ pub mod sources {
     pub enum S3KeySource {
         /// Scan the S3 Bucket to discover keys to download
         Scan { bucket: String },
+        /// Repeatedly scan the S3 Bucket, discovering keys that sort after the
+        /// greatest key discovered by the previous scan
+        Poll { bucket: String, interval: Duration },
         /// Load object keys based on the contents of an S3 Notifications channel
         ///
         /// S3 notifications channels can be configured to go to SQS, which is the
@@ -3316,10 +3649,14 @@ pub mod sources {
 
     impl RustType<ProtoS3KeySource> for S3KeySource {
         fn into_proto(&self) -> ProtoS3KeySource {
-            use proto_s3_key_source::Kind;
+            use proto_s3_key_source::{Kind, ProtoPoll};
             ProtoS3KeySource {
                 kind: Some(match self {
                     S3KeySource::Scan { bucket } => Kind::Scan(bucket.clone()),
+                    S3KeySource::Poll { bucket, interval } => Kind::Poll(ProtoPoll {
+                        bucket: bucket.clone(),
+                        interval: Some(interval.into_proto()),
+                    }),
                     S3KeySource::SqsNotifications { queue } => {
                         Kind::SqsNotifications(queue.clone())
                     }
@@ -3328,9 +3665,13 @@ pub mod sources {
         }
 
         fn from_proto(proto: ProtoS3KeySource) -> Result<Self, TryFromProtoError> {
-            use proto_s3_key_source::Kind;
+            use proto_s3_key_source::{Kind, ProtoPoll};
             Ok(match proto.kind {
                 Some(Kind::Scan(s)) => S3KeySource::Scan { bucket: s },
+                Some(Kind::Poll(ProtoPoll { bucket, interval })) => S3KeySource::Poll {
+                    bucket,
+                    interval: interval.into_rust_if_some("ProtoPoll::interval")?,
+                },
                 Some(Kind::SqsNotifications(s)) => S3KeySource::SqsNotifications { queue: s },
                 None => {
                     return Err(TryFromProtoError::MissingField(
@@ -3416,6 +3757,8 @@ pub mod sinks {
     use mz_repr::proto::{IntoRustIfSome, ProtoType, RustType, TryFromProtoError};
     use mz_repr::{GlobalId, RelationDesc};
 
+    use crate::aws::AwsConfig;
+
     include!(concat!(
         env!("OUT_DIR"),
         "/mz_dataflow_types.types.sinks.rs"
@@ -3484,6 +3827,12 @@ pub mod sinks {
     pub enum SinkEnvelope {
         Debezium,
         Upsert,
+        /// An envelope for sinks whose input never retracts. Each inserted row is emitted
+        /// as-is, without any before/after wrapping.
+        AppendOnly,
+        /// An envelope for sinks that write the CDCv2 format, which preserves the exact times
+        /// and diffs of updates and interleaves them with progress statements.
+        CdcV2,
         /// An envelope for sinks that directly write differential Rows. This is internal and
         /// cannot be requested via SQL.
         DifferentialRow,
@@ -3496,6 +3845,8 @@ pub mod sinks {
                 kind: Some(match self {
                     SinkEnvelope::Debezium => Kind::Debezium(()),
                     SinkEnvelope::Upsert => Kind::Upsert(()),
+                    SinkEnvelope::AppendOnly => Kind::AppendOnly(()),
+                    SinkEnvelope::CdcV2 => Kind::CdcV2(()),
                     SinkEnvelope::DifferentialRow => Kind::DifferentialRow(()),
                 }),
             }
@@ -3509,6 +3860,8 @@ pub mod sinks {
             Ok(match kind {
                 Kind::Debezium(()) => SinkEnvelope::Debezium,
                 Kind::Upsert(()) => SinkEnvelope::Upsert,
+                Kind::AppendOnly(()) => SinkEnvelope::AppendOnly,
+                Kind::CdcV2(()) => SinkEnvelope::CdcV2,
                 Kind::DifferentialRow(()) => SinkEnvelope::DifferentialRow,
             })
         }
@@ -3558,6 +3911,8 @@ pub mod sinks {
         Kafka(KafkaSinkConnector),
         Tail(TailSinkConnector),
         Persist(PersistSinkConnector),
+        S3(S3SinkConnector),
+        Postgres(PostgresSinkConnector),
     }
 
     impl RustType<ProtoSinkConnector> for SinkConnector {
@@ -3568,6 +3923,8 @@ pub mod sinks {
                     SinkConnector::Kafka(kafka) => Kind::Kafka(kafka.into_proto()),
                     SinkConnector::Tail(_) => Kind::Tail(()),
                     SinkConnector::Persist(persist) => Kind::Persist(persist.into_proto()),
+                    SinkConnector::S3(s3) => Kind::S3(s3.into_proto()),
+                    SinkConnector::Postgres(postgres) => Kind::Postgres(postgres.into_proto()),
                 }),
             }
         }
@@ -3581,6 +3938,8 @@ pub mod sinks {
                 Kind::Kafka(kafka) => SinkConnector::Kafka(kafka.into_rust()?),
                 Kind::Tail(()) => SinkConnector::Tail(TailSinkConnector {}),
                 Kind::Persist(persist) => SinkConnector::Persist(persist.into_rust()?),
+                Kind::S3(s3) => SinkConnector::S3(s3.into_rust()?),
+                Kind::Postgres(postgres) => SinkConnector::Postgres(postgres.into_rust()?),
             })
         }
     }
@@ -3588,7 +3947,9 @@ pub mod sinks {
     #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
     pub struct KafkaSinkConsistencyConnector {
         pub topic: String,
-        pub schema_id: i32,
+        /// The ID of the published Avro consistency schema, or `None` if
+        /// consistency records are encoded as JSON.
+        pub schema_id: Option<i32>,
     }
 
     impl RustType<ProtoKafkaSinkConsistencyConnector> for KafkaSinkConsistencyConnector {
@@ -3617,6 +3978,7 @@ pub mod sinks {
         pub key_desc_and_indices: Option<(RelationDesc, Vec<usize>)>,
         pub relation_key_indices: Option<Vec<usize>>,
         pub value_desc: RelationDesc,
+        pub encoding: KafkaSinkEncoding,
         pub published_schema_info: Option<PublishedSchemaInfo>,
         pub consistency: Option<KafkaSinkConsistencyConnector>,
         pub exactly_once: bool,
@@ -3636,6 +3998,7 @@ pub mod sinks {
             key_desc_and_indices in any::<Option<(RelationDesc, Vec<usize>)>>(),
             relation_key_indices in any::<Option<Vec<usize>>>(),
             value_desc in any::<RelationDesc>(),
+            encoding in any::<KafkaSinkEncoding>(),
             published_schema_info in any::<Option<PublishedSchemaInfo>>(),
             consistency in any::<Option<KafkaSinkConsistencyConnector>>(),
             exactly_once in any::<bool>(),
@@ -3650,6 +4013,7 @@ pub mod sinks {
                 key_desc_and_indices,
                 relation_key_indices,
                 value_desc,
+                encoding,
                 published_schema_info,
                 consistency,
                 exactly_once,
@@ -3712,6 +4076,7 @@ pub mod sinks {
                 key_desc_and_indices: self.key_desc_and_indices.into_proto(),
                 relation_key_indices: self.relation_key_indices.into_proto(),
                 value_desc: Some(self.value_desc.into_proto()),
+                encoding: Some(self.encoding.into_proto()),
                 published_schema_info: self.published_schema_info.into_proto(),
                 consistency: self.consistency.into_proto(),
                 exactly_once: self.exactly_once,
@@ -3733,6 +4098,9 @@ pub mod sinks {
                 value_desc: proto
                     .value_desc
                     .into_rust_if_some("ProtoKafkaSinkConnector::addrs")?,
+                encoding: proto
+                    .encoding
+                    .into_rust_if_some("ProtoKafkaSinkConnector::encoding")?,
                 published_schema_info: proto.published_schema_info.into_rust()?,
                 consistency: proto.consistency.into_rust()?,
                 exactly_once: proto.exactly_once,
@@ -3743,6 +4111,42 @@ pub mod sinks {
         }
     }
 
+    /// The format in which a Kafka sink encodes its keys and values.
+    #[derive(Arbitrary, Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub enum KafkaSinkEncoding {
+        Avro,
+        Json,
+        /// Protobuf messages whose schema is derived from the sinked relation.
+        /// Messages use the Confluent wire format if the schema was published
+        /// to a schema registry.
+        Protobuf,
+    }
+
+    impl RustType<ProtoKafkaSinkEncoding> for KafkaSinkEncoding {
+        fn into_proto(&self) -> ProtoKafkaSinkEncoding {
+            use proto_kafka_sink_encoding::Kind;
+            ProtoKafkaSinkEncoding {
+                kind: Some(match self {
+                    KafkaSinkEncoding::Avro => Kind::Avro(()),
+                    KafkaSinkEncoding::Json => Kind::Json(()),
+                    KafkaSinkEncoding::Protobuf => Kind::Protobuf(()),
+                }),
+            }
+        }
+
+        fn from_proto(proto: ProtoKafkaSinkEncoding) -> Result<Self, TryFromProtoError> {
+            use proto_kafka_sink_encoding::Kind;
+            let kind = proto
+                .kind
+                .ok_or_else(|| TryFromProtoError::missing_field("ProtoKafkaSinkEncoding::kind"))?;
+            Ok(match kind {
+                Kind::Avro(()) => KafkaSinkEncoding::Avro,
+                Kind::Json(()) => KafkaSinkEncoding::Json,
+                Kind::Protobuf(()) => KafkaSinkEncoding::Protobuf,
+            })
+        }
+    }
+
     /// TODO(JLDLaughlin): Documentation.
     #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
     pub struct PublishedSchemaInfo {
@@ -3796,6 +4200,137 @@ pub mod sinks {
         }
     }
 
+    /// A sink that writes the updates of each closed timestamp interval to a
+    /// file in S3, followed by a manifest describing the interval.
+    #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct S3SinkConnector {
+        pub bucket: String,
+        /// The key prefix under which all of the sink's objects are written.
+        /// It is unique to this incarnation of the sink.
+        pub prefix: String,
+        pub aws: AwsConfig,
+        pub format: S3SinkFormat,
+        pub value_desc: RelationDesc,
+    }
+
+    impl RustType<ProtoS3SinkConnector> for S3SinkConnector {
+        fn into_proto(&self) -> ProtoS3SinkConnector {
+            ProtoS3SinkConnector {
+                bucket: self.bucket.clone(),
+                prefix: self.prefix.clone(),
+                aws: Some(self.aws.into_proto()),
+                format: Some(self.format.into_proto()),
+                value_desc: Some(self.value_desc.into_proto()),
+            }
+        }
+
+        fn from_proto(proto: ProtoS3SinkConnector) -> Result<Self, TryFromProtoError> {
+            Ok(S3SinkConnector {
+                bucket: proto.bucket,
+                prefix: proto.prefix,
+                aws: proto.aws.into_rust_if_some("ProtoS3SinkConnector::aws")?,
+                format: proto
+                    .format
+                    .into_rust_if_some("ProtoS3SinkConnector::format")?,
+                value_desc: proto
+                    .value_desc
+                    .into_rust_if_some("ProtoS3SinkConnector::value_desc")?,
+            })
+        }
+    }
+
+    /// The file format of an S3 sink.
+    #[derive(Arbitrary, Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub enum S3SinkFormat {
+        /// CSV with a header row, using the given field delimiter.
+        Csv {
+            delimiter: u8,
+        },
+        Parquet,
+    }
+
+    impl RustType<ProtoS3SinkFormat> for S3SinkFormat {
+        fn into_proto(&self) -> ProtoS3SinkFormat {
+            use proto_s3_sink_format::{Kind, ProtoCsv};
+            ProtoS3SinkFormat {
+                kind: Some(match self {
+                    S3SinkFormat::Csv { delimiter } => Kind::Csv(ProtoCsv {
+                        delimiter: delimiter.into_proto(),
+                    }),
+                    S3SinkFormat::Parquet => Kind::Parquet(()),
+                }),
+            }
+        }
+
+        fn from_proto(proto: ProtoS3SinkFormat) -> Result<Self, TryFromProtoError> {
+            use proto_s3_sink_format::{Kind, ProtoCsv};
+            let kind = proto
+                .kind
+                .ok_or_else(|| TryFromProtoError::missing_field("ProtoS3SinkFormat::kind"))?;
+            Ok(match kind {
+                Kind::Csv(ProtoCsv { delimiter }) => S3SinkFormat::Csv {
+                    delimiter: delimiter.into_rust()?,
+                },
+                Kind::Parquet(()) => S3SinkFormat::Parquet,
+            })
+        }
+    }
+
+    impl S3SinkFormat {
+        /// Returns the file extension of objects written in this format.
+        pub fn extension(&self) -> &'static str {
+            match self {
+                S3SinkFormat::Csv { .. } => "csv",
+                S3SinkFormat::Parquet => "parquet",
+            }
+        }
+    }
+
+    /// A sink that applies the upserts of each timestamp to a table in an
+    /// upstream PostgreSQL database in a single transaction.
+    #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct PostgresSinkConnector {
+        pub conn: String,
+        /// The schema of the upstream table.
+        pub namespace: String,
+        /// The name of the upstream table.
+        pub table: String,
+        /// The upstream types of the columns of the sinked relation, as
+        /// rendered by `format_type`.
+        pub column_types: Vec<String>,
+        pub key_indices: Vec<usize>,
+        pub relation_key_indices: Option<Vec<usize>>,
+        pub value_desc: RelationDesc,
+    }
+
+    impl RustType<ProtoPostgresSinkConnector> for PostgresSinkConnector {
+        fn into_proto(&self) -> ProtoPostgresSinkConnector {
+            ProtoPostgresSinkConnector {
+                conn: self.conn.clone(),
+                namespace: self.namespace.clone(),
+                table: self.table.clone(),
+                column_types: self.column_types.clone(),
+                key_indices: self.key_indices.into_proto(),
+                relation_key_indices: self.relation_key_indices.into_proto(),
+                value_desc: Some(self.value_desc.into_proto()),
+            }
+        }
+
+        fn from_proto(proto: ProtoPostgresSinkConnector) -> Result<Self, TryFromProtoError> {
+            Ok(PostgresSinkConnector {
+                conn: proto.conn,
+                namespace: proto.namespace,
+                table: proto.table,
+                column_types: proto.column_types,
+                key_indices: proto.key_indices.into_rust()?,
+                relation_key_indices: proto.relation_key_indices.into_rust()?,
+                value_desc: proto
+                    .value_desc
+                    .into_rust_if_some("ProtoPostgresSinkConnector::value_desc")?,
+            })
+        }
+    }
+
     impl SinkConnector {
         /// Returns the name of the sink connector.
         pub fn name(&self) -> &'static str {
@@ -3803,6 +4338,8 @@ pub mod sinks {
                 SinkConnector::Kafka(_) => "kafka",
                 SinkConnector::Tail(_) => "tail",
                 SinkConnector::Persist(_) => "persist",
+                SinkConnector::S3(_) => "s3",
+                SinkConnector::Postgres(_) => "postgres",
             }
         }
 
@@ -3825,6 +4362,8 @@ pub mod sinks {
                 SinkConnector::Kafka(k) => k.exactly_once,
                 SinkConnector::Tail(_) => false,
                 SinkConnector::Persist(_) => false,
+                SinkConnector::S3(_) => false,
+                SinkConnector::Postgres(_) => false,
             }
         }
 
@@ -3835,6 +4374,8 @@ pub mod sinks {
                 SinkConnector::Kafka(k) => &k.transitive_source_dependencies,
                 SinkConnector::Tail(_) => &[],
                 SinkConnector::Persist(_) => &[],
+                SinkConnector::S3(_) => &[],
+                SinkConnector::Postgres(_) => &[],
             }
         }
     }
@@ -3846,6 +4387,8 @@ pub mod sinks {
     pub enum SinkConnectorBuilder {
         Kafka(KafkaSinkConnectorBuilder),
         Persist(PersistSinkConnectorBuilder),
+        S3(S3SinkConnectorBuilder),
+        Postgres(PostgresSinkConnectorBuilder),
     }
 
     #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -3856,6 +4399,30 @@ pub mod sinks {
         pub value_desc: RelationDesc,
     }
 
+    #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct S3SinkConnectorBuilder {
+        pub bucket: String,
+        /// The user-specified key prefix, to which the sink's ID and
+        /// `prefix_suffix_nonce` are appended.
+        pub prefix: String,
+        pub prefix_suffix_nonce: String,
+        pub aws: AwsConfig,
+        pub format: S3SinkFormat,
+        pub value_desc: RelationDesc,
+    }
+
+    #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    pub struct PostgresSinkConnectorBuilder {
+        pub conn: String,
+        /// The possibly schema-qualified name of the upstream table.
+        pub table: String,
+        /// The user-specified key for the sink.
+        pub key_indices: Vec<usize>,
+        /// A natural key of the sinked relation (view or source).
+        pub relation_key_indices: Option<Vec<usize>>,
+        pub value_desc: RelationDesc,
+    }
+
     #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
     pub struct KafkaSinkConnectorBuilder {
         pub broker_addrs: KafkaAddrs,
@@ -3896,6 +4463,13 @@ pub mod sinks {
             ccsr_config: mz_ccsr::ClientConfig,
         },
         Json,
+        Protobuf {
+            /// The configuration of the schema registry to publish the schemas
+            /// to, if any.
+            ccsr_config: Option<mz_ccsr::ClientConfig>,
+            key_schema: Option<String>,
+            value_schema: String,
+        },
     }
 }
 
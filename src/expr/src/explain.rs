@@ -24,7 +24,7 @@
 //! printed in contexts where trailing whitespace is unacceptable, like
 //! sqllogictest files.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::iter;
 
@@ -50,6 +50,8 @@ pub struct ViewExplanation<'a> {
     local_id_chains: HashMap<LocalId, usize>,
     /// Records the local ID that corresponds to a chain ID, if any.
     chain_local_ids: HashMap<usize, LocalId>,
+    /// Records the local IDs that are bound recursively.
+    recursive_local_ids: HashSet<LocalId>,
     /// The ID of the current chain. Incremented while constructing the
     /// `Explanation`.
     chain: usize,
@@ -75,7 +77,11 @@ impl<'a> fmt::Display for ViewExplanation<'a> {
                 }
                 write!(f, "%{} =", node.chain)?;
                 if let Some(local_id) = self.chain_local_ids.get(&node.chain) {
-                    write!(f, " Let {} =", local_id)?;
+                    if self.recursive_local_ids.contains(local_id) {
+                        write!(f, " LetRec {} =", local_id)?;
+                    } else {
+                        write!(f, " Let {} =", local_id)?;
+                    }
                 }
                 writeln!(f)?;
             }
@@ -131,6 +137,19 @@ impl<'a> ViewExplanation<'a> {
                     explanation.local_id_chains.insert(*id, value_chain);
                     explanation.chain_local_ids.insert(value_chain, *id);
 
+                    walk(body, explanation);
+                }
+                LetRec { id, body, value } => {
+                    // Recursive definitions are explained like lets, except
+                    // that gets of `id` within `value` precede the binding.
+                    explanation.recursive_local_ids.insert(*id);
+                    walk(value, explanation);
+                    explanation.chain += 1;
+
+                    let value_chain = explanation.expr_chain(value);
+                    explanation.local_id_chains.insert(*id, value_chain);
+                    explanation.chain_local_ids.insert(value_chain, *id);
+
                     walk(body, explanation);
                 }
             }
@@ -152,14 +171,18 @@ impl<'a> ViewExplanation<'a> {
         {
             for expr in exprs {
                 // Elide chains that would consist only a of single Get node.
-                if let MirRelationExpr::Get {
-                    id: Id::Local(id), ..
-                } = expr
-                {
-                    explanation.expr_chains.insert(
-                        expr as *const MirRelationExpr,
-                        explanation.local_id_chains[id],
-                    );
+                // Gets of recursive bindings within their own definition have
+                // no chain yet, and so get a chain of their own.
+                let local_chain = match expr {
+                    MirRelationExpr::Get {
+                        id: Id::Local(id), ..
+                    } => explanation.local_id_chains.get(id).copied(),
+                    _ => None,
+                };
+                if let Some(chain) = local_chain {
+                    explanation
+                        .expr_chains
+                        .insert(expr as *const MirRelationExpr, chain);
                 } else {
                     walk(expr, explanation);
                     explanation.chain += 1;
@@ -173,6 +196,7 @@ impl<'a> ViewExplanation<'a> {
             expr_chains: HashMap::new(),
             local_id_chains: HashMap::new(),
             chain_local_ids: HashMap::new(),
+            recursive_local_ids: HashSet::new(),
             chain: 0,
         };
         walk(expr, &mut explanation);
@@ -182,7 +206,7 @@ impl<'a> ViewExplanation<'a> {
     /// Attach type information into the explanation.
     pub fn explain_types(&mut self) {
         for node in &mut self.nodes {
-            if let MirRelationExpr::Let { .. } = &node.expr {
+            if let MirRelationExpr::Let { .. } | MirRelationExpr::LetRec { .. } = &node.expr {
                 // Skip.
                 // Since we don't print out Let nodes in the explanation,
                 // types of Let nodes should not be attached to the
@@ -234,7 +258,7 @@ impl<'a> ViewExplanation<'a> {
                 )?,
             },
             // Lets are annotated on the chain ID that they correspond to.
-            Let { .. } => (),
+            Let { .. } | LetRec { .. } => (),
             Project { outputs, .. } => {
                 writeln!(f, "| Project {}", bracketed("(", ")", Indices(outputs)))?
             }
@@ -303,6 +327,7 @@ impl<'a> ViewExplanation<'a> {
                 order_key,
                 limit,
                 offset,
+                with_ties,
                 ..
             } => {
                 write!(
@@ -314,7 +339,11 @@ impl<'a> ViewExplanation<'a> {
                 if let Some(limit) = limit {
                     write!(f, " limit={}", limit)?;
                 }
-                writeln!(f, " offset={}", offset)?
+                write!(f, " offset={}", offset)?;
+                if *with_ties {
+                    write!(f, " with_ties")?;
+                }
+                writeln!(f)?
             }
             Negate { .. } => writeln!(f, "| Negate")?,
             Threshold { .. } => writeln!(f, "| Threshold")?,
@@ -10,7 +10,7 @@
 use std::fmt;
 use std::str::FromStr;
 
-use anyhow::Error;
+use anyhow::{bail, Error};
 use bytes::BufMut;
 use proptest_derive::Arbitrary;
 use prost::Message;
@@ -133,11 +133,17 @@ impl fmt::Display for SourceInstanceId {
 
 /// Unique identifier for each part of a whole source.
 ///     Kafka -> partition
+///     Kinesis -> shard ID
+///     MySql -> UUID of the server that transactions originated on
+///     S3 -> bucket
 ///     None -> sources that have no notion of partitioning (e.g file sources)
 #[derive(Arbitrary, Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum PartitionId {
     Kafka(i32),
     None,
+    Kinesis(String),
+    MySql(String),
+    S3(String),
 }
 
 impl fmt::Display for PartitionId {
@@ -145,6 +151,9 @@ impl fmt::Display for PartitionId {
         match self {
             PartitionId::Kafka(id) => write!(f, "{}", id),
             PartitionId::None => write!(f, "none"),
+            PartitionId::Kinesis(shard_id) => write!(f, "kinesis:{}", shard_id),
+            PartitionId::MySql(server_uuid) => write!(f, "mysql:{}", server_uuid),
+            PartitionId::S3(bucket) => write!(f, "s3:{}", bucket),
         }
     }
 }
@@ -162,9 +171,13 @@ impl FromStr for PartitionId {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "none" => Ok(PartitionId::None),
-            s => {
+        match s.split_once(':') {
+            Some(("kinesis", shard_id)) => Ok(PartitionId::Kinesis(shard_id.into())),
+            Some(("mysql", server_uuid)) => Ok(PartitionId::MySql(server_uuid.into())),
+            Some(("s3", bucket)) => Ok(PartitionId::S3(bucket.into())),
+            Some(_) => bail!("invalid partition id: {}", s),
+            None if s == "none" => Ok(PartitionId::None),
+            None => {
                 let val: i32 = s.parse()?;
                 Ok(PartitionId::Kafka(val))
             }
@@ -179,6 +192,9 @@ impl RustType<ProtoPartitionId> for PartitionId {
             kind: Some(match self {
                 PartitionId::Kafka(x) => Kafka(*x),
                 PartitionId::None => None(()),
+                PartitionId::Kinesis(x) => Kinesis(x.clone()),
+                PartitionId::MySql(x) => MySql(x.clone()),
+                PartitionId::S3(x) => S3(x.clone()),
             }),
         }
     }
@@ -188,6 +204,9 @@ impl RustType<ProtoPartitionId> for PartitionId {
         match proto.kind {
             Option::Some(Kafka(x)) => Ok(PartitionId::Kafka(x)),
             Option::Some(None(_)) => Ok(PartitionId::None),
+            Option::Some(Kinesis(x)) => Ok(PartitionId::Kinesis(x)),
+            Option::Some(MySql(x)) => Ok(PartitionId::MySql(x)),
+            Option::Some(S3(x)) => Ok(PartitionId::S3(x)),
             Option::None => Err(TryFromProtoError::missing_field("ProtoPartitionId::kind")),
         }
     }
@@ -225,5 +244,18 @@ mod tests {
             assert!(actual.is_ok());
             assert_eq!(actual.unwrap(), expect);
         }
+
+        #[test]
+        fn partition_id_protobuf_roundtrip(expect in any::<PartitionId>()) {
+            let actual = protobuf_roundtrip::<_, ProtoPartitionId>(&expect);
+            assert!(actual.is_ok());
+            assert_eq!(actual.unwrap(), expect);
+        }
+
+        #[test]
+        fn partition_id_string_roundtrip(expect in any::<PartitionId>()) {
+            let actual: PartitionId = expect.to_string().parse().unwrap();
+            assert_eq!(actual, expect);
+        }
     }
 }
@@ -9,8 +9,10 @@
 
 #![allow(missing_docs)]
 
+use std::cmp::Ordering;
 use std::fmt;
 use std::iter;
+use std::ops::Range;
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use dec::OrderedDecimal;
@@ -731,81 +733,60 @@ fn first_value<'a, I>(
 where
     I: IntoIterator<Item = Datum<'a>>,
 {
-    // Sort the datums according to the ORDER BY expressions and return the (OriginalRow, InputValue) record
-    let datums = order_aggregate_datums(datums, order_by);
-
-    // Decode the input (OriginalRow, InputValue) into separate datums
-    let datums = datums
-        .into_iter()
-        .map(|d| {
-            let mut iter = d.unwrap_list().iter();
-            let original_row = iter.next().unwrap();
-            let input_value = iter.next().unwrap();
+    let datums = decode_window_frame_datums(datums, order_by);
+    let frames = WindowFrames::new(window_frame, order_by, &datums);
 
-            (input_value, original_row)
+    let result = datums
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, original_row, _))| {
+            // Return the value of the first row of the frame, or null if the frame is empty
+            let first_value = datums[frames.frame(idx)]
+                .first()
+                .map_or(Datum::Null, |(input_value, _, _)| *input_value);
+            (first_value, *original_row)
         })
         .collect_vec();
 
-    let length = datums.len();
-    let mut result: Vec<(Datum, Datum)> = Vec::with_capacity(length);
-    for (idx, (current_datum, original_row)) in datums.iter().enumerate() {
-        let first_value = match &window_frame.start_bound {
-            // Always return the current value
-            WindowFrameBound::CurrentRow => *current_datum,
-            WindowFrameBound::UnboundedPreceding => {
-                if let WindowFrameBound::OffsetPreceding(end_offset) = &window_frame.end_bound {
-                    let end_offset = usize::cast_from(*end_offset);
-
-                    // If the frame ends before the first row, return null
-                    if idx < end_offset {
-                        Datum::Null
-                    } else {
-                        datums[0].0
-                    }
-                } else {
-                    datums[0].0
-                }
-            }
-            WindowFrameBound::OffsetPreceding(offset) => {
-                let start_offset = usize::cast_from(*offset);
-                let start_idx = idx.saturating_sub(start_offset);
-                if let WindowFrameBound::OffsetPreceding(end_offset) = &window_frame.end_bound {
-                    let end_offset = usize::cast_from(*end_offset);
-
-                    // If the frame is empty or ends before the first row, return null
-                    if start_offset < end_offset || idx < end_offset {
-                        Datum::Null
-                    } else {
-                        datums[start_idx].0
-                    }
-                } else {
-                    datums[start_idx].0
-                }
-            }
-            WindowFrameBound::OffsetFollowing(offset) => {
-                let start_offset = usize::cast_from(*offset);
-                let start_idx = idx.saturating_add(start_offset);
-                if let WindowFrameBound::OffsetFollowing(end_offset) = &window_frame.end_bound {
-                    // If the frame is empty or starts after the last row, return null
-                    if offset > end_offset || start_idx >= length {
-                        Datum::Null
-                    } else {
-                        datums[start_idx].0
-                    }
-                } else {
-                    datums.get(start_idx).map(|d| d.0).unwrap_or(Datum::Null)
-                }
-            }
-            // Forbidden during planning
-            WindowFrameBound::UnboundedFollowing => unreachable!(),
-        };
+    let result = result.into_iter().map(|(first_value, original_row)| {
+        temp_storage.make_datum(|packer| {
+            packer.push_list(vec![first_value, original_row]);
+        })
+    });
 
-        result.push((first_value, *original_row));
-    }
+    temp_storage.make_datum(|packer| {
+        packer.push_list(result);
+    })
+}
 
-    let result = result.into_iter().map(|(lag, original_row)| {
+// The expected input is in the format of [((OriginalRow, InputValue), OrderByExprs...)]
+fn last_value<'a, I>(
+    datums: I,
+    temp_storage: &'a RowArena,
+    order_by: &[ColumnOrder],
+    window_frame: &WindowFrame,
+) -> Datum<'a>
+where
+    I: IntoIterator<Item = Datum<'a>>,
+{
+    let datums = decode_window_frame_datums(datums, order_by);
+    let frames = WindowFrames::new(window_frame, order_by, &datums);
+
+    let result = datums
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, original_row, _))| {
+            // Return the value of the last row of the frame, or null if the frame is empty
+            let last_value = datums[frames.frame(idx)]
+                .last()
+                .map_or(Datum::Null, |(input_value, _, _)| *input_value);
+            (last_value, *original_row)
+        })
+        .collect_vec();
+
+    let result = result.into_iter().map(|(last_value, original_row)| {
         temp_storage.make_datum(|packer| {
-            packer.push_list(vec![lag, original_row]);
+            packer.push_list(vec![last_value, original_row]);
         })
     });
 
@@ -815,22 +796,267 @@ where
 }
 
 // The expected input is in the format of [((OriginalRow, InputValue), OrderByExprs...)]
-fn last_value<'a, I>(
+fn window_aggregate<'a, I>(
     datums: I,
     temp_storage: &'a RowArena,
+    wrapped_aggregate: &AggregateFunc,
     order_by: &[ColumnOrder],
     window_frame: &WindowFrame,
 ) -> Datum<'a>
 where
     I: IntoIterator<Item = Datum<'a>>,
 {
-    // Sort the datums according to the ORDER BY expressions and return the ((OriginalRow, InputValue), OrderByRow) record
-    // The OrderByRow is kept around because it is required to compute the peer groups in RANGE mode
-    let datums = order_aggregate_datums_with_rank(datums, order_by);
+    let datums = decode_window_frame_datums(datums, order_by);
+    let frames = WindowFrames::new(window_frame, order_by, &datums);
+    let input_values = datums
+        .iter()
+        .map(|(input_value, _, _)| *input_value)
+        .collect_vec();
+    let mut window = SlidingWindow::new(wrapped_aggregate, &input_values, temp_storage);
 
-    // Decode the input (OriginalRow, InputValue) into separate datums, while keeping the OrderByRow
-    let datums = datums
-        .into_iter()
+    let result = datums
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, original_row, _))| {
+            // Empty frames produce the result of the aggregate over no rows, e.g. 0 for count.
+            let value = window.eval(frames.frame(idx));
+            (value, *original_row)
+        })
+        .collect_vec();
+
+    let result = result.into_iter().map(|(value, original_row)| {
+        temp_storage.make_datum(|packer| {
+            packer.push_list(vec![value, original_row]);
+        })
+    });
+
+    temp_storage.make_datum(|packer| {
+        packer.push_list(result);
+    })
+}
+
+/// Evaluates an aggregate over the window frames of the rows of a partition,
+/// reusing the result over the previous frame where the aggregate allows it.
+///
+/// The starts and ends of the frames of consecutive rows never decrease, so
+/// each row enters and leaves the window at most once. Frames that do move
+/// backwards restart the window from scratch.
+struct SlidingWindow<'a, 'b> {
+    aggregate: &'b AggregateFunc,
+    input_values: &'b [Datum<'a>],
+    temp_storage: &'a RowArena,
+    /// The rows that are currently in the window
+    rows: Range<usize>,
+    state: SlidingWindowState<'a>,
+}
+
+enum SlidingWindowState<'a> {
+    /// Aggregates whose result over several rows can be combined with the
+    /// results over more rows by aggregating the results, e.g. `max`.
+    ///
+    /// The window is a queue made of two stacks. `back` holds the rows that
+    /// entered the window most recently, along with their aggregate. `front`
+    /// holds the rows that leave the window next, each replaced by the
+    /// aggregate of itself and the rows of `front` that entered the window
+    /// after it. Whenever `front` runs empty,
+    /// the rows of `back` move over to it, so each row is aggregated a
+    /// constant number of times.
+    Combinable {
+        front: Vec<Datum<'a>>,
+        back: Vec<Datum<'a>>,
+        back_result: Datum<'a>,
+    },
+    /// `count`, which counts the non-null rows in the window
+    Count(i64),
+    /// Integer sums, which add the non-null rows entering the window and
+    /// subtract the ones leaving it. `wide` sums of `bigint`s produce a
+    /// `numeric`.
+    SumInt { sum: i128, count: usize, wide: bool },
+    /// Any other aggregate, which is evaluated over each frame in full
+    Frame,
+}
+
+impl<'a, 'b> SlidingWindow<'a, 'b> {
+    fn new(
+        aggregate: &'b AggregateFunc,
+        input_values: &'b [Datum<'a>],
+        temp_storage: &'a RowArena,
+    ) -> Self {
+        let mut window = SlidingWindow {
+            aggregate,
+            input_values,
+            temp_storage,
+            rows: 0..0,
+            state: SlidingWindowState::Frame,
+        };
+        window.reset(0);
+        window
+    }
+
+    /// Empties the window, which then starts at the row at `start`.
+    fn reset(&mut self, start: usize) {
+        use AggregateFunc::*;
+        self.rows = start..start;
+        self.state = match self.aggregate {
+            MaxNumeric | MaxInt16 | MaxInt32 | MaxInt64 | MaxFloat32 | MaxFloat64 | MaxBool
+            | MaxString | MaxDate | MaxTimestamp | MaxTimestampTz | MinNumeric | MinInt16
+            | MinInt32 | MinInt64 | MinFloat32 | MinFloat64 | MinBool | MinString | MinDate
+            | MinTimestamp | MinTimestampTz | SumFloat32 | SumFloat64 | SumNumeric | Any | All => {
+                SlidingWindowState::Combinable {
+                    front: vec![],
+                    back: vec![],
+                    back_result: self.combine(None, None),
+                }
+            }
+            Count => SlidingWindowState::Count(0),
+            SumInt16 | SumInt32 | SumInt64 => SlidingWindowState::SumInt {
+                sum: 0,
+                count: 0,
+                wide: matches!(self.aggregate, SumInt64),
+            },
+            _ => SlidingWindowState::Frame,
+        };
+    }
+
+    /// Returns the result of the aggregate over the given rows, or over no
+    /// rows if neither is given.
+    fn combine(&self, a: Option<Datum<'a>>, b: Option<Datum<'a>>) -> Datum<'a> {
+        self.aggregate
+            .eval(a.into_iter().chain(b), self.temp_storage)
+    }
+
+    /// Returns the result of the aggregate over the rows in `frame`.
+    fn eval(&mut self, frame: Range<usize>) -> Datum<'a> {
+        if let SlidingWindowState::Frame = self.state {
+            return self
+                .aggregate
+                .eval(self.input_values[frame].iter().copied(), self.temp_storage);
+        }
+
+        if frame.start < self.rows.start || frame.end < self.rows.end || frame.start > self.rows.end
+        {
+            self.reset(frame.start);
+        }
+        while self.rows.end < frame.end {
+            self.push(self.input_values[self.rows.end]);
+            self.rows.end += 1;
+        }
+        while self.rows.start < frame.start {
+            self.pop(self.input_values[self.rows.start]);
+            self.rows.start += 1;
+        }
+
+        match &self.state {
+            SlidingWindowState::Combinable {
+                front, back_result, ..
+            } => self.combine(front.last().copied(), Some(*back_result)),
+            SlidingWindowState::Count(count) => Datum::Int64(*count),
+            SlidingWindowState::SumInt { count: 0, .. } => Datum::Null,
+            SlidingWindowState::SumInt {
+                sum, wide: false, ..
+            } => Datum::Int64(i64::try_from(*sum).expect("sum of a window fits in i64")),
+            SlidingWindowState::SumInt {
+                sum, wide: true, ..
+            } => Datum::from(*sum),
+            SlidingWindowState::Frame => unreachable!(),
+        }
+    }
+
+    /// Adds a row to the end of the window.
+    fn push(&mut self, value: Datum<'a>) {
+        let back_result = match &self.state {
+            SlidingWindowState::Combinable { back_result, .. } => {
+                Some(self.combine(Some(*back_result), Some(value)))
+            }
+            _ => None,
+        };
+        match &mut self.state {
+            SlidingWindowState::Combinable {
+                back,
+                back_result: result,
+                ..
+            } => {
+                back.push(value);
+                *result = back_result.unwrap();
+            }
+            SlidingWindowState::Count(count) => {
+                if !value.is_null() {
+                    *count += 1;
+                }
+            }
+            SlidingWindowState::SumInt { sum, count, .. } => {
+                if !value.is_null() {
+                    *sum += int_window_value(value);
+                    *count += 1;
+                }
+            }
+            SlidingWindowState::Frame => unreachable!(),
+        }
+    }
+
+    /// Removes `value`, the row at the start of the window.
+    fn pop(&mut self, value: Datum<'a>) {
+        match &mut self.state {
+            SlidingWindowState::Combinable {
+                front,
+                back,
+                back_result,
+            } => {
+                if front.is_empty() {
+                    let back = std::mem::take(back);
+                    let aggregate = self.aggregate;
+                    let temp_storage = self.temp_storage;
+                    for value in back.into_iter().rev() {
+                        let result = aggregate.eval(
+                            std::iter::once(value).chain(front.last().copied()),
+                            temp_storage,
+                        );
+                        front.push(result);
+                    }
+                    *back_result = aggregate.eval(std::iter::empty(), temp_storage);
+                }
+                front.pop().expect("window is not empty");
+            }
+            SlidingWindowState::Count(count) => {
+                if !value.is_null() {
+                    *count -= 1;
+                }
+            }
+            SlidingWindowState::SumInt { sum, count, .. } => {
+                if !value.is_null() {
+                    *sum -= int_window_value(value);
+                    *count -= 1;
+                }
+            }
+            SlidingWindowState::Frame => unreachable!(),
+        }
+    }
+}
+
+/// Returns the value of a non-null input to an integer sum.
+fn int_window_value(value: Datum) -> i128 {
+    match value {
+        Datum::Int16(v) => i128::from(v),
+        Datum::Int32(v) => i128::from(v),
+        Datum::Int64(v) => i128::from(v),
+        _ => unreachable!("integer sums only accept integers"),
+    }
+}
+
+/// Sorts the input of a window function that uses a window frame according to
+/// the ORDER BY expressions, and decodes each ((OriginalRow, InputValue), OrderByExprs...)
+/// record into an (InputValue, OriginalRow, OrderByRow) tuple.
+///
+/// The OrderByRow is kept around because it is required to compute the peer
+/// groups in RANGE and GROUPS mode.
+fn decode_window_frame_datums<'a, I>(
+    datums: I,
+    order_by: &[ColumnOrder],
+) -> Vec<(Datum<'a>, Datum<'a>, Row)>
+where
+    I: IntoIterator<Item = Datum<'a>>,
+{
+    order_aggregate_datums_with_rank(datums, order_by)
         .map(|(d, order_by_row)| {
             let mut iter = d.unwrap_list().iter();
             let original_row = iter.next().unwrap();
@@ -838,103 +1064,260 @@ where
 
             (input_value, original_row, order_by_row)
         })
-        .collect_vec();
+        .collect_vec()
+}
+
+/// Computes the window frame of each row of a partition sorted by the ORDER BY
+/// expressions of the window.
+struct WindowFrames<'a> {
+    window_frame: &'a WindowFrame,
+    order_by: &'a [ColumnOrder],
+    /// The ORDER BY row of each row of the partition
+    order_by_rows: Vec<&'a Row>,
+    /// The index of the peer group of each row of the partition
+    peer_group: Vec<usize>,
+    /// The rows that make up each peer group, i.e. the rows with the same
+    /// ORDER BY values
+    peer_groups: Vec<Range<usize>>,
+}
+
+impl<'a> WindowFrames<'a> {
+    fn new(
+        window_frame: &'a WindowFrame,
+        order_by: &'a [ColumnOrder],
+        datums: &'a [(Datum, Datum, Row)],
+    ) -> Self {
+        let order_by_rows = datums.iter().map(|(_, _, row)| row).collect_vec();
+
+        let mut peer_group = Vec::with_capacity(order_by_rows.len());
+        let mut peer_groups: Vec<Range<usize>> = vec![];
+        for (idx, row) in order_by_rows.iter().enumerate() {
+            match peer_groups.last_mut() {
+                Some(group) if order_by_rows[group.start] == *row => group.end = idx + 1,
+                _ => peer_groups.push(idx..idx + 1),
+            }
+            peer_group.push(peer_groups.len() - 1);
+        }
 
-    let length = datums.len();
-    let mut result: Vec<(Datum, Datum)> = Vec::with_capacity(length);
-    for (idx, (current_datum, original_row, order_by_row)) in datums.iter().enumerate() {
-        let last_value = match &window_frame.end_bound {
-            WindowFrameBound::CurrentRow => match &window_frame.units {
-                // Always return the current value when in ROWS mode
-                WindowFrameUnits::Rows => *current_datum,
-                WindowFrameUnits::Range => {
-                    // When in RANGE mode, return the last value of the peer group
-                    // The peer group is the group of rows with the same ORDER BY value
-                    // Note: Range is only supported for the default window frame (RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW),
-                    // which is why it does not appear in the other branches
-                    datums[idx..]
-                        .iter()
-                        .take_while(|(_, _, row)| row == order_by_row)
-                        .last()
-                        .unwrap()
-                        .0
-                }
-                // GROUPS is not supported, and forbidden during planning
-                WindowFrameUnits::Groups => unreachable!(),
-            },
-            WindowFrameBound::UnboundedFollowing => {
-                if let WindowFrameBound::OffsetFollowing(start_offset) = &window_frame.start_bound {
-                    let start_offset = usize::cast_from(*start_offset);
-
-                    // If the frame starts after the last row of the window, return null
-                    if idx + start_offset > length - 1 {
-                        Datum::Null
-                    } else {
-                        datums[length - 1].0
-                    }
-                } else {
-                    datums[length - 1].0
+        WindowFrames {
+            window_frame,
+            order_by,
+            order_by_rows,
+            peer_group,
+            peer_groups,
+        }
+    }
+
+    /// Returns the indexes of the rows that make up the window frame of the
+    /// row at `idx`.
+    fn frame(&self, idx: usize) -> Range<usize> {
+        let length = self.order_by_rows.len();
+        let group = self.peer_group[idx];
+
+        let start = match &self.window_frame.start_bound {
+            WindowFrameBound::UnboundedPreceding => 0,
+            WindowFrameBound::OffsetPreceding(offset) => {
+                let count = usize::cast_from(*offset);
+                match &self.window_frame.units {
+                    WindowFrameUnits::Rows => idx.saturating_sub(count),
+                    WindowFrameUnits::Groups => self.peer_groups[group.saturating_sub(count)].start,
+                    WindowFrameUnits::Range => self.range_offset_bound(idx, *offset, true, true),
                 }
             }
+            WindowFrameBound::CurrentRow => match &self.window_frame.units {
+                WindowFrameUnits::Rows => idx,
+                // In RANGE and GROUPS mode, the frame starts at the first peer of the current row
+                WindowFrameUnits::Range | WindowFrameUnits::Groups => self.peer_groups[group].start,
+            },
             WindowFrameBound::OffsetFollowing(offset) => {
-                let end_offset = usize::cast_from(*offset);
-                let end_idx = idx.saturating_add(end_offset);
-                if let WindowFrameBound::OffsetFollowing(start_offset) = &window_frame.start_bound {
-                    let start_offset = usize::cast_from(*start_offset);
-                    let start_idx = idx.saturating_add(start_offset);
-
-                    // If the frame is empty or starts after the last row of the window, return null
-                    if end_offset < start_offset || start_idx >= length {
-                        Datum::Null
-                    } else {
-                        // Return the last valid element in the window
-                        datums
-                            .get(end_idx)
-                            .map(|d| d.0)
-                            .unwrap_or(datums[length - 1].0)
-                    }
-                } else {
-                    datums
-                        .get(end_idx)
-                        .map(|d| d.0)
-                        .unwrap_or(datums[length - 1].0)
+                let count = usize::cast_from(*offset);
+                match &self.window_frame.units {
+                    WindowFrameUnits::Rows => idx.saturating_add(count),
+                    WindowFrameUnits::Groups => self
+                        .peer_groups
+                        .get(group.saturating_add(count))
+                        .map_or(length, |group| group.start),
+                    WindowFrameUnits::Range => self.range_offset_bound(idx, *offset, false, true),
                 }
             }
+            // Forbidden during planning
+            WindowFrameBound::UnboundedFollowing => unreachable!(),
+        };
+
+        let end = match &self.window_frame.end_bound {
             WindowFrameBound::OffsetPreceding(offset) => {
-                let end_offset = usize::cast_from(*offset);
-                let end_idx = idx.saturating_sub(end_offset);
-                if idx < end_offset {
-                    // If the frame ends before the first row, return null
-                    Datum::Null
-                } else if let WindowFrameBound::OffsetPreceding(start_offset) =
-                    &window_frame.start_bound
-                {
-                    // If the frame is empty, return null
-                    if offset > start_offset {
-                        Datum::Null
-                    } else {
-                        datums[end_idx].0
-                    }
-                } else {
-                    datums[end_idx].0
+                let count = usize::cast_from(*offset);
+                // If the frame ends before the first row, the frame is empty
+                match &self.window_frame.units {
+                    WindowFrameUnits::Rows => idx.checked_sub(count).map_or(0, |idx| idx + 1),
+                    WindowFrameUnits::Groups => group
+                        .checked_sub(count)
+                        .map_or(0, |group| self.peer_groups[group].end),
+                    WindowFrameUnits::Range => self.range_offset_bound(idx, *offset, true, false),
+                }
+            }
+            WindowFrameBound::CurrentRow => match &self.window_frame.units {
+                WindowFrameUnits::Rows => idx + 1,
+                // In RANGE and GROUPS mode, the frame ends at the last peer of the current row
+                WindowFrameUnits::Range | WindowFrameUnits::Groups => self.peer_groups[group].end,
+            },
+            WindowFrameBound::OffsetFollowing(offset) => {
+                let count = usize::cast_from(*offset);
+                match &self.window_frame.units {
+                    WindowFrameUnits::Rows => idx.saturating_add(count).saturating_add(1),
+                    WindowFrameUnits::Groups => self
+                        .peer_groups
+                        .get(group.saturating_add(count))
+                        .map_or(length, |group| group.end),
+                    WindowFrameUnits::Range => self.range_offset_bound(idx, *offset, false, false),
                 }
             }
+            WindowFrameBound::UnboundedFollowing => length,
             // Forbidden during planning
             WindowFrameBound::UnboundedPreceding => unreachable!(),
         };
 
-        result.push((last_value, *original_row));
+        // Clamp the frame to the partition, collapsing frames whose start is past their end
+        let end = end.min(length);
+        start.min(end)..end
     }
 
-    let result = result.into_iter().map(|(lag, original_row)| {
-        temp_storage.make_datum(|packer| {
-            packer.push_list(vec![lag, original_row]);
-        })
-    });
+    /// Returns the first row (if `is_start`) or one past the last row (otherwise)
+    /// whose ORDER BY value is within `offset` of the ORDER BY value of the row
+    /// at `idx`, in the direction given by `preceding`.
+    ///
+    /// RANGE frames with offsets have exactly one ORDER BY expression, which is
+    /// enforced during planning.
+    fn range_offset_bound(
+        &self,
+        idx: usize,
+        offset: u64,
+        preceding: bool,
+        is_start: bool,
+    ) -> usize {
+        let ColumnOrder { column, desc } = &self.order_by[0];
+        let key = |row: &'a Row| row.iter().nth(*column).unwrap();
+
+        // Null values are only peers of other null values, regardless of the offset.
+        // Otherwise, preceding rows have smaller values, unless the order is descending.
+        let current = key(self.order_by_rows[idx]);
+        let target = if current.is_null() {
+            Some(Datum::Null)
+        } else {
+            window_frame_offset(current, offset, preceding != *desc)
+        };
+        let target = match target {
+            Some(target) => target,
+            // The target value is out of the range of its type, so the bound
+            // lies past the first or last row of the partition
+            None if preceding => return 0,
+            None => return self.order_by_rows.len(),
+        };
 
-    temp_storage.make_datum(|packer| {
-        packer.push_list(result);
-    })
+        let cmp = |row: &&'a Row| {
+            let value = key(*row);
+            if *desc {
+                target.cmp(&value)
+            } else {
+                value.cmp(&target)
+            }
+        };
+        if is_start {
+            self.order_by_rows
+                .partition_point(|row| cmp(row) == Ordering::Less)
+        } else {
+            self.order_by_rows
+                .partition_point(|row| cmp(row) != Ordering::Greater)
+        }
+    }
+}
+
+/// Adds `offset` to (or subtracts it from) the ORDER BY value of a RANGE
+/// window frame, returning `None` if the result is out of range.
+///
+/// The offset is expressed in the unit of the value: a number for numeric
+/// types, and microseconds for timestamps.
+fn window_frame_offset<'a>(datum: Datum<'a>, offset: u64, subtract: bool) -> Option<Datum<'a>> {
+    match datum {
+        Datum::Int16(v) => {
+            let offset = i16::try_from(offset).ok()?;
+            if subtract {
+                v.checked_sub(offset)
+            } else {
+                v.checked_add(offset)
+            }
+            .map(Datum::Int16)
+        }
+        Datum::Int32(v) => {
+            let offset = i32::try_from(offset).ok()?;
+            if subtract {
+                v.checked_sub(offset)
+            } else {
+                v.checked_add(offset)
+            }
+            .map(Datum::Int32)
+        }
+        Datum::Int64(v) => {
+            let offset = i64::try_from(offset).ok()?;
+            if subtract {
+                v.checked_sub(offset)
+            } else {
+                v.checked_add(offset)
+            }
+            .map(Datum::Int64)
+        }
+        Datum::Float32(v) => {
+            let offset = offset as f32;
+            Some(Datum::from(if subtract {
+                *v - offset
+            } else {
+                *v + offset
+            }))
+        }
+        Datum::Float64(v) => {
+            let offset = offset as f64;
+            Some(Datum::from(if subtract {
+                *v - offset
+            } else {
+                *v + offset
+            }))
+        }
+        Datum::Numeric(v) => {
+            let mut cx = numeric::cx_datum();
+            let mut v = v.0;
+            let offset = numeric::Numeric::from(offset);
+            if subtract {
+                cx.sub(&mut v, &offset);
+            } else {
+                cx.add(&mut v, &offset);
+            }
+            if cx.status().overflow() {
+                None
+            } else {
+                Some(Datum::from(v))
+            }
+        }
+        Datum::Timestamp(v) => {
+            let offset = chrono::Duration::microseconds(i64::try_from(offset).ok()?);
+            if subtract {
+                v.checked_sub_signed(offset)
+            } else {
+                v.checked_add_signed(offset)
+            }
+            .map(Datum::Timestamp)
+        }
+        Datum::TimestampTz(v) => {
+            let offset = chrono::Duration::microseconds(i64::try_from(offset).ok()?);
+            if subtract {
+                v.checked_sub_signed(offset)
+            } else {
+                v.checked_add_signed(offset)
+            }
+            .map(Datum::TimestampTz)
+        }
+        // Other types are rejected during planning
+        _ => unreachable!("RANGE offset on {:?}", datum),
+    }
 }
 
 /// Identify whether the given aggregate function is Lag or Lead, since they share
@@ -1027,6 +1410,13 @@ pub enum AggregateFunc {
         order_by: Vec<ColumnOrder>,
         window_frame: WindowFrame,
     },
+    /// Evaluates `wrapped_aggregate` over the window frame of each row of the
+    /// partition, e.g. `sum(x) OVER (ORDER BY y)`.
+    WindowAggregate {
+        wrapped_aggregate: Box<AggregateFunc>,
+        order_by: Vec<ColumnOrder>,
+        window_frame: WindowFrame,
+    },
     /// Accumulates any number of `Datum::Dummy`s into `Datum::Dummy`.
     ///
     /// Useful for removing an expensive aggregation while maintaining the shape
@@ -1115,6 +1505,24 @@ impl Arbitrary for AggregateFunc {
                     order_by,
                     window_frame,
                 }),
+            (
+                prop_oneof![
+                    Just(AggregateFunc::MaxInt64),
+                    Just(AggregateFunc::MinString),
+                    Just(AggregateFunc::SumInt32),
+                    Just(AggregateFunc::SumNumeric),
+                    Just(AggregateFunc::Count),
+                ],
+                vec(proptest_any::<ColumnOrder>(), 1..4),
+                proptest_any::<WindowFrame>()
+            )
+                .prop_map(|(wrapped_aggregate, order_by, window_frame)| {
+                    AggregateFunc::WindowAggregate {
+                        wrapped_aggregate: Box::new(wrapped_aggregate),
+                        order_by,
+                        window_frame,
+                    }
+                }),
             Just(AggregateFunc::Dummy)
         ]
     }
@@ -1204,6 +1612,15 @@ impl RustType<ProtoAggregateFunc> for AggregateFunc {
                     order_by: Some(order_by.into_proto()),
                     window_frame: Some(window_frame.into_proto()),
                 }),
+                AggregateFunc::WindowAggregate {
+                    wrapped_aggregate,
+                    order_by,
+                    window_frame,
+                } => Kind::WindowAggregate(Box::new(proto_aggregate_func::ProtoWindowAggregate {
+                    wrapped_aggregate: Some(wrapped_aggregate.into_proto()),
+                    order_by: Some(order_by.into_proto()),
+                    window_frame: Some(window_frame.into_proto()),
+                })),
                 AggregateFunc::Dummy => Kind::Dummy(()),
             }),
         }
@@ -1299,6 +1716,17 @@ impl RustType<ProtoAggregateFunc> for AggregateFunc {
                     .window_frame
                     .into_rust_if_some("ProtoWindowFrame::window_frame")?,
             },
+            Kind::WindowAggregate(pwa) => AggregateFunc::WindowAggregate {
+                wrapped_aggregate: pwa
+                    .wrapped_aggregate
+                    .into_rust_if_some("ProtoWindowAggregate::wrapped_aggregate")?,
+                order_by: pwa
+                    .order_by
+                    .into_rust_if_some("ProtoWindowAggregate::order_by")?,
+                window_frame: pwa
+                    .window_frame
+                    .into_rust_if_some("ProtoWindowAggregate::window_frame")?,
+            },
             Kind::Dummy(()) => AggregateFunc::Dummy,
         })
     }
@@ -1362,6 +1790,17 @@ impl AggregateFunc {
                 order_by,
                 window_frame,
             } => last_value(datums, temp_storage, order_by, window_frame),
+            AggregateFunc::WindowAggregate {
+                wrapped_aggregate,
+                order_by,
+                window_frame,
+            } => window_aggregate(
+                datums,
+                temp_storage,
+                wrapped_aggregate,
+                order_by,
+                window_frame,
+            ),
             AggregateFunc::Dummy => Datum::Dummy,
         }
     }
@@ -1392,6 +1831,7 @@ impl AggregateFunc {
             AggregateFunc::LagLead { .. } => Datum::empty_list(),
             AggregateFunc::FirstValue { .. } => Datum::empty_list(),
             AggregateFunc::LastValue { .. } => Datum::empty_list(),
+            AggregateFunc::WindowAggregate { .. } => Datum::empty_list(),
             _ => Datum::Null,
         }
     }
@@ -1533,6 +1973,32 @@ impl AggregateFunc {
                     custom_id: None,
                 }
             }
+            AggregateFunc::WindowAggregate {
+                wrapped_aggregate, ..
+            } => {
+                // The input type for a window aggregate is ((OriginalRow, InputValue), OrderByExprs...)
+                let fields = input_type.scalar_type.unwrap_record_element_type();
+                let original_row_type = fields[0].unwrap_record_element_type()[0]
+                    .clone()
+                    .nullable(false);
+                let input_value_type = fields[0].unwrap_record_element_type()[1]
+                    .clone()
+                    .nullable(true);
+                // Frames can be empty, so the result is as nullable as the
+                // wrapped aggregate over a nullable input
+                let value_type = wrapped_aggregate.output_type(input_value_type);
+
+                ScalarType::List {
+                    element_type: Box::new(ScalarType::Record {
+                        fields: vec![
+                            (ColumnName::from("?window_agg?"), value_type),
+                            (ColumnName::from("?record?"), original_row_type),
+                        ],
+                        custom_id: None,
+                    }),
+                    custom_id: None,
+                }
+            }
             // Note AggregateFunc::MaxString, MinString rely on returning input
             // type as output type to support the proper return type for
             // character input.
@@ -1831,6 +2297,9 @@ impl fmt::Display for AggregateFunc {
             } => f.write_str("lead"),
             AggregateFunc::FirstValue { .. } => f.write_str("first_value"),
             AggregateFunc::LastValue { .. } => f.write_str("last_value"),
+            AggregateFunc::WindowAggregate {
+                wrapped_aggregate, ..
+            } => write!(f, "{}", wrapped_aggregate),
             AggregateFunc::Dummy => f.write_str("dummy"),
         }
     }
@@ -2295,10 +2764,13 @@ impl fmt::Display for TableFunc {
 
 #[cfg(test)]
 mod tests {
-    use super::{AggregateFunc, ProtoAggregateFunc, ProtoTableFunc, TableFunc};
+    use itertools::Itertools;
     use mz_repr::proto::protobuf_roundtrip;
+    use mz_repr::{Datum, RowArena};
     use proptest::prelude::*;
 
+    use super::{AggregateFunc, ProtoAggregateFunc, ProtoTableFunc, SlidingWindow, TableFunc};
+
     proptest! {
        #[test]
         fn aggregate_func_protobuf_roundtrip(expect in any::<AggregateFunc>() ) {
@@ -2316,4 +2788,40 @@ mod tests {
             assert_eq!(actual.unwrap(), expect);
         }
     }
+
+    proptest! {
+        #[test]
+        fn sliding_window_matches_frame_evaluation(
+            values in proptest::collection::vec(proptest::option::of(-1000i32..1000), 0..20),
+            bounds in proptest::collection::vec((0usize..24, 0usize..24), 0..24),
+        ) {
+            let arena = RowArena::new();
+            let values = values.into_iter().map(Datum::from).collect_vec();
+            let frames = bounds
+                .into_iter()
+                .map(|(a, b)| {
+                    let end = a.max(b).min(values.len());
+                    a.min(b).min(end)..end
+                })
+                .collect_vec();
+            // The frames of a partition usually move forward, but may also move backwards.
+            let mut sorted_frames = frames.clone();
+            sorted_frames.sort_by_key(|frame| (frame.end, frame.start));
+            for aggregate in [
+                AggregateFunc::SumInt32,
+                AggregateFunc::Count,
+                AggregateFunc::MaxInt32,
+                AggregateFunc::MinInt32,
+            ] {
+                for frames in [&frames, &sorted_frames] {
+                    let mut window = SlidingWindow::new(&aggregate, &values, &arena);
+                    for frame in frames {
+                        let rows = values[frame.clone()].iter().copied();
+                        let expected = aggregate.eval(rows, &arena);
+                        prop_assert_eq!(window.eval(frame.clone()), expected);
+                    }
+                }
+            }
+        }
+    }
 }
@@ -104,6 +104,25 @@ pub enum MirRelationExpr {
         /// The result of the `Let`, evaluated with `id` bound to `value`.
         body: Box<MirRelationExpr>,
     },
+    /// Introduce a temporary dataflow that may refer to itself.
+    ///
+    /// Starting from an empty collection bound to `id`, `value` is evaluated
+    /// repeatedly, each time with `id` bound to the result of the previous
+    /// evaluation, until the result no longer changes.
+    ///
+    /// The runtime memory footprint of this operator is zero, but the operators
+    /// within `value` retain state for each iteration.
+    LetRec {
+        /// The identifier to be used in `Get` variants to retrieve `value`,
+        /// both within `value` itself and within `body`.
+        #[mzreflect(ignore)]
+        id: LocalId,
+        /// The collection to be bound to `id`.
+        value: Box<MirRelationExpr>,
+        /// The result of the `LetRec`, evaluated with `id` bound to the fixed
+        /// point of `value`.
+        body: Box<MirRelationExpr>,
+    },
     /// Project out some columns from a dataflow
     ///
     /// The runtime memory footprint of this operator is zero.
@@ -205,6 +224,10 @@ pub enum MirRelationExpr {
         /// Number of records to skip
         #[serde(default)]
         offset: usize,
+        /// True iff records that tie with the last retained record on
+        /// `order_key` should be retained as well, even beyond `limit`.
+        #[serde(default)]
+        with_ties: bool,
         /// True iff the input is known to monotonically increase (only addition of records).
         #[serde(default)]
         monotonic: bool,
@@ -260,7 +283,8 @@ impl MirRelationExpr {
         #[allow(deprecated)]
         self.visit_pre_post_nolimit(
             &mut |e: &MirRelationExpr| -> Option<Vec<&MirRelationExpr>> {
-                if let MirRelationExpr::Let { body, .. } = &e {
+                if let MirRelationExpr::Let { body, .. } | MirRelationExpr::LetRec { body, .. } = &e
+                {
                     // Do not traverse the value sub-graph, since it's not relevant for
                     // determining the relation type of Let operators.
                     Some(vec![&*body])
@@ -269,7 +293,7 @@ impl MirRelationExpr {
                 }
             },
             &mut |e: &MirRelationExpr| {
-                if let MirRelationExpr::Let { .. } = &e {
+                if let MirRelationExpr::Let { .. } | MirRelationExpr::LetRec { .. } = &e {
                     let body_typ = type_stack.pop().unwrap();
                     // Insert a dummy relation type for the value, since `typ_with_input_types`
                     // won't look at it, but expects the relation type of the body to be second.
@@ -345,7 +369,9 @@ impl MirRelationExpr {
                 }
             }
             MirRelationExpr::Get { typ, .. } => typ.clone(),
-            MirRelationExpr::Let { .. } => input_types.last().unwrap().clone(),
+            MirRelationExpr::Let { .. } | MirRelationExpr::LetRec { .. } => {
+                input_types.last().unwrap().clone()
+            }
             MirRelationExpr::Project { input: _, outputs } => {
                 let input_typ = &input_types[0];
                 let mut output_typ = RelationType::new(
@@ -623,12 +649,16 @@ impl MirRelationExpr {
                 result
             }
             MirRelationExpr::TopK {
-                group_key, limit, ..
+                group_key,
+                limit,
+                with_ties,
+                ..
             } => {
                 // If `limit` is `Some(1)` then the group key will become
-                // a unique key, as there will be only one record with that key.
+                // a unique key, as there will be only one record with that key,
+                // unless ties are retained as well.
                 let mut typ = input_types[0].clone();
-                if limit == &Some(1) {
+                if limit == &Some(1) && !with_ties {
                     typ = typ.with_key(group_key.clone())
                 }
                 typ
@@ -740,7 +770,9 @@ impl MirRelationExpr {
         match self {
             MirRelationExpr::Constant { rows: _, typ } => typ.arity(),
             MirRelationExpr::Get { typ, .. } => typ.arity(),
-            MirRelationExpr::Let { body, .. } => body.arity(),
+            MirRelationExpr::Let { body, .. } | MirRelationExpr::LetRec { body, .. } => {
+                body.arity()
+            }
             MirRelationExpr::Project { input: _, outputs } => outputs.len(),
             MirRelationExpr::Map { input, scalars } => input.arity() + scalars.len(),
             MirRelationExpr::FlatMap { input, func, .. } => {
@@ -952,13 +984,16 @@ impl MirRelationExpr {
     /// The `group_key` argument indicates columns in the input collection that should
     /// be grouped, the `order_key` argument indicates columns that should be further
     /// used to order records within groups, and the `limit` argument constrains the
-    /// total number of records that should be produced in each group.
+    /// total number of records that should be produced in each group. If `with_ties`
+    /// is set, records that tie with the last produced record on `order_key` are
+    /// produced as well.
     pub fn top_k(
         self,
         group_key: Vec<usize>,
         order_key: Vec<ColumnOrder>,
         limit: Option<usize>,
         offset: usize,
+        with_ties: bool,
     ) -> Self {
         MirRelationExpr::TopK {
             input: Box::new(self),
@@ -966,6 +1001,7 @@ impl MirRelationExpr {
             order_key,
             limit,
             offset,
+            with_ties,
             monotonic: false,
         }
     }
@@ -1044,6 +1080,14 @@ impl MirRelationExpr {
         }
     }
 
+    /// Indicates if this expression contains a recursive binding, i.e. a
+    /// [`MirRelationExpr::LetRec`].
+    pub fn is_recursive(&self) -> bool {
+        let mut is_recursive = matches!(self, MirRelationExpr::LetRec { .. });
+        self.visit_children(|expr| is_recursive |= expr.is_recursive());
+        is_recursive
+    }
+
     /// Pretty-print this MirRelationExpr to a string.
     ///
     /// This method allows an additional `ExprHumanizer` which can annotate
@@ -1249,6 +1293,7 @@ impl MirRelationExpr {
             Constant { .. }
             | Get { .. }
             | Let { .. }
+            | LetRec { .. }
             | Project { .. }
             | TopK { .. }
             | Negate { .. }
@@ -1304,7 +1349,7 @@ impl VisitChildren for MirRelationExpr {
         use MirRelationExpr::*;
         match self {
             Constant { .. } | Get { .. } => (),
-            Let { value, body, .. } => {
+            Let { value, body, .. } | LetRec { value, body, .. } => {
                 f(value);
                 f(body);
             }
@@ -1340,7 +1385,7 @@ impl VisitChildren for MirRelationExpr {
         use MirRelationExpr::*;
         match self {
             Constant { .. } | Get { .. } => (),
-            Let { value, body, .. } => {
+            Let { value, body, .. } | LetRec { value, body, .. } => {
                 f(value);
                 f(body);
             }
@@ -1376,7 +1421,7 @@ impl VisitChildren for MirRelationExpr {
         use MirRelationExpr::*;
         match self {
             Constant { .. } | Get { .. } => (),
-            Let { value, body, .. } => {
+            Let { value, body, .. } | LetRec { value, body, .. } => {
                 f(value)?;
                 f(body)?;
             }
@@ -1413,7 +1458,7 @@ impl VisitChildren for MirRelationExpr {
         use MirRelationExpr::*;
         match self {
             Constant { .. } | Get { .. } => (),
-            Let { value, body, .. } => {
+            Let { value, body, .. } | LetRec { value, body, .. } => {
                 f(value)?;
                 f(body)?;
             }
@@ -1856,6 +1901,62 @@ impl AggregateExpr {
                 }
             }
 
+            // The input type for a window aggregate is a ((OriginalRow, InputValue), OrderByExprs...)
+            AggregateFunc::WindowAggregate {
+                wrapped_aggregate,
+                window_frame,
+                ..
+            } => {
+                let tuple = self
+                    .expr
+                    .clone()
+                    .call_unary(UnaryFunc::RecordGet(scalar_func::RecordGet(0)));
+
+                // Get the overall return type
+                let return_type = self
+                    .typ(input_type)
+                    .scalar_type
+                    .unwrap_list_element_type()
+                    .clone();
+                let window_agg_return_type = return_type.unwrap_record_element_type()[0].clone();
+
+                // Extract the original row
+                let original_row = tuple
+                    .clone()
+                    .call_unary(UnaryFunc::RecordGet(scalar_func::RecordGet(0)));
+
+                // Extract the input value
+                let expr = tuple.call_unary(UnaryFunc::RecordGet(scalar_func::RecordGet(1)));
+
+                // If the window frame includes the current (single) row, evaluate the wrapped
+                // aggregate on it, otherwise return the wrapped aggregate's result on no rows
+                let value = if window_frame.includes_current_row() {
+                    AggregateExpr {
+                        func: (**wrapped_aggregate).clone(),
+                        expr,
+                        distinct: false,
+                    }
+                    .on_unique(input_type)
+                } else {
+                    MirScalarExpr::literal_ok(wrapped_aggregate.default(), window_agg_return_type)
+                };
+
+                MirScalarExpr::CallVariadic {
+                    func: VariadicFunc::ListCreate {
+                        elem_type: return_type,
+                    },
+                    exprs: vec![MirScalarExpr::CallVariadic {
+                        func: VariadicFunc::RecordCreate {
+                            field_names: vec![
+                                ColumnName::from("?window_agg?"),
+                                ColumnName::from("?record?"),
+                            ],
+                        },
+                        exprs: vec![value, original_row],
+                    }],
+                }
+            }
+
             // All other variants should return the argument to the aggregation.
             AggregateFunc::MaxNumeric
             | AggregateFunc::MaxInt16
@@ -2204,8 +2305,8 @@ pub enum WindowFrameUnits {
     /// Each peer group is treated as the unit of work for bounds,
     /// and offset-based bounds use the value of the ORDER BY expression
     Range,
-    /// Each peer group is treated as the unit of work for bounds.
-    /// Groups is currently not supported, and it is rejected during planning.
+    /// Each peer group is treated as the unit of work for bounds,
+    /// and offset-based bounds count peer groups
     Groups,
 }
 
@@ -2242,6 +2343,9 @@ impl RustType<proto_window_frame::ProtoWindowFrameUnits> for WindowFrameUnits {
 ///
 /// The order between frame bounds is significant, as Postgres enforces
 /// some restrictions there.
+///
+/// In `RANGE` mode, offsets are expressed in the unit of the ORDER BY
+/// expression: a number for numeric types, and microseconds for timestamps.
 #[derive(
     Arbitrary, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, MzReflect, PartialOrd, Ord,
 )]
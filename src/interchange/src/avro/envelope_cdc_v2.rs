@@ -9,8 +9,11 @@
 
 //! Logic for the Avro representation of the CDCv2 protocol.
 
+use std::collections::HashMap;
+
 use mz_avro::schema::{FullName, SchemaNode};
-use mz_repr::{Diff, Row, Timestamp};
+use mz_avro::types::Value;
+use mz_repr::{ColumnName, ColumnType, Diff, RelationDesc, Row, Timestamp};
 use serde_json::json;
 
 use anyhow::anyhow;
@@ -25,6 +28,9 @@ use mz_avro_derive::AvroDecodable;
 use std::{cell::RefCell, rc::Rc};
 
 use super::decode::RowWrapper;
+use super::encode::{encode_avro_header, encode_datums_as_avro};
+use crate::encode::column_names_and_types;
+use crate::json::build_row_schema_json;
 
 pub fn extract_data_columns<'a>(schema: &'a Schema) -> anyhow::Result<SchemaNode<'a>> {
     let data_name = FullName::from_parts("data", Some("com.materialize.cdc"), "");
@@ -119,12 +125,105 @@ impl AvroDecode for Decoder {
 #[derive(Debug)]
 pub struct Decoder;
 
+/// Collected state to encode update batches and progress statements.
+#[derive(Debug)]
+pub struct Encoder {
+    columns: Vec<(ColumnName, ColumnType)>,
+    schema: Schema,
+}
+
+impl Encoder {
+    /// Creates a new CDCv2 encoder from a relation description.
+    pub fn new(desc: RelationDesc) -> Self {
+        let columns = column_names_and_types(desc);
+        let row_schema = build_row_schema_json(&columns, "data", &HashMap::new());
+        let schema = build_schema(row_schema);
+        Self { columns, schema }
+    }
+
+    /// Returns the Avro schema of the encoded messages.
+    pub fn writer_schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Encodes a batch of updates as an Avro value.
+    pub fn encode_updates(&self, updates: &[(Row, Timestamp, Diff)]) -> Value {
+        let mut enc_updates = Vec::new();
+        for (data, time, diff) in updates {
+            let enc_data = encode_datums_as_avro(&**data, &self.columns);
+            let enc_time = Value::Long(i64::try_from(*time).expect("timestamp fits in i64"));
+            let enc_diff = Value::Long(*diff);
+            enc_updates.push(Value::Record(vec![
+                ("data".to_string(), enc_data),
+                ("time".to_string(), enc_time),
+                ("diff".to_string(), enc_diff),
+            ]));
+        }
+        Value::Union {
+            index: 0,
+            inner: Box::new(Value::Array(enc_updates)),
+            n_variants: 2,
+            null_variant: None,
+        }
+    }
+
+    /// Encodes the contents of a progress statement as an Avro value.
+    pub fn encode_progress(
+        &self,
+        lower: &[Timestamp],
+        upper: &[Timestamp],
+        counts: &[(Timestamp, usize)],
+    ) -> Value {
+        let long = |t: &Timestamp| Value::Long(i64::try_from(*t).expect("timestamp fits in i64"));
+        let enc_lower = Value::Array(lower.iter().map(long).collect());
+        let enc_upper = Value::Array(upper.iter().map(long).collect());
+        let enc_counts = Value::Array(
+            counts
+                .iter()
+                .map(|(time, count)| {
+                    Value::Record(vec![
+                        ("time".to_string(), long(time)),
+                        (
+                            "count".to_string(),
+                            Value::Long(i64::try_from(*count).expect("count fits in i64")),
+                        ),
+                    ])
+                })
+                .collect(),
+        );
+        let enc_progress = Value::Record(vec![
+            ("lower".to_string(), enc_lower),
+            ("upper".to_string(), enc_upper),
+            ("counts".to_string(), enc_counts),
+        ]);
+
+        Value::Union {
+            index: 1,
+            inner: Box::new(enc_progress),
+            n_variants: 2,
+            null_variant: None,
+        }
+    }
+
+    /// Encodes `value`, as produced by [`Encoder::encode_updates`] or
+    /// [`Encoder::encode_progress`], in the Confluent wire format for the
+    /// schema with the given ID.
+    pub fn encode_unchecked(&self, schema_id: i32, value: &Value) -> Vec<u8> {
+        let mut buf = Vec::new();
+        encode_avro_header(&mut buf, schema_id);
+        debug_assert!(value.validate(self.schema.top_node()));
+        mz_avro::encode_unchecked(value, &self.schema, &mut buf);
+        buf
+    }
+}
+
 /// Construct the schema for the CDC V2 protocol.
 pub fn build_schema(row_schema: serde_json::Value) -> Schema {
     let updates_schema = json!({
         "type": "array",
         "items": {
             "name" : "update",
+            "namespace" : "com.materialize.cdc",
             "type" : "record",
             "fields" : [
                 {
@@ -145,6 +244,7 @@ pub fn build_schema(row_schema: serde_json::Value) -> Schema {
 
     let progress_schema = json!({
         "name" : "progress",
+        "namespace" : "com.materialize.cdc",
         "type" : "record",
         "fields" : [
             {
@@ -190,86 +290,11 @@ pub fn build_schema(row_schema: serde_json::Value) -> Schema {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
-    use mz_avro::types::Value;
     use mz_avro::AvroDeserializer;
     use mz_avro::GeneralDeserializer;
-    use mz_repr::{ColumnName, ColumnType, RelationDesc, Row, ScalarType};
+    use mz_repr::{Datum, ScalarType};
 
     use super::*;
-    use crate::avro::encode_datums_as_avro;
-    use crate::encode::column_names_and_types;
-    use crate::json::build_row_schema_json;
-
-    /// Collected state to encode update batches and progress statements.
-    #[derive(Debug)]
-    struct Encoder {
-        columns: Vec<(ColumnName, ColumnType)>,
-    }
-
-    impl Encoder {
-        /// Creates a new CDCv2 encoder from a relation description.
-        pub fn new(desc: RelationDesc) -> Self {
-            let columns = column_names_and_types(desc);
-            Self { columns }
-        }
-
-        /// Encodes a batch of updates as an Avro value.
-        pub fn encode_updates(&self, updates: &[(Row, i64, i64)]) -> Value {
-            let mut enc_updates = Vec::new();
-            for (data, time, diff) in updates {
-                let enc_data = encode_datums_as_avro(&**data, &self.columns);
-                let enc_time = Value::Long(time.clone());
-                let enc_diff = Value::Long(diff.clone());
-                enc_updates.push(Value::Record(vec![
-                    ("data".to_string(), enc_data),
-                    ("time".to_string(), enc_time),
-                    ("diff".to_string(), enc_diff),
-                ]));
-            }
-            Value::Union {
-                index: 0,
-                inner: Box::new(Value::Array(enc_updates)),
-                n_variants: 2,
-                null_variant: None,
-            }
-        }
-
-        /// Encodes the contents of a progress statement as an Avro value.
-        pub fn encode_progress(
-            &self,
-            lower: &[i64],
-            upper: &[i64],
-            counts: &[(i64, i64)],
-        ) -> Value {
-            let enc_lower = Value::Array(lower.iter().cloned().map(Value::Long).collect());
-            let enc_upper = Value::Array(upper.iter().cloned().map(Value::Long).collect());
-            let enc_counts = Value::Array(
-                counts
-                    .iter()
-                    .map(|(time, count)| {
-                        Value::Record(vec![
-                            ("time".to_string(), Value::Long(time.clone())),
-                            ("count".to_string(), Value::Long(count.clone())),
-                        ])
-                    })
-                    .collect(),
-            );
-            let enc_progress = Value::Record(vec![
-                ("lower".to_string(), enc_lower),
-                ("upper".to_string(), enc_upper),
-                ("counts".to_string(), enc_counts),
-            ]);
-
-            Value::Union {
-                index: 1,
-                inner: Box::new(enc_progress),
-                n_variants: 2,
-                null_variant: None,
-            }
-        }
-    }
 
     #[test]
     fn test_roundtrip() {
@@ -277,39 +302,50 @@ mod tests {
             .with_column("id", ScalarType::Int64.nullable(false))
             .with_column("price", ScalarType::Float64.nullable(true));
 
-        let encoder = Encoder::new(desc.clone());
-        let row_schema = build_row_schema_json(
-            &crate::encode::column_names_and_types(desc),
-            "data",
-            &HashMap::new(),
-        );
-        let schema = build_schema(row_schema);
+        let encoder = Encoder::new(desc);
+        let schema = encoder.writer_schema();
 
+        let row = Row::pack_slice(&[Datum::Int64(1), Datum::Null]);
         let values = vec![
-            encoder.encode_updates(&[]),
-            encoder.encode_progress(&[0], &[3], &[]),
+            encoder.encode_updates(&[(row.clone(), 1, 1), (row.clone(), 2, -1)]),
+            encoder.encode_progress(&[0], &[3], &[(1, 1), (2, 1)]),
             encoder.encode_progress(&[3], &[], &[]),
         ];
         use mz_avro::encode::encode_to_vec;
         let mut values: Vec<_> = values
             .into_iter()
-            .map(|v| encode_to_vec(&v, &schema))
+            .map(|v| encode_to_vec(&v, schema))
             .collect();
 
         let g = GeneralDeserializer {
             schema: schema.top_node(),
         };
-        assert!(matches!(
-            g.deserialize(&mut &values.remove(0)[..], Decoder).unwrap(),
-            Message::Updates(_)
-        ),);
-        assert!(matches!(
-            g.deserialize(&mut &values.remove(0)[..], Decoder).unwrap(),
-            Message::Progress(_)
-        ),);
+        match g.deserialize(&mut &values.remove(0)[..], Decoder).unwrap() {
+            Message::Updates(updates) => {
+                assert_eq!(updates, vec![(row.clone(), 1, 1), (row, 2, -1)])
+            }
+            Message::Progress(_) => panic!("expected updates"),
+        }
+        match g.deserialize(&mut &values.remove(0)[..], Decoder).unwrap() {
+            Message::Progress(progress) => {
+                assert_eq!(progress.lower, vec![0]);
+                assert_eq!(progress.upper, vec![3]);
+                assert_eq!(progress.counts, vec![(1, 1), (2, 1)]);
+            }
+            Message::Updates(_) => panic!("expected progress"),
+        }
         assert!(matches!(
             g.deserialize(&mut &values.remove(0)[..], Decoder).unwrap(),
             Message::Progress(_)
         ),);
     }
+
+    #[test]
+    fn test_data_columns_resolvable() {
+        let desc = RelationDesc::empty().with_column("id", ScalarType::Int64.nullable(false));
+        let encoder = Encoder::new(desc);
+        // Sources ingesting CDCv2 locate the data columns by name, so the
+        // generated schema must be readable by them.
+        assert!(extract_data_columns(encoder.writer_schema()).is_ok());
+    }
 }
@@ -52,21 +52,96 @@ pub fn extract_avro_header(buf: &[u8]) -> Result<(i32, &[u8])> {
     extract_schema_id(buf, "avro")
 }
 
-pub fn extract_protobuf_header(buf: &[u8]) -> Result<(i32, &[u8])> {
-    let (schema_id, buf) = extract_schema_id(buf, "protobuf")?;
-
-    match buf.get(0) {
-        Some(0) => Ok((schema_id, &buf[1..])),
-        Some(message_id) => bail!(
-            "unsupported Confluent-style protobuf message descriptor id: \
-                expected 0, but found: {}. \
-                See https://github.com/MaterializeInc/materialize/issues/9250",
-            message_id
-        ),
-        None => bail!(
+/// Extracts the schema ID and message indexes placed in front of a serialized
+/// Protobuf message by the Confluent stack.
+///
+/// The message indexes are the path to the message within the schema that was
+/// used to serialize it: the index of a top-level message in the file, followed
+/// by the indexes of any nested messages. They are encoded as a zigzag varint
+/// count followed by that many zigzag varint indexes, except that the common
+/// path `[0]`, the first message in the file, is encoded as a single zero.
+///
+/// This function returns the schema ID, the message indexes, and a subslice of
+/// the rest of the buffer.
+pub fn extract_protobuf_header(buf: &[u8]) -> Result<(i32, Vec<i32>, &[u8])> {
+    let (schema_id, mut buf) = extract_schema_id(buf, "protobuf")?;
+
+    if buf.is_empty() {
+        bail!(
             "Confluent-style protobuf datum is too few bytes: expected a message id after magic \
             and schema id, got a buffer of length {}",
             buf.len()
+        );
+    }
+    let message_indexes = match read_zigzag_varint(&mut buf)? {
+        0 => vec![0],
+        count if count < 0 => bail!(
+            "invalid Confluent-style protobuf message index count: {}",
+            count
         ),
+        count => (0..count)
+            .map(|_| read_zigzag_varint(&mut buf))
+            .collect::<Result<_>>()?,
+    };
+    Ok((schema_id, message_indexes, buf))
+}
+
+/// Reads a zigzag-encoded variable-length 32-bit integer from the front of
+/// `buf`, advancing `buf` past it.
+fn read_zigzag_varint(buf: &mut &[u8]) -> Result<i32> {
+    let mut value: u32 = 0;
+    for shift in (0..32).step_by(7) {
+        let (byte, rest) = match buf.split_first() {
+            Some((byte, rest)) => (*byte, rest),
+            None => bail!("Confluent-style protobuf message indexes are truncated"),
+        };
+        *buf = rest;
+        value |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(((value >> 1) as i32) ^ -((value & 1) as i32));
+        }
+    }
+    bail!("Confluent-style protobuf message index is too large")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_protobuf_header() {
+        let header = |indexes: &[u8]| {
+            let mut buf = vec![0, 0, 0, 0, 7];
+            buf.extend(indexes);
+            buf.push(42);
+            buf
+        };
+
+        // The first message is abbreviated to a single zero.
+        let buf = header(&[0]);
+        let (schema_id, indexes, rest) = extract_protobuf_header(&buf).unwrap();
+        assert_eq!(schema_id, 7);
+        assert_eq!(indexes, vec![0]);
+        assert_eq!(rest, &[42]);
+
+        // The second top-level message: a count of one, then index one.
+        let buf = header(&[2, 2]);
+        let (_, indexes, rest) = extract_protobuf_header(&buf).unwrap();
+        assert_eq!(indexes, vec![1]);
+        assert_eq!(rest, &[42]);
+
+        // A nested message: the first message nested in the 65th message.
+        let buf = header(&[4, 0x80, 0x01, 0]);
+        let (_, indexes, rest) = extract_protobuf_header(&buf).unwrap();
+        assert_eq!(indexes, vec![64, 0]);
+        assert_eq!(rest, &[42]);
+
+        // Negative counts are invalid.
+        let buf = header(&[1]);
+        assert!(extract_protobuf_header(&buf).is_err());
+
+        // So are missing and truncated indexes.
+        assert!(extract_protobuf_header(&[0, 0, 0, 0, 7]).is_err());
+        assert!(extract_protobuf_header(&[0, 0, 0, 0, 7, 4, 2]).is_err());
     }
 }
@@ -0,0 +1,118 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Encoding of batches of updates as CSV files.
+
+use bytes::BytesMut;
+
+use mz_repr::{ColumnType, Diff, RelationDesc, Row, Timestamp};
+
+use crate::encode::column_names_and_types;
+
+/// The name of the column that holds the timestamp of each update in files
+/// written by sinks.
+pub const TIMESTAMP_COLUMN_NAME: &str = "mz_timestamp";
+
+/// The name of the column that holds the diff of each update in files written
+/// by sinks.
+pub const DIFF_COLUMN_NAME: &str = "mz_diff";
+
+/// Encodes batches of updates as CSV files.
+///
+/// Each file starts with a header row. Each update is written as a record that
+/// holds the columns of the updated row in their PostgreSQL text format,
+/// followed by the timestamp and diff of the update. Null values are written
+/// as empty fields.
+#[derive(Debug)]
+pub struct CsvEncoder {
+    header: Vec<String>,
+    types: Vec<ColumnType>,
+    delimiter: u8,
+}
+
+impl CsvEncoder {
+    pub fn new(desc: RelationDesc, delimiter: u8) -> Self {
+        let (mut header, types): (Vec<_>, Vec<_>) = column_names_and_types(desc)
+            .into_iter()
+            .map(|(name, typ)| (name.as_str().to_owned(), typ))
+            .unzip();
+        header.push(TIMESTAMP_COLUMN_NAME.into());
+        header.push(DIFF_COLUMN_NAME.into());
+        CsvEncoder {
+            header,
+            types,
+            delimiter,
+        }
+    }
+
+    /// Encodes `updates` as a CSV file.
+    pub fn encode_updates(&self, updates: &[(Row, Timestamp, Diff)]) -> Vec<u8> {
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(vec![]);
+        writer
+            .write_record(&self.header)
+            .expect("writing to a vec cannot fail");
+        let mut buf = BytesMut::new();
+        for (row, time, diff) in updates {
+            for (datum, typ) in row.iter().zip(&self.types) {
+                buf.clear();
+                if let Some(value) = mz_pgrepr::Value::from_datum(datum, &typ.scalar_type) {
+                    value.encode_text(&mut buf);
+                }
+                writer
+                    .write_field(&buf)
+                    .expect("writing to a vec cannot fail");
+            }
+            writer
+                .write_field(time.to_string())
+                .expect("writing to a vec cannot fail");
+            writer
+                .write_field(diff.to_string())
+                .expect("writing to a vec cannot fail");
+            writer
+                .write_record(None::<&[u8]>)
+                .expect("writing to a vec cannot fail");
+        }
+        writer.into_inner().expect("writing to a vec cannot fail")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mz_repr::{ColumnName, Datum, ScalarType};
+
+    use super::*;
+
+    #[test]
+    fn test_encode_updates() {
+        let desc = RelationDesc::empty()
+            .with_column(ColumnName::from("a"), ScalarType::Int32.nullable(false))
+            .with_column(ColumnName::from("b"), ScalarType::String.nullable(true))
+            .with_column(ColumnName::from("a"), ScalarType::Bool.nullable(false));
+        let encoder = CsvEncoder::new(desc, b',');
+        let updates = vec![
+            (
+                Row::pack_slice(&[Datum::Int32(1), Datum::String("one, uno"), Datum::True]),
+                5,
+                1,
+            ),
+            (
+                Row::pack_slice(&[Datum::Int32(2), Datum::Null, Datum::False]),
+                6,
+                -2,
+            ),
+        ];
+        let csv = String::from_utf8(encoder.encode_updates(&updates)).unwrap();
+        assert_eq!(
+            csv,
+            "a,b,a1,mz_timestamp,mz_diff\n1,\"one, uno\",t,5,1\n2,,f,6,-2\n"
+        );
+    }
+}
@@ -382,3 +382,83 @@ pub fn build_row_schema_json(
         "name": name
     })
 }
+
+/// Encodes a Debezium-style transaction metadata record as JSON.
+///
+/// The record has the same fields as the Avro record produced by
+/// [`crate::avro::encode_debezium_transaction_unchecked`], so that consumers of
+/// a JSON consistency topic see the same structure as those of an Avro one.
+pub fn encode_debezium_transaction(
+    collection: &str,
+    id: &str,
+    status: &str,
+    message_count: Option<i64>,
+) -> Vec<u8> {
+    let data_collections = message_count.map(|count| {
+        json!([{
+            "data_collection": collection,
+            "event_count": count,
+        }])
+    });
+    json!({
+        "id": id,
+        "status": status,
+        "event_count": message_count,
+        "data_collections": data_collections,
+    })
+    .to_string()
+    .into_bytes()
+}
+
+/// Decodes the transaction ID and status from a JSON-encoded transaction
+/// metadata record, as produced by [`encode_debezium_transaction`].
+///
+/// Returns `None` if the record is valid JSON but lacks either field.
+pub fn decode_debezium_transaction(
+    bytes: &[u8],
+) -> Result<Option<(String, String)>, anyhow::Error> {
+    let record: serde_json::Value = serde_json::from_slice(bytes)?;
+    let id = record.get("id").and_then(|id| id.as_str());
+    let status = record.get("status").and_then(|status| status.as_str());
+    match (id, status) {
+        (Some(id), Some(status)) => Ok(Some((id.to_owned(), status.to_owned()))),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debezium_transaction_roundtrip() -> anyhow::Result<()> {
+        let begin = encode_debezium_transaction("sink", "5", "BEGIN", None);
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&begin)?,
+            json!({
+                "id": "5",
+                "status": "BEGIN",
+                "event_count": null,
+                "data_collections": null,
+            })
+        );
+
+        let end = encode_debezium_transaction("sink", "5", "END", Some(3));
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&end)?,
+            json!({
+                "id": "5",
+                "status": "END",
+                "event_count": 3,
+                "data_collections": [{"data_collection": "sink", "event_count": 3}],
+            })
+        );
+        assert_eq!(
+            decode_debezium_transaction(&end)?,
+            Some(("5".to_owned(), "END".to_owned()))
+        );
+        assert_eq!(decode_debezium_transaction(b"{}")?, None);
+        assert!(decode_debezium_transaction(b"garbage").is_err());
+        Ok(())
+    }
+}
@@ -13,7 +13,9 @@
 
 pub mod avro;
 mod confluent;
+pub mod csv;
 pub mod encode;
 pub mod envelopes;
 pub mod json;
+pub mod parquet;
 pub mod protobuf;
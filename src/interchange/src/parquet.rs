@@ -0,0 +1,638 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Encoding and decoding of Apache Parquet files.
+
+use std::io::Cursor;
+use std::str;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail};
+use arrow2::array::{
+    Array, BinaryArray, BooleanArray, FixedSizeBinaryArray, MutableArray, MutableBinaryArray,
+    MutableBooleanArray, MutablePrimitiveArray, MutableUtf8Array, PrimitiveArray, Utf8Array,
+};
+use arrow2::chunk::Chunk;
+use arrow2::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow2::io::parquet::read::{infer_schema, read_metadata, FileReader};
+use arrow2::io::parquet::write::{
+    CompressionOptions, Encoding, FileWriter, RowGroupIterator, Version, WriteOptions,
+};
+use bytes::BytesMut;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+
+use mz_repr::adt::numeric::{self, Numeric, NumericMaxScale};
+use mz_repr::{Datum, Diff, RelationDesc, Row, ScalarType, Timestamp};
+
+use crate::csv::{DIFF_COLUMN_NAME, TIMESTAMP_COLUMN_NAME};
+use crate::encode::column_names_and_types;
+
+/// Encodes batches of updates as Parquet files.
+///
+/// Each update is written as a row that holds the columns of the updated row,
+/// followed by the timestamp and diff of the update. Types without a natural
+/// Parquet counterpart, like `numeric`, `jsonb`, and lists, are written as
+/// strings in their PostgreSQL text format.
+#[derive(Debug)]
+pub struct ParquetEncoder {
+    schema: Schema,
+    types: Vec<ScalarType>,
+}
+
+impl ParquetEncoder {
+    pub fn new(desc: RelationDesc) -> Self {
+        let mut fields = vec![];
+        let mut types = vec![];
+        for (name, typ) in column_names_and_types(desc) {
+            fields.push(Field::new(
+                name.as_str(),
+                data_type(&typ.scalar_type),
+                typ.nullable,
+            ));
+            types.push(typ.scalar_type);
+        }
+        fields.push(Field::new(TIMESTAMP_COLUMN_NAME, DataType::UInt64, false));
+        fields.push(Field::new(DIFF_COLUMN_NAME, DataType::Int64, false));
+        ParquetEncoder {
+            schema: Schema::from(fields),
+            types,
+        }
+    }
+
+    /// Encodes `updates` as a Parquet file with a single row group.
+    pub fn encode_updates(
+        &self,
+        updates: &[(Row, Timestamp, Diff)],
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let mut builders: Vec<_> = self
+            .schema
+            .fields
+            .iter()
+            .take(self.types.len())
+            .map(|field| ColumnBuilder::new(&field.data_type, updates.len()))
+            .collect();
+        let mut times = MutablePrimitiveArray::<u64>::with_capacity(updates.len());
+        let mut diffs = MutablePrimitiveArray::<i64>::with_capacity(updates.len());
+        let mut buf = BytesMut::new();
+        for (row, time, diff) in updates {
+            for ((datum, typ), builder) in row.iter().zip(&self.types).zip(&mut builders) {
+                builder.push(datum, typ, &mut buf);
+            }
+            times.push(Some(*time));
+            diffs.push(Some(*diff));
+        }
+        let mut columns: Vec<_> = builders.iter_mut().map(ColumnBuilder::finish).collect();
+        columns.push(times.as_arc());
+        columns.push(diffs.as_arc());
+        let chunk = Chunk::try_new(columns)?;
+
+        let options = WriteOptions {
+            write_statistics: true,
+            compression: CompressionOptions::Uncompressed,
+            version: Version::V2,
+        };
+        let row_groups = RowGroupIterator::try_new(
+            std::iter::once(Ok(chunk)),
+            &self.schema,
+            options,
+            vec![Encoding::Plain; self.schema.fields.len()],
+        )?;
+        let mut out = vec![];
+        let mut writer = FileWriter::try_new(&mut out, self.schema.clone(), options)?;
+        writer.start()?;
+        for group in row_groups {
+            writer.write(group?)?;
+        }
+        writer.end(None)?;
+        Ok(out)
+    }
+}
+
+/// Returns the Arrow type of the Parquet column that holds values of `typ`.
+fn data_type(typ: &ScalarType) -> DataType {
+    match typ {
+        ScalarType::Bool => DataType::Boolean,
+        ScalarType::Int16 => DataType::Int16,
+        ScalarType::Int32 => DataType::Int32,
+        ScalarType::Int64 => DataType::Int64,
+        ScalarType::Oid | ScalarType::RegClass | ScalarType::RegProc | ScalarType::RegType => {
+            DataType::UInt32
+        }
+        ScalarType::Float32 => DataType::Float32,
+        ScalarType::Float64 => DataType::Float64,
+        ScalarType::Date => DataType::Date32,
+        ScalarType::Time => DataType::Time64(TimeUnit::Microsecond),
+        ScalarType::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, None),
+        ScalarType::TimestampTz => {
+            DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into()))
+        }
+        ScalarType::Bytes => DataType::Binary,
+        ScalarType::Numeric { .. }
+        | ScalarType::Interval
+        | ScalarType::PgLegacyChar
+        | ScalarType::String
+        | ScalarType::Char { .. }
+        | ScalarType::VarChar { .. }
+        | ScalarType::Jsonb
+        | ScalarType::Uuid
+        | ScalarType::Array(_)
+        | ScalarType::List { .. }
+        | ScalarType::Record { .. }
+        | ScalarType::Map { .. }
+        | ScalarType::Int2Vector => DataType::Utf8,
+    }
+}
+
+/// Accumulates the values of a single column.
+enum ColumnBuilder {
+    Boolean(MutableBooleanArray),
+    Int16(MutablePrimitiveArray<i16>),
+    /// Also holds dates, as days since the Unix epoch.
+    Int32(MutablePrimitiveArray<i32>),
+    /// Also holds times and timestamps, in microseconds.
+    Int64(MutablePrimitiveArray<i64>),
+    UInt32(MutablePrimitiveArray<u32>),
+    Float32(MutablePrimitiveArray<f32>),
+    Float64(MutablePrimitiveArray<f64>),
+    Binary(MutableBinaryArray<i32>),
+    Utf8(MutableUtf8Array<i32>),
+}
+
+impl ColumnBuilder {
+    fn new(data_type: &DataType, capacity: usize) -> Self {
+        match data_type {
+            DataType::Boolean => {
+                ColumnBuilder::Boolean(MutableBooleanArray::with_capacity(capacity))
+            }
+            DataType::Int16 => ColumnBuilder::Int16(MutablePrimitiveArray::with_capacity(capacity)),
+            DataType::Int32 | DataType::Date32 => ColumnBuilder::Int32(
+                MutablePrimitiveArray::with_capacity_from(capacity, data_type.clone()),
+            ),
+            DataType::Int64 | DataType::Time64(_) | DataType::Timestamp(..) => {
+                ColumnBuilder::Int64(MutablePrimitiveArray::with_capacity_from(
+                    capacity,
+                    data_type.clone(),
+                ))
+            }
+            DataType::UInt32 => {
+                ColumnBuilder::UInt32(MutablePrimitiveArray::with_capacity(capacity))
+            }
+            DataType::Float32 => {
+                ColumnBuilder::Float32(MutablePrimitiveArray::with_capacity(capacity))
+            }
+            DataType::Float64 => {
+                ColumnBuilder::Float64(MutablePrimitiveArray::with_capacity(capacity))
+            }
+            DataType::Binary => ColumnBuilder::Binary(MutableBinaryArray::with_capacity(capacity)),
+            DataType::Utf8 => ColumnBuilder::Utf8(MutableUtf8Array::with_capacity(capacity)),
+            other => unreachable!("unexpected Parquet column type {:?}", other),
+        }
+    }
+
+    fn push(&mut self, datum: Datum, typ: &ScalarType, buf: &mut BytesMut) {
+        let datum = if datum.is_null() { None } else { Some(datum) };
+        match self {
+            ColumnBuilder::Boolean(values) => values.push(datum.map(|d| d.unwrap_bool())),
+            ColumnBuilder::Int16(values) => values.push(datum.map(|d| d.unwrap_int16())),
+            ColumnBuilder::Int32(values) => values.push(datum.map(|d| match typ {
+                ScalarType::Date => {
+                    (d.unwrap_date() - NaiveDate::from_ymd(1970, 1, 1)).num_days() as i32
+                }
+                _ => d.unwrap_int32(),
+            })),
+            ColumnBuilder::Int64(values) => values.push(datum.map(|d| match typ {
+                ScalarType::Time => time_micros(d.unwrap_time()),
+                ScalarType::Timestamp => timestamp_micros(d.unwrap_timestamp()),
+                ScalarType::TimestampTz => timestamp_micros(d.unwrap_timestamptz().naive_utc()),
+                _ => d.unwrap_int64(),
+            })),
+            ColumnBuilder::UInt32(values) => values.push(datum.map(|d| d.unwrap_uint32())),
+            ColumnBuilder::Float32(values) => values.push(datum.map(|d| d.unwrap_float32())),
+            ColumnBuilder::Float64(values) => values.push(datum.map(|d| d.unwrap_float64())),
+            ColumnBuilder::Binary(values) => values.push(datum.map(|d| d.unwrap_bytes())),
+            ColumnBuilder::Utf8(values) => {
+                match datum.and_then(|d| mz_pgrepr::Value::from_datum(d, typ)) {
+                    Some(value) => {
+                        buf.clear();
+                        value.encode_text(buf);
+                        let s = str::from_utf8(buf).expect("text encoding produces valid UTF-8");
+                        values.push(Some(s));
+                    }
+                    None => values.push(None::<&str>),
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Arc<dyn Array> {
+        match self {
+            ColumnBuilder::Boolean(values) => values.as_arc(),
+            ColumnBuilder::Int16(values) => values.as_arc(),
+            ColumnBuilder::Int32(values) => values.as_arc(),
+            ColumnBuilder::Int64(values) => values.as_arc(),
+            ColumnBuilder::UInt32(values) => values.as_arc(),
+            ColumnBuilder::Float32(values) => values.as_arc(),
+            ColumnBuilder::Float64(values) => values.as_arc(),
+            ColumnBuilder::Binary(values) => values.as_arc(),
+            ColumnBuilder::Utf8(values) => values.as_arc(),
+        }
+    }
+}
+
+fn time_micros(time: NaiveTime) -> i64 {
+    i64::from(time.num_seconds_from_midnight()) * 1_000_000 + i64::from(time.nanosecond()) / 1_000
+}
+
+fn timestamp_micros(ts: NaiveDateTime) -> i64 {
+    ts.timestamp() * 1_000_000 + i64::from(ts.timestamp_subsec_micros())
+}
+
+/// The magic bytes at the start and the end of every Parquet file.
+pub const PARQUET_MAGIC: &[u8; 4] = b"PAR1";
+
+/// Infers the columns of a Parquet file from its footer, i.e. the last bytes
+/// of the file that hold its metadata, followed by the length of the metadata
+/// and the magic bytes.
+///
+/// Returns the name of each column, along with the type that its values are
+/// decoded as.
+pub fn infer_columns(footer: &[u8]) -> Result<Vec<(String, ScalarType)>, anyhow::Error> {
+    // The metadata reader only looks at the end of the file, but checks that
+    // the file is large enough to hold the magic bytes at its start.
+    let mut file = PARQUET_MAGIC.to_vec();
+    file.extend_from_slice(footer);
+    let metadata = read_metadata(&mut Cursor::new(file))?;
+    let schema = infer_schema(&metadata)?;
+    schema
+        .fields
+        .iter()
+        .map(|field| Ok((field.name.clone(), scalar_type(field)?)))
+        .collect()
+}
+
+/// Returns the type that values of the Parquet column `field` are decoded as.
+fn scalar_type(field: &Field) -> Result<ScalarType, anyhow::Error> {
+    Ok(match field.data_type.to_logical_type() {
+        DataType::Boolean => ScalarType::Bool,
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 => ScalarType::Int16,
+        DataType::Int32 | DataType::UInt16 => ScalarType::Int32,
+        DataType::Int64 | DataType::UInt32 => ScalarType::Int64,
+        DataType::UInt64 => ScalarType::Numeric {
+            max_scale: Some(NumericMaxScale::ZERO),
+        },
+        DataType::Float32 => ScalarType::Float32,
+        DataType::Float64 => ScalarType::Float64,
+        DataType::Utf8 | DataType::LargeUtf8 => ScalarType::String,
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => {
+            ScalarType::Bytes
+        }
+        DataType::Date32 | DataType::Date64 => ScalarType::Date,
+        DataType::Time32(_) | DataType::Time64(_) => ScalarType::Time,
+        DataType::Timestamp(_, None) => ScalarType::Timestamp,
+        DataType::Timestamp(_, Some(_)) => ScalarType::TimestampTz,
+        DataType::Decimal(_, scale) => ScalarType::Numeric {
+            max_scale: NumericMaxScale::try_from(i64::try_from(*scale)?).ok(),
+        },
+        other => bail!(
+            "Parquet column {} has unsupported type {:?}",
+            field.name,
+            other
+        ),
+    })
+}
+
+/// Decodes Parquet files into rows.
+///
+/// Columns are matched to the columns of a file by name. Columns that the file
+/// does not contain are decoded as `NULL`, and columns of the file that are not
+/// requested are skipped.
+#[derive(Debug)]
+pub struct ParquetDecoder {
+    columns: Vec<(String, ScalarType)>,
+}
+
+impl ParquetDecoder {
+    pub fn new(columns: Vec<(String, ScalarType)>) -> Self {
+        ParquetDecoder { columns }
+    }
+
+    /// Decodes all rows of the Parquet file `bytes`.
+    pub fn decode_file(&self, bytes: Vec<u8>) -> Result<Vec<Row>, anyhow::Error> {
+        let mut file = Cursor::new(bytes);
+        let schema = infer_schema(&read_metadata(&mut file)?)?;
+        let positions: Vec<_> = self
+            .columns
+            .iter()
+            .map(|(name, _)| schema.fields.iter().position(|f| &f.name == name))
+            .collect();
+        let mut projection: Vec<_> = positions.iter().flatten().copied().collect();
+        projection.sort_unstable();
+        projection.dedup();
+        if projection.is_empty() {
+            // The reader requires at least one column, which tells us how many
+            // rows of nulls to produce.
+            projection.push(0);
+        }
+        // The reader returns the projected columns in file order.
+        let indexes: Vec<_> = positions
+            .iter()
+            .map(|pos| pos.and_then(|pos| projection.binary_search(&pos).ok()))
+            .collect();
+
+        let reader = FileReader::try_new(file, Some(&projection), None, None, None)?;
+        let mut rows = vec![];
+        let mut row = Row::default();
+        for chunk in reader {
+            let chunk = chunk?;
+            let arrays = chunk.columns();
+            for i in 0..chunk.len() {
+                let mut packer = row.packer();
+                for (index, (name, typ)) in indexes.iter().zip(&self.columns) {
+                    let datum = match index {
+                        Some(index) => decode_datum(arrays[*index].as_ref(), i, typ)
+                            .map_err(|e| anyhow!("decoding Parquet column {}: {}", name, e))?,
+                        None => Datum::Null,
+                    };
+                    packer.push(datum);
+                }
+                rows.push(row.clone());
+            }
+        }
+        Ok(rows)
+    }
+}
+
+/// A value of a Parquet column, before its conversion to the type of the
+/// decoded column.
+#[derive(Debug)]
+enum Value<'a> {
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    /// An unscaled value, along with its scale.
+    Decimal(i128, u8),
+    Str(&'a str),
+    Bytes(&'a [u8]),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Timestamp(NaiveDateTime),
+}
+
+fn downcast<T: 'static>(array: &dyn Array) -> &T {
+    array
+        .as_any()
+        .downcast_ref()
+        .expect("array type matches its data type")
+}
+
+fn primitive<T: arrow2::types::NativeType>(array: &dyn Array, i: usize) -> T {
+    downcast::<PrimitiveArray<T>>(array).value(i)
+}
+
+/// Decodes the value at index `i` of `array` as a datum of type `typ`.
+fn decode_datum<'a>(
+    array: &'a dyn Array,
+    i: usize,
+    typ: &ScalarType,
+) -> Result<Datum<'a>, anyhow::Error> {
+    if array.is_null(i) {
+        return Ok(Datum::Null);
+    }
+    let value = match array.data_type().to_logical_type() {
+        DataType::Boolean => Value::Bool(downcast::<BooleanArray>(array).value(i)),
+        DataType::Int8 => Value::Int(primitive::<i8>(array, i).into()),
+        DataType::Int16 => Value::Int(primitive::<i16>(array, i).into()),
+        DataType::Int32 => Value::Int(primitive::<i32>(array, i).into()),
+        DataType::Int64 => Value::Int(primitive::<i64>(array, i).into()),
+        DataType::UInt8 => Value::Int(primitive::<u8>(array, i).into()),
+        DataType::UInt16 => Value::Int(primitive::<u16>(array, i).into()),
+        DataType::UInt32 => Value::Int(primitive::<u32>(array, i).into()),
+        DataType::UInt64 => Value::Int(primitive::<u64>(array, i).into()),
+        DataType::Float32 => Value::Float(primitive::<f32>(array, i).into()),
+        DataType::Float64 => Value::Float(primitive::<f64>(array, i)),
+        DataType::Utf8 => Value::Str(downcast::<Utf8Array<i32>>(array).value(i)),
+        DataType::LargeUtf8 => Value::Str(downcast::<Utf8Array<i64>>(array).value(i)),
+        DataType::Binary => Value::Bytes(downcast::<BinaryArray<i32>>(array).value(i)),
+        DataType::LargeBinary => Value::Bytes(downcast::<BinaryArray<i64>>(array).value(i)),
+        DataType::FixedSizeBinary(_) => {
+            Value::Bytes(downcast::<FixedSizeBinaryArray>(array).value(i))
+        }
+        DataType::Date32 => {
+            let days = primitive::<i32>(array, i);
+            Value::Date(NaiveDate::from_ymd(1970, 1, 1) + Duration::days(days.into()))
+        }
+        DataType::Date64 => {
+            Value::Date(timestamp(primitive::<i64>(array, i), &TimeUnit::Millisecond)?.date())
+        }
+        DataType::Time32(unit) => Value::Time(time(primitive::<i32>(array, i).into(), unit)?),
+        DataType::Time64(unit) => Value::Time(time(primitive::<i64>(array, i), unit)?),
+        DataType::Timestamp(unit, _) => {
+            Value::Timestamp(timestamp(primitive::<i64>(array, i), unit)?)
+        }
+        DataType::Decimal(_, scale) => {
+            Value::Decimal(primitive::<i128>(array, i), u8::try_from(*scale)?)
+        }
+        other => bail!("unsupported Parquet type {:?}", other),
+    };
+    Ok(match (typ, value) {
+        (ScalarType::Bool, Value::Bool(b)) => Datum::from(b),
+        (ScalarType::Int16, Value::Int(i)) => Datum::Int16(i16::try_from(i)?),
+        (ScalarType::Int32, Value::Int(i)) => Datum::Int32(i32::try_from(i)?),
+        (ScalarType::Int64, Value::Int(i)) => Datum::Int64(i64::try_from(i)?),
+        (ScalarType::Float32, Value::Float(f)) => Datum::from(f as f32),
+        (ScalarType::Float32, Value::Int(i)) => Datum::from(i as f32),
+        (ScalarType::Float64, Value::Float(f)) => Datum::from(f),
+        (ScalarType::Float64, Value::Int(i)) => Datum::from(i as f64),
+        (ScalarType::Numeric { max_scale }, value) => {
+            let mut n = match value {
+                Value::Int(i) => numeric::twos_complement_be_to_numeric(&mut i.to_be_bytes(), 0)?,
+                Value::Decimal(i, scale) => {
+                    numeric::twos_complement_be_to_numeric(&mut i.to_be_bytes(), scale)?
+                }
+                Value::Float(f) if f.is_finite() => Numeric::from(f),
+                value => bail!("cannot decode {:?} as numeric", value),
+            };
+            if let Some(scale) = max_scale {
+                numeric::rescale(&mut n, scale.into_u8())?;
+            }
+            numeric::munge_numeric(&mut n)?;
+            Datum::from(n)
+        }
+        (ScalarType::String, Value::Str(s)) => Datum::String(s),
+        (ScalarType::String, Value::Bytes(b)) => Datum::String(str::from_utf8(b)?),
+        (ScalarType::Bytes, Value::Bytes(b)) => Datum::Bytes(b),
+        (ScalarType::Bytes, Value::Str(s)) => Datum::Bytes(s.as_bytes()),
+        (ScalarType::Date, Value::Date(d)) => Datum::Date(d),
+        (ScalarType::Date, Value::Timestamp(ts)) => Datum::Date(ts.date()),
+        (ScalarType::Time, Value::Time(t)) => Datum::Time(t),
+        (ScalarType::Timestamp, Value::Timestamp(ts)) => Datum::Timestamp(ts),
+        (ScalarType::Timestamp, Value::Date(d)) => Datum::Timestamp(d.and_hms(0, 0, 0)),
+        (ScalarType::TimestampTz, Value::Timestamp(ts)) => {
+            Datum::TimestampTz(DateTime::<Utc>::from_utc(ts, Utc))
+        }
+        (typ, value) => bail!("cannot decode {:?} as {:?}", value, typ),
+    })
+}
+
+/// Returns the number of nanoseconds in `value` units of `unit`.
+fn nanos(value: i64, unit: &TimeUnit) -> i128 {
+    let per_unit = match unit {
+        TimeUnit::Second => 1_000_000_000,
+        TimeUnit::Millisecond => 1_000_000,
+        TimeUnit::Microsecond => 1_000,
+        TimeUnit::Nanosecond => 1,
+    };
+    i128::from(value) * per_unit
+}
+
+fn time(value: i64, unit: &TimeUnit) -> Result<NaiveTime, anyhow::Error> {
+    let nanos = nanos(value, unit);
+    let secs = u32::try_from(nanos / 1_000_000_000)?;
+    let frac = u32::try_from(nanos % 1_000_000_000)?;
+    NaiveTime::from_num_seconds_from_midnight_opt(secs, frac)
+        .ok_or_else(|| anyhow!("time out of range"))
+}
+
+fn timestamp(value: i64, unit: &TimeUnit) -> Result<NaiveDateTime, anyhow::Error> {
+    let nanos = nanos(value, unit);
+    let secs = i64::try_from(nanos.div_euclid(1_000_000_000))?;
+    let frac = u32::try_from(nanos.rem_euclid(1_000_000_000))?;
+    NaiveDateTime::from_timestamp_opt(secs, frac).ok_or_else(|| anyhow!("timestamp out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use arrow2::array::{PrimitiveArray, Utf8Array};
+    use arrow2::io::parquet::read::FileReader;
+
+    use mz_repr::ColumnName;
+
+    use super::*;
+
+    #[test]
+    fn test_encode_updates() {
+        let desc = RelationDesc::empty()
+            .with_column(ColumnName::from("a"), ScalarType::Int32.nullable(false))
+            .with_column(ColumnName::from("b"), ScalarType::Jsonb.nullable(true))
+            .with_column(ColumnName::from("c"), ScalarType::Date.nullable(false));
+        let encoder = ParquetEncoder::new(desc);
+        let jsonb = mz_repr::adt::jsonb::Jsonb::from_str("{\"x\": 1}").unwrap();
+        let updates = vec![
+            (
+                Row::pack_slice(&[
+                    Datum::Int32(1),
+                    jsonb.as_ref().into_datum(),
+                    Datum::Date(NaiveDate::from_ymd(1970, 1, 2)),
+                ]),
+                5,
+                1,
+            ),
+            (
+                Row::pack_slice(&[
+                    Datum::Int32(2),
+                    Datum::Null,
+                    Datum::Date(NaiveDate::from_ymd(1969, 12, 31)),
+                ]),
+                6,
+                -1,
+            ),
+        ];
+        let bytes = encoder.encode_updates(&updates).unwrap();
+
+        let reader = FileReader::try_new(Cursor::new(bytes), None, None, None, None).unwrap();
+        let fields = reader.schema().fields.clone();
+        let names: Vec<_> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b", "c", "mz_timestamp", "mz_diff"]);
+        assert_eq!(fields[2].data_type, DataType::Date32);
+
+        let chunks = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(chunks.len(), 1);
+        let columns = chunks[0].columns();
+        let b = columns[1]
+            .as_any()
+            .downcast_ref::<Utf8Array<i32>>()
+            .unwrap();
+        assert_eq!(b.iter().collect::<Vec<_>>(), vec![Some("{\"x\":1}"), None]);
+        let c = columns[2]
+            .as_any()
+            .downcast_ref::<PrimitiveArray<i32>>()
+            .unwrap();
+        assert_eq!(c.values().as_slice(), &[1, -1]);
+        let diffs = columns[4]
+            .as_any()
+            .downcast_ref::<PrimitiveArray<i64>>()
+            .unwrap();
+        assert_eq!(diffs.values().as_slice(), &[1, -1]);
+    }
+
+    #[test]
+    fn test_decode_file() {
+        let desc = RelationDesc::empty()
+            .with_column(ColumnName::from("a"), ScalarType::Int16.nullable(false))
+            .with_column(ColumnName::from("b"), ScalarType::String.nullable(true))
+            .with_column(ColumnName::from("c"), ScalarType::Timestamp.nullable(false));
+        let ts = NaiveDate::from_ymd(2022, 6, 1).and_hms_micro(12, 30, 0, 5);
+        let updates = vec![
+            (
+                Row::pack_slice(&[Datum::Int16(1), Datum::String("x"), Datum::Timestamp(ts)]),
+                5,
+                1,
+            ),
+            (
+                Row::pack_slice(&[Datum::Int16(-2), Datum::Null, Datum::Timestamp(ts)]),
+                5,
+                1,
+            ),
+        ];
+        let bytes = ParquetEncoder::new(desc).encode_updates(&updates).unwrap();
+
+        let footer_len =
+            8 + u32::from_le_bytes(bytes[bytes.len() - 8..bytes.len() - 4].try_into().unwrap())
+                as usize;
+        let columns = infer_columns(&bytes[bytes.len() - footer_len..]).unwrap();
+        assert_eq!(
+            columns,
+            vec![
+                ("a".into(), ScalarType::Int16),
+                ("b".into(), ScalarType::String),
+                ("c".into(), ScalarType::Timestamp),
+                (
+                    "mz_timestamp".into(),
+                    ScalarType::Numeric {
+                        max_scale: Some(NumericMaxScale::ZERO)
+                    }
+                ),
+                ("mz_diff".into(), ScalarType::Int64),
+            ]
+        );
+
+        // Columns are matched by name, converted to the requested type, and
+        // decoded as nulls if missing.
+        let decoder = ParquetDecoder::new(vec![
+            ("c".into(), ScalarType::Date),
+            ("a".into(), ScalarType::Int64),
+            ("missing".into(), ScalarType::Bool),
+            ("b".into(), ScalarType::String),
+        ]);
+        let rows = decoder.decode_file(bytes.clone()).unwrap();
+        let date = Datum::Date(ts.date());
+        assert_eq!(
+            rows,
+            vec![
+                Row::pack_slice(&[date, Datum::Int64(1), Datum::Null, Datum::String("x")]),
+                Row::pack_slice(&[date, Datum::Int64(-2), Datum::Null, Datum::Null]),
+            ]
+        );
+
+        let decoder = ParquetDecoder::new(vec![("a".into(), ScalarType::Bool)]);
+        assert!(decoder.decode_file(bytes).is_err());
+    }
+}
@@ -19,12 +19,17 @@ use prost_reflect::{
 use mz_ore::str::StrExt;
 use mz_repr::{ColumnName, ColumnType, Datum, Row, RowPacker, ScalarType};
 
+mod encode;
+
+pub use self::encode::{ProtobufEncoder, ProtobufSchemaGenerator};
+
 /// A decoded description of the schema of a Protobuf message.
 #[derive(Debug, PartialEq)]
 pub struct DecodedDescriptors {
     message_descriptor: MessageDescriptor,
     columns: Vec<(ColumnName, ColumnType)>,
     message_name: String,
+    message_indexes: Vec<i32>,
 }
 
 impl DecodedDescriptors {
@@ -46,10 +51,12 @@ impl DecodedDescriptors {
             let ty = derive_column_type(&mut seen_messages, &field)?;
             columns.push((name, ty))
         }
+        let message_indexes = derive_message_indexes(&message_descriptor);
         Ok(DecodedDescriptors {
             message_descriptor,
             columns,
             message_name,
+            message_indexes,
         })
     }
 
@@ -103,7 +110,20 @@ impl Decoder {
             // allocations).
             //
             // [0]: https://developers.google.com/protocol-buffers/docs/overview
-            let (_schema_id, adjusted_bytes) = crate::confluent::extract_protobuf_header(bytes)?;
+            //
+            // The message indexes, however, must identify the message we are
+            // decoding, as a schema may define many messages.
+            let (_schema_id, message_indexes, adjusted_bytes) =
+                crate::confluent::extract_protobuf_header(bytes)?;
+            if message_indexes != self.descriptors.message_indexes {
+                bail!(
+                    "Confluent-style protobuf message indexes {:?} do not match the \
+                    indexes {:?} of message {}",
+                    message_indexes,
+                    self.descriptors.message_indexes,
+                    self.descriptors.message_name.quoted(),
+                );
+            }
             bytes = adjusted_bytes;
         }
         let message = DynamicMessage::decode(self.descriptors.message_descriptor.clone(), bytes)?;
@@ -113,6 +133,32 @@ impl Decoder {
     }
 }
 
+/// Computes the path of indexes that identifies `message` within its file, in
+/// the form used by the Confluent wire format: the index of the enclosing
+/// top-level message, followed by the indexes of any nested messages.
+fn derive_message_indexes(message: &MessageDescriptor) -> Vec<i32> {
+    let mut indexes = vec![];
+    let mut message = message.clone();
+    loop {
+        let parent = message.parent_message();
+        let siblings: Vec<_> = match &parent {
+            Some(parent) => parent.child_messages().collect(),
+            None => message.parent_file().messages().collect(),
+        };
+        let index = siblings
+            .iter()
+            .position(|m| m.full_name() == message.full_name())
+            .expect("message is defined by its parent");
+        indexes.push(i32::try_from(index).expect("message index fits in i32"));
+        match parent {
+            Some(parent) => message = parent,
+            None => break,
+        }
+    }
+    indexes.reverse();
+    indexes
+}
+
 fn derive_column_type(
     seen_messages: &mut HashSet<String>,
     field: &FieldDescriptor,
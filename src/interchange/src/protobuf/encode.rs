@@ -0,0 +1,578 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Encoding of rows as Protobuf messages.
+//!
+//! Sinks do not have a user-provided Protobuf schema. Instead, a `.proto`
+//! schema is derived from the description of the sinked relation, in the same
+//! way that Avro schemas are derived for Avro sinks. Each column becomes a
+//! field whose number is the column's position plus one, and each record type
+//! becomes a message nested inside the top-level message, so that the
+//! top-level message is always the first message in the schema.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use anyhow::bail;
+use byteorder::{NetworkEndian, WriteBytesExt};
+use chrono::{NaiveDate, NaiveDateTime, Timelike};
+use prost::encoding::{encode_key, encode_varint, WireType};
+
+use mz_ore::str::StrExt;
+use mz_repr::adt::jsonb::JsonbRef;
+use mz_repr::{ColumnName, ColumnType, Datum, GlobalId, RelationDesc, Row, ScalarType};
+
+use crate::encode::{column_names_and_types, Encode};
+use crate::envelopes::{self, ENVELOPE_CUSTOM_NAMES};
+
+/// Generates key and value Protobuf schemas.
+#[derive(Debug)]
+pub struct ProtobufSchemaGenerator {
+    key_columns: Option<Vec<(ColumnName, ColumnType)>>,
+    key_schema: Option<String>,
+    value_columns: Vec<(ColumnName, ColumnType)>,
+    value_schema: String,
+}
+
+impl ProtobufSchemaGenerator {
+    /// Derives the key and value schemas for a sink.
+    ///
+    /// Returns an error if the relations contain types that cannot be
+    /// represented in Protobuf, like lists of lists.
+    pub fn new(
+        key_desc: Option<RelationDesc>,
+        value_desc: RelationDesc,
+        debezium: bool,
+        include_transaction: bool,
+    ) -> Result<Self, anyhow::Error> {
+        let mut value_columns = column_names_and_types(value_desc);
+        if debezium {
+            value_columns = envelopes::dbz_envelope(value_columns);
+        }
+        if include_transaction {
+            envelopes::txn_metadata(&mut value_columns);
+        }
+        let value_schema = build_schema("Value", &value_columns, &ENVELOPE_CUSTOM_NAMES)?;
+        let (key_columns, key_schema) = match key_desc {
+            Some(key_desc) => {
+                let columns = column_names_and_types(key_desc);
+                let schema = build_schema("Key", &columns, &HashMap::new())?;
+                (Some(columns), Some(schema))
+            }
+            None => (None, None),
+        };
+        Ok(ProtobufSchemaGenerator {
+            key_columns,
+            key_schema,
+            value_columns,
+            value_schema,
+        })
+    }
+
+    /// Returns the text of the `.proto` file that describes the value message.
+    pub fn value_schema(&self) -> &str {
+        &self.value_schema
+    }
+
+    /// Returns the text of the `.proto` file that describes the key message,
+    /// if the sink has a key.
+    pub fn key_schema(&self) -> Option<&str> {
+        self.key_schema.as_deref()
+    }
+}
+
+/// Manages encoding of Protobuf-encoded bytes.
+///
+/// Messages are prefixed with the Confluent wire format header if the schema
+/// for the message was published to a schema registry.
+#[derive(Debug)]
+pub struct ProtobufEncoder {
+    schema_generator: ProtobufSchemaGenerator,
+    key_schema_id: Option<i32>,
+    value_schema_id: Option<i32>,
+}
+
+impl ProtobufEncoder {
+    pub fn new(
+        schema_generator: ProtobufSchemaGenerator,
+        key_schema_id: Option<i32>,
+        value_schema_id: Option<i32>,
+    ) -> Self {
+        ProtobufEncoder {
+            schema_generator,
+            key_schema_id,
+            value_schema_id,
+        }
+    }
+}
+
+impl Encode for ProtobufEncoder {
+    fn get_format_name(&self) -> &str {
+        "protobuf"
+    }
+
+    fn encode_key_unchecked(&self, row: Row) -> Vec<u8> {
+        let columns = self
+            .schema_generator
+            .key_columns
+            .as_ref()
+            .expect("key schema must exist");
+        encode_message_unchecked(self.key_schema_id, row, columns)
+    }
+
+    fn encode_value_unchecked(&self, row: Row) -> Vec<u8> {
+        encode_message_unchecked(
+            self.value_schema_id,
+            row,
+            &self.schema_generator.value_columns,
+        )
+    }
+}
+
+fn encode_message_unchecked(
+    schema_id: Option<i32>,
+    row: Row,
+    columns: &[(ColumnName, ColumnType)],
+) -> Vec<u8> {
+    let mut buf = vec![];
+    if let Some(schema_id) = schema_id {
+        encode_protobuf_header(&mut buf, schema_id);
+    }
+    encode_fields(&mut buf, row.iter(), columns);
+    buf
+}
+
+fn encode_protobuf_header(buf: &mut Vec<u8>, schema_id: i32) {
+    // The Confluent wire format is a magic byte (0) and a 32-bit schema ID,
+    // followed by the path of indexes to the message within the schema. The
+    // path to the first message, `[0]`, is abbreviated to a single zero.
+    //
+    // https://docs.confluent.io/platform/current/schema-registry/serdes-develop/index.html#wire-format
+    buf.write_u8(0).expect("writing to vec cannot fail");
+    buf.write_i32::<NetworkEndian>(schema_id)
+        .expect("writing to vec cannot fail");
+    buf.write_u8(0).expect("writing to vec cannot fail");
+}
+
+/// Encodes `datums` as the fields of a message described by `columns`.
+///
+/// Null datums are omitted from the message.
+fn encode_fields<'a, I>(buf: &mut Vec<u8>, datums: I, columns: &[(ColumnName, ColumnType)])
+where
+    I: IntoIterator<Item = Datum<'a>>,
+{
+    for (number, (datum, (_name, typ))) in (1..).zip(datums.into_iter().zip(columns)) {
+        if datum.is_null() {
+            continue;
+        }
+        match &typ.scalar_type {
+            ty @ (ScalarType::Array(..) | ScalarType::Int2Vector | ScalarType::List { .. }) => {
+                let list = match ty {
+                    ScalarType::List { .. } => datum.unwrap_list(),
+                    _ => datum.unwrap_array().elements(),
+                };
+                let element_type = ty.unwrap_collection_element_type();
+                for datum in list.iter() {
+                    encode_value(buf, number, datum, element_type);
+                }
+            }
+            ScalarType::Map { value_type, .. } => {
+                for (key, datum) in datum.unwrap_map().iter() {
+                    let mut entry = vec![];
+                    encode_length_delimited(&mut entry, 1, key.as_bytes());
+                    if !datum.is_null() {
+                        encode_value(&mut entry, 2, datum, value_type);
+                    }
+                    encode_length_delimited(buf, number, &entry);
+                }
+            }
+            ty => encode_value(buf, number, datum, ty),
+        }
+    }
+}
+
+/// Encodes a single value of a singular or repeated field.
+///
+/// Protobuf cannot represent null elements of repeated fields, so those are
+/// encoded as the default value of the field's type.
+fn encode_value(buf: &mut Vec<u8>, number: u32, datum: Datum, ty: &ScalarType) {
+    if datum.is_null() {
+        match ty {
+            ScalarType::Float32 => encode_fixed32(buf, number, 0),
+            ScalarType::Float64 => encode_fixed64(buf, number, 0),
+            ScalarType::Bool
+            | ScalarType::Int16
+            | ScalarType::Int32
+            | ScalarType::Int64
+            | ScalarType::PgLegacyChar
+            | ScalarType::Oid
+            | ScalarType::RegClass
+            | ScalarType::RegProc
+            | ScalarType::RegType
+            | ScalarType::Date
+            | ScalarType::Time
+            | ScalarType::Timestamp
+            | ScalarType::TimestampTz => encode_varint_field(buf, number, 0),
+            _ => encode_length_delimited(buf, number, &[]),
+        }
+        return;
+    }
+    match ty {
+        ScalarType::Bool => encode_varint_field(buf, number, u64::from(datum.unwrap_bool())),
+        // Negative `int32` values are sign-extended to 64 bits, per the
+        // Protobuf encoding rules.
+        ScalarType::Int16 => {
+            encode_varint_field(buf, number, i64::from(datum.unwrap_int16()) as u64)
+        }
+        ScalarType::Int32 => {
+            encode_varint_field(buf, number, i64::from(datum.unwrap_int32()) as u64)
+        }
+        ScalarType::Int64 => encode_varint_field(buf, number, datum.unwrap_int64() as u64),
+        ScalarType::PgLegacyChar => {
+            encode_varint_field(buf, number, u64::from(datum.unwrap_uint8()))
+        }
+        ScalarType::Oid | ScalarType::RegClass | ScalarType::RegProc | ScalarType::RegType => {
+            encode_varint_field(buf, number, u64::from(datum.unwrap_uint32()))
+        }
+        ScalarType::Float32 => encode_fixed32(buf, number, datum.unwrap_float32().to_bits()),
+        ScalarType::Float64 => encode_fixed64(buf, number, datum.unwrap_float64().to_bits()),
+        ScalarType::Numeric { .. } => {
+            let s = datum.unwrap_numeric().0.to_standard_notation_string();
+            encode_length_delimited(buf, number, s.as_bytes())
+        }
+        ScalarType::Date => {
+            let days = (datum.unwrap_date() - NaiveDate::from_ymd(1970, 1, 1)).num_days();
+            encode_varint_field(buf, number, days as u64)
+        }
+        ScalarType::Time => {
+            let time = datum.unwrap_time();
+            let micros = i64::from(time.num_seconds_from_midnight()) * 1_000_000
+                + i64::from(time.nanosecond()) / 1_000;
+            encode_varint_field(buf, number, micros as u64)
+        }
+        ScalarType::Timestamp => {
+            let micros = timestamp_micros(datum.unwrap_timestamp());
+            encode_varint_field(buf, number, micros as u64)
+        }
+        ScalarType::TimestampTz => {
+            let micros = timestamp_micros(datum.unwrap_timestamptz().naive_utc());
+            encode_varint_field(buf, number, micros as u64)
+        }
+        ScalarType::Interval => {
+            let s = datum.unwrap_interval().to_string();
+            encode_length_delimited(buf, number, s.as_bytes())
+        }
+        ScalarType::Bytes => encode_length_delimited(buf, number, datum.unwrap_bytes()),
+        ScalarType::String | ScalarType::VarChar { .. } => {
+            encode_length_delimited(buf, number, datum.unwrap_str().as_bytes())
+        }
+        ScalarType::Char { length } => {
+            let s = mz_repr::adt::char::format_str_pad(datum.unwrap_str(), *length);
+            encode_length_delimited(buf, number, s.as_bytes())
+        }
+        ScalarType::Jsonb => {
+            let s = JsonbRef::from_datum(datum).to_string();
+            encode_length_delimited(buf, number, s.as_bytes())
+        }
+        ScalarType::Uuid => {
+            let s = datum.unwrap_uuid().to_string();
+            encode_length_delimited(buf, number, s.as_bytes())
+        }
+        ScalarType::Record { fields, .. } => {
+            let mut message = vec![];
+            encode_fields(&mut message, datum.unwrap_list().iter(), fields);
+            encode_length_delimited(buf, number, &message)
+        }
+        ScalarType::Array(..)
+        | ScalarType::Int2Vector
+        | ScalarType::List { .. }
+        | ScalarType::Map { .. } => {
+            unreachable!("nested collections are rejected by the schema generator")
+        }
+    }
+}
+
+fn timestamp_micros(ts: NaiveDateTime) -> i64 {
+    ts.timestamp() * 1_000_000 + i64::from(ts.timestamp_subsec_micros())
+}
+
+fn encode_varint_field(buf: &mut Vec<u8>, number: u32, value: u64) {
+    encode_key(number, WireType::Varint, buf);
+    encode_varint(value, buf);
+}
+
+fn encode_fixed32(buf: &mut Vec<u8>, number: u32, value: u32) {
+    encode_key(number, WireType::ThirtyTwoBit, buf);
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn encode_fixed64(buf: &mut Vec<u8>, number: u32, value: u64) {
+    encode_key(number, WireType::SixtyFourBit, buf);
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn encode_length_delimited(buf: &mut Vec<u8>, number: u32, value: &[u8]) {
+    encode_key(number, WireType::LengthDelimited, buf);
+    encode_varint(value.len() as u64, buf);
+    buf.extend_from_slice(value);
+}
+
+/// Builds the text of a `.proto` file that declares a single top-level
+/// message named `name` with the fields described by `columns`.
+fn build_schema(
+    name: &str,
+    columns: &[(ColumnName, ColumnType)],
+    custom_names: &HashMap<GlobalId, String>,
+) -> Result<String, anyhow::Error> {
+    let mut builder = SchemaBuilder {
+        custom_names,
+        names_seen: HashSet::new(),
+        next_record: 0,
+        messages: vec![],
+    };
+    let fields = builder.build_fields(columns)?;
+
+    let mut schema = String::new();
+    writeln!(schema, "syntax = \"proto3\";\n").unwrap();
+    writeln!(schema, "message {} {{", name).unwrap();
+    for (name, nested_fields) in &builder.messages {
+        writeln!(schema, "  message {} {{", name).unwrap();
+        for field in nested_fields {
+            writeln!(schema, "    {}", field).unwrap();
+        }
+        writeln!(schema, "  }}\n").unwrap();
+    }
+    for field in fields {
+        writeln!(schema, "  {}", field).unwrap();
+    }
+    writeln!(schema, "}}").unwrap();
+    Ok(schema)
+}
+
+struct SchemaBuilder<'a> {
+    custom_names: &'a HashMap<GlobalId, String>,
+    names_seen: HashSet<String>,
+    next_record: usize,
+    /// The names and field declarations of the messages that describe record
+    /// types, in the order they were discovered.
+    messages: Vec<(String, Vec<String>)>,
+}
+
+impl SchemaBuilder<'_> {
+    /// Returns the field declarations for a message with the given columns.
+    fn build_fields(
+        &mut self,
+        columns: &[(ColumnName, ColumnType)],
+    ) -> Result<Vec<String>, anyhow::Error> {
+        let mut field_names = HashSet::new();
+        let mut fields = vec![];
+        for (number, (name, typ)) in (1..).zip(columns) {
+            let field_name = field_name(name, &mut field_names);
+            let field = match &typ.scalar_type {
+                ty @ (ScalarType::Array(..) | ScalarType::Int2Vector | ScalarType::List { .. }) => {
+                    let element_type =
+                        self.element_type(name, ty.unwrap_collection_element_type())?;
+                    format!("repeated {} {} = {};", element_type, field_name, number)
+                }
+                ScalarType::Map { value_type, .. } => {
+                    let value_type = self.element_type(name, value_type)?;
+                    format!("map<string, {}> {} = {};", value_type, field_name, number)
+                }
+                // Message fields always track presence, so only scalars need
+                // to be marked optional to represent null values.
+                ty @ ScalarType::Record { .. } => {
+                    format!("{} {} = {};", self.type_name(ty)?, field_name, number)
+                }
+                ty => {
+                    let label = if typ.nullable { "optional " } else { "" };
+                    let ty = self.type_name(ty)?;
+                    format!("{}{} {} = {};", label, ty, field_name, number)
+                }
+            };
+            fields.push(field);
+        }
+        Ok(fields)
+    }
+
+    /// Returns the type of the elements of a repeated or map field, which
+    /// cannot themselves be repeated or maps.
+    fn element_type(
+        &mut self,
+        name: &ColumnName,
+        ty: &ScalarType,
+    ) -> Result<String, anyhow::Error> {
+        match ty {
+            ScalarType::Array(..)
+            | ScalarType::Int2Vector
+            | ScalarType::List { .. }
+            | ScalarType::Map { .. } => bail!(
+                "Protobuf sinks do not support nested lists, arrays, or maps, \
+                 as in column {}",
+                name.as_str().quoted()
+            ),
+            ty => self.type_name(ty),
+        }
+    }
+
+    /// Returns the name of the Protobuf type that represents `ty`, declaring
+    /// a nested message if `ty` is a record type that has not been seen.
+    fn type_name(&mut self, ty: &ScalarType) -> Result<String, anyhow::Error> {
+        let name = match ty {
+            ScalarType::Bool => "bool",
+            ScalarType::Int16 | ScalarType::Int32 | ScalarType::Date => "int32",
+            ScalarType::Int64
+            | ScalarType::Time
+            | ScalarType::Timestamp
+            | ScalarType::TimestampTz => "int64",
+            ScalarType::PgLegacyChar
+            | ScalarType::Oid
+            | ScalarType::RegClass
+            | ScalarType::RegProc
+            | ScalarType::RegType => "uint32",
+            ScalarType::Float32 => "float",
+            ScalarType::Float64 => "double",
+            ScalarType::Bytes => "bytes",
+            ScalarType::Numeric { .. }
+            | ScalarType::Interval
+            | ScalarType::String
+            | ScalarType::Char { .. }
+            | ScalarType::VarChar { .. }
+            | ScalarType::Jsonb
+            | ScalarType::Uuid => "string",
+            ScalarType::Record { fields, custom_id } => {
+                let custom_name = custom_id.as_ref().and_then(|id| self.custom_names.get(id));
+                let name = match custom_name {
+                    Some(name) => {
+                        let mut chars = name.chars();
+                        let name = match chars.next() {
+                            Some(c) => c.to_ascii_uppercase().to_string() + chars.as_str(),
+                            None => String::new(),
+                        };
+                        if !self.names_seen.insert(name.clone()) {
+                            return Ok(name);
+                        }
+                        name
+                    }
+                    None => {
+                        let name = format!("Record{}", self.next_record);
+                        self.next_record += 1;
+                        name
+                    }
+                };
+                let fields = self.build_fields(fields)?;
+                self.messages.push((name.clone(), fields));
+                return Ok(name);
+            }
+            ScalarType::Array(..)
+            | ScalarType::Int2Vector
+            | ScalarType::List { .. }
+            | ScalarType::Map { .. } => unreachable!("collections are handled by the caller"),
+        };
+        Ok(name.into())
+    }
+}
+
+/// Converts a column name into a valid, unique Protobuf field name by
+/// replacing invalid characters with underscores.
+fn field_name(name: &ColumnName, seen: &mut HashSet<String>) -> String {
+    let mut stem: String = name
+        .as_str()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !stem.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        stem.insert(0, '_');
+    }
+    let mut field_name = stem.clone();
+    let mut i = 1;
+    while !seen.insert(field_name.clone()) {
+        field_name = format!("{}_{}", stem, i);
+        i += 1;
+    }
+    field_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_schema() {
+        let desc = RelationDesc::empty()
+            .with_column("id", ScalarType::Int32.nullable(false))
+            .with_column("?column?", ScalarType::String.nullable(true))
+            .with_column(
+                "tags",
+                ScalarType::List {
+                    element_type: Box::new(ScalarType::String),
+                    custom_id: None,
+                }
+                .nullable(true),
+            );
+        let generator = ProtobufSchemaGenerator::new(None, desc, true, false).unwrap();
+        assert_eq!(
+            generator.value_schema(),
+            "syntax = \"proto3\";
+
+message Value {
+  message Row {
+    int32 id = 1;
+    optional string _column_ = 2;
+    repeated string tags = 3;
+  }
+
+  Row before = 1;
+  Row after = 2;
+}
+"
+        );
+        assert_eq!(generator.key_schema(), None);
+    }
+
+    #[test]
+    fn test_nested_collections_rejected() {
+        let list = ScalarType::List {
+            element_type: Box::new(ScalarType::Int64),
+            custom_id: None,
+        };
+        let desc = RelationDesc::empty().with_column(
+            "a",
+            ScalarType::List {
+                element_type: Box::new(list),
+                custom_id: None,
+            }
+            .nullable(false),
+        );
+        let err = ProtobufSchemaGenerator::new(None, desc, false, false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Protobuf sinks do not support nested lists, arrays, or maps, as in column \"a\""
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        let desc = RelationDesc::empty()
+            .with_column("a", ScalarType::Int32.nullable(false))
+            .with_column("b", ScalarType::String.nullable(true))
+            .with_column("c", ScalarType::Int64.nullable(true));
+        let generator =
+            ProtobufSchemaGenerator::new(Some(desc.clone()), desc, false, false).unwrap();
+        let encoder = ProtobufEncoder::new(generator, None, Some(7));
+        let row = Row::pack_slice(&[Datum::Int32(150), Datum::String("hi"), Datum::Null]);
+        assert_eq!(
+            encoder.encode_key_unchecked(row.clone()),
+            vec![0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i']
+        );
+        assert_eq!(
+            encoder.encode_value_unchecked(row),
+            vec![0, 0, 0, 0, 7, 0, 0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i']
+        );
+    }
+}
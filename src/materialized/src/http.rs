@@ -11,7 +11,8 @@
 //!
 //! materialized embeds an HTTP server for introspection into the running
 //! process. At the moment, its primary exports are Prometheus metrics, heap
-//! profiles, and catalog dumps.
+//! profiles, and catalog dumps. It also accepts the requests that are
+//! ingested by webhook sources.
 
 // Axum handlers must use async, but often don't actually use `await`.
 #![allow(clippy::unused_async)]
@@ -55,6 +56,7 @@ mod memory;
 mod metrics;
 mod root;
 mod sql;
+mod webhook;
 
 const SYSTEM_USER: &str = "mz_system";
 
@@ -108,6 +110,14 @@ impl Server {
     ) -> Server {
         let tls_mode = tls.as_ref().map(|tls| tls.mode);
         let frontegg = Arc::new(frontegg);
+        // Webhook requests are validated by their signatures rather than by
+        // the authentication middleware.
+        let webhook_router = Router::new()
+            .route(
+                "/api/webhook/:database/:schema/:name",
+                routing::post(webhook::handle_webhook),
+            )
+            .layer(Extension(coord_client.clone()));
         let router = Router::new()
             .route("/", routing::get(root::handle_home))
             .route(
@@ -127,6 +137,7 @@ impl Server {
                 let frontegg = Arc::clone(&frontegg);
                 async move { auth(req, next, tls_mode, &frontegg).await }
             }))
+            .merge(webhook_router)
             .layer(
                 CorsLayer::new()
                     .allow_credentials(false)
@@ -0,0 +1,52 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::BTreeMap;
+
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::Extension;
+use bytes::Bytes;
+use http::{HeaderMap, StatusCode};
+
+use mz_coord::WebhookError;
+
+/// Appends the body of a request to a webhook source.
+///
+/// Requests are not authenticated like other requests to the HTTP server.
+/// Instead, webhook sources that are created with a secret require that each
+/// request be signed with it.
+pub async fn handle_webhook(
+    Path((database, schema, name)): Path<(String, String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+    Extension(client): Extension<mz_coord::Client>,
+) -> impl IntoResponse {
+    // Header names are already lowercase. Headers whose values are not valid
+    // UTF-8 are dropped.
+    let headers: BTreeMap<_, _> = headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+        .collect();
+    match client
+        .append_webhook(database, schema, name, headers, body)
+        .await
+    {
+        Ok(()) => Ok(StatusCode::OK),
+        Err(e) => {
+            let status = match &e {
+                WebhookError::UnknownSource(_) => StatusCode::NOT_FOUND,
+                WebhookError::InvalidSignature(_) => StatusCode::UNAUTHORIZED,
+                WebhookError::InvalidBody(_) => StatusCode::BAD_REQUEST,
+                WebhookError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            Err((status, e.to_string()))
+        }
+    }
+}
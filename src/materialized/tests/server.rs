@@ -252,6 +252,118 @@ fn test_http_sql() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// Test the webhook endpoint of the HTTP server.
+#[test]
+fn test_http_webhook() -> Result<(), Box<dyn Error>> {
+    mz_ore::test::init_logging();
+    let server = util::start_server(util::Config::default())?;
+    let mut client = server.connect(postgres::NoTls)?;
+    let url = |name: &str| {
+        Url::parse(&format!(
+            "http://{}/api/webhook/materialize/public/{}",
+            server.inner.local_addr(),
+            name
+        ))
+    };
+
+    client.batch_execute("CREATE TABLE t (a int)")?;
+    client.batch_execute("CREATE SOURCE plain FROM WEBHOOK FORMAT TEXT INCLUDE HEADERS")?;
+    client.batch_execute("CREATE SECRET hook_key AS 'sekrit'")?;
+    client
+        .batch_execute("CREATE SOURCE signed FROM WEBHOOK VALIDATE SECRET hook_key FORMAT JSON")?;
+    let sign = |client: &mut postgres::Client, body: &str| -> Result<String, postgres::Error> {
+        client
+            .query_one(
+                "SELECT encode(hmac($1, 'sekrit', 'sha256'), 'hex')",
+                &[&body],
+            )
+            .map(|row| row.get(0))
+    };
+
+    struct TestCase {
+        source: &'static str,
+        signature: Option<String>,
+        body: Vec<u8>,
+        status: StatusCode,
+    }
+
+    let tests = vec![
+        // Sources without a secret accept any request.
+        TestCase {
+            source: "plain",
+            signature: None,
+            body: b"hello".to_vec(),
+            status: StatusCode::OK,
+        },
+        // Bodies must be decodable in the format of the source.
+        TestCase {
+            source: "plain",
+            signature: None,
+            body: vec![0xff],
+            status: StatusCode::BAD_REQUEST,
+        },
+        // Only webhook sources accept requests.
+        TestCase {
+            source: "noexist",
+            signature: None,
+            body: b"hello".to_vec(),
+            status: StatusCode::NOT_FOUND,
+        },
+        TestCase {
+            source: "t",
+            signature: None,
+            body: b"hello".to_vec(),
+            status: StatusCode::NOT_FOUND,
+        },
+        // Sources with a secret require a valid signature.
+        TestCase {
+            source: "signed",
+            signature: None,
+            body: br#"{"a": 1}"#.to_vec(),
+            status: StatusCode::UNAUTHORIZED,
+        },
+        TestCase {
+            source: "signed",
+            signature: Some(sign(&mut client, r#"{"a": 2}"#)?),
+            body: br#"{"a": 1}"#.to_vec(),
+            status: StatusCode::UNAUTHORIZED,
+        },
+        TestCase {
+            source: "signed",
+            signature: Some(format!("sha256={}", sign(&mut client, r#"{"a": 1}"#)?)),
+            body: br#"{"a": 1}"#.to_vec(),
+            status: StatusCode::OK,
+        },
+        TestCase {
+            source: "signed",
+            signature: Some(sign(&mut client, "{")?),
+            body: b"{".to_vec(),
+            status: StatusCode::BAD_REQUEST,
+        },
+    ];
+
+    for tc in tests {
+        let mut req = Client::new()
+            .post(url(tc.source)?)
+            .header("x-test", "test")
+            .body(tc.body);
+        if let Some(signature) = tc.signature {
+            req = req.header("x-signature", signature);
+        }
+        assert_eq!(req.send()?.status(), tc.status);
+    }
+
+    let rows = client.query("SELECT text, headers -> 'x-test' FROM plain", &[])?;
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, String>(0), "hello");
+    assert_eq!(rows[0].get::<_, String>(1), "test");
+    let rows = client.query("SELECT data::text FROM signed", &[])?;
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, String>(0), r#"{"a":1}"#);
+
+    Ok(())
+}
+
 // Test that the server properly handles cancellation requests.
 #[test]
 fn test_cancel_long_running_query() -> Result<(), Box<dyn Error>> {
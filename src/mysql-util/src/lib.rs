@@ -0,0 +1,565 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Provides convenience functions for working with upstream MySQL databases for sources.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+use mysql_async::prelude::Queryable;
+use mysql_async::{Conn, Opts};
+use uuid::Uuid;
+
+/// The schema of a single column
+#[derive(Eq, PartialEq)]
+pub struct MySqlColumnInfo {
+    pub name: String,
+    /// The name of the type of the column, e.g. `int`
+    pub data_type: String,
+    /// The full type of the column, e.g. `int unsigned` or `decimal(10,2)`
+    pub column_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+}
+
+/// Information about a remote table
+#[derive(Eq, PartialEq)]
+pub struct MySqlTableInfo {
+    /// The database the table belongs to
+    pub database: String,
+    /// The name of the table
+    pub name: String,
+    /// The schema of each column, in order
+    pub columns: Vec<MySqlColumnInfo>,
+}
+
+/// Connects to the upstream database
+pub async fn connect(conn: &str) -> Result<Conn, anyhow::Error> {
+    let opts = Opts::from_url(conn)?;
+    Ok(Conn::new(opts).await?)
+}
+
+/// Verifies that the upstream server writes a binary log that sources can replicate from, i.e.
+/// a row-based log with full row images whose transactions have global identifiers.
+pub async fn validate_binlog_config(conn: &mut Conn) -> Result<(), anyhow::Error> {
+    for (variable, expected) in [
+        ("log_bin", "1"),
+        ("binlog_format", "ROW"),
+        ("binlog_row_image", "FULL"),
+        ("gtid_mode", "ON"),
+    ] {
+        let value: Option<String> = conn
+            .query_first(format!("SELECT @@GLOBAL.{}", variable))
+            .await?;
+        let value = value.unwrap_or_default();
+        if !value.eq_ignore_ascii_case(expected) {
+            bail!(
+                "MySQL sources require {} to be {}, but it is {}",
+                variable,
+                expected,
+                value
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Fetches the schema information of all base tables in a database of an upstream MySQL server,
+/// given a connection string and the name of the database.
+///
+/// # Errors
+///
+/// - Invalid connection string, user information, or user permissions.
+/// - Upstream database does not exist.
+pub async fn table_info(conn: &str, database: &str) -> Result<Vec<MySqlTableInfo>, anyhow::Error> {
+    let mut conn = connect(conn).await?;
+
+    let exists: Option<u8> = conn
+        .exec_first(
+            "SELECT 1 FROM information_schema.SCHEMATA WHERE SCHEMA_NAME = ?",
+            (database,),
+        )
+        .await?;
+    if exists.is_none() {
+        bail!("database {:?} does not exist", database);
+    }
+
+    let columns: Vec<(String, String, String, String, String, String)> = conn
+        .exec(
+            "SELECT
+                c.TABLE_NAME, c.COLUMN_NAME, c.DATA_TYPE, c.COLUMN_TYPE, c.IS_NULLABLE,
+                c.COLUMN_KEY
+            FROM
+                information_schema.COLUMNS AS c
+                JOIN information_schema.TABLES AS t ON
+                        c.TABLE_SCHEMA = t.TABLE_SCHEMA AND c.TABLE_NAME = t.TABLE_NAME
+            WHERE
+                c.TABLE_SCHEMA = ? AND t.TABLE_TYPE = 'BASE TABLE'
+            ORDER BY c.TABLE_NAME, c.ORDINAL_POSITION",
+            (database,),
+        )
+        .await?;
+    conn.disconnect().await?;
+
+    let mut table_infos: Vec<MySqlTableInfo> = vec![];
+    for (table, name, data_type, column_type, nullable, key) in columns {
+        let column = MySqlColumnInfo {
+            name,
+            data_type: data_type.to_lowercase(),
+            column_type: column_type.to_lowercase(),
+            nullable: nullable == "YES",
+            primary_key: key == "PRI",
+        };
+        match table_infos.last_mut() {
+            Some(info) if info.name == table => info.columns.push(column),
+            _ => table_infos.push(MySqlTableInfo {
+                database: database.into(),
+                name: table,
+                columns: vec![column],
+            }),
+        }
+    }
+    Ok(table_infos)
+}
+
+/// A set of global transaction identifiers (GTIDs), in the format of the
+/// `gtid_executed` system variable, e.g.
+/// `3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5:7`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GtidSet {
+    /// The half-open, disjoint, and sorted intervals of transaction numbers
+    /// in the set, by the UUID of the server that the transactions originated
+    /// on
+    intervals: BTreeMap<Uuid, Vec<(u64, u64)>>,
+}
+
+impl GtidSet {
+    /// Adds the transaction `gno` that originated on the server `sid`.
+    pub fn insert(&mut self, sid: Uuid, gno: u64) {
+        let intervals = self.intervals.entry(sid).or_default();
+        // The first interval that contains or ends right before `gno`, or
+        // that starts after it.
+        let i = intervals.partition_point(|(_, end)| *end < gno);
+        match intervals.get(i).copied() {
+            Some((start, end)) if start <= gno && gno < end => (),
+            Some((_, end)) if end == gno => {
+                intervals[i].1 = gno + 1;
+                if matches!(intervals.get(i + 1), Some((start, _)) if *start == gno + 1) {
+                    let (_, end) = intervals.remove(i + 1);
+                    intervals[i].1 = end;
+                }
+            }
+            Some((start, _)) if start == gno + 1 => intervals[i].0 = gno,
+            _ => intervals.insert(i, (gno, gno + 1)),
+        }
+    }
+
+    /// Adds the transactions `1` through `gno` that originated on the server
+    /// `sid`.
+    pub fn insert_through(&mut self, sid: Uuid, gno: u64) {
+        let intervals = self.intervals.entry(sid).or_default();
+        intervals.retain(|(_, end)| *end > gno + 1);
+        match intervals.first_mut() {
+            Some((start, _)) if *start <= gno + 1 => *start = 1,
+            _ => intervals.insert(0, (1, gno + 1)),
+        }
+    }
+
+    /// Reports whether the set contains no transactions.
+    pub fn is_empty(&self) -> bool {
+        self.intervals
+            .values()
+            .all(|intervals| intervals.is_empty())
+    }
+
+    /// Returns the half-open intervals of transaction numbers in the set, by
+    /// the UUID of the server that the transactions originated on.
+    pub fn intervals(&self) -> impl Iterator<Item = (&Uuid, &[(u64, u64)])> {
+        self.intervals
+            .iter()
+            .map(|(sid, intervals)| (sid, intervals.as_slice()))
+    }
+}
+
+impl FromStr for GtidSet {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = GtidSet::default();
+        for gtids in s.split(',').map(str::trim).filter(|g| !g.is_empty()) {
+            let mut parts = gtids.split(':');
+            let sid = parts.next().expect("split returns at least one part");
+            let sid = Uuid::parse_str(sid).map_err(|e| anyhow!("invalid GTID set {}: {}", s, e))?;
+            let intervals = set.intervals.entry(sid).or_default();
+            for interval in parts {
+                let (start, end) = match interval.split_once('-') {
+                    Some((start, end)) => (start.parse::<u64>(), end.parse::<u64>()),
+                    None => (interval.parse(), interval.parse()),
+                };
+                match (start, end) {
+                    (Ok(start), Ok(end)) if start <= end => intervals.push((start, end + 1)),
+                    _ => bail!("invalid GTID set {}: invalid interval {}", s, interval),
+                }
+            }
+            intervals.sort();
+        }
+        Ok(set)
+    }
+}
+
+impl fmt::Display for GtidSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (sid, intervals)) in self.intervals.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}", sid)?;
+            for (start, end) in intervals {
+                if *end == start + 1 {
+                    write!(f, ":{}", start)?;
+                } else {
+                    write!(f, ":{}-{}", start, end - 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An object that a DDL statement alters, drops, renames or truncates
+#[derive(Debug, Eq, PartialEq)]
+pub enum DdlTarget {
+    /// A table, qualified by its database if the statement names one
+    Table {
+        database: Option<String>,
+        name: String,
+    },
+    /// A database and all of its tables
+    Database(String),
+}
+
+/// Returns the objects whose schema or contents a statement of the binary log
+/// changes other than by row events, i.e. the tables that it alters, drops,
+/// renames or truncates and the databases that it drops.
+///
+/// Statements of other kinds have no targets. Only the names of the affected
+/// objects are considered, so that e.g. columns or foreign keys that share
+/// their name with another table do not make it a target.
+pub fn ddl_targets(query: &str) -> Result<Vec<DdlTarget>, anyhow::Error> {
+    let mut tokens = DdlTokens::new(query);
+    let mut targets = vec![];
+    let mut table_list = |tokens: &mut DdlTokens, renames: bool| -> Result<(), anyhow::Error> {
+        loop {
+            targets.push(tokens.table()?);
+            if renames {
+                tokens.expect_keyword("to")?;
+                targets.push(tokens.table()?);
+            }
+            if !tokens.next_if(|t| *t == DdlToken::Punct(',')) {
+                return Ok(());
+            }
+        }
+    };
+    match tokens.next() {
+        Some(t) if t.is_keyword("alter") => {
+            while tokens.next_if(|t| t.is_keyword("online") || t.is_keyword("ignore")) {}
+            if tokens.next_if(|t| t.is_keyword("table")) {
+                targets.push(tokens.table()?);
+            }
+        }
+        Some(t) if t.is_keyword("drop") => {
+            tokens.next_if(|t| t.is_keyword("temporary"));
+            match tokens.next() {
+                Some(t) if t.is_keyword("table") || t.is_keyword("tables") => {
+                    if tokens.next_if(|t| t.is_keyword("if")) {
+                        tokens.expect_keyword("exists")?;
+                    }
+                    table_list(&mut tokens, false)?;
+                }
+                Some(t) if t.is_keyword("database") || t.is_keyword("schema") => {
+                    if tokens.next_if(|t| t.is_keyword("if")) {
+                        tokens.expect_keyword("exists")?;
+                    }
+                    targets.push(DdlTarget::Database(tokens.ident()?));
+                }
+                _ => (),
+            }
+        }
+        Some(t) if t.is_keyword("rename") => {
+            if tokens.next_if(|t| t.is_keyword("table") || t.is_keyword("tables")) {
+                table_list(&mut tokens, true)?;
+            }
+        }
+        Some(t) if t.is_keyword("truncate") => {
+            tokens.next_if(|t| t.is_keyword("table"));
+            targets.push(tokens.table()?);
+        }
+        _ => (),
+    }
+    Ok(targets)
+}
+
+/// A token of a MySQL statement
+#[derive(Debug, Eq, PartialEq)]
+enum DdlToken {
+    /// An unquoted word, i.e. a keyword or an identifier
+    Word(String),
+    /// An identifier quoted in backticks
+    QuotedIdent(String),
+    /// A string literal
+    String,
+    /// Any other character
+    Punct(char),
+}
+
+impl DdlToken {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, DdlToken::Word(w) if w.eq_ignore_ascii_case(keyword))
+    }
+}
+
+/// The tokens of a MySQL statement, without whitespace and comments
+struct DdlTokens<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    peeked: Option<Option<DdlToken>>,
+}
+
+impl<'a> DdlTokens<'a> {
+    fn new(query: &'a str) -> Self {
+        DdlTokens {
+            chars: query.chars().peekable(),
+            peeked: None,
+        }
+    }
+
+    fn next(&mut self) -> Option<DdlToken> {
+        match self.peeked.take() {
+            Some(token) => token,
+            None => self.lex(),
+        }
+    }
+
+    /// Consumes the next token if it satisfies `f`, and reports whether it did.
+    fn next_if(&mut self, f: impl FnOnce(&DdlToken) -> bool) -> bool {
+        let token = match self.peeked.take() {
+            Some(token) => token,
+            None => self.lex(),
+        };
+        match token {
+            Some(token) if f(&token) => true,
+            token => {
+                self.peeked = Some(token);
+                false
+            }
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), anyhow::Error> {
+        match self.next() {
+            Some(t) if t.is_keyword(keyword) => Ok(()),
+            t => bail!("expected {}, found {:?}", keyword.to_uppercase(), t),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, anyhow::Error> {
+        match self.next() {
+            Some(DdlToken::Word(ident)) | Some(DdlToken::QuotedIdent(ident)) => Ok(ident),
+            t => bail!("expected identifier, found {:?}", t),
+        }
+    }
+
+    /// Consumes a possibly qualified table name
+    fn table(&mut self) -> Result<DdlTarget, anyhow::Error> {
+        let name = self.ident()?;
+        if self.next_if(|t| *t == DdlToken::Punct('.')) {
+            Ok(DdlTarget::Table {
+                database: Some(name),
+                name: self.ident()?,
+            })
+        } else {
+            Ok(DdlTarget::Table {
+                database: None,
+                name,
+            })
+        }
+    }
+
+    fn lex(&mut self) -> Option<DdlToken> {
+        loop {
+            let c = self.chars.next()?;
+            match c {
+                c if c.is_whitespace() => (),
+                '#' => self.skip_line(),
+                '-' if self.chars.peek() == Some(&'-') => self.skip_line(),
+                '/' if self.chars.peek() == Some(&'*') => {
+                    self.chars.next();
+                    let mut prev = None;
+                    for c in self.chars.by_ref() {
+                        if prev == Some('*') && c == '/' {
+                            break;
+                        }
+                        prev = Some(c);
+                    }
+                }
+                '`' => return Some(DdlToken::QuotedIdent(self.quoted('`'))),
+                '\'' | '"' => {
+                    self.quoted(c);
+                    return Some(DdlToken::String);
+                }
+                c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                    let mut word = String::from(c);
+                    while let Some(c) = self
+                        .chars
+                        .next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+                    {
+                        word.push(c);
+                    }
+                    return Some(DdlToken::Word(word));
+                }
+                c => return Some(DdlToken::Punct(c)),
+            }
+        }
+    }
+
+    fn skip_line(&mut self) {
+        for c in self.chars.by_ref() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    /// Consumes the rest of a token quoted by `quote`, in which the quote is
+    /// escaped by doubling it, and returns its contents
+    fn quoted(&mut self, quote: char) -> String {
+        let mut contents = String::new();
+        while let Some(c) = self.chars.next() {
+            if c == quote {
+                if self.chars.next_if_eq(&quote).is_none() {
+                    break;
+                }
+            } else if c == '\\' && quote != '`' {
+                if let Some(c) = self.chars.next() {
+                    contents.push(c);
+                }
+                continue;
+            }
+            contents.push(c);
+        }
+        contents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SID: &str = "3e11fa47-71ca-11e1-9e33-c80aa9429562";
+
+    #[test]
+    fn test_gtid_set_parse() {
+        let set: GtidSet = format!("{SID}:1-5:7,\n4e11fa47-71ca-11e1-9e33-c80aa9429562:3")
+            .parse()
+            .unwrap();
+        assert_eq!(
+            set.to_string(),
+            format!("{SID}:1-5:7,4e11fa47-71ca-11e1-9e33-c80aa9429562:3")
+        );
+        assert_eq!("".parse::<GtidSet>().unwrap(), GtidSet::default());
+        assert!(format!("{SID}:5-1").parse::<GtidSet>().is_err());
+        assert!("not-a-uuid:1".parse::<GtidSet>().is_err());
+    }
+
+    #[test]
+    fn test_gtid_set_insert() {
+        let sid = Uuid::parse_str(SID).unwrap();
+        let mut set: GtidSet = format!("{SID}:2-3:7").parse().unwrap();
+        for (gno, expected) in [
+            (3, "2-3:7"),
+            (4, "2-4:7"),
+            (1, "1-4:7"),
+            (6, "1-4:6-7"),
+            (5, "1-7"),
+            (10, "1-7:10"),
+        ] {
+            set.insert(sid, gno);
+            assert_eq!(set.to_string(), format!("{SID}:{expected}"));
+        }
+    }
+
+    #[test]
+    fn test_gtid_set_insert_through() {
+        let sid = Uuid::parse_str(SID).unwrap();
+        let mut set = GtidSet::default();
+        assert!(set.is_empty());
+        for (gno, expected) in [(3, "1-3"), (2, "1-3"), (5, "1-5")] {
+            set.insert_through(sid, gno);
+            assert_eq!(set.to_string(), format!("{SID}:{expected}"));
+        }
+        let mut set: GtidSet = format!("{SID}:3-4:7:9-10").parse().unwrap();
+        set.insert_through(sid, 7);
+        assert_eq!(set.to_string(), format!("{SID}:1-7:9-10"));
+        set.insert_through(sid, 8);
+        assert_eq!(set.to_string(), format!("{SID}:1-10"));
+    }
+
+    #[test]
+    fn test_ddl_targets() {
+        fn table(database: Option<&str>, name: &str) -> DdlTarget {
+            DdlTarget::Table {
+                database: database.map(String::from),
+                name: name.into(),
+            }
+        }
+
+        for (query, expected) in [
+            ("ALTER TABLE t ADD COLUMN c int", vec![table(None, "t")]),
+            (
+                "alter table `db`.`weird``name` add column `t2` int",
+                vec![table(Some("db"), "weird`name")],
+            ),
+            (
+                "ALTER TABLE t ADD FOREIGN KEY (a) REFERENCES other (a)",
+                vec![table(None, "t")],
+            ),
+            (
+                "DROP TABLE IF EXISTS `t1`, db.t2 /* generated by server */",
+                vec![table(None, "t1"), table(Some("db"), "t2")],
+            ),
+            (
+                "RENAME TABLE t1 TO t2, db.t3 TO db.t4",
+                vec![
+                    table(None, "t1"),
+                    table(None, "t2"),
+                    table(Some("db"), "t3"),
+                    table(Some("db"), "t4"),
+                ],
+            ),
+            ("TRUNCATE t", vec![table(None, "t")]),
+            (
+                "/* comment */ truncate table db.t",
+                vec![table(Some("db"), "t")],
+            ),
+            (
+                "DROP DATABASE IF EXISTS db",
+                vec![DdlTarget::Database("db".into())],
+            ),
+            ("BEGIN", vec![]),
+            ("CREATE TABLE t (c int)", vec![]),
+            ("ALTER USER 'u'@'%' IDENTIFIED BY 'alter table t'", vec![]),
+            ("DROP INDEX i ON t", vec![]),
+        ] {
+            assert_eq!(ddl_targets(query).unwrap(), expected, "{}", query);
+        }
+        assert!(ddl_targets("DROP TABLE").is_err());
+        assert!(ddl_targets("RENAME TABLE t1 t2").is_err());
+    }
+}
@@ -7,7 +7,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-//! Provides convenience functions for working with upstream Postgres sources from the `sql` package.
+//! Provides convenience functions for working with upstream Postgres databases for sources and sinks.
 
 use anyhow::{anyhow, bail};
 use openssl::ssl::{SslConnector, SslFiletype, SslMethod, SslVerifyMode};
@@ -41,6 +41,24 @@ pub struct TableInfo {
     pub schema: Vec<PgColumn>,
 }
 
+/// Information about a remote table that a sink writes to
+#[derive(Eq, PartialEq)]
+pub struct SinkTableInfo {
+    /// The namespace the table belongs to
+    pub namespace: String,
+    /// The name of the table
+    pub name: String,
+    /// The name and type of each column, in order, with types as rendered by
+    /// `format_type`
+    pub columns: Vec<(String, String)>,
+    /// The columns of each of the table's unique indexes
+    pub unique_keys: Vec<Vec<String>>,
+}
+
+/// The name of the table, in the namespace of a sink's table, that records
+/// the latest timestamp that each sink has applied.
+pub const SINK_PROGRESS_TABLE: &str = "mz_sink_progress";
+
 /// Creates a TLS connector for the given [`Config`].
 pub fn make_tls(config: &Config) -> Result<MakeTlsConnector, anyhow::Error> {
     let mut builder = SslConnector::builder(SslMethod::tls_client())?;
@@ -227,6 +245,88 @@ pub async fn drop_replication_slots(conn: &str, slots: &[String]) -> Result<(),
     Ok(())
 }
 
+/// Connects to the upstream database
+pub async fn connect(conn: &str) -> Result<Client, anyhow::Error> {
+    let config: Config = conn.parse()?;
+    let tls = make_tls(&config)?;
+    let (client, connection) = config
+        .connect_timeout(Duration::from_secs(30))
+        .connect(tls)
+        .await?;
+    task::spawn(|| format!("postgres_connect:{conn}"), connection);
+    Ok(client)
+}
+
+/// Fetches the namespace, columns, and unique keys of the table that a sink
+/// writes to.
+///
+/// # Errors
+///
+/// - Invalid connection string, user information, or user permissions.
+/// - Upstream table does not exist.
+pub async fn sink_table_info(conn: &str, table: &str) -> Result<SinkTableInfo, anyhow::Error> {
+    let client = connect(conn).await?;
+
+    let row = client
+        .query_opt(
+            "SELECT c.oid, n.nspname, c.relname
+            FROM pg_catalog.pg_class AS c
+            JOIN pg_catalog.pg_namespace AS n ON c.relnamespace = n.oid
+            WHERE c.oid = pg_catalog.to_regclass($1)",
+            &[&table],
+        )
+        .await?
+        .ok_or_else(|| anyhow!("table {:?} does not exist", table))?;
+    let rel_id: u32 = row.get("oid");
+    let namespace: String = row.get("nspname");
+    let name: String = row.get("relname");
+
+    let columns = client
+        .query(
+            "SELECT a.attname, pg_catalog.format_type(a.atttypid, a.atttypmod) AS type
+            FROM pg_catalog.pg_attribute a
+            WHERE a.attnum > 0::pg_catalog.int2
+                AND NOT a.attisdropped
+                AND a.attrelid = $1
+            ORDER BY a.attnum",
+            &[&rel_id],
+        )
+        .await?
+        .into_iter()
+        .map(|row| (row.get("attname"), row.get("type")))
+        .collect();
+
+    let unique_keys = client
+        .query(
+            "SELECT pg_catalog.array_agg(a.attname::pg_catalog.text) AS columns
+            FROM pg_catalog.pg_index i
+            JOIN pg_catalog.pg_attribute a
+                ON a.attrelid = i.indrelid AND a.attnum = ANY (i.indkey)
+            WHERE i.indrelid = $1
+                AND i.indisunique
+                AND i.indpred IS NULL
+                AND i.indexprs IS NULL
+            GROUP BY i.indexrelid",
+            &[&rel_id],
+        )
+        .await?
+        .into_iter()
+        .map(|row| row.get("columns"))
+        .collect();
+
+    Ok(SinkTableInfo {
+        namespace,
+        name,
+        columns,
+        unique_keys,
+    })
+}
+
+/// Quotes `ident` for use as an identifier in a SQL statement.
+pub fn escape_identifier(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
 /// Starts a replication connection to the upstream database
 pub async fn connect_replication(conn: &str) -> Result<Client, anyhow::Error> {
     let mut config: Config = conn.parse()?;
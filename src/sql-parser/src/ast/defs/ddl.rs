@@ -28,7 +28,7 @@ use std::path::PathBuf;
 use enum_kinds::EnumKind;
 
 use crate::ast::display::{self, AstDisplay, AstFormatter};
-use crate::ast::{AstInfo, Expr, Ident, UnresolvedObjectName, WithOption};
+use crate::ast::{AstInfo, Expr, Ident, UnresolvedObjectName, Value, WithOption};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Schema {
@@ -94,17 +94,31 @@ impl_display_t!(AvroSchema);
 pub enum ProtobufSchema<T: AstInfo> {
     Csr {
         csr_connector: CsrConnectorProto<T>,
+        /// The fully qualified name of the message to decode, if the schema
+        /// in the registry defines more than one message.
+        message_name: Option<String>,
     },
     InlineSchema {
         message_name: String,
         schema: Schema,
     },
+    /// No schema. Only valid for sinks, which derive the schema from the
+    /// sinked relation.
+    Derived,
 }
 
 impl<T: AstInfo> AstDisplay for ProtobufSchema<T> {
     fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
         match self {
-            Self::Csr { csr_connector } => {
+            Self::Csr {
+                csr_connector,
+                message_name,
+            } => {
+                if let Some(message_name) = message_name {
+                    f.write_str("MESSAGE '");
+                    f.write_node(&display::escape_single_quote_string(message_name));
+                    f.write_str("' ");
+                }
                 f.write_node(csr_connector);
             }
             Self::InlineSchema {
@@ -116,6 +130,7 @@ impl<T: AstInfo> AstDisplay for ProtobufSchema<T> {
                 f.write_str("' USING ");
                 f.write_str(schema);
             }
+            Self::Derived => {}
         }
     }
 }
@@ -321,8 +336,19 @@ pub enum Format<T: AstInfo> {
         columns: CsvColumns,
         delimiter: char,
     },
-    Json,
+    Json {
+        /// `(name type, ...)`: the top-level fields of each JSON object to
+        /// project out as typed columns. If empty, the whole document is
+        /// decoded into a single `jsonb` column.
+        columns: Vec<ColumnDef<T>>,
+    },
     Text,
+    Parquet {
+        /// `(name type, ...)`: the columns to decode from each Parquet file.
+        /// Only valid for sources, which derive the columns from the footer of
+        /// a file if they are not specified.
+        columns: Vec<ColumnDef<T>>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -437,6 +463,7 @@ impl<T: AstInfo> AstDisplay for Format<T> {
                 f.write_str("AVRO ");
                 f.write_node(inner);
             }
+            Self::Protobuf(ProtobufSchema::Derived) => f.write_str("PROTOBUF"),
             Self::Protobuf(inner) => {
                 f.write_str("PROTOBUF ");
                 f.write_node(inner);
@@ -456,8 +483,23 @@ impl<T: AstInfo> AstDisplay for Format<T> {
                     f.write_str("'");
                 }
             }
-            Self::Json => f.write_str("JSON"),
+            Self::Json { columns } => {
+                f.write_str("JSON");
+                if !columns.is_empty() {
+                    f.write_str(" (");
+                    f.write_node(&display::comma_separated(columns));
+                    f.write_str(")");
+                }
+            }
             Self::Text => f.write_str("TEXT"),
+            Self::Parquet { columns } => {
+                f.write_str("PARQUET");
+                if !columns.is_empty() {
+                    f.write_str(" (");
+                    f.write_node(&display::comma_separated(columns));
+                    f.write_str(")");
+                }
+            }
         }
     }
 }
@@ -466,6 +508,8 @@ impl_display_t!(Format);
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Compression {
     Gzip,
+    Zstd,
+    Snappy,
     None,
 }
 
@@ -473,6 +517,8 @@ impl AstDisplay for Compression {
     fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
         match self {
             Self::Gzip => f.write_str("GZIP"),
+            Self::Zstd => f.write_str("ZSTD"),
+            Self::Snappy => f.write_str("SNAPPY"),
             Self::None => f.write_str("NONE"),
         }
     }
@@ -563,6 +609,9 @@ pub struct KafkaSourceConnector {
     pub connector: KafkaConnector,
     pub topic: String,
     pub key: Option<Vec<Ident>>,
+    /// The argument to `START TIMESTAMP`, which purification resolves to
+    /// the `start_offset` option.
+    pub start_timestamp: Option<Value>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EnumKind)]
@@ -589,6 +638,20 @@ pub enum CreateSourceConnector<T: AstInfo> {
         /// Hex encoded string of binary serialization of `dataflow_types::PostgresSourceDetails`
         details: Option<String>,
     },
+    MySql {
+        /// The MySQL connection string
+        conn: String,
+        /// The name of the database whose tables to replicate
+        database: String,
+        /// Hex encoded string of binary serialization of `dataflow_types::MySqlSourceDetails`
+        details: Option<String>,
+    },
+    SqlServer {
+        /// The SQL Server connection string, in ADO.NET format
+        conn: String,
+        /// Hex encoded string of binary serialization of `dataflow_types::PostgresSourceDetails`
+        details: Option<String>,
+    },
     PubNub {
         /// PubNub's subscribe key
         subscribe_key: String,
@@ -601,6 +664,11 @@ pub enum CreateSourceConnector<T: AstInfo> {
         collection_id: String,
         columns: Vec<ColumnDef<T>>,
     },
+    Webhook {
+        /// How to validate the requests that deliver data to the source, if
+        /// at all.
+        validation: Option<WebhookValidation>,
+    },
 }
 
 impl<T: AstInfo> AstDisplay for CreateSourceConnector<T> {
@@ -610,6 +678,7 @@ impl<T: AstInfo> AstDisplay for CreateSourceConnector<T> {
                 connector: broker,
                 topic,
                 key,
+                start_timestamp,
             }) => {
                 f.write_str("KAFKA ");
                 match broker {
@@ -626,6 +695,10 @@ impl<T: AstInfo> AstDisplay for CreateSourceConnector<T> {
                 f.write_str(" TOPIC '");
                 f.write_node(&display::escape_single_quote_string(topic));
                 f.write_str("'");
+                if let Some(start_timestamp) = start_timestamp {
+                    f.write_str(" START TIMESTAMP ");
+                    f.write_node(start_timestamp);
+                }
                 if let Some(key) = key.as_ref() {
                     f.write_str(" KEY (");
                     f.write_node(&display::comma_separated(&key));
@@ -673,6 +746,30 @@ impl<T: AstInfo> AstDisplay for CreateSourceConnector<T> {
                 }
                 f.write_str("'");
             }
+            CreateSourceConnector::MySql {
+                conn,
+                database,
+                details,
+            } => {
+                f.write_str("MYSQL CONNECTION '");
+                f.write_str(&display::escape_single_quote_string(conn));
+                f.write_str("' DATABASE '");
+                f.write_str(&display::escape_single_quote_string(database));
+                if let Some(details) = details {
+                    f.write_str("' DETAILS '");
+                    f.write_str(&display::escape_single_quote_string(details));
+                }
+                f.write_str("'");
+            }
+            CreateSourceConnector::SqlServer { conn, details } => {
+                f.write_str("SQL SERVER CONNECTION '");
+                f.write_str(&display::escape_single_quote_string(conn));
+                if let Some(details) = details {
+                    f.write_str("' DETAILS '");
+                    f.write_str(&display::escape_single_quote_string(details));
+                }
+                f.write_str("'");
+            }
             CreateSourceConnector::PubNub {
                 subscribe_key,
                 channel,
@@ -701,11 +798,41 @@ impl<T: AstInfo> AstDisplay for CreateSourceConnector<T> {
                 f.write_node(&display::comma_separated(columns));
                 f.write_str(")");
             }
+            CreateSourceConnector::Webhook { validation } => {
+                f.write_str("WEBHOOK");
+                if let Some(validation) = validation {
+                    f.write_str(" ");
+                    f.write_node(validation);
+                }
+            }
         }
     }
 }
 impl_display_t!(CreateSourceConnector);
 
+/// The validation of requests to a webhook source: the body of each request
+/// must be signed with an HMAC keyed by a secret.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WebhookValidation {
+    /// The secret that keys the HMAC.
+    pub secret: UnresolvedObjectName,
+    /// The request header that carries the signature.
+    pub header: Option<String>,
+}
+
+impl AstDisplay for WebhookValidation {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        f.write_str("VALIDATE SECRET ");
+        f.write_node(&self.secret);
+        if let Some(header) = &self.header {
+            f.write_str(" HEADER '");
+            f.write_node(&display::escape_single_quote_string(header));
+            f.write_str("'");
+        }
+    }
+}
+impl_display!(WebhookValidation);
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EnumKind)]
 #[enum_kind(CreateSinkConnectorKind)]
 pub enum CreateSinkConnector<T: AstInfo> {
@@ -720,6 +847,17 @@ pub enum CreateSinkConnector<T: AstInfo> {
         consensus_uri: String,
         shard_id: String,
     },
+    S3 {
+        bucket: String,
+        /// The key prefix under which the sink's objects are written, if any.
+        prefix: Option<String>,
+    },
+    Postgres {
+        conn: String,
+        /// The possibly schema-qualified name of the upstream table.
+        table: String,
+        key: Option<KafkaSinkKey>,
+    },
 }
 
 impl<T: AstInfo> AstDisplay for CreateSinkConnector<T> {
@@ -757,6 +895,26 @@ impl<T: AstInfo> AstDisplay for CreateSinkConnector<T> {
                 f.write_node(&display::escape_single_quote_string(shard_id));
                 f.write_str("'");
             }
+            CreateSinkConnector::S3 { bucket, prefix } => {
+                f.write_str("S3 BUCKET '");
+                f.write_node(&display::escape_single_quote_string(bucket));
+                f.write_str("'");
+                if let Some(prefix) = prefix {
+                    f.write_str(" PREFIX '");
+                    f.write_node(&display::escape_single_quote_string(prefix));
+                    f.write_str("'");
+                }
+            }
+            CreateSinkConnector::Postgres { conn, table, key } => {
+                f.write_str("POSTGRES CONNECTION '");
+                f.write_node(&display::escape_single_quote_string(conn));
+                f.write_str("' TABLE '");
+                f.write_node(&display::escape_single_quote_string(table));
+                f.write_str("'");
+                if let Some(key) = key.as_ref() {
+                    f.write_node(key);
+                }
+            }
         }
     }
 }
@@ -830,6 +988,11 @@ impl_display_t!(PgTable);
 pub enum S3KeySource {
     /// `SCAN BUCKET '<bucket>'`
     Scan { bucket: String },
+    /// `BUCKET POLL '<bucket>' [INTERVAL '<interval>']`
+    Poll {
+        bucket: String,
+        interval: Option<String>,
+    },
     /// `SQS NOTIFICATIONS '<queue-name>'`
     SqsNotifications { queue: String },
 }
@@ -842,6 +1005,16 @@ impl AstDisplay for S3KeySource {
                 f.write_str(&display::escape_single_quote_string(bucket));
                 f.write_str("'");
             }
+            S3KeySource::Poll { bucket, interval } => {
+                f.write_str(" BUCKET POLL '");
+                f.write_str(&display::escape_single_quote_string(bucket));
+                f.write_str("'");
+                if let Some(interval) = interval {
+                    f.write_str(" INTERVAL '");
+                    f.write_str(&display::escape_single_quote_string(interval));
+                    f.write_str("'");
+                }
+            }
             S3KeySource::SqsNotifications { queue } => {
                 f.write_str(" SQS NOTIFICATIONS '");
                 f.write_str(&display::escape_single_quote_string(queue));
@@ -1013,7 +1186,7 @@ pub enum ColumnOption<T: AstInfo> {
     /// A referential integrity constraint (`[FOREIGN KEY REFERENCES
     /// <foreign_table> (<referred_columns>)`).
     ForeignKey {
-        foreign_table: UnresolvedObjectName,
+        foreign_table: T::ObjectName,
         referred_columns: Vec<Ident>,
     },
     // `CHECK (<expr>)`
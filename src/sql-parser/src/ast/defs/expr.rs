@@ -761,10 +761,10 @@ impl_display!(WindowFrameUnits);
 pub enum WindowFrameBound {
     /// `CURRENT ROW`
     CurrentRow,
-    /// `<N> PRECEDING` or `UNBOUNDED PRECEDING`
-    Preceding(Option<u64>),
-    /// `<N> FOLLOWING` or `UNBOUNDED FOLLOWING`.
-    Following(Option<u64>),
+    /// `<N> PRECEDING`, `INTERVAL '<...>' PRECEDING` or `UNBOUNDED PRECEDING`
+    Preceding(Option<Value>),
+    /// `<N> FOLLOWING`, `INTERVAL '<...>' FOLLOWING` or `UNBOUNDED FOLLOWING`.
+    Following(Option<Value>),
 }
 
 impl AstDisplay for WindowFrameBound {
@@ -773,12 +773,12 @@ impl AstDisplay for WindowFrameBound {
             WindowFrameBound::CurrentRow => f.write_str("CURRENT ROW"),
             WindowFrameBound::Preceding(None) => f.write_str("UNBOUNDED PRECEDING"),
             WindowFrameBound::Following(None) => f.write_str("UNBOUNDED FOLLOWING"),
-            WindowFrameBound::Preceding(Some(n)) => {
-                f.write_str(n);
+            WindowFrameBound::Preceding(Some(offset)) => {
+                f.write_node(offset);
                 f.write_str(" PRECEDING");
             }
-            WindowFrameBound::Following(Some(n)) => {
-                f.write_str(n);
+            WindowFrameBound::Following(Some(offset)) => {
+                f.write_node(offset);
                 f.write_str(" FOLLOWING");
             }
         }
@@ -130,6 +130,8 @@ impl AstInfo for Raw {
 pub struct Query<T: AstInfo> {
     /// WITH (common table expressions, or CTEs)
     pub ctes: Vec<Cte<T>>,
+    /// Whether the CTEs are declared `WITH RECURSIVE`
+    pub recursive: bool,
     /// SELECT or UNION / EXCEPT / INTECEPT
     pub body: SetExpr<T>,
     /// ORDER BY
@@ -145,6 +147,9 @@ impl<T: AstInfo> AstDisplay for Query<T> {
     fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
         if !self.ctes.is_empty() {
             f.write_str("WITH ");
+            if self.recursive {
+                f.write_str("RECURSIVE ");
+            }
             f.write_node(&display::comma_separated(&self.ctes));
             f.write_str(" ");
         }
@@ -183,6 +188,7 @@ impl<T: AstInfo> Query<T> {
     pub fn select(select: Select<T>) -> Query<T> {
         Query {
             ctes: vec![],
+            recursive: false,
             body: SetExpr::Select(Box::new(select)),
             order_by: vec![],
             limit: None,
@@ -193,6 +199,7 @@ impl<T: AstInfo> Query<T> {
     pub fn query(query: Query<T>) -> Query<T> {
         Query {
             ctes: vec![],
+            recursive: false,
             body: SetExpr::Query(Box::new(query)),
             order_by: vec![],
             limit: None,
@@ -535,7 +535,7 @@ impl<'a> Parser<'a> {
                         let query = SetExpr::Query(Box::new(query));
                         let ctes = vec![];
                         let body = parser.parse_query_body_seeded(SetPrecedence::Zero, query)?;
-                        Ok(Either::Query(parser.parse_query_tail(ctes, body)?))
+                        Ok(Either::Query(parser.parse_query_tail(ctes, false, body)?))
                     }
 
                     // The next token is something else. That implies we have a
@@ -640,20 +640,26 @@ impl<'a> Parser<'a> {
         })
     }
 
-    /// Parse `CURRENT ROW` or `{ <positive number> | UNBOUNDED } { PRECEDING | FOLLOWING }`
+    /// Parse `CURRENT ROW` or
+    /// `{ <positive number> | INTERVAL '<...>' | UNBOUNDED } { PRECEDING | FOLLOWING }`
     fn parse_window_frame_bound(&mut self) -> Result<WindowFrameBound, ParserError> {
         if self.parse_keywords(&[CURRENT, ROW]) {
             Ok(WindowFrameBound::CurrentRow)
         } else {
-            let rows = if self.parse_keyword(UNBOUNDED) {
+            let offset = if self.parse_keyword(UNBOUNDED) {
                 None
+            } else if self.parse_keyword(INTERVAL) {
+                match self.parse_literal_interval()? {
+                    Expr::Value(value) => Some(value),
+                    _ => unreachable!(),
+                }
             } else {
-                Some(self.parse_literal_uint()?)
+                Some(Value::Number(self.parse_literal_uint()?.to_string()))
             };
             if self.parse_keyword(PRECEDING) {
-                Ok(WindowFrameBound::Preceding(rows))
+                Ok(WindowFrameBound::Preceding(offset))
             } else if self.parse_keyword(FOLLOWING) {
-                Ok(WindowFrameBound::Following(rows))
+                Ok(WindowFrameBound::Following(offset))
             } else {
                 self.expected(self.peek_pos(), "PRECEDING or FOLLOWING", self.peek_token())
             }
@@ -1644,7 +1650,7 @@ impl<'a> Parser<'a> {
             self.expect_keyword(WITH)?;
             let columns = if self.parse_keyword(HEADER) || self.parse_keyword(HEADERS) {
                 CsvColumns::Header {
-                    names: self.parse_parenthesized_column_list(Mandatory)?,
+                    names: self.parse_parenthesized_column_list(Optional)?,
                 }
             } else {
                 let n_cols = self.parse_literal_uint()? as usize;
@@ -1662,15 +1668,45 @@ impl<'a> Parser<'a> {
             };
             Format::Csv { columns, delimiter }
         } else if self.parse_keyword(JSON) {
-            Format::Json
+            let columns = if self.consume_token(&Token::LParen) {
+                let columns = self.parse_comma_separated(|parser| {
+                    Ok(ColumnDef {
+                        name: parser.parse_identifier()?,
+                        data_type: parser.parse_data_type()?,
+                        collation: None,
+                        options: vec![],
+                    })
+                })?;
+                self.expect_token(&Token::RParen)?;
+                columns
+            } else {
+                vec![]
+            };
+            Format::Json { columns }
         } else if self.parse_keyword(TEXT) {
             Format::Text
         } else if self.parse_keyword(BYTES) {
             Format::Bytes
+        } else if self.parse_keyword(PARQUET) {
+            let columns = if self.consume_token(&Token::LParen) {
+                let columns = self.parse_comma_separated(|parser| {
+                    Ok(ColumnDef {
+                        name: parser.parse_identifier()?,
+                        data_type: parser.parse_data_type()?,
+                        collation: None,
+                        options: vec![],
+                    })
+                })?;
+                self.expect_token(&Token::RParen)?;
+                columns
+            } else {
+                vec![]
+            };
+            Format::Parquet { columns }
         } else {
             return self.expected(
                 self.peek_pos(),
-                "AVRO, PROTOBUF, REGEX, CSV, JSON, TEXT, or BYTES",
+                "AVRO, PROTOBUF, REGEX, CSV, JSON, TEXT, BYTES, or PARQUET",
                 self.peek_token(),
             );
         };
@@ -1709,21 +1745,33 @@ impl<'a> Parser<'a> {
     fn parse_protobuf_schema(&mut self) -> Result<ProtobufSchema<Raw>, ParserError> {
         if self.parse_keywords(&[USING, CONFLUENT, SCHEMA, REGISTRY]) {
             let csr_connector = self.parse_csr_connector_proto()?;
-            Ok(ProtobufSchema::Csr { csr_connector })
+            Ok(ProtobufSchema::Csr {
+                csr_connector,
+                message_name: None,
+            })
         } else if self.parse_keyword(MESSAGE) {
             let message_name = self.parse_literal_string()?;
             self.expect_keyword(USING)?;
+            if self.parse_keywords(&[CONFLUENT, SCHEMA, REGISTRY]) {
+                let csr_connector = self.parse_csr_connector_proto()?;
+                return Ok(ProtobufSchema::Csr {
+                    csr_connector,
+                    message_name: Some(message_name),
+                });
+            }
             let schema = self.parse_schema()?;
             Ok(ProtobufSchema::InlineSchema {
                 message_name,
                 schema,
             })
-        } else {
-            return self.expected(
+        } else if self.parse_keyword(USING) {
+            self.expected(
                 self.peek_pos(),
-                "CONFLUENT SCHEMA REGISTRY or MESSAGE",
+                "CONFLUENT SCHEMA REGISTRY",
                 self.peek_token(),
-            );
+            )
+        } else {
+            Ok(ProtobufSchema::Derived)
         }
     }
 
@@ -1882,8 +1930,16 @@ impl<'a> Parser<'a> {
             Compression::None
         } else if self.parse_keyword(GZIP) {
             Compression::Gzip
+        } else if self.parse_keyword(ZSTD) {
+            Compression::Zstd
+        } else if self.parse_keyword(SNAPPY) {
+            Compression::Snappy
         } else {
-            return self.expected(self.peek_pos(), "NONE or GZIP", self.peek_token());
+            return self.expected(
+                self.peek_pos(),
+                "NONE, GZIP, ZSTD, or SNAPPY",
+                self.peek_token(),
+            );
         };
         Ok(compression)
     }
@@ -2078,7 +2134,24 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_create_source_connector(&mut self) -> Result<CreateSourceConnector<Raw>, ParserError> {
-        match self.expect_one_of_keywords(&[KAFKA, KINESIS, AVRO, S3, PERSIST, POSTGRES, PUBNUB])? {
+        match self.expect_one_of_keywords(&[
+            KAFKA, KINESIS, AVRO, S3, PERSIST, POSTGRES, MYSQL, SQL, PUBNUB, WEBHOOK,
+        ])? {
+            WEBHOOK => {
+                let validation = if self.parse_keyword(VALIDATE) {
+                    self.expect_keyword(SECRET)?;
+                    let secret = self.parse_object_name()?;
+                    let header = if self.parse_keyword(HEADER) {
+                        Some(self.parse_literal_string()?)
+                    } else {
+                        None
+                    };
+                    Some(WebhookValidation { secret, header })
+                } else {
+                    None
+                };
+                Ok(CreateSourceConnector::Webhook { validation })
+            }
             PUBNUB => {
                 self.expect_keywords(&[SUBSCRIBE, KEY])?;
                 let subscribe_key = self.parse_literal_string()?;
@@ -2113,6 +2186,34 @@ impl<'a> Parser<'a> {
                     details,
                 })
             }
+            MYSQL => {
+                self.expect_keyword(CONNECTION)?;
+                let conn = self.parse_literal_string()?;
+                self.expect_keyword(DATABASE)?;
+                let database = self.parse_literal_string()?;
+                let details = if self.parse_keyword(DETAILS) {
+                    Some(self.parse_literal_string()?)
+                } else {
+                    None
+                };
+
+                Ok(CreateSourceConnector::MySql {
+                    conn,
+                    database,
+                    details,
+                })
+            }
+            SQL => {
+                self.expect_keywords(&[SERVER, CONNECTION])?;
+                let conn = self.parse_literal_string()?;
+                let details = if self.parse_keyword(DETAILS) {
+                    Some(self.parse_literal_string()?)
+                } else {
+                    None
+                };
+
+                Ok(CreateSourceConnector::SqlServer { conn, details })
+            }
             PERSIST => {
                 self.expect_keyword(CONSENSUS)?;
                 let consensus_uri = self.parse_literal_string()?;
@@ -2146,6 +2247,11 @@ impl<'a> Parser<'a> {
                 };
                 self.expect_keyword(TOPIC)?;
                 let topic = self.parse_literal_string()?;
+                let start_timestamp = if self.parse_keywords(&[START, TIMESTAMP]) {
+                    Some(self.parse_value()?)
+                } else {
+                    None
+                };
                 // one token of lookahead:
                 // * `KEY (` means we're parsing a list of columns for the key
                 // * `KEY FORMAT` means there is no key, we'll parse a KeyValueFormat later
@@ -2161,6 +2267,7 @@ impl<'a> Parser<'a> {
                     connector,
                     topic,
                     key,
+                    start_timestamp,
                 }))
             }
             KINESIS => {
@@ -2172,7 +2279,8 @@ impl<'a> Parser<'a> {
                 // FROM S3 DISCOVER OBJECTS
                 // (MATCHING '<pattern>')?
                 // USING
-                // (BUCKET SCAN '<bucket>' | SQS NOTIFICATIONS '<channel>')+
+                // (BUCKET SCAN '<bucket>' | BUCKET POLL '<bucket>' (INTERVAL '<interval>')?
+                //     | SQS NOTIFICATIONS '<channel>')+
                 self.expect_keywords(&[DISCOVER, OBJECTS])?;
                 let pattern = if self.parse_keyword(MATCHING) {
                     Some(self.parse_literal_string()?)
@@ -2183,11 +2291,22 @@ impl<'a> Parser<'a> {
                 let mut key_sources = Vec::new();
                 while let Some(keyword) = self.parse_one_of_keywords(&[BUCKET, SQS]) {
                     match keyword {
-                        BUCKET => {
-                            self.expect_keyword(SCAN)?;
-                            let bucket = self.parse_literal_string()?;
-                            key_sources.push(S3KeySource::Scan { bucket });
-                        }
+                        BUCKET => match self.expect_one_of_keywords(&[SCAN, POLL])? {
+                            SCAN => {
+                                let bucket = self.parse_literal_string()?;
+                                key_sources.push(S3KeySource::Scan { bucket });
+                            }
+                            POLL => {
+                                let bucket = self.parse_literal_string()?;
+                                let interval = if self.parse_keyword(INTERVAL) {
+                                    Some(self.parse_literal_string()?)
+                                } else {
+                                    None
+                                };
+                                key_sources.push(S3KeySource::Poll { bucket, interval });
+                            }
+                            _ => unreachable!(),
+                        },
                         SQS => {
                             self.expect_keyword(NOTIFICATIONS)?;
                             let queue = self.parse_literal_string()?;
@@ -2218,34 +2337,13 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_create_sink_connector(&mut self) -> Result<CreateSinkConnector<Raw>, ParserError> {
-        match self.expect_one_of_keywords(&[KAFKA, AVRO, PERSIST])? {
+        match self.expect_one_of_keywords(&[KAFKA, AVRO, PERSIST, S3, POSTGRES])? {
             KAFKA => {
                 self.expect_keyword(BROKER)?;
                 let broker = self.parse_literal_string()?;
                 self.expect_keyword(TOPIC)?;
                 let topic = self.parse_literal_string()?;
-                // one token of lookahead:
-                // * `KEY (` means we're parsing a list of columns for the key
-                // * `KEY FORMAT` means there is no key, we'll parse a KeyValueFormat later
-                let key = if self.peek_keyword(KEY)
-                    && self.peek_nth_token(1) != Some(Token::Keyword(FORMAT))
-                {
-                    let _ = self.expect_keyword(KEY);
-                    let key_columns = self.parse_parenthesized_column_list(Mandatory)?;
-
-                    let not_enforced = if self.peek_keywords(&[NOT, ENFORCED]) {
-                        let _ = self.expect_keywords(&[NOT, ENFORCED])?;
-                        true
-                    } else {
-                        false
-                    };
-                    Some(KafkaSinkKey {
-                        key_columns,
-                        not_enforced,
-                    })
-                } else {
-                    None
-                };
+                let key = self.parse_sink_key()?;
                 let consistency = self.parse_kafka_consistency()?;
                 Ok(CreateSinkConnector::Kafka {
                     broker,
@@ -2283,10 +2381,53 @@ impl<'a> Parser<'a> {
                     shard_id,
                 })
             }
+            S3 => {
+                // INTO S3 BUCKET '<bucket>' (PREFIX '<prefix>')?
+                self.expect_keyword(BUCKET)?;
+                let bucket = self.parse_literal_string()?;
+                let prefix = if self.parse_keyword(PREFIX) {
+                    Some(self.parse_literal_string()?)
+                } else {
+                    None
+                };
+                Ok(CreateSinkConnector::S3 { bucket, prefix })
+            }
+            POSTGRES => {
+                // INTO POSTGRES CONNECTION '<conn>' TABLE '<table>' (KEY (<cols>))?
+                self.expect_keyword(CONNECTION)?;
+                let conn = self.parse_literal_string()?;
+                self.expect_keyword(TABLE)?;
+                let table = self.parse_literal_string()?;
+                let key = self.parse_sink_key()?;
+                Ok(CreateSinkConnector::Postgres { conn, table, key })
+            }
             _ => unreachable!(),
         }
     }
 
+    fn parse_sink_key(&mut self) -> Result<Option<KafkaSinkKey>, ParserError> {
+        // one token of lookahead:
+        // * `KEY (` means we're parsing a list of columns for the key
+        // * `KEY FORMAT` means there is no key, we'll parse a KeyValueFormat later
+        if self.peek_keyword(KEY) && self.peek_nth_token(1) != Some(Token::Keyword(FORMAT)) {
+            let _ = self.expect_keyword(KEY);
+            let key_columns = self.parse_parenthesized_column_list(Mandatory)?;
+
+            let not_enforced = if self.peek_keywords(&[NOT, ENFORCED]) {
+                let _ = self.expect_keywords(&[NOT, ENFORCED])?;
+                true
+            } else {
+                false
+            };
+            Ok(Some(KafkaSinkKey {
+                key_columns,
+                not_enforced,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
     fn parse_kafka_consistency(&mut self) -> Result<Option<KafkaConsistency<Raw>>, ParserError> {
         if self.parse_keyword(CONSISTENCY) {
             // We would prefer for all consistency parameters to be
@@ -2913,7 +3054,7 @@ impl<'a> Parser<'a> {
         } else if self.parse_keyword(UNIQUE) {
             ColumnOption::Unique { is_primary: false }
         } else if self.parse_keyword(REFERENCES) {
-            let foreign_table = self.parse_object_name()?;
+            let foreign_table = self.parse_raw_name()?;
             let referred_columns = self.parse_parenthesized_column_list(Mandatory)?;
             ColumnOption::ForeignKey {
                 foreign_table,
@@ -3040,7 +3181,7 @@ impl<'a> Parser<'a> {
         let object_type =
             match self.expect_one_of_keywords(&[SINK, SOURCE, VIEW, TABLE, INDEX, SECRET])? {
                 SINK => ObjectType::Sink,
-                SOURCE => ObjectType::Source,
+                SOURCE => return self.parse_alter_source(),
                 VIEW => ObjectType::View,
                 TABLE => ObjectType::Table,
                 INDEX => return self.parse_alter_index(),
@@ -3101,6 +3242,31 @@ impl<'a> Parser<'a> {
         })
     }
 
+    fn parse_alter_source(&mut self) -> Result<Statement<Raw>, ParserError> {
+        let if_exists = self.parse_if_exists()?;
+        let name = self.parse_raw_name()?;
+
+        Ok(match self.expect_one_of_keywords(&[REFRESH, RENAME])? {
+            REFRESH => Statement::AlterSource(AlterSourceStatement {
+                source_name: name,
+                if_exists,
+                action: AlterSourceAction::Refresh,
+            }),
+            RENAME => {
+                self.expect_keyword(TO)?;
+                let to_item_name = self.parse_identifier()?;
+
+                Statement::AlterObjectRename(AlterObjectRenameStatement {
+                    object_type: ObjectType::Source,
+                    if_exists,
+                    name,
+                    to_item_name,
+                })
+            }
+            _ => unreachable!(),
+        })
+    }
+
     fn parse_alter_secret(&mut self) -> Result<Statement<Raw>, ParserError> {
         let if_exists = self.parse_if_exists()?;
         let name = self.parse_raw_name()?;
@@ -3719,44 +3885,47 @@ impl<'a> Parser<'a> {
     /// expect the initial keyword to be already consumed
     fn parse_query(&mut self) -> Result<Query<Raw>, ParserError> {
         self.checked_recur_mut(|parser| {
-            let ctes = if parser.parse_keyword(WITH) {
-                // TODO: optional RECURSIVE
-                parser.parse_comma_separated(Parser::parse_cte)?
+            let (ctes, recursive) = if parser.parse_keyword(WITH) {
+                let recursive = parser.parse_keyword(RECURSIVE);
+                (parser.parse_comma_separated(Parser::parse_cte)?, recursive)
             } else {
-                vec![]
+                (vec![], false)
             };
 
             let body = parser.parse_query_body(SetPrecedence::Zero)?;
 
-            parser.parse_query_tail(ctes, body)
+            parser.parse_query_tail(ctes, recursive, body)
         })
     }
 
     fn parse_query_tail(
         &mut self,
         ctes: Vec<Cte<Raw>>,
+        recursive: bool,
         body: SetExpr<Raw>,
     ) -> Result<Query<Raw>, ParserError> {
-        let (inner_ctes, inner_order_by, inner_limit, inner_offset, body) = match body {
-            SetExpr::Query(query) => {
-                let Query {
-                    ctes,
-                    body,
-                    order_by,
-                    limit,
-                    offset,
-                } = *query;
-                (ctes, order_by, limit, offset, body)
-            }
-            _ => (vec![], vec![], None, None, body),
-        };
+        let (inner_ctes, inner_recursive, inner_order_by, inner_limit, inner_offset, body) =
+            match body {
+                SetExpr::Query(query) => {
+                    let Query {
+                        ctes,
+                        recursive,
+                        body,
+                        order_by,
+                        limit,
+                        offset,
+                    } = *query;
+                    (ctes, recursive, order_by, limit, offset, body)
+                }
+                _ => (vec![], false, vec![], None, None, body),
+            };
 
-        let ctes = if ctes.is_empty() {
-            inner_ctes
+        let (ctes, recursive) = if ctes.is_empty() {
+            (inner_ctes, inner_recursive)
         } else if !inner_ctes.is_empty() {
             return parser_err!(self, self.peek_pos(), "multiple WITH clauses not allowed");
         } else {
-            ctes
+            (ctes, recursive)
         };
 
         let order_by = if self.parse_keywords(&[ORDER, BY]) {
@@ -3835,6 +4004,7 @@ impl<'a> Parser<'a> {
 
         Ok(Query {
             ctes,
+            recursive,
             body,
             order_by,
             limit,
@@ -0,0 +1,336 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Provides convenience functions for working with upstream SQL Server databases for sources.
+
+use std::fmt;
+
+use anyhow::{anyhow, bail};
+use tiberius::{Client, Config, Row};
+use tokio::net::TcpStream;
+use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
+
+use mz_repr::adt::numeric::NumericMaxScale;
+use mz_repr::ScalarType;
+
+/// A client connected to an upstream SQL Server
+pub type SqlServerClient = Client<Compat<TcpStream>>;
+
+/// The schema of a single column
+#[derive(Eq, PartialEq)]
+pub struct SqlServerColumnInfo {
+    pub name: String,
+    /// The name of the system type of the column, e.g. `nvarchar`
+    pub data_type: String,
+    /// The scale of the column's type, which is only meaningful for exact
+    /// numeric types
+    pub scale: u8,
+    pub nullable: bool,
+    pub primary_key: bool,
+}
+
+/// Information about a remote table
+#[derive(Eq, PartialEq)]
+pub struct SqlServerTableInfo {
+    /// The object ID of the table
+    pub object_id: u32,
+    /// The schema the table belongs to
+    pub schema: String,
+    /// The name of the table
+    pub name: String,
+    /// The schema of each column, in order
+    pub columns: Vec<SqlServerColumnInfo>,
+}
+
+impl SqlServerColumnInfo {
+    /// Returns the type that the values of this column are represented as.
+    ///
+    /// Types without a counterpart, like `sql_variant` or the spatial types,
+    /// are represented as their text.
+    pub fn scalar_type(&self) -> ScalarType {
+        match self.data_type.as_str() {
+            "bit" => ScalarType::Bool,
+            // `tinyint` is unsigned, so it does not fit into a single byte
+            // in our representation.
+            "tinyint" | "smallint" => ScalarType::Int16,
+            "int" => ScalarType::Int32,
+            "bigint" => ScalarType::Int64,
+            "decimal" | "numeric" => ScalarType::Numeric {
+                max_scale: NumericMaxScale::try_from(i64::from(self.scale)).ok(),
+            },
+            "money" | "smallmoney" => ScalarType::Numeric {
+                max_scale: NumericMaxScale::try_from(4i64).ok(),
+            },
+            "real" => ScalarType::Float32,
+            "float" => ScalarType::Float64,
+            // `timestamp` is a synonym of `rowversion`, not a date/time type.
+            "binary" | "varbinary" | "image" | "timestamp" | "rowversion" => ScalarType::Bytes,
+            "date" => ScalarType::Date,
+            "time" => ScalarType::Time,
+            "datetime" | "datetime2" | "smalldatetime" => ScalarType::Timestamp,
+            "datetimeoffset" => ScalarType::TimestampTz,
+            "uniqueidentifier" => ScalarType::Uuid,
+            _ => ScalarType::String,
+        }
+    }
+
+    /// Returns a T-SQL expression that converts the values of this column into
+    /// the text that views over a source cast to the column's type.
+    ///
+    /// The default conversions of several types lose precision or produce
+    /// text that we cannot parse, so they are converted with explicit styles.
+    pub fn text_expr(&self) -> String {
+        let column = quote_identifier(&self.name);
+        match self.data_type.as_str() {
+            "binary" | "varbinary" | "image" | "timestamp" | "rowversion" => format!(
+                "'\\x' + CONVERT(varchar(max), CONVERT(varbinary(max), {}), 2)",
+                column
+            ),
+            // Style 3 produces enough digits to round trip.
+            "real" | "float" => format!("CONVERT(varchar(max), {}, 3)", column),
+            // Style 2 produces all four decimal places.
+            "money" | "smallmoney" => format!("CONVERT(varchar(max), {}, 2)", column),
+            // Style 121 is ISO 8601 with a space between date and time.
+            "date" | "datetime" | "datetime2" | "smalldatetime" | "datetimeoffset" => {
+                format!("CONVERT(varchar(max), {}, 121)", column)
+            }
+            "geography" | "geometry" | "hierarchyid" => format!("{}.ToString()", column),
+            _ => format!("CONVERT(nvarchar(max), {})", column),
+        }
+    }
+}
+
+impl SqlServerTableInfo {
+    /// Returns the quoted, schema-qualified name of the table
+    pub fn qualified_name(&self) -> String {
+        format!(
+            "{}.{}",
+            quote_identifier(&self.schema),
+            quote_identifier(&self.name)
+        )
+    }
+}
+
+/// A log sequence number (LSN), which identifies a record in the transaction
+/// log of a database.
+///
+/// LSNs order like the records they identify. They are displayed as binary
+/// literals, e.g. `0x0000002A000001F80003`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Lsn([u8; 10]);
+
+impl TryFrom<&[u8]> for Lsn {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Lsn(bytes.try_into().map_err(|_| {
+            anyhow!("invalid LSN of {} bytes", bytes.len())
+        })?))
+    }
+}
+
+impl fmt::Display for Lsn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("0x")?;
+        for b in self.0 {
+            write!(f, "{:02X}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Quotes a SQL Server identifier
+pub fn quote_identifier(ident: &str) -> String {
+    format!("[{}]", ident.replace(']', "]]"))
+}
+
+/// Connects to the upstream database, given a connection string in ADO.NET
+/// format
+pub async fn connect(conn: &str) -> Result<SqlServerClient, anyhow::Error> {
+    let config = Config::from_ado_string(conn)?;
+    let tcp = TcpStream::connect(config.get_addr()).await?;
+    tcp.set_nodelay(true)?;
+    // Tiberius speaks the protocol over the traits of the `futures` crate.
+    Ok(Client::connect(config, tcp.compat_write()).await?)
+}
+
+/// Verifies that change data capture is enabled for the database of the
+/// connection.
+pub async fn validate_cdc_config(client: &mut SqlServerClient) -> Result<(), anyhow::Error> {
+    let row = client
+        .simple_query("SELECT name, is_cdc_enabled FROM sys.databases WHERE database_id = DB_ID()")
+        .await?
+        .into_row()
+        .await?
+        .ok_or_else(|| anyhow!("connection does not have a current database"))?;
+    if row.get::<bool, _>(1) != Some(true) {
+        bail!(
+            "change data capture is not enabled for database {}",
+            row.get::<&str, _>(0).unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Fetches the schema information of all tables in the database of the
+/// connection that change data capture is enabled for.
+///
+/// # Errors
+///
+/// - Invalid user permissions.
+/// - Change data capture is not enabled for the database.
+pub async fn table_info(
+    client: &mut SqlServerClient,
+) -> Result<Vec<SqlServerTableInfo>, anyhow::Error> {
+    let rows = client
+        .simple_query(
+            "SELECT
+                t.object_id, s.name, t.name, c.name, TYPE_NAME(c.system_type_id), c.scale,
+                c.is_nullable,
+                CAST(CASE WHEN EXISTS (
+                    SELECT 1
+                    FROM
+                        sys.indexes AS i
+                        JOIN sys.index_columns AS ic ON
+                                i.object_id = ic.object_id AND i.index_id = ic.index_id
+                    WHERE
+                        i.is_primary_key = 1 AND ic.object_id = c.object_id
+                        AND ic.column_id = c.column_id
+                ) THEN 1 ELSE 0 END AS bit)
+            FROM
+                sys.tables AS t
+                JOIN sys.schemas AS s ON t.schema_id = s.schema_id
+                JOIN sys.columns AS c ON t.object_id = c.object_id
+            WHERE t.object_id IN (SELECT source_object_id FROM cdc.change_tables)
+            ORDER BY s.name, t.name, c.column_id",
+        )
+        .await?
+        .into_first_result()
+        .await?;
+
+    let mut table_infos: Vec<SqlServerTableInfo> = vec![];
+    for row in rows {
+        let object_id = u32::try_from(get::<i32>(&row, 0)?)?;
+        let column = SqlServerColumnInfo {
+            name: get::<&str>(&row, 3)?.into(),
+            data_type: get::<&str>(&row, 4)?.into(),
+            scale: get(&row, 5)?,
+            nullable: get(&row, 6)?,
+            primary_key: get(&row, 7)?,
+        };
+        match table_infos.last_mut() {
+            Some(info) if info.object_id == object_id => info.columns.push(column),
+            _ => table_infos.push(SqlServerTableInfo {
+                object_id,
+                schema: get::<&str>(&row, 1)?.into(),
+                name: get::<&str>(&row, 2)?.into(),
+                columns: vec![column],
+            }),
+        }
+    }
+    Ok(table_infos)
+}
+
+/// Returns the LSN of the end of the transaction log of the database of the
+/// connection.
+///
+/// Changes to tables that the connection holds a lock on are guaranteed to
+/// be recorded at a higher LSN.
+pub async fn log_end_lsn(client: &mut SqlServerClient) -> Result<Lsn, anyhow::Error> {
+    // The LSN is formatted as three colon-separated hex numbers, whose
+    // concatenation is the binary form of the LSN.
+    query_lsn(
+        client,
+        "SELECT CONVERT(binary(10), REPLACE(log_end_lsn, ':', ''), 2)
+        FROM sys.dm_db_log_stats(DB_ID())",
+    )
+    .await
+}
+
+/// Returns the highest LSN of the changes that the capture job has recorded
+/// in the change tables of the database of the connection.
+pub async fn max_lsn(client: &mut SqlServerClient) -> Result<Lsn, anyhow::Error> {
+    query_lsn(client, "SELECT sys.fn_cdc_get_max_lsn()").await
+}
+
+async fn query_lsn(client: &mut SqlServerClient, query: &str) -> Result<Lsn, anyhow::Error> {
+    let row = client
+        .simple_query(query)
+        .await?
+        .into_row()
+        .await?
+        .ok_or_else(|| anyhow!("query for LSN returned no rows"))?;
+    Lsn::try_from(get::<&[u8]>(&row, 0)?)
+}
+
+/// Gets the non-null value of the column at `idx` in `row`
+fn get<'a, T>(row: &'a Row, idx: usize) -> Result<T, anyhow::Error>
+where
+    T: tiberius::FromSql<'a>,
+{
+    row.try_get(idx)?
+        .ok_or_else(|| anyhow!("unexpected null in column {}", idx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(data_type: &str) -> SqlServerColumnInfo {
+        SqlServerColumnInfo {
+            name: "c]".into(),
+            data_type: data_type.into(),
+            scale: 2,
+            nullable: true,
+            primary_key: false,
+        }
+    }
+
+    #[test]
+    fn test_scalar_type() {
+        for (data_type, expected) in [
+            ("bit", ScalarType::Bool),
+            ("tinyint", ScalarType::Int16),
+            ("bigint", ScalarType::Int64),
+            ("timestamp", ScalarType::Bytes),
+            ("datetimeoffset", ScalarType::TimestampTz),
+            ("nvarchar", ScalarType::String),
+            ("sql_variant", ScalarType::String),
+        ] {
+            assert_eq!(column(data_type).scalar_type(), expected);
+        }
+        assert_eq!(
+            column("decimal").scalar_type(),
+            ScalarType::Numeric {
+                max_scale: Some(NumericMaxScale::try_from(2i64).unwrap())
+            }
+        );
+    }
+
+    #[test]
+    fn test_text_expr() {
+        assert_eq!(
+            column("varbinary").text_expr(),
+            "'\\x' + CONVERT(varchar(max), CONVERT(varbinary(max), [c]]]), 2)"
+        );
+        assert_eq!(
+            column("datetime2").text_expr(),
+            "CONVERT(varchar(max), [c]]], 121)"
+        );
+        assert_eq!(column("int").text_expr(), "CONVERT(nvarchar(max), [c]]])");
+    }
+
+    #[test]
+    fn test_lsn() {
+        let lsn = Lsn::try_from(&[0, 0, 0, 0x2a, 0, 0, 0x01, 0xf8, 0, 3][..]).unwrap();
+        assert_eq!(lsn.to_string(), "0x0000002A000001F80003");
+        assert!(Lsn::default() < lsn);
+        assert!(Lsn::try_from(&[0; 9][..]).is_err());
+    }
+}
@@ -17,8 +17,8 @@ use mz_sql_parser::ast::{CreateSecretStatement, RawObjectName};
 use crate::ast::visit::{self, Visit};
 use crate::ast::visit_mut::{self, VisitMut};
 use crate::ast::{
-    AstInfo, CreateIndexStatement, CreateSinkStatement, CreateSourceStatement,
-    CreateTableStatement, CreateViewStatement, Expr, Ident, Query, Raw, Statement,
+    AstInfo, ColumnOption, CreateIndexStatement, CreateSinkStatement, CreateSourceStatement,
+    CreateTableStatement, CreateViewStatement, Expr, Ident, Query, Raw, Statement, TableConstraint,
     UnresolvedObjectName, ViewDefinition,
 };
 use crate::names::FullObjectName;
@@ -102,6 +102,41 @@ pub fn create_stmt_rename_refs(
     Ok(())
 }
 
+/// Removes the foreign keys of the table created by `create_stmt` that
+/// reference any of the tables identified by `parent_ids`.
+pub fn create_stmt_drop_foreign_keys(
+    create_stmt: &mut Statement<Raw>,
+    parent_ids: &HashSet<String>,
+) {
+    let references_parent = |foreign_table: &RawObjectName| match foreign_table {
+        RawObjectName::Id(id, _) => parent_ids.contains(id),
+        RawObjectName::Name(_) => false,
+    };
+    match create_stmt {
+        Statement::CreateTable(CreateTableStatement {
+            columns,
+            constraints,
+            ..
+        }) => {
+            for column in columns {
+                column.options.retain(|option| match &option.option {
+                    ColumnOption::ForeignKey { foreign_table, .. } => {
+                        !references_parent(foreign_table)
+                    }
+                    _ => true,
+                });
+            }
+            constraints.retain(|constraint| match constraint {
+                TableConstraint::ForeignKey { foreign_table, .. } => {
+                    !references_parent(foreign_table)
+                }
+                _ => true,
+            });
+        }
+        _ => unreachable!("Internal error: only tables have foreign keys"),
+    }
+}
+
 /// Rewrites `query`'s references of `from` to `to` or errors if too ambiguous.
 fn rewrite_query(from: FullObjectName, to: String, query: &mut Query<Raw>) -> Result<(), String> {
     let from_ident = Ident::new(from.item.clone());
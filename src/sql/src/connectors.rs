@@ -105,6 +105,7 @@ fn populate_connector_for_format<T: AstInfo>(
                         connector: csr_connector @ CsrConnector::Reference { .. },
                         ..
                     },
+                ..
             } => {
                 let name = match csr_connector {
                     CsrConnector::Reference { connector, .. } => connector,
@@ -183,6 +184,7 @@ fn qualify_connector_in_format<T: AstInfo>(
                         connector: CsrConnector::Reference { connector, .. },
                         ..
                     },
+                ..
             } => {
                 *connector = allocate_name(connector)?;
             }
@@ -26,6 +26,8 @@ use reqwest::Url;
 use tokio::time::Duration;
 
 use mz_ccsr::tls::{Certificate, Identity};
+use mz_ore::str::StrExt;
+use mz_repr::strconv;
 use mz_sql_parser::ast::Value;
 
 enum ValType {
@@ -236,12 +238,7 @@ pub async fn create_consumer(
 }
 
 /// Returns start offsets for the partitions of `topic` and the provided
-/// `kafka_time_offset` option.
-///
-/// For each partition, the returned offset is the earliest offset whose
-/// timestamp is greater than or equal to the given timestamp for the
-/// partition. If no such message exists (or the Kafka broker is before
-/// 0.10.0), the current end offset is returned for the partition.
+/// `kafka_time_offset` option, as described by [`lookup_offsets_for_time`].
 ///
 /// The provided `kafka_time_offset` option must be a non-zero number:
 /// * Non-Negative numbers will used as is (e.g. `1622659034343`)
@@ -282,6 +279,61 @@ pub async fn lookup_start_offsets(
         _ => bail!("`kafka_time_offset` must be a number"),
     };
 
+    lookup_offsets_for_time(consumer, topic, time_offset)
+        .await
+        .map(Some)
+}
+
+/// Resolves the argument of `START TIMESTAMP` to a timestamp in milliseconds
+/// since the Unix epoch.
+///
+/// The argument is either
+/// * a number, which is interpreted like the `kafka_time_offset` option, or
+/// * a string, which is either a timestamp with a time zone
+///   (e.g. `'2022-06-01 12:00:00+00'`) or an interval relative to now
+///   (e.g. `'-2 hours'`).
+pub fn resolve_start_timestamp(value: &Value, now: u64) -> Result<i64, anyhow::Error> {
+    let now: i64 = now.try_into()?;
+    let ts = match value {
+        Value::Number(n) => match n.parse::<i64>() {
+            // Timestamp in millis *before* now (e.g. -10 means 10 millis ago)
+            Ok(ts) if ts < 0 => now + ts,
+            Ok(ts) => ts,
+            Err(_) => bail!("START TIMESTAMP must be an integer number of milliseconds"),
+        },
+        Value::String(s) => match strconv::parse_timestamptz(s) {
+            Ok(ts) => ts.timestamp_millis(),
+            Err(_) => {
+                let interval = strconv::parse_interval(s).map_err(|_| {
+                    anyhow!(
+                        "START TIMESTAMP must be a timestamp or an interval relative to \
+                         the current time, got {}",
+                        s.quoted()
+                    )
+                })?;
+                now + i64::try_from(interval.as_microseconds() / 1_000)?
+            }
+        },
+        _ => bail!("START TIMESTAMP must be a number or a string"),
+    };
+    if ts <= 0 {
+        bail!("START TIMESTAMP must be after the Unix epoch");
+    }
+    Ok(ts)
+}
+
+/// Returns start offsets for the partitions of `topic` at `time_offset`, a
+/// timestamp in milliseconds since the Unix epoch.
+///
+/// For each partition, the returned offset is the earliest offset whose
+/// timestamp is greater than or equal to the given timestamp for the
+/// partition. If no such message exists (or the Kafka broker is before
+/// 0.10.0), the current end offset is returned for the partition.
+pub async fn lookup_offsets_for_time(
+    consumer: Arc<BaseConsumer<KafkaErrCheckContext>>,
+    topic: &str,
+    time_offset: i64,
+) -> Result<Vec<i64>, anyhow::Error> {
     // Lookup offsets
     // TODO(guswynn): see if we can add broker to this name
     task::spawn_blocking(|| format!("kafka_lookup_start_offsets:{topic}"), {
@@ -324,7 +376,7 @@ pub async fn lookup_start_offsets(
                 );
             }
 
-            Ok(Some(start_offsets))
+            Ok(start_offsets)
         }
     })
     .await?
@@ -769,16 +769,25 @@ impl<'a> Fold<Raw, Aug> for NameResolver<'a> {
             used_names.insert(cte_name.clone());
 
             let id = LocalId::new(self.ctes.len() as u64);
+            // The CTEs of a `WITH RECURSIVE` block are also in scope within
+            // their own definitions.
+            if q.recursive {
+                let old_val = self.ctes.insert(cte_name.clone(), id);
+                old_cte_values.push((cte_name.clone(), old_val));
+            }
             ctes.push(Cte {
                 alias: cte.alias,
                 id,
                 query: self.fold_query(cte.query),
             });
-            let old_val = self.ctes.insert(cte_name.clone(), id);
-            old_cte_values.push((cte_name, old_val));
+            if !q.recursive {
+                let old_val = self.ctes.insert(cte_name.clone(), id);
+                old_cte_values.push((cte_name, old_val));
+            }
         }
         let result = Query {
             ctes,
+            recursive: q.recursive,
             body: self.fold_set_expr(q.body),
             limit: q.limit.map(|l| self.fold_limit(l)),
             offset: q.offset.map(|l| self.fold_expr(l)),
@@ -114,6 +114,7 @@ pub enum Plan {
     AlterIndexResetOptions(AlterIndexResetOptionsPlan),
     AlterItemRename(AlterItemRenamePlan),
     AlterSecret(AlterSecretPlan),
+    AlterSourceRefresh(AlterSourceRefreshPlan),
     Declare(DeclarePlan),
     Fetch(FetchPlan),
     Close(ClosePlan),
@@ -394,6 +395,11 @@ pub struct AlterItemRenamePlan {
     pub object_type: ObjectType,
 }
 
+#[derive(Debug)]
+pub struct AlterSourceRefreshPlan {
+    pub id: GlobalId,
+}
+
 #[derive(Debug)]
 pub struct AlterSecretPlan {
     pub id: GlobalId,
@@ -446,6 +452,12 @@ pub struct Table {
     pub create_sql: String,
     pub desc: RelationDesc,
     pub defaults: Vec<Expr<Aug>>,
+    /// The named `CHECK` constraints that rows written to the table must
+    /// satisfy, i.e., not evaluate to `false`.
+    pub checks: Vec<(String, MirScalarExpr)>,
+    /// The declared foreign keys, as the parent table and the pairs of child
+    /// and parent columns.
+    pub foreign_keys: Vec<(GlobalId, Vec<(usize, usize)>)>,
     pub temporary: bool,
     pub depends_on: Vec<GlobalId>,
 }
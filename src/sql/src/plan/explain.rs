@@ -18,7 +18,7 @@
 //!   * Subqueries can be embedded in scalar expressions.
 //!
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 
 use mz_expr::explain::Indices;
@@ -49,6 +49,8 @@ pub struct Explanation<'a> {
     local_id_chains: HashMap<LocalId, (String, u64)>,
     /// Records the local ID that corresponds to a chain ID, if any.
     chain_local_ids: HashMap<u64, (String, LocalId)>,
+    /// Records the local IDs that are bound recursively.
+    recursive_local_ids: HashSet<LocalId>,
     /// The ID of the current chain. Incremented while constructing the
     /// `Explanation`.
     chain: u64,
@@ -76,7 +78,11 @@ impl<'a> fmt::Display for Explanation<'a> {
                 }
                 write!(f, "%{} =", node.chain)?;
                 if let Some((name, local_id)) = self.chain_local_ids.get(&node.chain) {
-                    write!(f, " Let {} ({}) =", name, local_id)?;
+                    if self.recursive_local_ids.contains(local_id) {
+                        write!(f, " LetRec {} ({}) =", name, local_id)?;
+                    } else {
+                        write!(f, " Let {} ({}) =", name, local_id)?;
+                    }
                 }
                 writeln!(f)?;
             }
@@ -173,6 +179,29 @@ impl<'a> Explanation<'a> {
 
                     walk(body, explanation, id_gen);
                 }
+                LetRec {
+                    name,
+                    id,
+                    body,
+                    value,
+                    ..
+                } => {
+                    // Recursive definitions are explained like lets, except
+                    // that gets of `id` within `value` precede the binding.
+                    explanation.recursive_local_ids.insert(*id);
+                    walk(value, explanation, id_gen);
+                    explanation.chain = id_gen.allocate_id();
+
+                    let value_chain = explanation.expr_chain(value);
+                    explanation
+                        .local_id_chains
+                        .insert(*id, (name.clone(), value_chain));
+                    explanation
+                        .chain_local_ids
+                        .insert(value_chain, (name.clone(), *id));
+
+                    walk(body, explanation, id_gen);
+                }
             }
 
             // Then collect subqueries.
@@ -181,6 +210,7 @@ impl<'a> Explanation<'a> {
                 Constant { .. }
                 | Get { .. }
                 | Let { .. }
+                | LetRec { .. }
                 | Project { .. }
                 | Distinct { .. }
                 | Negate { .. }
@@ -237,14 +267,18 @@ impl<'a> Explanation<'a> {
         {
             for expr in exprs {
                 // Elide chains that would consist only a of single Get node.
-                if let HirRelationExpr::Get {
-                    id: Id::Local(id), ..
-                } = expr
-                {
-                    explanation.expr_chains.insert(
-                        expr as *const HirRelationExpr,
-                        explanation.local_id_chains[id].1,
-                    );
+                // Gets of recursive bindings within their own definition have
+                // no chain yet, and so get a chain of their own.
+                let local_chain = match expr {
+                    HirRelationExpr::Get {
+                        id: Id::Local(id), ..
+                    } => explanation.local_id_chains.get(id).map(|(_, chain)| *chain),
+                    _ => None,
+                };
+                if let Some(chain) = local_chain {
+                    explanation
+                        .expr_chains
+                        .insert(expr as *const HirRelationExpr, chain);
                 } else {
                     walk(expr, explanation, id_gen);
                     explanation.chain = id_gen.allocate_id();
@@ -259,6 +293,7 @@ impl<'a> Explanation<'a> {
             expr_chains: HashMap::new(),
             local_id_chains,
             chain_local_ids: HashMap::new(),
+            recursive_local_ids: HashSet::new(),
             chain: id_gen.allocate_id(),
         };
         walk(expr, &mut explanation, id_gen);
@@ -297,7 +332,7 @@ impl<'a> Explanation<'a> {
 
         match node.expr {
             // Lets are annotated on the chain ID that they correspond to.
-            Let { .. } => (),
+            Let { .. } | LetRec { .. } => (),
             Constant { rows, .. } => {
                 write!(f, "| Constant")?;
                 for row in rows {
@@ -395,6 +430,7 @@ impl<'a> Explanation<'a> {
                 order_key,
                 limit,
                 offset,
+                with_ties,
                 ..
             } => {
                 write!(
@@ -406,7 +442,11 @@ impl<'a> Explanation<'a> {
                 if let Some(limit) = limit {
                     write!(f, " limit={}", limit)?;
                 }
-                writeln!(f, " offset={}", offset)?
+                write!(f, " offset={}", offset)?;
+                if *with_ties {
+                    write!(f, " with_ties")?;
+                }
+                writeln!(f)?
             }
             Negate { .. } => writeln!(f, "| Negate")?,
             Threshold { .. } => write!(f, "| Threshold")?,
@@ -514,6 +554,14 @@ impl<'a> Explanation<'a> {
                         self.fmt_scalar_expr(f, &scalar.expr)?;
                         write!(f, ")")?
                     }
+                    WindowExprType::Aggregate(aggregate) => {
+                        write!(f, "{}(", aggregate.clone().into_expr())?;
+                        if aggregate.aggregate_expr.distinct {
+                            write!(f, "distinct ")?;
+                        }
+                        self.fmt_scalar_expr(f, &aggregate.aggregate_expr.expr)?;
+                        write!(f, ")")?
+                    }
                 }
                 write!(f, " over (")?;
                 for (i, e) in expr.partition.iter().enumerate() {
@@ -62,6 +62,21 @@ pub enum HirRelationExpr {
         /// The result of the `Let`, evaluated with `name` bound to `value`.
         body: Box<HirRelationExpr>,
     },
+    /// Recursive CTE
+    LetRec {
+        name: String,
+        /// The identifier to be used in `Get` variants to retrieve the fixed
+        /// point of `value`, both within `value` and within `body`.
+        id: mz_expr::LocalId,
+        /// The collection to be bound to `name`, whose fixed point is
+        /// evaluated starting from an empty collection.
+        value: Box<HirRelationExpr>,
+        /// The declared type of the binding.
+        typ: RelationType,
+        /// The result of the `LetRec`, evaluated with `name` bound to the
+        /// fixed point of `value`.
+        body: Box<HirRelationExpr>,
+    },
     Project {
         input: Box<HirRelationExpr>,
         outputs: Vec<usize>,
@@ -110,6 +125,9 @@ pub enum HirRelationExpr {
         limit: Option<usize>,
         /// Number of records to skip
         offset: usize,
+        /// Whether to also retain records that tie with the last retained
+        /// record on `order_key`
+        with_ties: bool,
     },
     Negate {
         input: Box<HirRelationExpr>,
@@ -223,6 +241,7 @@ impl WindowExpr {
 pub enum WindowExprType {
     Scalar(ScalarWindowExpr),
     Value(ValueWindowExpr),
+    Aggregate(AggregateWindowExpr),
 }
 
 impl WindowExprType {
@@ -233,6 +252,7 @@ impl WindowExprType {
         match self {
             Self::Scalar(expr) => expr.visit_expressions(f),
             Self::Value(expr) => expr.visit_expressions(f),
+            Self::Aggregate(expr) => expr.visit_expressions(f),
         }
     }
 
@@ -243,6 +263,7 @@ impl WindowExprType {
         match self {
             Self::Scalar(expr) => expr.visit_expressions_mut(f),
             Self::Value(expr) => expr.visit_expressions_mut(f),
+            Self::Aggregate(expr) => expr.visit_expressions_mut(f),
         }
     }
 
@@ -255,6 +276,7 @@ impl WindowExprType {
         match self {
             Self::Scalar(expr) => expr.typ(outers, inner, params),
             Self::Value(expr) => expr.typ(outers, inner, params),
+            Self::Aggregate(expr) => expr.typ(outers, inner, params),
         }
     }
 }
@@ -405,6 +427,46 @@ impl ValueWindowFunc {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AggregateWindowExpr {
+    pub aggregate_expr: AggregateExpr,
+    pub order_by: Vec<ColumnOrder>,
+    pub window_frame: WindowFrame,
+}
+
+impl AggregateWindowExpr {
+    pub fn visit_expressions<'a, F, E>(&'a self, f: &mut F) -> Result<(), E>
+    where
+        F: FnMut(&'a HirScalarExpr) -> Result<(), E>,
+    {
+        f(&self.aggregate_expr.expr)
+    }
+
+    pub fn visit_expressions_mut<'a, F, E>(&'a mut self, f: &mut F) -> Result<(), E>
+    where
+        F: FnMut(&'a mut HirScalarExpr) -> Result<(), E>,
+    {
+        f(&mut self.aggregate_expr.expr)
+    }
+
+    fn typ(
+        &self,
+        outers: &[RelationType],
+        inner: &RelationType,
+        params: &BTreeMap<usize, ScalarType>,
+    ) -> ColumnType {
+        self.aggregate_expr.typ(outers, inner, params)
+    }
+
+    pub fn into_expr(self) -> mz_expr::AggregateFunc {
+        mz_expr::AggregateFunc::WindowAggregate {
+            wrapped_aggregate: Box::new(self.aggregate_expr.func.into_expr()),
+            order_by: self.order_by,
+            window_frame: self.window_frame,
+        }
+    }
+}
+
 /// A `CoercibleScalarExpr` is a [`HirScalarExpr`] whose type is not fully
 /// determined. Several SQL expressions can be freely coerced based upon where
 /// in the expression tree they appear. For example, the string literal '42'
@@ -800,7 +862,9 @@ impl HirRelationExpr {
         stack::maybe_grow(|| match self {
             HirRelationExpr::Constant { typ, .. } => typ.clone(),
             HirRelationExpr::Get { typ, .. } => typ.clone(),
-            HirRelationExpr::Let { body, .. } => body.typ(outers, params),
+            HirRelationExpr::Let { body, .. } | HirRelationExpr::LetRec { body, .. } => {
+                body.typ(outers, params)
+            }
             HirRelationExpr::Project { input, outputs } => {
                 let input_typ = input.typ(outers, params);
                 RelationType::new(
@@ -883,7 +947,9 @@ impl HirRelationExpr {
         match self {
             HirRelationExpr::Constant { typ, .. } => typ.column_types.len(),
             HirRelationExpr::Get { typ, .. } => typ.column_types.len(),
-            HirRelationExpr::Let { body, .. } => body.arity(),
+            HirRelationExpr::Let { body, .. } | HirRelationExpr::LetRec { body, .. } => {
+                body.arity()
+            }
             HirRelationExpr::Project { outputs, .. } => outputs.len(),
             HirRelationExpr::Map { input, scalars } => input.arity() + scalars.len(),
             HirRelationExpr::CallTable { func, .. } => func.output_arity(),
@@ -986,6 +1052,7 @@ impl HirRelationExpr {
         order_key: Vec<ColumnOrder>,
         limit: Option<usize>,
         offset: usize,
+        with_ties: bool,
     ) -> Self {
         HirRelationExpr::TopK {
             input: Box::new(self),
@@ -993,6 +1060,7 @@ impl HirRelationExpr {
             order_key,
             limit,
             offset,
+            with_ties,
         }
     }
 
@@ -1098,7 +1166,8 @@ impl HirRelationExpr {
             HirRelationExpr::Constant { .. }
             | HirRelationExpr::Get { .. }
             | HirRelationExpr::CallTable { .. } => (),
-            HirRelationExpr::Let { body, value, .. } => {
+            HirRelationExpr::Let { body, value, .. }
+            | HirRelationExpr::LetRec { body, value, .. } => {
                 f(value, depth)?;
                 f(body, depth)?;
             }
@@ -1170,7 +1239,8 @@ impl HirRelationExpr {
             HirRelationExpr::Constant { .. }
             | HirRelationExpr::Get { .. }
             | HirRelationExpr::CallTable { .. } => (),
-            HirRelationExpr::Let { body, value, .. } => {
+            HirRelationExpr::Let { body, value, .. }
+            | HirRelationExpr::LetRec { body, value, .. } => {
                 f(value, depth)?;
                 f(body, depth)?;
             }
@@ -1250,6 +1320,7 @@ impl HirRelationExpr {
                 }
                 HirRelationExpr::Union { .. }
                 | HirRelationExpr::Let { .. }
+                | HirRelationExpr::LetRec { .. }
                 | HirRelationExpr::Project { .. }
                 | HirRelationExpr::Distinct { .. }
                 | HirRelationExpr::TopK { .. }
@@ -1296,6 +1367,7 @@ impl HirRelationExpr {
                 }
                 HirRelationExpr::Union { .. }
                 | HirRelationExpr::Let { .. }
+                | HirRelationExpr::LetRec { .. }
                 | HirRelationExpr::Project { .. }
                 | HirRelationExpr::Distinct { .. }
                 | HirRelationExpr::TopK { .. }
@@ -1380,6 +1452,7 @@ impl HirRelationExpr {
                     order_key: finishing.order_by,
                     limit: finishing.limit,
                     offset: finishing.offset,
+                    with_ties: false,
                 }),
                 outputs: finishing.project,
             }
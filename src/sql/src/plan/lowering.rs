@@ -294,6 +294,45 @@ impl HirRelationExpr {
                         body
                     })
                 }
+                LetRec {
+                    name: _,
+                    id,
+                    value,
+                    typ,
+                    body,
+                } => {
+                    // The binding is referenced within its own value, and so
+                    // must be registered before the value is lowered.
+                    let new_id = mz_expr::LocalId::new(id_gen.allocate_id());
+                    let relation_type = RelationType::new(
+                        get_outer
+                            .typ()
+                            .column_types
+                            .into_iter()
+                            .chain(typ.column_types)
+                            .collect(),
+                    );
+                    let old_value = cte_map.insert(
+                        id.clone(),
+                        CteDesc {
+                            new_id,
+                            relation_type,
+                            outer_relation: get_outer.clone(),
+                        },
+                    );
+                    let value = value.applied_to(id_gen, get_outer.clone(), col_map, cte_map);
+                    let body = body.applied_to(id_gen, get_outer, col_map, cte_map);
+                    if let Some(old_value) = old_value {
+                        cte_map.insert(id, old_value);
+                    } else {
+                        cte_map.remove(&id);
+                    }
+                    SR::LetRec {
+                        id: new_id,
+                        value: Box::new(value),
+                        body: Box::new(body),
+                    }
+                }
                 Project { input, outputs } => {
                     // Projections should be applied to the decorrelated `inner`, and to its columns,
                     // which means rebasing `outputs` to start `get_outer.arity()` columns later.
@@ -634,6 +673,7 @@ impl HirRelationExpr {
                     order_key,
                     limit,
                     offset,
+                    with_ties,
                 } => {
                     // TopK is uncomplicated, except that we must group by the columns of `get_outer` as well.
                     let input = input.applied_to(id_gen, get_outer.clone(), col_map, cte_map);
@@ -647,7 +687,13 @@ impl HirRelationExpr {
                             desc: column_order.desc,
                         })
                         .collect();
-                    input.top_k(applied_group_key, applied_order_key, limit, offset)
+                    input.top_k(
+                        applied_group_key,
+                        applied_order_key,
+                        limit,
+                        offset,
+                        with_ties,
+                    )
                 }
                 Negate { input } => {
                     // Negate is uncomplicated.
@@ -1042,8 +1088,19 @@ impl HirScalarExpr {
                                     });
                             SS::Column(inner.arity() - 1)
                         }
-                        WindowExprType::Value(func) => {
-                            let hir_scalar_input = func.expr.clone();
+                        func @ (WindowExprType::Value(_) | WindowExprType::Aggregate(_)) => {
+                            // Value and aggregate window functions share the same encoding of
+                            // their input, and only differ in the aggregate function that
+                            // computes the result for each row of the partition.
+                            let (hir_scalar_input, func) = match func {
+                                WindowExprType::Value(func) => {
+                                    (func.expr.clone(), func.into_expr())
+                                }
+                                WindowExprType::Aggregate(func) => {
+                                    (func.aggregate_expr.expr.clone(), func.into_expr())
+                                }
+                                WindowExprType::Scalar(_) => unreachable!(),
+                            };
                             *inner =
                                 inner
                                     .take_dangerous()
@@ -1184,8 +1241,6 @@ impl HirScalarExpr {
                                                 custom_id: None,
                                             }
                                             .nullable(false);
-
-                                            let func = func.into_expr();
                                             let aggregate = mz_expr::AggregateExpr {
                                                 func,
                                                 expr: agg_input,
@@ -1546,7 +1601,7 @@ where
                 );
             }
         }
-        HirRelationExpr::Let { id, .. } => {
+        HirRelationExpr::Let { id, .. } | HirRelationExpr::LetRec { id, .. } => {
             // Note: if ID uniqueness is not guaranteed, we can't use `visit` since
             // we would need to remove the old CTE with the same ID temporarily while
             // traversing the definition of the new CTE under the same ID.
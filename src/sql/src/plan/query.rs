@@ -47,6 +47,7 @@ use mz_ore::collections::CollectionExt;
 use mz_ore::stack::{CheckedRecursion, RecursionGuard};
 use mz_ore::str::StrExt;
 use mz_repr::adt::char::CharLength;
+use mz_repr::adt::interval::Interval;
 use mz_repr::adt::numeric::{NumericMaxScale, NUMERIC_DATUM_MAX_PRECISION};
 use mz_repr::adt::varchar::VarCharMaxLength;
 use mz_repr::{
@@ -54,13 +55,15 @@ use mz_repr::{
     ScalarType,
 };
 
+use mz_sql_parser::ast::visit::{self, Visit};
 use mz_sql_parser::ast::visit_mut::{self, VisitMut};
 use mz_sql_parser::ast::{
-    AsOf, Assignment, DeleteStatement, Distinct, Expr, Function, FunctionArgs,
-    HomogenizingFunction, Ident, InsertSource, IsExprConstruct, Join, JoinConstraint, JoinOperator,
-    Limit, OrderByExpr, Query, Select, SelectItem, SetExpr, SetOperator, SubscriptPosition,
-    TableAlias, TableFactor, TableFunction, TableWithJoins, UnresolvedObjectName, UpdateStatement,
-    Value, Values, WindowFrame, WindowFrameBound, WindowFrameUnits, WindowSpec,
+    AsOf, Assignment, Cte, DeleteStatement, Distinct, Expr, Function, FunctionArgs,
+    HomogenizingFunction, Ident, InsertSource, IntervalValue, IsExprConstruct, Join,
+    JoinConstraint, JoinOperator, Limit, OrderByExpr, Query, Select, SelectItem, SetExpr,
+    SetOperator, SubscriptPosition, TableAlias, TableFactor, TableFunction, TableWithJoins,
+    UnresolvedObjectName, UpdateStatement, Value, Values, WindowFrame, WindowFrameBound,
+    WindowFrameUnits, WindowSpec,
 };
 
 use crate::catalog::{CatalogItemType, CatalogType, SessionCatalog};
@@ -69,10 +72,10 @@ use crate::names::{Aug, PartialObjectName, ResolvedDataType, ResolvedObjectName}
 use crate::normalize;
 use crate::plan::error::PlanError;
 use crate::plan::expr::{
-    AbstractColumnType, AbstractExpr, AggregateExpr, AggregateFunc, BinaryFunc,
-    CoercibleScalarExpr, ColumnOrder, ColumnRef, HirRelationExpr, HirScalarExpr, JoinKind,
-    ScalarWindowExpr, ScalarWindowFunc, UnaryFunc, ValueWindowExpr, VariadicFunc, WindowExpr,
-    WindowExprType,
+    AbstractColumnType, AbstractExpr, AggregateExpr, AggregateFunc, AggregateWindowExpr,
+    BinaryFunc, CoercibleScalarExpr, ColumnOrder, ColumnRef, HirRelationExpr, HirScalarExpr,
+    JoinKind, ScalarWindowExpr, ScalarWindowFunc, UnaryFunc, ValueWindowExpr, VariadicFunc,
+    WindowExpr, WindowExprType,
 };
 use crate::plan::plan_utils::{self, JoinSide};
 use crate::plan::scope::{Scope, ScopeItem};
@@ -236,6 +239,7 @@ pub fn plan_insert_query(
                 Query {
                     body: SetExpr::Values(Values(values)),
                     ctes,
+                    recursive: _,
                     order_by,
                     limit: None,
                     offset: None,
@@ -825,6 +829,74 @@ pub fn plan_default_expr(
     Ok(hir)
 }
 
+/// Plans a `CHECK` constraint on a table with the given columns.
+///
+/// The returned expression is evaluated against each row written to the
+/// table. The row violates the constraint iff the expression evaluates to
+/// `false`.
+pub fn plan_check_expr(
+    scx: &StatementContext,
+    on_desc: &RelationDesc,
+    mut expr: Expr<Aug>,
+) -> Result<MirScalarExpr, PlanError> {
+    let scope = Scope::from_source(None, on_desc.iter_names());
+    let qcx = QueryContext::root(scx, QueryLifetime::Static);
+    let ecx = &ExprContext {
+        qcx: &qcx,
+        name: "CHECK constraint",
+        scope: &scope,
+        relation_type: on_desc.typ(),
+        allow_aggregates: false,
+        allow_subqueries: false,
+        allow_windows: false,
+    };
+    transform_ast::transform_expr(scx, &mut expr)?;
+    let expr = plan_expr(ecx, &expr)?
+        .type_as(ecx, &ScalarType::Bool)?
+        .lower_uncorrelated()?;
+    if expr.contains_unmaterializable() {
+        bail_unsupported!("calls to unmaterializable functions in CHECK constraints");
+    }
+    Ok(expr)
+}
+
+/// Plans the conversion of a field of a JSON-encoded source to the type
+/// declared for its column.
+///
+/// The returned expression is evaluated against two columns: the field as
+/// `jsonb`, and the field as `text`. `jsonb` columns take the former as is;
+/// every other type is cast from the latter, so that a JSON string like
+/// `"2022-01-01"` converts exactly as the equivalent SQL string would.
+pub fn plan_json_column_cast(
+    scx: &StatementContext,
+    target_ty: &ScalarType,
+) -> Result<MirScalarExpr, PlanError> {
+    let qcx = QueryContext::root(scx, QueryLifetime::Static);
+    let relation_type = RelationType::new(vec![
+        ScalarType::Jsonb.nullable(true),
+        ScalarType::String.nullable(true),
+    ]);
+    let ecx = &ExprContext {
+        qcx: &qcx,
+        name: "FORMAT JSON",
+        scope: &Scope::empty(),
+        relation_type: &relation_type,
+        allow_aggregates: false,
+        allow_subqueries: false,
+        allow_windows: false,
+    };
+    let expr = match target_ty {
+        ScalarType::Jsonb => HirScalarExpr::column(0),
+        _ => typeconv::plan_cast(
+            ecx,
+            CastContext::Explicit,
+            HirScalarExpr::column(1),
+            target_ty,
+        )?,
+    };
+    Ok(expr.lower_uncorrelated()?)
+}
+
 pub fn plan_params<'a>(
     scx: &'a StatementContext,
     params: Vec<Expr<Aug>>,
@@ -947,6 +1019,8 @@ fn plan_query_inner(
     let mut old_cte_values = Vec::new();
     // A single WITH block cannot use the same name multiple times.
     let mut used_names = HashSet::new();
+    // The CTEs that reference themselves, and so are bound recursively.
+    let mut recursive_ctes = HashSet::new();
     for cte in &q.ctes {
         let cte_name = normalize::ident(cte.alias.name.clone());
 
@@ -959,14 +1033,21 @@ fn plan_query_inner(
         used_names.insert(cte_name.clone());
 
         // Plan CTE.
-        let (val, scope) = plan_nested_query(qcx, &cte.query)?;
-        let typ = qcx.relation_type(&val);
-        let mut val_desc = RelationDesc::new(typ, scope.column_names());
-        val_desc = plan_utils::maybe_rename_columns(
-            format!("CTE {}", cte.alias.name),
-            val_desc,
-            &cte.alias.columns,
-        )?;
+        let (val, val_desc) = if q.recursive
+            && CteReferenceCounter::count(cte.id, |v| v.visit_query(&cte.query)) > 0
+        {
+            recursive_ctes.insert(cte.id);
+            plan_recursive_cte(qcx, cte, &cte_name)?
+        } else {
+            let (val, scope) = plan_nested_query(qcx, &cte.query)?;
+            let typ = qcx.relation_type(&val);
+            let val_desc = plan_utils::maybe_rename_columns(
+                format!("CTE {}", cte.alias.name),
+                RelationDesc::new(typ, scope.column_names()),
+                &cte.alias.columns,
+            )?;
+            (val, val_desc)
+        };
 
         let old_val = qcx.ctes.insert(
             cte.id,
@@ -978,28 +1059,27 @@ fn plan_query_inner(
         );
         old_cte_values.push((cte.id, old_val));
     }
-    let limit = match &q.limit {
-        None => None,
+    let (limit, with_ties) = match &q.limit {
+        None => (None, false),
         Some(Limit {
             quantity: Expr::Value(Value::Number(x)),
-            with_ties: false,
-        }) => Some(x.parse()?),
-        Some(Limit {
-            quantity: _,
-            with_ties: true,
-        }) => bail_unsupported!("FETCH ... WITH TIES"),
+            with_ties,
+        }) => (Some(x.parse()?), *with_ties),
         Some(Limit {
             quantity: _,
             with_ties: _,
         }) => sql_bail!("LIMIT must be an integer constant"),
     };
+    if with_ties && q.order_by.is_empty() {
+        sql_bail!("WITH TIES cannot be specified without ORDER BY clause");
+    }
     let offset = match &q.offset {
         None => 0,
         Some(Expr::Value(Value::Number(x))) => x.parse()?,
         _ => sql_bail!("OFFSET must be an integer constant"),
     };
 
-    let (mut result, scope, finishing) = match &q.body {
+    let (mut result, scope, mut finishing) = match &q.body {
         SetExpr::Select(s) => {
             let plan = plan_view_select(qcx, *s.clone(), q.order_by.clone())?;
             let finishing = RowSetFinishing {
@@ -1033,13 +1113,36 @@ fn plan_query_inner(
         }
     }?;
 
+    if with_ties {
+        // The finishing cannot retain the rows that tie with the last row
+        // within the limit, so we apply the limit and offset with a TopK.
+        result = HirRelationExpr::TopK {
+            input: Box::new(result),
+            group_key: vec![],
+            order_key: finishing.order_by.clone(),
+            limit: finishing.limit.take(),
+            offset: std::mem::take(&mut finishing.offset),
+            with_ties: true,
+        };
+    }
+
     for (id, old_val) in old_cte_values.into_iter().rev() {
         if let Some(cte) = qcx.ctes.remove(&id) {
-            result = HirRelationExpr::Let {
-                name: cte.name,
-                id: id.clone(),
-                value: Box::new(cte.val),
-                body: Box::new(result),
+            result = if recursive_ctes.contains(&id) {
+                HirRelationExpr::LetRec {
+                    name: cte.name,
+                    id: id.clone(),
+                    value: Box::new(cte.val),
+                    typ: cte.val_desc.typ().clone(),
+                    body: Box::new(result),
+                }
+            } else {
+                HirRelationExpr::Let {
+                    name: cte.name,
+                    id: id.clone(),
+                    value: Box::new(cte.val),
+                    body: Box::new(result),
+                }
             };
         }
         if let Some(old_val) = old_val {
@@ -1050,6 +1153,342 @@ fn plan_query_inner(
     Ok((result, scope, finishing))
 }
 
+/// Plans a CTE of a `WITH RECURSIVE` block that references itself.
+///
+/// As in PostgreSQL, the CTE must be of the form `non-recursive-term UNION
+/// [ALL] recursive-term`, where only the recursive term references the CTE,
+/// and does so at most once. The type of the CTE is determined by the
+/// non-recursive term, with all columns nullable.
+fn plan_recursive_cte(
+    qcx: &mut QueryContext,
+    cte: &Cte<Aug>,
+    cte_name: &str,
+) -> Result<(HirRelationExpr, RelationDesc), PlanError> {
+    let query = &cte.query;
+    let (all, left, right) = match &query.body {
+        SetExpr::SetOperation {
+            op: SetOperator::Union,
+            all,
+            left,
+            right,
+        } => (*all, left, right),
+        _ => sql_bail!(
+            "recursive query {} does not have the form non-recursive-term UNION [ALL] recursive-term",
+            cte_name.quoted()
+        ),
+    };
+    if !query.ctes.is_empty() {
+        bail_unsupported!("WITH in a recursive query");
+    }
+    if !query.order_by.is_empty() || query.limit.is_some() || query.offset.is_some() {
+        bail_unsupported!("ORDER BY, LIMIT, or OFFSET in a recursive query");
+    }
+    if CteReferenceCounter::count(cte.id, |v| v.visit_set_expr(left)) > 0 {
+        sql_bail!(
+            "recursive reference to query {} must not appear within its non-recursive term",
+            cte_name.quoted()
+        );
+    }
+    if CteReferenceCounter::count(cte.id, |v| v.visit_set_expr(right)) > 1 {
+        sql_bail!(
+            "recursive reference to query {} must not appear more than once",
+            cte_name.quoted()
+        );
+    }
+    let mut checker = RecursiveReferenceChecker::new(qcx.scx, cte.id, cte_name);
+    checker.visit_set_expr(right);
+    checker.into_result()?;
+
+    // Plan the non-recursive term, which determines the type of the CTE.
+    let (base, base_scope) = plan_set_expr(qcx, left)?;
+    let typ = RelationType::new(
+        qcx.relation_type(&base)
+            .column_types
+            .into_iter()
+            .map(|typ| typ.scalar_type.nullable(true))
+            .collect(),
+    );
+    let val_desc = plan_utils::maybe_rename_columns(
+        format!("CTE {}", cte.alias.name),
+        RelationDesc::new(typ, base_scope.column_names()),
+        &cte.alias.columns,
+    )?;
+
+    // Plan the recursive term with the CTE in scope.
+    let old_val = qcx.ctes.insert(
+        cte.id,
+        CteDesc {
+            val: base.clone(),
+            name: cte_name.to_owned(),
+            val_desc: val_desc.clone(),
+        },
+    );
+    let planned = plan_set_expr(qcx, right);
+    match old_val {
+        Some(old_val) => qcx.ctes.insert(cte.id, old_val),
+        None => qcx.ctes.remove(&cte.id),
+    };
+    let (recursive, _scope) = planned?;
+
+    let base_type = val_desc.typ();
+    let recursive_type = qcx.relation_type(&recursive);
+    if base_type.arity() != recursive_type.arity() {
+        sql_bail!(
+            "each UNION query must have the same number of columns: {} vs {}",
+            base_type.arity(),
+            recursive_type.arity(),
+        );
+    }
+    for (i, (base_column, recursive_column)) in base_type
+        .column_types
+        .iter()
+        .zip(recursive_type.column_types.iter())
+        .enumerate()
+    {
+        if !base_column
+            .scalar_type
+            .base_eq(&recursive_column.scalar_type)
+        {
+            sql_bail!(
+                "recursive query {} column {} has type {} in non-recursive term but type {} overall",
+                cte_name.quoted(),
+                i + 1,
+                qcx.humanize_scalar_type(&base_column.scalar_type),
+                qcx.humanize_scalar_type(&recursive_column.scalar_type),
+            );
+        }
+    }
+
+    let val = if all {
+        base.union(recursive)
+    } else {
+        base.union(recursive).distinct()
+    };
+    Ok((val, val_desc))
+}
+
+/// Counts the references to a CTE within an AST node.
+struct CteReferenceCounter {
+    id: LocalId,
+    count: usize,
+}
+
+impl CteReferenceCounter {
+    fn count<F>(id: LocalId, visit: F) -> usize
+    where
+        F: FnOnce(&mut CteReferenceCounter),
+    {
+        let mut counter = CteReferenceCounter { id, count: 0 };
+        visit(&mut counter);
+        counter.count
+    }
+}
+
+impl<'ast> Visit<'ast, Aug> for CteReferenceCounter {
+    fn visit_object_name(&mut self, name: &'ast ResolvedObjectName) {
+        if let ResolvedObjectName::Cte { id, .. } = name {
+            if *id == self.id {
+                self.count += 1;
+            }
+        }
+    }
+}
+
+/// Rejects references to a recursive CTE within its recursive term that
+/// appear within constructs whose result is not monotonic in the reference,
+/// as the fixed point of such a term need not exist.
+struct RecursiveReferenceChecker<'a> {
+    scx: &'a StatementContext<'a>,
+    id: LocalId,
+    name: &'a str,
+    /// The outermost enclosing construct that may not contain a reference.
+    context: Option<&'static str>,
+    /// Whether the next query visited is a derived table, which, unlike other
+    /// subqueries, may contain a reference.
+    derived: bool,
+    /// Whether an aggregate function call was seen in the current `SELECT`.
+    aggregate: bool,
+    err: Option<PlanError>,
+}
+
+impl<'a> RecursiveReferenceChecker<'a> {
+    fn new(
+        scx: &'a StatementContext<'a>,
+        id: LocalId,
+        name: &'a str,
+    ) -> RecursiveReferenceChecker<'a> {
+        RecursiveReferenceChecker {
+            scx,
+            id,
+            name,
+            context: None,
+            derived: false,
+            aggregate: false,
+            err: None,
+        }
+    }
+
+    fn into_result(self) -> Result<(), PlanError> {
+        match self.err {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    fn reject(&mut self, context: &'static str) {
+        if self.err.is_none() {
+            self.err = Some(PlanError::Unstructured(format!(
+                "recursive reference to query {} must not appear within {}",
+                self.name.quoted(),
+                context
+            )));
+        }
+    }
+
+    fn within<F>(&mut self, context: Option<&'static str>, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let old_context = self.context;
+        self.context = old_context.or(context);
+        f(self);
+        self.context = old_context;
+    }
+}
+
+impl<'a, 'ast> Visit<'ast, Aug> for RecursiveReferenceChecker<'a> {
+    fn visit_object_name(&mut self, name: &'ast ResolvedObjectName) {
+        if let ResolvedObjectName::Cte { id, .. } = name {
+            if *id == self.id {
+                if let Some(context) = self.context {
+                    self.reject(context);
+                }
+            }
+        }
+    }
+
+    fn visit_query(&mut self, query: &'ast Query<Aug>) {
+        let context = if std::mem::take(&mut self.derived) {
+            None
+        } else {
+            Some("a subquery")
+        };
+        let old_aggregate = std::mem::take(&mut self.aggregate);
+        self.within(context, |v| visit::visit_query(v, query));
+        self.aggregate = old_aggregate;
+    }
+
+    fn visit_set_expr(&mut self, set_expr: &'ast SetExpr<Aug>) {
+        let context = match set_expr {
+            SetExpr::SetOperation {
+                op: SetOperator::Except,
+                ..
+            } => Some("EXCEPT"),
+            SetExpr::SetOperation {
+                op: SetOperator::Intersect,
+                ..
+            } => Some("INTERSECT"),
+            _ => None,
+        };
+        self.within(context, |v| visit::visit_set_expr(v, set_expr));
+    }
+
+    fn visit_select(&mut self, select: &'ast Select<Aug>) {
+        let Select {
+            distinct,
+            projection,
+            from,
+            selection,
+            group_by,
+            having,
+            options,
+        } = select;
+        // References may only appear in the FROM clause outside of subqueries,
+        // so we can learn whether the select aggregates before visiting it.
+        let old_aggregate = std::mem::take(&mut self.aggregate);
+        for item in projection {
+            self.visit_select_item(item);
+        }
+        if let Some(having) = having {
+            self.visit_expr(having);
+        }
+        let aggregate = self.aggregate || !group_by.is_empty() || having.is_some();
+        self.aggregate = old_aggregate;
+
+        let context = if distinct.is_some() {
+            Some("DISTINCT")
+        } else if aggregate {
+            Some("an aggregate")
+        } else {
+            None
+        };
+        self.within(context, |v| {
+            if let Some(distinct) = distinct {
+                v.visit_distinct(distinct);
+            }
+            for table_with_joins in from {
+                v.visit_table_with_joins(table_with_joins);
+            }
+            if let Some(selection) = selection {
+                v.visit_expr(selection);
+            }
+            for expr in group_by {
+                v.visit_expr(expr);
+            }
+            for option in options {
+                v.visit_with_option(option);
+            }
+        });
+    }
+
+    fn visit_function(&mut self, func: &'ast Function<Aug>) {
+        if let Ok(item) = self.scx.resolve_function(func.name.clone()) {
+            if let Ok(Func::Aggregate { .. }) = item.func() {
+                self.aggregate = true;
+            }
+        }
+        visit::visit_function(self, func);
+    }
+
+    fn visit_table_factor(&mut self, table_factor: &'ast TableFactor<Aug>) {
+        if let TableFactor::Derived { .. } = table_factor {
+            self.derived = true;
+        }
+        visit::visit_table_factor(self, table_factor);
+    }
+
+    fn visit_table_with_joins(&mut self, table_with_joins: &'ast TableWithJoins<Aug>) {
+        let TableWithJoins { relation, joins } = table_with_joins;
+        self.visit_table_factor(relation);
+        for (i, join) in joins.iter().enumerate() {
+            let (left_nullable, right_nullable) = match &join.join_operator {
+                JoinOperator::LeftOuter(_) => (false, true),
+                JoinOperator::RightOuter(_) => (true, false),
+                JoinOperator::FullOuter(_) => (true, true),
+                JoinOperator::Inner(_) | JoinOperator::CrossJoin => (false, false),
+            };
+            if left_nullable {
+                let count = CteReferenceCounter::count(self.id, |v| {
+                    v.visit_table_factor(relation);
+                    for join in &joins[..i] {
+                        v.visit_join(join);
+                    }
+                });
+                if count > 0 {
+                    self.reject("the nullable side of an outer join");
+                }
+            }
+            let context = if right_nullable {
+                Some("the nullable side of an outer join")
+            } else {
+                None
+            };
+            self.within(context, |v| v.visit_table_factor(&join.relation));
+            self.visit_join_operator(&join.join_operator);
+        }
+    }
+}
+
 pub fn plan_nested_query(
     qcx: &mut QueryContext,
     q: &Query<Aug>,
@@ -1062,6 +1501,7 @@ pub fn plan_nested_query(
             order_key: finishing.order_by,
             limit: finishing.limit,
             offset: finishing.offset,
+            with_ties: false,
         };
     }
     Ok((expr.project(finishing.project), scope))
@@ -1793,6 +2233,7 @@ fn plan_view_select(
                     group_key: distinct_key,
                     limit: Some(1),
                     offset: 0,
+                    with_ties: false,
                 }
             }
         }
@@ -3447,6 +3888,7 @@ where
             order_key: finishing.order_by.clone(),
             limit: finishing.limit,
             offset: finishing.offset,
+            with_ties: false,
         };
     }
 
@@ -3754,7 +4196,7 @@ fn plan_aggregate(
         name,
         args,
         filter,
-        over,
+        over: _,
         distinct,
     }: &Function<Aug>,
 ) -> Result<AggregateExpr, PlanError> {
@@ -3776,10 +4218,6 @@ fn plan_aggregate(
         _ => unreachable!("plan_aggregate called on non-aggregate function,"),
     };
 
-    if over.is_some() {
-        bail_unsupported!("aggregate window functions");
-    }
-
     let name = normalize::unresolved_object_name(name.clone())?;
 
     // We follow PostgreSQL's rule here for mapping `count(*)` into the
@@ -3973,6 +4411,30 @@ fn plan_function<'a>(
     let unresolved_name = normalize::unresolved_object_name(name.clone())?;
 
     let impls = match resolve_func(ecx, name, args)? {
+        Func::Aggregate(_) if over.is_some() => {
+            let (window_frame, partition, order_by, col_orders) =
+                validate_aggregate_window_function_plan(ecx, f)?;
+
+            // The arguments are planned exactly like those of a regular
+            // aggregate, but cannot contain further window functions.
+            let aggregate_expr = plan_aggregate(
+                &ExprContext {
+                    allow_windows: false,
+                    ..ecx.with_name("window function")
+                },
+                f,
+            )?;
+
+            return Ok(HirScalarExpr::Windowing(WindowExpr {
+                func: WindowExprType::Aggregate(AggregateWindowExpr {
+                    aggregate_expr,
+                    order_by: col_orders,
+                    window_frame,
+                }),
+                partition,
+                order_by,
+            }));
+        }
         Func::Aggregate(_) if ecx.allow_aggregates => {
             // should already have been caught by `scope.resolve_expr` in `plan_expr`
             sql_bail!(
@@ -3988,7 +4450,8 @@ fn plan_function<'a>(
         }
         Func::Scalar(impls) => impls,
         Func::ScalarWindow(impls) => {
-            let (window_spec, _, scalar_args, partition) = validate_window_function_plan(ecx, f)?;
+            let (_, scalar_args, partition, order_by, col_orders) =
+                validate_window_function_plan(ecx, f)?;
 
             let func = func::select_impl(
                 ecx,
@@ -3998,8 +4461,6 @@ fn plan_function<'a>(
                 vec![],
             )?;
 
-            let (order_by, col_orders) = plan_function_order_by(ecx, &window_spec.order_by)?;
-
             return Ok(HirScalarExpr::Windowing(WindowExpr {
                 func: WindowExprType::Scalar(ScalarWindowExpr {
                     func,
@@ -4010,7 +4471,7 @@ fn plan_function<'a>(
             }));
         }
         Func::ValueWindow(impls) => {
-            let (window_spec, window_frame, scalar_args, partition) =
+            let (window_frame, scalar_args, partition, order_by, col_orders) =
                 validate_window_function_plan(ecx, f)?;
 
             let (expr, func) = func::select_impl(
@@ -4021,8 +4482,6 @@ fn plan_function<'a>(
                 vec![],
             )?;
 
-            let (order_by, col_orders) = plan_function_order_by(ecx, &window_spec.order_by)?;
-
             return Ok(HirScalarExpr::Windowing(WindowExpr {
                 func: WindowExprType::Value(ValueWindowExpr {
                     func,
@@ -4214,24 +4673,7 @@ fn plan_literal<'a>(l: &'a Value) -> Result<CoercibleScalarExpr, PlanError> {
             false => (Datum::False, ScalarType::Bool),
             true => (Datum::True, ScalarType::Bool),
         },
-        Value::Interval(iv) => {
-            let leading_precision = parser_datetimefield_to_adt(iv.precision_high);
-            let mut i = strconv::parse_interval_w_disambiguator(
-                &iv.value,
-                match leading_precision {
-                    mz_repr::adt::datetime::DateTimeField::Hour
-                    | mz_repr::adt::datetime::DateTimeField::Minute => Some(leading_precision),
-                    _ => None,
-                },
-                parser_datetimefield_to_adt(iv.precision_low),
-            )?;
-            i.truncate_high_fields(parser_datetimefield_to_adt(iv.precision_high));
-            i.truncate_low_fields(
-                parser_datetimefield_to_adt(iv.precision_low),
-                iv.fsec_max_precision,
-            )?;
-            (Datum::Interval(i), ScalarType::Interval)
-        }
+        Value::Interval(iv) => (Datum::Interval(plan_interval(iv)?), ScalarType::Interval),
         Value::String(s) => return Ok(CoercibleScalarExpr::LiteralString(s.clone())),
         Value::Null => return Ok(CoercibleScalarExpr::LiteralNull),
         Value::Array(_) => {
@@ -4244,7 +4686,26 @@ fn plan_literal<'a>(l: &'a Value) -> Result<CoercibleScalarExpr, PlanError> {
     Ok(expr.into())
 }
 
-fn validate_window_function_plan<'a>(
+fn plan_interval(iv: &IntervalValue) -> Result<Interval, PlanError> {
+    let leading_precision = parser_datetimefield_to_adt(iv.precision_high);
+    let mut i = strconv::parse_interval_w_disambiguator(
+        &iv.value,
+        match leading_precision {
+            mz_repr::adt::datetime::DateTimeField::Hour
+            | mz_repr::adt::datetime::DateTimeField::Minute => Some(leading_precision),
+            _ => None,
+        },
+        parser_datetimefield_to_adt(iv.precision_low),
+    )?;
+    i.truncate_high_fields(parser_datetimefield_to_adt(iv.precision_high));
+    i.truncate_low_fields(
+        parser_datetimefield_to_adt(iv.precision_low),
+        iv.fsec_max_precision,
+    )?;
+    Ok(i)
+}
+
+fn validate_window_function_plan(
     ecx: &ExprContext,
     Function {
         name,
@@ -4252,13 +4713,14 @@ fn validate_window_function_plan<'a>(
         filter,
         over,
         distinct,
-    }: &'a Function<Aug>,
+    }: &Function<Aug>,
 ) -> Result<
     (
-        &'a WindowSpec<Aug>,
         mz_expr::WindowFrame,
         Vec<CoercibleScalarExpr>,
         Vec<HirScalarExpr>,
+        Vec<HirScalarExpr>,
+        Vec<ColumnOrder>,
     ),
     PlanError,
 > {
@@ -4283,14 +4745,7 @@ fn validate_window_function_plan<'a>(
         Some(over) => over,
         None => sql_bail!("window function {} requires an OVER clause", name),
     };
-    let window_frame = match window_spec.window_frame.as_ref() {
-        Some(frame) => plan_window_frame(frame)?,
-        None => mz_expr::WindowFrame::default(),
-    };
-    let mut partition = Vec::new();
-    for expr in &window_spec.partition_by {
-        partition.push(plan_expr(ecx, expr)?.type_as_any(ecx)?);
-    }
+    let (window_frame, partition, order_by, col_orders) = plan_window_spec(ecx, window_spec)?;
 
     let scalar_args = match &args {
         FunctionArgs::Star => {
@@ -4307,23 +4762,87 @@ fn validate_window_function_plan<'a>(
         }
     };
 
-    Ok((window_spec, window_frame, scalar_args, partition))
+    Ok((window_frame, scalar_args, partition, order_by, col_orders))
+}
+
+fn validate_aggregate_window_function_plan(
+    ecx: &ExprContext,
+    Function {
+        name: _,
+        args: _,
+        filter: _,
+        over,
+        distinct,
+    }: &Function<Aug>,
+) -> Result<
+    (
+        mz_expr::WindowFrame,
+        Vec<HirScalarExpr>,
+        Vec<HirScalarExpr>,
+        Vec<ColumnOrder>,
+    ),
+    PlanError,
+> {
+    if !ecx.allow_windows {
+        sql_bail!("window functions are not allowed in {}", ecx.name);
+    }
+
+    if *distinct {
+        bail_unsupported!("DISTINCT in aggregate window functions");
+    }
+
+    let window_spec = over
+        .as_ref()
+        .expect("aggregate window functions have an OVER clause");
+
+    plan_window_spec(ecx, window_spec)
+}
+
+/// Plans the window frame, the PARTITION BY expressions and the ORDER BY
+/// expressions of a window function.
+fn plan_window_spec(
+    ecx: &ExprContext,
+    window_spec: &WindowSpec<Aug>,
+) -> Result<
+    (
+        mz_expr::WindowFrame,
+        Vec<HirScalarExpr>,
+        Vec<HirScalarExpr>,
+        Vec<ColumnOrder>,
+    ),
+    PlanError,
+> {
+    let mut partition = Vec::new();
+    for expr in &window_spec.partition_by {
+        partition.push(plan_expr(ecx, expr)?.type_as_any(ecx)?);
+    }
+
+    let (order_by, col_orders) = plan_function_order_by(ecx, &window_spec.order_by)?;
+
+    let window_frame = match window_spec.window_frame.as_ref() {
+        Some(frame) => plan_window_frame(ecx, frame, &order_by)?,
+        None => mz_expr::WindowFrame::default(),
+    };
+
+    Ok((window_frame, partition, order_by, col_orders))
 }
 
 fn plan_window_frame(
+    ecx: &ExprContext,
     WindowFrame {
         units,
         start_bound,
         end_bound,
     }: &WindowFrame,
+    order_by: &[HirScalarExpr],
 ) -> Result<mz_expr::WindowFrame, PlanError> {
     use mz_expr::WindowFrameBound::*;
-    let units = window_frame_unit_ast_to_expr(units)?;
-    let start_bound = window_frame_bound_ast_to_expr(start_bound);
-    let end_bound = end_bound
-        .as_ref()
-        .map(window_frame_bound_ast_to_expr)
-        .unwrap_or(CurrentRow);
+    let units = window_frame_unit_ast_to_expr(units);
+    let start_bound = window_frame_bound_ast_to_expr(ecx, &units, start_bound, order_by)?;
+    let end_bound = match end_bound {
+        Some(end_bound) => window_frame_bound_ast_to_expr(ecx, &units, end_bound, order_by)?,
+        None => CurrentRow,
+    };
 
     // Validate bounds according to Postgres rules
     match (&start_bound, &end_bound) {
@@ -4346,11 +4865,8 @@ fn plan_window_frame(
         (_, _) => (),
     }
 
-    // RANGE is only supported in the default frame
-    if units == mz_expr::WindowFrameUnits::Range
-        && (start_bound != UnboundedPreceding || end_bound != CurrentRow)
-    {
-        bail_unsupported!("RANGE in non-default window frames")
+    if units == mz_expr::WindowFrameUnits::Groups && order_by.is_empty() {
+        sql_bail!("GROUPS mode requires an ORDER BY clause")
     }
 
     let frame = mz_expr::WindowFrame {
@@ -4361,26 +4877,95 @@ fn plan_window_frame(
     Ok(frame)
 }
 
-fn window_frame_unit_ast_to_expr(
-    unit: &WindowFrameUnits,
-) -> Result<mz_expr::WindowFrameUnits, PlanError> {
+fn window_frame_unit_ast_to_expr(unit: &WindowFrameUnits) -> mz_expr::WindowFrameUnits {
     match unit {
-        WindowFrameUnits::Rows => Ok(mz_expr::WindowFrameUnits::Rows),
-        WindowFrameUnits::Range => Ok(mz_expr::WindowFrameUnits::Range),
-        WindowFrameUnits::Groups => bail_unsupported!("GROUPS in window frames"),
+        WindowFrameUnits::Rows => mz_expr::WindowFrameUnits::Rows,
+        WindowFrameUnits::Range => mz_expr::WindowFrameUnits::Range,
+        WindowFrameUnits::Groups => mz_expr::WindowFrameUnits::Groups,
     }
 }
 
-fn window_frame_bound_ast_to_expr(bound: &WindowFrameBound) -> mz_expr::WindowFrameBound {
-    match bound {
+fn window_frame_bound_ast_to_expr(
+    ecx: &ExprContext,
+    units: &mz_expr::WindowFrameUnits,
+    bound: &WindowFrameBound,
+    order_by: &[HirScalarExpr],
+) -> Result<mz_expr::WindowFrameBound, PlanError> {
+    Ok(match bound {
         WindowFrameBound::CurrentRow => mz_expr::WindowFrameBound::CurrentRow,
         WindowFrameBound::Preceding(None) => mz_expr::WindowFrameBound::UnboundedPreceding,
-        WindowFrameBound::Preceding(Some(offset)) => {
-            mz_expr::WindowFrameBound::OffsetPreceding(*offset)
-        }
+        WindowFrameBound::Preceding(Some(offset)) => mz_expr::WindowFrameBound::OffsetPreceding(
+            plan_window_frame_offset(ecx, units, offset, order_by)?,
+        ),
         WindowFrameBound::Following(None) => mz_expr::WindowFrameBound::UnboundedFollowing,
-        WindowFrameBound::Following(Some(offset)) => {
-            mz_expr::WindowFrameBound::OffsetFollowing(*offset)
+        WindowFrameBound::Following(Some(offset)) => mz_expr::WindowFrameBound::OffsetFollowing(
+            plan_window_frame_offset(ecx, units, offset, order_by)?,
+        ),
+    })
+}
+
+/// Plans the offset of a `<offset> PRECEDING` or `<offset> FOLLOWING` frame bound.
+///
+/// In ROWS and GROUPS mode, the offset is a number of rows or peer groups. In
+/// RANGE mode, the offset is compared against the values of the single ORDER BY
+/// expression, so it is converted to the unit of that expression: a number for
+/// numeric types, and microseconds for timestamps.
+fn plan_window_frame_offset(
+    ecx: &ExprContext,
+    units: &mz_expr::WindowFrameUnits,
+    offset: &Value,
+    order_by: &[HirScalarExpr],
+) -> Result<u64, PlanError> {
+    let parse_count = |offset: &Value| match offset {
+        Value::Number(n) => n
+            .parse::<u64>()
+            .map_err(|_| PlanError::Unstructured(format!("invalid frame offset: {}", n))),
+        _ => sql_bail!(
+            "frame offset must be a non-negative integer, not {}",
+            offset
+        ),
+    };
+
+    match units {
+        mz_expr::WindowFrameUnits::Rows | mz_expr::WindowFrameUnits::Groups => parse_count(offset),
+        mz_expr::WindowFrameUnits::Range => {
+            let order_by = match order_by {
+                [order_by] => order_by,
+                _ => sql_bail!(
+                    "RANGE with offset PRECEDING/FOLLOWING requires exactly one ORDER BY column"
+                ),
+            };
+            match ecx.scalar_type(order_by) {
+                ScalarType::Int16
+                | ScalarType::Int32
+                | ScalarType::Int64
+                | ScalarType::Float32
+                | ScalarType::Float64
+                | ScalarType::Numeric { .. } => parse_count(offset),
+                ScalarType::Timestamp | ScalarType::TimestampTz => {
+                    let interval = match offset {
+                        Value::Interval(iv) => plan_interval(iv)?,
+                        _ => sql_bail!(
+                            "RANGE offset for type {} must be an interval, not {}",
+                            ecx.humanize_scalar_type(&ecx.scalar_type(order_by)),
+                            offset
+                        ),
+                    };
+                    if interval.is_negative() {
+                        sql_bail!("invalid preceding or following size in window function")
+                    }
+                    if interval.months != 0 {
+                        bail_unsupported!("RANGE offsets with month or year intervals")
+                    }
+                    u64::try_from(interval.as_microseconds()).map_err(|_| {
+                        PlanError::Unstructured(format!("invalid frame offset: {}", offset))
+                    })
+                }
+                typ => bail_unsupported!(format!(
+                    "RANGE with offset PRECEDING/FOLLOWING for type {}",
+                    ecx.humanize_scalar_type(&typ)
+                )),
+            }
         }
     }
 }
@@ -4651,6 +5236,12 @@ impl<'a> VisitMut<'_, Aug> for AggregateTableFuncVisitor<'a> {
         };
 
         match item.func() {
+            // Aggregate functions used as window functions are planned along
+            // with the other window functions, but their arguments may still
+            // contain regular aggregates, e.g. `sum(sum(x)) OVER ()`.
+            Ok(Func::Aggregate { .. }) if func.over.is_some() => {
+                visit_mut::visit_function_mut(self, func)
+            }
             Ok(Func::Aggregate { .. }) => {
                 if self.within_aggregate {
                     self.err = Some(PlanError::Unstructured(
@@ -134,6 +134,7 @@ pub fn describe(
         Statement::AlterObjectRename(stmt) => Some(ddl::describe_alter_object_rename(&scx, stmt)?),
         Statement::AlterIndex(stmt) => Some(ddl::describe_alter_index_options(&scx, stmt)?),
         Statement::AlterSecret(stmt) => Some(ddl::describe_alter_secret_options(&scx, stmt)?),
+        Statement::AlterSource(stmt) => Some(ddl::describe_alter_source(&scx, stmt)?),
 
         // `SHOW` statements.
         Statement::ShowCreateTable(stmt) => Some(show::describe_show_create_table(&scx, stmt)?),
@@ -360,6 +361,11 @@ pub fn plan(
             ddl::plan_alter_secret(scx, stmt)
         }
 
+        stmt @ Statement::AlterSource(_) => {
+            let (stmt, _) = resolve_stmt!(Statement::AlterSource, scx, stmt);
+            ddl::plan_alter_source(scx, stmt)
+        }
+
         // DML statements.
         stmt @ Statement::Insert(_) => {
             let (stmt, _) = resolve_stmt!(Statement::Insert, scx, stmt);
@@ -27,24 +27,29 @@ use regex::Regex;
 use reqwest::Url;
 use tracing::{debug, warn};
 
+use mz_dataflow_types::mysql_source::MySqlSourceDetails;
 use mz_dataflow_types::postgres_source::PostgresSourceDetails;
 use mz_dataflow_types::sinks::{
     KafkaSinkConnectorBuilder, KafkaSinkConnectorRetention, KafkaSinkFormat,
-    PersistSinkConnectorBuilder, SinkConnectorBuilder, SinkEnvelope,
+    PersistSinkConnectorBuilder, PostgresSinkConnectorBuilder, S3SinkConnectorBuilder,
+    S3SinkFormat, SinkConnectorBuilder, SinkEnvelope,
 };
 use mz_dataflow_types::sources::encoding::{
-    included_column_desc, AvroEncoding, ColumnSpec, CsvEncoding, DataEncoding, ProtobufEncoding,
-    RegexEncoding, SourceDataEncoding,
+    included_column_desc, AvroEncoding, ColumnSpec, CsvEncoding, DataEncoding, JsonColumn,
+    JsonEncoding, ParquetColumn, ParquetEncoding, ProtobufEncoding, RegexEncoding,
+    SourceDataEncoding,
 };
 use mz_dataflow_types::sources::{
     provide_default_metadata, ConnectorInner, DebeziumDedupProjection, DebeziumEnvelope,
     DebeziumMode, DebeziumSourceProjection, DebeziumTransactionMetadata, ExternalSourceConnector,
     IncludedColumnPos, KafkaSourceConnector, KeyEnvelope, KinesisSourceConnector,
-    PersistSourceConnector, PostgresSourceConnector, PubNubSourceConnector, S3SourceConnector,
-    SourceConnector, SourceEnvelope, Timeline, UnplannedSourceEnvelope, UpsertStyle,
+    MySqlSourceConnector, PersistSourceConnector, PostgresSourceConnector, PubNubSourceConnector,
+    S3SourceConnector, SourceConnector, SourceEnvelope, SqlServerSourceConnector, Timeline,
+    UnplannedSourceEnvelope, UpsertStyle, WebhookSourceConnector,
 };
 use mz_expr::CollectionPlan;
 use mz_interchange::avro::{self, AvroSchemaGenerator};
+use mz_interchange::protobuf::ProtobufSchemaGenerator;
 use mz_ore::collections::CollectionExt;
 use mz_ore::str::StrExt;
 use mz_postgres_util::TableInfo;
@@ -60,19 +65,19 @@ use crate::ast::display::AstDisplay;
 use crate::ast::visit::Visit;
 use crate::ast::{
     AlterIndexAction, AlterIndexStatement, AlterObjectRenameStatement, AlterSecretStatement,
-    AvroSchema, ClusterOption, ColumnOption, Compression, CreateClusterStatement,
-    CreateDatabaseStatement, CreateIndexStatement, CreateRoleOption, CreateRoleStatement,
-    CreateSchemaStatement, CreateSecretStatement, CreateSinkConnector, CreateSinkStatement,
-    CreateSourceConnector, CreateSourceFormat, CreateSourceStatement, CreateTableStatement,
-    CreateTypeAs, CreateTypeStatement, CreateViewStatement, CreateViewsDefinitions,
-    CreateViewsSourceTarget, CreateViewsStatement, CsrConnectorAvro, CsrConnectorProto,
-    CsrSeedCompiled, CsrSeedCompiledOrLegacy, CsvColumns, DbzMode, DropClusterReplicasStatement,
-    DropClustersStatement, DropDatabaseStatement, DropObjectsStatement, DropRolesStatement,
-    DropSchemaStatement, Envelope, Expr, Format, Ident, IfExistsBehavior, KafkaConsistency,
-    KeyConstraint, ObjectType, Op, ProtobufSchema, Query, Raw, Select, SelectItem, SetExpr,
-    SourceIncludeMetadata, SourceIncludeMetadataType, Statement, SubscriptPosition,
-    TableConstraint, TableFactor, TableWithJoins, UnresolvedDatabaseName, UnresolvedObjectName,
-    Value, ViewDefinition, WithOption,
+    AlterSourceAction, AlterSourceStatement, AvroSchema, ClusterOption, ColumnOption, Compression,
+    CreateClusterStatement, CreateDatabaseStatement, CreateIndexStatement, CreateRoleOption,
+    CreateRoleStatement, CreateSchemaStatement, CreateSecretStatement, CreateSinkConnector,
+    CreateSinkStatement, CreateSourceConnector, CreateSourceFormat, CreateSourceStatement,
+    CreateTableStatement, CreateTypeAs, CreateTypeStatement, CreateViewStatement,
+    CreateViewsDefinitions, CreateViewsSourceTarget, CreateViewsStatement, CsrConnectorAvro,
+    CsrConnectorProto, CsrSeedCompiled, CsrSeedCompiledOrLegacy, CsvColumns, DbzMode,
+    DropClusterReplicasStatement, DropClustersStatement, DropDatabaseStatement,
+    DropObjectsStatement, DropRolesStatement, DropSchemaStatement, Envelope, Expr, Format, Ident,
+    IfExistsBehavior, KafkaConsistency, KeyConstraint, ObjectType, Op, ProtobufSchema, Query, Raw,
+    Select, SelectItem, SetExpr, SourceIncludeMetadata, SourceIncludeMetadataType, Statement,
+    SubscriptPosition, TableConstraint, TableFactor, TableWithJoins, UnresolvedDatabaseName,
+    UnresolvedObjectName, Value, ViewDefinition, WebhookValidation, WithOption,
 };
 use crate::catalog::{CatalogItem, CatalogItemType, CatalogType, CatalogTypeDetails};
 use crate::connectors::populate_connectors;
@@ -89,8 +94,8 @@ use crate::plan::query::QueryLifetime;
 use crate::plan::statement::{StatementContext, StatementDesc};
 use crate::plan::{
     plan_utils, query, AlterIndexResetOptionsPlan, AlterIndexSetOptionsPlan, AlterItemRenamePlan,
-    AlterNoopPlan, AlterSecretPlan, ComputeInstanceIntrospectionConfig, Connector,
-    CreateComputeInstancePlan, CreateComputeInstanceReplicaPlan, CreateConnectorPlan,
+    AlterNoopPlan, AlterSecretPlan, AlterSourceRefreshPlan, ComputeInstanceIntrospectionConfig,
+    Connector, CreateComputeInstancePlan, CreateComputeInstanceReplicaPlan, CreateConnectorPlan,
     CreateDatabasePlan, CreateIndexPlan, CreateRolePlan, CreateSchemaPlan, CreateSecretPlan,
     CreateSinkPlan, CreateSourcePlan, CreateTablePlan, CreateTypePlan, CreateViewPlan,
     CreateViewsPlan, DropComputeInstanceReplicaPlan, DropComputeInstancesPlan, DropDatabasePlan,
@@ -197,6 +202,10 @@ pub fn plan_create_table(
     let mut column_types = Vec::with_capacity(columns.len());
     let mut defaults = Vec::with_capacity(columns.len());
     let mut keys = Vec::new();
+    // Check constraints and foreign keys are planned once all columns are
+    // known, as they may refer to columns declared later.
+    let mut check_exprs = vec![];
+    let mut foreign_key_defs = vec![];
 
     for (i, c) in columns.into_iter().enumerate() {
         let aug_data_type = &c.data_type;
@@ -218,6 +227,11 @@ pub fn plan_create_table(
                         nullable = false;
                     }
                 }
+                ColumnOption::ForeignKey {
+                    foreign_table,
+                    referred_columns,
+                } => foreign_key_defs.push((vec![i], foreign_table, referred_columns)),
+                ColumnOption::Check(expr) => check_exprs.push((option.name.as_ref(), expr)),
                 other => {
                     bail_unsupported!(format!("CREATE TABLE with column constraint: {}", other))
                 }
@@ -249,16 +263,23 @@ pub fn plan_create_table(
                 }
                 keys.push(key);
             }
-            TableConstraint::ForeignKey { .. } => {
-                // Foreign key constraints are not presently enforced. We allow
-                // them in experimental mode for sqllogictest's sake.
-                scx.require_experimental_mode("CREATE TABLE with a foreign key")?
-            }
-            TableConstraint::Check { .. } => {
-                // Check constraints are not presently enforced. We allow them
-                // in experimental mode for sqllogictest's sake.
-                scx.require_experimental_mode("CREATE TABLE with a check constraint")?
+            TableConstraint::ForeignKey {
+                name: _,
+                columns,
+                foreign_table,
+                referred_columns,
+            } => {
+                let mut key = vec![];
+                for column in columns {
+                    let column = normalize::column_name(column.clone());
+                    match names.iter().position(|name| *name == column) {
+                        None => bail!("unknown column in constraint: {}", column),
+                        Some(i) => key.push(i),
+                    }
+                }
+                foreign_key_defs.push((key, foreign_table, referred_columns));
             }
+            TableConstraint::Check { name, expr } => check_exprs.push((name.as_ref(), &**expr)),
         }
     }
 
@@ -278,12 +299,110 @@ pub fn plan_create_table(
     };
     let desc = RelationDesc::new(typ, names);
 
+    let mut checks: Vec<(String, _)> = Vec::with_capacity(check_exprs.len());
+    for (constraint_name, expr) in check_exprs {
+        let expr = query::plan_check_expr(scx, &desc, expr.clone())?;
+        let constraint_name = match constraint_name {
+            Some(constraint_name) => {
+                let constraint_name = normalize::ident(constraint_name.clone());
+                if checks
+                    .iter()
+                    .any(|(existing, _)| *existing == constraint_name)
+                {
+                    bail!(
+                        "constraint {} for relation {} already exists",
+                        constraint_name.quoted(),
+                        name.item.quoted()
+                    );
+                }
+                constraint_name
+            }
+            None => {
+                // Like PostgreSQL, we name unnamed constraints after the table
+                // and, if there is exactly one, the column they refer to, and
+                // disambiguate them with a counter.
+                let support = expr.support();
+                let prefix = match support.iter().exactly_one() {
+                    Ok(column) => format!("{}_{}", name.item, desc.get_name(*column).as_str()),
+                    Err(_) => name.item.clone(),
+                };
+                let mut constraint_name = format!("{}_check", prefix);
+                let mut counter = 0;
+                while checks
+                    .iter()
+                    .any(|(existing, _)| *existing == constraint_name)
+                {
+                    counter += 1;
+                    constraint_name = format!("{}_check{}", prefix, counter);
+                }
+                constraint_name
+            }
+        };
+        checks.push((constraint_name, expr));
+    }
+
+    let mut foreign_keys = Vec::with_capacity(foreign_key_defs.len());
+    for (columns, foreign_table, referred_columns) in foreign_key_defs {
+        // Foreign key constraints are not presently enforced. We allow
+        // them in experimental mode for sqllogictest's sake.
+        scx.require_experimental_mode("CREATE TABLE with a foreign key")?;
+
+        let parent = scx.get_item_by_resolved_name(foreign_table)?;
+        if parent.item_type() != CatalogItemType::Table {
+            bail!(
+                "referenced relation {} is not a table",
+                foreign_table.full_name_str().quoted()
+            );
+        }
+        if columns.len() != referred_columns.len() {
+            bail!("number of referencing and referenced columns for foreign key disagree");
+        }
+        let parent_desc = parent.desc(&scx.catalog.resolve_full_name(parent.name()))?;
+        let mut pairs = Vec::with_capacity(columns.len());
+        for (child_column, parent_column) in columns.into_iter().zip(referred_columns) {
+            let parent_column = normalize::column_name(parent_column.clone());
+            let (parent_column, parent_type) = match parent_desc.get_by_name(&parent_column) {
+                Some(column) => column,
+                None => bail!(
+                    "column {} referenced in foreign key constraint does not exist",
+                    parent_column.as_str().quoted()
+                ),
+            };
+            let child_type = &desc.typ().column_types[child_column];
+            if !child_type.scalar_type.base_eq(&parent_type.scalar_type) {
+                bail!(
+                    "foreign key constraint cannot be implemented: key columns {} and {} are of \
+                     incompatible types",
+                    desc.get_name(child_column).as_str().quoted(),
+                    parent_desc.get_name(parent_column).as_str().quoted()
+                );
+            }
+            pairs.push((child_column, parent_column));
+        }
+        // The referenced columns must form a key of the parent table.
+        let referred: BTreeSet<_> = pairs.iter().map(|(_, parent)| *parent).collect();
+        if !parent_desc
+            .typ()
+            .keys
+            .iter()
+            .any(|key| key.iter().copied().collect::<BTreeSet<_>>() == referred)
+        {
+            bail!(
+                "there is no unique constraint matching given keys for referenced table {}",
+                foreign_table.full_name_str().quoted()
+            );
+        }
+        foreign_keys.push((parent.id(), pairs));
+    }
+
     let create_sql = normalize::create_statement(&scx, Statement::CreateTable(stmt.clone()))?;
     let depends_on = depends_on.into_iter().collect();
     let table = Table {
         create_sql,
         desc,
         defaults,
+        checks,
+        foreign_keys,
         temporary,
         depends_on,
     };
@@ -294,6 +413,14 @@ pub fn plan_create_table(
     }))
 }
 
+/// The request header that carries the signature of requests to webhook
+/// sources, if not otherwise specified.
+const DEFAULT_WEBHOOK_SIGNATURE_HEADER: &str = "x-signature";
+
+/// How often S3 sources re-list buckets that are discovered with `BUCKET POLL`,
+/// if not otherwise specified.
+const DEFAULT_S3_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
 pub fn describe_create_source(
     _: &StatementContext,
     _: &CreateSourceStatement<Raw>,
@@ -347,7 +474,11 @@ pub fn plan_create_source(
         None => scx.catalog.config().timestamp_frequency,
     };
 
-    if !matches!(connector, CreateSourceConnector::Kafka { .. })
+    let supports_include = matches!(
+        connector,
+        CreateSourceConnector::Kafka { .. } | CreateSourceConnector::Webhook { .. }
+    );
+    if !supports_include
         && include_metadata
             .iter()
             .any(|sic| sic.ty == SourceIncludeMetadataType::Headers)
@@ -355,12 +486,15 @@ pub fn plan_create_source(
         // TODO(guswynn): should this be `bail_unsupported!`?
         bail!("INCLUDE HEADERS with non-Kafka sources not supported");
     }
-    if !matches!(connector, CreateSourceConnector::Kafka { .. }) && !include_metadata.is_empty() {
+    if !supports_include && !include_metadata.is_empty() {
         bail_unsupported!("INCLUDE metadata with non-Kafka sources");
     }
 
     let (external_connector, encoding) = match connector {
         CreateSourceConnector::Kafka(kafka) => {
+            if kafka.start_timestamp.is_some() {
+                bail!("[internal error] START TIMESTAMP should be resolved in purify");
+            }
             let (broker, options) = match &kafka.connector {
                 mz_sql_parser::ast::KafkaConnector::Inline { broker } => (broker.to_owned(), None),
                 mz_sql_parser::ast::KafkaConnector::Reference {
@@ -420,7 +554,7 @@ pub fn plan_create_source(
                 Some(v) => bail!("invalid start_offset value: {}", v),
             }
 
-            let encoding = get_encoding(format, &envelope, with_options_original)?;
+            let encoding = get_encoding(scx, format, &envelope, with_options_original)?;
 
             let mut connector = KafkaSourceConnector {
                 addrs: broker.parse()?,
@@ -516,7 +650,7 @@ pub fn plan_create_source(
             let aws = normalize::aws_config(&mut with_options, Some(region.into()))?;
             let connector =
                 ExternalSourceConnector::Kinesis(KinesisSourceConnector { stream_name, aws });
-            let encoding = get_encoding(format, &envelope, with_options_original)?;
+            let encoding = get_encoding(scx, format, &envelope, with_options_original)?;
             (connector, encoding)
         }
         CreateSourceConnector::S3 {
@@ -533,6 +667,23 @@ pub fn plan_create_source(
                             bucket: bucket.clone(),
                         }
                     }
+                    mz_sql_parser::ast::S3KeySource::Poll { bucket, interval } => {
+                        let interval = match interval {
+                            Some(interval) => {
+                                let interval =
+                                    mz_repr::strconv::parse_interval(interval)?.duration()?;
+                                if interval.is_zero() {
+                                    bail!("BUCKET POLL interval must be greater than zero");
+                                }
+                                interval
+                            }
+                            None => DEFAULT_S3_POLL_INTERVAL,
+                        };
+                        mz_dataflow_types::sources::S3KeySource::Poll {
+                            bucket: bucket.clone(),
+                            interval,
+                        }
+                    }
                     mz_sql_parser::ast::S3KeySource::SqsNotifications { queue } => {
                         mz_dataflow_types::sources::S3KeySource::SqsNotifications {
                             queue: queue.clone(),
@@ -555,10 +706,12 @@ pub fn plan_create_source(
                 aws,
                 compression: match compression {
                     Compression::Gzip => mz_dataflow_types::sources::Compression::Gzip,
+                    Compression::Zstd => mz_dataflow_types::sources::Compression::Zstd,
+                    Compression::Snappy => mz_dataflow_types::sources::Compression::Snappy,
                     Compression::None => mz_dataflow_types::sources::Compression::None,
                 },
             });
-            let encoding = get_encoding(format, &envelope, with_options_original)?;
+            let encoding = get_encoding(scx, format, &envelope, with_options_original)?;
             if matches!(encoding, SourceDataEncoding::KeyValue { .. }) {
                 bail!("S3 sources do not support key decoding");
             }
@@ -573,6 +726,21 @@ pub fn plan_create_source(
             let slot_name = slot
                 .as_ref()
                 .ok_or_else(|| anyhow!("Postgres sources must provide a slot name"))?;
+            let retract_on_truncate = match with_options.remove("truncate") {
+                None => false,
+                Some(Value::String(s)) if s == "error" => false,
+                Some(Value::String(s)) if s == "retract" => true,
+                Some(v) => bail!("truncate must be 'error' or 'retract', got {}", v),
+            };
+            let track_primary_keys = match with_options.remove("default_replica_identity") {
+                None => false,
+                Some(Value::String(s)) if s == "error" => false,
+                Some(Value::String(s)) if s == "track" => true,
+                Some(v) => bail!(
+                    "default_replica_identity must be 'error' or 'track', got {}",
+                    v
+                ),
+            };
             let connector = ExternalSourceConnector::Postgres(PostgresSourceConnector {
                 conn: conn.clone(),
                 publication: publication.clone(),
@@ -582,8 +750,42 @@ pub fn plan_create_source(
                         .as_ref()
                         .expect("Postgres source must provide associated details"),
                 )?))?,
+                retract_on_truncate,
+                track_primary_keys,
+            });
+
+            let encoding = SourceDataEncoding::Single(DataEncoding::Postgres);
+            (connector, encoding)
+        }
+        CreateSourceConnector::MySql {
+            conn,
+            database,
+            details,
+        } => {
+            let connector = ExternalSourceConnector::MySql(MySqlSourceConnector {
+                conn: conn.clone(),
+                database: database.clone(),
+                details: MySqlSourceDetails::decode(Bytes::from(hex::decode(
+                    details
+                        .as_ref()
+                        .expect("MySQL source must provide associated details"),
+                )?))?,
             });
 
+            let encoding = SourceDataEncoding::Single(DataEncoding::MySql);
+            (connector, encoding)
+        }
+        CreateSourceConnector::SqlServer { conn, details } => {
+            let connector = ExternalSourceConnector::SqlServer(SqlServerSourceConnector {
+                conn: conn.clone(),
+                details: PostgresSourceDetails::decode(Bytes::from(hex::decode(
+                    details
+                        .as_ref()
+                        .expect("SQL Server source must provide associated details"),
+                )?))?,
+            });
+
+            // SQL Server sources produce the same rows as Postgres sources.
             let encoding = SourceDataEncoding::Single(DataEncoding::Postgres);
             (connector, encoding)
         }
@@ -674,6 +876,72 @@ pub fn plan_create_source(
                 SourceDataEncoding::Single(DataEncoding::RowCodec(desc)),
             )
         }
+        CreateSourceConnector::Webhook { validation } => {
+            if envelope != Envelope::None {
+                bail!("CREATE SOURCE ... WEBHOOK does not support ENVELOPE");
+            }
+            // Requests are appended at the timestamps of table writes.
+            if with_options.contains_key("timeline") {
+                bail!("CREATE SOURCE ... WEBHOOK does not support the timeline option");
+            }
+            // Request bodies are decoded by the coordinator as they arrive,
+            // so only formats that map a body to a single column are
+            // supported.
+            let encoding = match format {
+                CreateSourceFormat::Bare(Format::Bytes) => DataEncoding::Bytes,
+                CreateSourceFormat::Bare(Format::Text) => DataEncoding::Text,
+                CreateSourceFormat::Bare(Format::Json { columns }) if columns.is_empty() => {
+                    DataEncoding::Json(JsonEncoding { columns: vec![] })
+                }
+                _ => bail!("CREATE SOURCE ... WEBHOOK must specify FORMAT BYTES, TEXT, or JSON"),
+            };
+
+            let validation = match validation {
+                Some(WebhookValidation { secret, header }) => {
+                    let item = scx
+                        .catalog
+                        .resolve_item(&normalize::unresolved_object_name(secret.clone())?)?;
+                    if item.item_type() != CatalogItemType::Secret {
+                        bail!(
+                            "{} is not a secret",
+                            scx.catalog.resolve_full_name(item.name())
+                        );
+                    }
+                    depends_on.push(item.id());
+                    Some(mz_dataflow_types::sources::WebhookValidation {
+                        secret_id: item.id(),
+                        header: header
+                            .clone()
+                            .unwrap_or_else(|| DEFAULT_WEBHOOK_SIGNATURE_HEADER.into())
+                            .to_lowercase(),
+                    })
+                }
+                None => None,
+            };
+
+            let mut include_headers = None;
+            for (pos, item) in include_metadata.iter().enumerate() {
+                match item.ty {
+                    SourceIncludeMetadataType::Headers => {
+                        include_headers = Some(IncludedColumnPos {
+                            name: item
+                                .alias
+                                .as_ref()
+                                .map(|a| a.to_string())
+                                .unwrap_or_else(|| "headers".into()),
+                            pos,
+                        });
+                    }
+                    ty => bail_unsupported!(format!("INCLUDE {} with webhook sources", ty)),
+                }
+            }
+
+            let connector = ExternalSourceConnector::Webhook(WebhookSourceConnector {
+                validation,
+                include_headers,
+            });
+            (connector, SourceDataEncoding::Single(encoding))
+        }
     };
     let (key_desc, value_desc) = encoding.desc()?;
 
@@ -865,7 +1133,17 @@ pub fn plan_create_source(
             //TODO check that key envelope is not set
             match format {
                 CreateSourceFormat::Bare(Format::Avro(_)) => {}
-                _ => bail_unsupported!("non-Avro-encoded ENVELOPE MATERIALIZE"),
+                CreateSourceFormat::Bare(Format::Protobuf(_)) => {
+                    typecheck_cdcv2_protobuf(&value_desc)?
+                }
+                CreateSourceFormat::Bare(Format::Json { columns }) => {
+                    if columns.is_empty() {
+                        bail!("ENVELOPE MATERIALIZE with FORMAT JSON requires a column list");
+                    }
+                }
+                _ => bail_unsupported!(
+                    "ENVELOPE MATERIALIZE with formats other than Avro, Protobuf, or JSON"
+                ),
             }
             UnplannedSourceEnvelope::CdcV2
         }
@@ -876,6 +1154,12 @@ pub fn plan_create_source(
     let metadata_columns = external_connector.metadata_columns(include_defaults);
     let metadata_column_types = external_connector.metadata_column_types(include_defaults);
     let metadata_desc = included_column_desc(metadata_columns.clone());
+    // The column list of a JSON-encoded CDCv2 source describes the update data,
+    // so nest it in the update batch shape that the envelope unwraps.
+    let value_desc = match (&envelope, encoding.value_ref()) {
+        (UnplannedSourceEnvelope::CdcV2, DataEncoding::Json(_)) => cdcv2_updates_desc(value_desc),
+        _ => value_desc,
+    };
     let (envelope, mut desc) = envelope.desc(key_desc, value_desc, metadata_desc)?;
 
     // Append default metadata columns if column aliases were provided but do not include them.
@@ -890,6 +1174,7 @@ pub fn plan_create_source(
     // actually exist?
     let tmp_col;
     let col_names = if include_defaults
+        && external_connector.default_metadata_column_name().is_some()
         && !col_names.is_empty()
         && metadata_columns.len() + col_names.len() == desc.arity()
     {
@@ -1018,6 +1303,47 @@ fn typecheck_debezium(value_desc: &RelationDesc) -> Result<(usize, usize), anyho
     Ok((before_idx, after_idx))
 }
 
+/// Checks that a Protobuf message has the shape of a CDCv2 message: an
+/// `updates` field holding the repeated update batch, followed by an optional
+/// `progress` field.
+fn typecheck_cdcv2_protobuf(value_desc: &RelationDesc) -> Result<(), anyhow::Error> {
+    let names: Vec<_> = value_desc.iter_names().map(|name| name.as_str()).collect();
+    if names != ["updates", "progress"] {
+        bail!(
+            "ENVELOPE MATERIALIZE with FORMAT PROTOBUF requires a message with \
+            exactly the fields 'updates' and 'progress', in that order"
+        );
+    }
+    Ok(())
+}
+
+/// Describes a batch of CDCv2 updates whose data has the columns in `data_desc`.
+fn cdcv2_updates_desc(data_desc: RelationDesc) -> RelationDesc {
+    let data = ScalarType::Record {
+        fields: data_desc
+            .iter()
+            .map(|(name, typ)| (name.clone(), typ.clone()))
+            .collect(),
+        custom_id: None,
+    };
+    let update = ScalarType::Record {
+        fields: vec![
+            ("data".into(), data.nullable(false)),
+            ("time".into(), ScalarType::Int64.nullable(false)),
+            ("diff".into(), ScalarType::Int64.nullable(false)),
+        ],
+        custom_id: None,
+    };
+    RelationDesc::empty().with_column(
+        "updates",
+        ScalarType::List {
+            element_type: Box::new(update),
+            custom_id: None,
+        }
+        .nullable(false),
+    )
+}
+
 fn typecheck_debezium_dedup(
     value_desc: &RelationDesc,
     tx_metadata: Option<DebeziumTransactionMetadata>,
@@ -1247,19 +1573,20 @@ fn typecheck_debezium_transaction_metadata(
 }
 
 fn get_encoding(
+    scx: &StatementContext,
     format: &CreateSourceFormat<Aug>,
     envelope: &Envelope,
     with_options: &Vec<WithOption<Aug>>,
 ) -> Result<SourceDataEncoding, anyhow::Error> {
     let encoding = match format {
         CreateSourceFormat::None => bail!("Source format must be specified"),
-        CreateSourceFormat::Bare(format) => get_encoding_inner(format, with_options)?,
+        CreateSourceFormat::Bare(format) => get_encoding_inner(scx, format, with_options)?,
         CreateSourceFormat::KeyValue { key, value } => {
-            let key = match get_encoding_inner(key, with_options)? {
+            let key = match get_encoding_inner(scx, key, with_options)? {
                 SourceDataEncoding::Single(key) => key,
                 SourceDataEncoding::KeyValue { key, .. } => key,
             };
-            let value = match get_encoding_inner(value, with_options)? {
+            let value = match get_encoding_inner(scx, value, with_options)? {
                 SourceDataEncoding::Single(value) => value,
                 SourceDataEncoding::KeyValue { value, .. } => value,
             };
@@ -1280,6 +1607,7 @@ fn get_encoding(
 }
 
 fn get_encoding_inner(
+    scx: &StatementContext,
     format: &Format<Aug>,
     with_options: &Vec<WithOption<Aug>>,
 ) -> Result<SourceDataEncoding, anyhow::Error> {
@@ -1398,6 +1726,7 @@ fn get_encoding_inner(
                         seed,
                         with_options: ccsr_options,
                     },
+                ..
             } => {
                 if let Some(CsrSeedCompiledOrLegacy::Compiled(CsrSeedCompiled { key, value })) =
                     seed
@@ -1468,6 +1797,10 @@ fn get_encoding_inner(
                     confluent_wire_format: false,
                 })
             }
+            ProtobufSchema::Derived => bail!(
+                "FORMAT PROTOBUF sources require a schema; \
+                 specify MESSAGE ... USING SCHEMA or USING CONFLUENT SCHEMA REGISTRY"
+            ),
         },
         Format::Regex(regex) => {
             let regex = Regex::new(&regex)?;
@@ -1495,8 +1828,57 @@ fn get_encoding_inner(
                 },
             })
         }
-        Format::Json => bail_unsupported!("JSON sources"),
+        Format::Json { columns } => {
+            let mut json_columns = Vec::with_capacity(columns.len());
+            for c in columns {
+                let ty = query::scalar_type_from_sql(scx, &c.data_type)?;
+                let cast = query::plan_json_column_cast(scx, &ty)?;
+                json_columns.push(JsonColumn {
+                    name: normalize::ident(c.name.clone()),
+                    typ: ty.nullable(true),
+                    cast,
+                });
+            }
+            DataEncoding::Json(JsonEncoding {
+                columns: json_columns,
+            })
+        }
         Format::Text => DataEncoding::Text,
+        Format::Parquet { columns } => {
+            if columns.is_empty() {
+                bail!("[internal error] FORMAT PARQUET column list should be derived in purify")
+            }
+            let mut parquet_columns = Vec::with_capacity(columns.len());
+            for c in columns {
+                let ty = query::scalar_type_from_sql(scx, &c.data_type)?;
+                match ty {
+                    ScalarType::Bool
+                    | ScalarType::Int16
+                    | ScalarType::Int32
+                    | ScalarType::Int64
+                    | ScalarType::Float32
+                    | ScalarType::Float64
+                    | ScalarType::Numeric { .. }
+                    | ScalarType::String
+                    | ScalarType::Bytes
+                    | ScalarType::Date
+                    | ScalarType::Time
+                    | ScalarType::Timestamp
+                    | ScalarType::TimestampTz => (),
+                    _ => bail!(
+                        "FORMAT PARQUET does not support columns of type {}",
+                        scx.humanize_scalar_type(&ty)
+                    ),
+                }
+                parquet_columns.push(ParquetColumn {
+                    name: normalize::ident(c.name.clone()),
+                    typ: ty.nullable(true),
+                });
+            }
+            DataEncoding::Parquet(ParquetEncoding {
+                columns: parquet_columns,
+            })
+        }
     }))
 }
 
@@ -1523,12 +1905,14 @@ fn get_key_envelope(
                 //
                 // Otherwise it gets the names of the columns in the type
                 let is_composite = match key {
-                    DataEncoding::Postgres | DataEncoding::RowCodec(_) => {
+                    DataEncoding::Postgres | DataEncoding::MySql | DataEncoding::RowCodec(_) => {
                         bail!("{} sources cannot use INCLUDE KEY", key.op_name())
                     }
                     DataEncoding::Bytes | DataEncoding::Text => false,
+                    DataEncoding::Json(JsonEncoding { columns }) => !columns.is_empty(),
                     DataEncoding::Avro(_)
                     | DataEncoding::Csv(_)
+                    | DataEncoding::Parquet(_)
                     | DataEncoding::Protobuf(_)
                     | DataEncoding::Regex { .. } => true,
                 };
@@ -1711,7 +2095,8 @@ pub fn plan_create_views(
             match source_connector {
                 SourceConnector::External {
                     connector:
-                        ExternalSourceConnector::Postgres(PostgresSourceConnector { details, .. }),
+                        ExternalSourceConnector::Postgres(PostgresSourceConnector { details, .. })
+                        | ExternalSourceConnector::SqlServer(SqlServerSourceConnector { details, .. }),
                     ..
                 } => {
                     let targets = targets.unwrap_or_else(|| {
@@ -1757,8 +2142,8 @@ pub fn plan_create_views(
                                 ))
                             })?,
                         };
-                        let mut projection = vec![];
-                        for (i, column) in table_info.schema.iter().enumerate() {
+                        let mut columns = vec![];
+                        for column in &table_info.schema {
                             let mut ty =
                                 mz_pgrepr::Type::from_oid_and_typmod(column.oid, column.typmod)?;
                             // Ignore precision constraints on date/time types until we support
@@ -1787,75 +2172,69 @@ pub fn plan_create_views(
                             if ty == "pg_catalog.json" {
                                 ty = "pg_catalog.jsonb".into();
                             }
-                            let data_type = mz_sql_parser::parser::parse_data_type(&ty)?;
-                            let (data_type, _) = resolve_names_data_type(scx, data_type)?;
-                            projection.push(SelectItem::Expr {
-                                expr: Expr::Cast {
-                                    expr: Box::new(Expr::Subscript {
-                                        expr: Box::new(Expr::Identifier(vec![Ident::new(
-                                            "row_data",
-                                        )])),
-                                        positions: vec![SubscriptPosition {
-                                            start: Some(Expr::Value(Value::Number(
-                                                // LIST is one based
-                                                (i + 1).to_string(),
-                                            ))),
-                                            end: None,
-                                            explicit_slice: false,
-                                        }],
-                                    }),
-                                    data_type,
-                                },
-                                alias: Some(Ident::new(column.name.clone())),
-                            });
+                            columns.push((column.name.clone(), ty));
                         }
-                        let query = Query {
-                            ctes: vec![],
-                            body: SetExpr::Select(Box::new(Select {
-                                distinct: None,
-                                projection,
-                                from: vec![TableWithJoins {
-                                    relation: TableFactor::Table {
-                                        name: source_name.clone(),
-                                        alias: None,
-                                    },
-                                    joins: vec![],
-                                }],
-                                selection: Some(Expr::Op {
-                                    op: Op::bare("="),
-                                    expr1: Box::new(Expr::Identifier(vec![Ident::new("oid")])),
-                                    expr2: Some(Box::new(Expr::Value(Value::Number(
-                                        table_info.rel_id.to_string(),
-                                    )))),
-                                }),
-                                group_by: vec![],
-                                having: None,
-                                options: vec![],
-                            })),
-                            order_by: vec![],
-                            limit: None,
-                            offset: None,
-                        };
-
-                        let mut viewdef = ViewDefinition {
-                            name: view_name,
-                            columns: table_info
-                                .schema
-                                .iter()
-                                .map(|c| Ident::new(c.name.clone()))
-                                .collect(),
-                            with_options: vec![],
-                            query,
-                        };
-                        let mut depends_on_collector = DependsOnCollector::new();
-                        depends_on_collector.visit_view_definition(&viewdef);
-                        let depends_on = depends_on_collector.get_ids().clone();
-                        views.push(plan_view(
+                        views.push(plan_source_table_view(
+                            scx,
+                            &source_name,
+                            view_name,
+                            ("oid", table_info.rel_id.to_string()),
+                            columns,
+                            temporary,
+                        )?);
+                    }
+                    Ok(Plan::CreateViews(CreateViewsPlan {
+                        views,
+                        if_not_exists: if_exists == IfExistsBehavior::Skip,
+                        materialize: materialized,
+                    }))
+                }
+                SourceConnector::External {
+                    connector: ExternalSourceConnector::MySql(MySqlSourceConnector { details, .. }),
+                    ..
+                } => {
+                    // Tables are identified by their position in the source's
+                    // details.
+                    let targets = targets.unwrap_or_else(|| {
+                        details
+                            .tables
+                            .iter()
+                            .map(|t| {
+                                let name = UnresolvedObjectName::unqualified(&t.name);
+                                CreateViewsSourceTarget {
+                                    name: name.clone(),
+                                    alias: Some(name),
+                                }
+                            })
+                            .collect()
+                    });
+                    let mut views = Vec::with_capacity(targets.len());
+                    for target in targets {
+                        let view_name = target.alias.clone().unwrap_or_else(|| target.name.clone());
+                        let name = normalize::unresolved_object_name(target.name.clone())?;
+                        let (table_id, table) = details
+                            .tables
+                            .iter()
+                            .enumerate()
+                            .find(|(_, t)| {
+                                t.name == name.item
+                                    && name.schema.as_ref().map_or(true, |db| *db == t.database)
+                            })
+                            .ok_or_else(|| {
+                                anyhow!("table {} not found in upstream database", name)
+                            })?;
+                        let columns = table
+                            .columns
+                            .iter()
+                            .map(|c| Ok((c.name.clone(), c.cast_type()?)))
+                            .collect::<Result<_, anyhow::Error>>()?;
+                        views.push(plan_source_table_view(
                             scx,
-                            &mut viewdef,
-                            &Params::empty(),
+                            &source_name,
+                            view_name,
+                            ("table_id", table_id.to_string()),
+                            columns,
                             temporary,
-                            depends_on,
                         )?);
                     }
                     Ok(Plan::CreateViews(CreateViewsPlan {
@@ -1878,6 +2257,84 @@ pub fn plan_create_views(
     }
 }
 
+/// Plans a view that selects the rows of one upstream table from a source
+/// that replicates several tables, like Postgres and MySQL sources.
+///
+/// The rows of the table are those whose `id_column` is `id`. Each of the
+/// `columns` is a `(name, type)` pair; its values are cast from the text in the
+/// corresponding element of the `row_data` column.
+fn plan_source_table_view(
+    scx: &StatementContext,
+    source_name: &ResolvedObjectName,
+    view_name: UnresolvedObjectName,
+    (id_column, id): (&str, String),
+    columns: Vec<(String, String)>,
+    temporary: bool,
+) -> Result<(QualifiedObjectName, View), anyhow::Error> {
+    let mut projection = vec![];
+    for (i, (name, ty)) in columns.iter().enumerate() {
+        let data_type = mz_sql_parser::parser::parse_data_type(ty)?;
+        let (data_type, _) = resolve_names_data_type(scx, data_type)?;
+        projection.push(SelectItem::Expr {
+            expr: Expr::Cast {
+                expr: Box::new(Expr::Subscript {
+                    expr: Box::new(Expr::Identifier(vec![Ident::new("row_data")])),
+                    positions: vec![SubscriptPosition {
+                        start: Some(Expr::Value(Value::Number(
+                            // LIST is one based
+                            (i + 1).to_string(),
+                        ))),
+                        end: None,
+                        explicit_slice: false,
+                    }],
+                }),
+                data_type,
+            },
+            alias: Some(Ident::new(name.clone())),
+        });
+    }
+    let query = Query {
+        ctes: vec![],
+        recursive: false,
+        body: SetExpr::Select(Box::new(Select {
+            distinct: None,
+            projection,
+            from: vec![TableWithJoins {
+                relation: TableFactor::Table {
+                    name: source_name.clone(),
+                    alias: None,
+                },
+                joins: vec![],
+            }],
+            selection: Some(Expr::Op {
+                op: Op::bare("="),
+                expr1: Box::new(Expr::Identifier(vec![Ident::new(id_column)])),
+                expr2: Some(Box::new(Expr::Value(Value::Number(id)))),
+            }),
+            group_by: vec![],
+            having: None,
+            options: vec![],
+        })),
+        order_by: vec![],
+        limit: None,
+        offset: None,
+    };
+
+    let mut viewdef = ViewDefinition {
+        name: view_name,
+        columns: columns
+            .into_iter()
+            .map(|(name, _ty)| Ident::new(name))
+            .collect(),
+        with_options: vec![],
+        query,
+    };
+    let mut depends_on_collector = DependsOnCollector::new();
+    depends_on_collector.visit_view_definition(&viewdef);
+    let depends_on = depends_on_collector.get_ids().clone();
+    plan_view(scx, &mut viewdef, &Params::empty(), temporary, depends_on)
+}
+
 #[allow(clippy::too_many_arguments)]
 fn kafka_sink_builder(
     scx: &StatementContext,
@@ -1932,6 +2389,21 @@ fn kafka_sink_builder(
         bail!("Must specify both avro_key_fullname and avro_value_fullname when specifying generated schema names");
     }
 
+    if envelope == SinkEnvelope::CdcV2 {
+        if key_desc_and_indices.is_some() {
+            bail!("ENVELOPE MATERIALIZE sinks do not support KEY");
+        }
+        if reuse_topic || consistency_topic.is_some() || consistency.is_some() {
+            bail!(
+                "ENVELOPE MATERIALIZE sinks do not support consistency topics; \
+                 progress is reported inline in the sink topic"
+            );
+        }
+        if avro_value_fullname.is_some() {
+            bail!("ENVELOPE MATERIALIZE sinks do not support avro_value_fullname");
+        }
+    }
+
     let format = match format {
         Some(Format::Avro(AvroSchema::Csr {
             csr_connector:
@@ -1953,33 +2425,102 @@ fn kafka_sink_builder(
                 &mut ccsr_with_options,
             )?;
 
+            let (key_schema, value_schema) = if envelope == SinkEnvelope::CdcV2 {
+                let encoder = avro::cdc_v2::Encoder::new(value_desc.clone());
+                (None, encoder.writer_schema().to_string())
+            } else {
+                let include_transaction =
+                    reuse_topic || consistency_topic.is_some() || consistency.is_some();
+                let schema_generator = AvroSchemaGenerator::new(
+                    avro_key_fullname.as_deref(),
+                    avro_value_fullname.as_deref(),
+                    key_desc_and_indices
+                        .as_ref()
+                        .map(|(desc, _indices)| desc.clone()),
+                    value_desc.clone(),
+                    matches!(envelope, SinkEnvelope::Debezium),
+                    include_transaction,
+                );
+                let value_schema = schema_generator.value_writer_schema().to_string();
+                let key_schema = schema_generator
+                    .key_writer_schema()
+                    .map(|key_schema| key_schema.to_string());
+                (key_schema, value_schema)
+            };
+
+            normalize::ensure_empty_options(&ccsr_with_options, "CONFLUENT SCHEMA REGISTRY")?;
+
+            KafkaSinkFormat::Avro {
+                schema_registry_url,
+                key_schema,
+                value_schema,
+                ccsr_config,
+            }
+        }
+        Some(Format::Json { columns }) => {
+            if !columns.is_empty() {
+                bail!("FORMAT JSON sinks do not support a column list");
+            }
+            if envelope == SinkEnvelope::CdcV2 {
+                bail_unsupported!("ENVELOPE MATERIALIZE sinks with FORMAT JSON");
+            }
+            KafkaSinkFormat::Json
+        }
+        Some(Format::Protobuf(schema @ (ProtobufSchema::Csr { .. } | ProtobufSchema::Derived))) => {
+            if envelope == SinkEnvelope::CdcV2 {
+                bail_unsupported!("ENVELOPE MATERIALIZE sinks with FORMAT PROTOBUF");
+            }
+            let ccsr_config = match schema {
+                ProtobufSchema::Csr {
+                    csr_connector:
+                        CsrConnectorProto {
+                            connector: CsrConnector::Inline { url },
+                            seed,
+                            with_options,
+                        },
+                    message_name,
+                } => {
+                    if seed.is_some() {
+                        bail!("SEED option does not make sense with sinks");
+                    }
+                    if message_name.is_some() {
+                        bail!(
+                            "MESSAGE option does not make sense with sinks; \
+                             the message is derived from the sinked relation"
+                        );
+                    }
+                    let mut ccsr_with_options = normalize::options(&with_options);
+                    let ccsr_config = kafka_util::generate_ccsr_client_config(
+                        url.parse::<Url>()?,
+                        &config_options,
+                        &mut ccsr_with_options,
+                    )?;
+                    normalize::ensure_empty_options(
+                        &ccsr_with_options,
+                        "CONFLUENT SCHEMA REGISTRY",
+                    )?;
+                    Some(ccsr_config)
+                }
+                ProtobufSchema::Derived => None,
+                schema => bail_unsupported!(format!("sink format PROTOBUF {}", schema)),
+            };
+
             let include_transaction =
                 reuse_topic || consistency_topic.is_some() || consistency.is_some();
-            let schema_generator = AvroSchemaGenerator::new(
-                avro_key_fullname.as_deref(),
-                avro_value_fullname.as_deref(),
+            let schema_generator = ProtobufSchemaGenerator::new(
                 key_desc_and_indices
                     .as_ref()
                     .map(|(desc, _indices)| desc.clone()),
                 value_desc.clone(),
                 matches!(envelope, SinkEnvelope::Debezium),
                 include_transaction,
-            );
-            let value_schema = schema_generator.value_writer_schema().to_string();
-            let key_schema = schema_generator
-                .key_writer_schema()
-                .map(|key_schema| key_schema.to_string());
-
-            normalize::ensure_empty_options(&ccsr_with_options, "CONFLUENT SCHEMA REGISTRY")?;
-
-            KafkaSinkFormat::Avro {
-                schema_registry_url,
-                key_schema,
-                value_schema,
+            )?;
+            KafkaSinkFormat::Protobuf {
                 ccsr_config,
+                key_schema: schema_generator.key_schema().map(|s| s.to_owned()),
+                value_schema: schema_generator.value_schema().to_owned(),
             }
         }
-        Some(Format::Json) => KafkaSinkFormat::Json,
         Some(format) => bail_unsupported!(format!("sink format {:?}", format)),
         None => bail_unsupported!("sink without format"),
     };
@@ -2090,6 +2631,9 @@ fn kafka_sink_builder(
     }))
 }
 
+const PROTOBUF_CONSISTENCY_FORMAT_ERROR: &str =
+    "FORMAT PROTOBUF sinks require CONSISTENCY FORMAT AVRO or JSON for their consistency topic";
+
 /// Determines the consistency configuration (topic and format) that should be used for a Kafka
 /// sink based on the given configuration items.
 ///
@@ -2139,49 +2683,52 @@ fn get_kafka_sink_consistency_config(
                     },
                 ))
             }
+            Some(Format::Json { columns }) => {
+                if !columns.is_empty() {
+                    bail!("CONSISTENCY FORMAT JSON does not support a column list");
+                }
+                Some((topic, KafkaSinkFormat::Json))
+            }
             None => {
                 // If a CONSISTENCY FORMAT is not provided, default to the FORMAT of the sink.
                 match sink_format {
                     format @ KafkaSinkFormat::Avro { .. } => Some((topic, format.clone())),
-                    KafkaSinkFormat::Json => bail_unsupported!("CONSISTENCY FORMAT JSON"),
+                    KafkaSinkFormat::Json => Some((topic, KafkaSinkFormat::Json)),
+                    KafkaSinkFormat::Protobuf { .. } => bail!(PROTOBUF_CONSISTENCY_FORMAT_ERROR),
                 }
             }
             Some(other) => bail_unsupported!(format!("CONSISTENCY FORMAT {}", &other)),
         },
         None => {
-            // Support use of `consistency_topic` with option if the sink is Avro-formatted
-            // for backwards compatibility.
+            // Support use of `consistency_topic` with option for backwards compatibility.
             if reuse_topic | consistency_topic.is_some() {
+                let consistency_topic = match consistency_topic {
+                    Some(topic) => topic,
+                    None => {
+                        let default_consistency_topic = format!("{}-consistency", topic_prefix);
+                        debug!(
+                            "Using default consistency topic '{}' for topic '{}'",
+                            default_consistency_topic, topic_prefix
+                        );
+                        default_consistency_topic
+                    }
+                };
                 match sink_format {
                     KafkaSinkFormat::Avro {
                         schema_registry_url,
                         ccsr_config,
                         ..
-                    } => {
-                        let consistency_topic = match consistency_topic {
-                            Some(topic) => topic,
-                            None => {
-                                let default_consistency_topic =
-                                    format!("{}-consistency", topic_prefix);
-                                debug!(
-                                    "Using default consistency topic '{}' for topic '{}'",
-                                    default_consistency_topic, topic_prefix
-                                );
-                                default_consistency_topic
-                            }
-                        };
-                        Some((
-                            consistency_topic,
-                            KafkaSinkFormat::Avro {
-                                schema_registry_url: schema_registry_url.clone(),
-                                key_schema: None,
-                                value_schema: avro::get_debezium_transaction_schema()
-                                    .canonical_form(),
-                                ccsr_config: ccsr_config.clone(),
-                            },
-                        ))
-                    }
-                    KafkaSinkFormat::Json => bail!("For FORMAT JSON, you need to manually specify an Avro consistency topic using 'CONSISTENCY TOPIC consistency_topic CONSISTENCY FORMAT AVRO USING CONFLUENT SCHEMA REGISTRY url'. The default of using a JSON consistency topic is not supported."),
+                    } => Some((
+                        consistency_topic,
+                        KafkaSinkFormat::Avro {
+                            schema_registry_url: schema_registry_url.clone(),
+                            key_schema: None,
+                            value_schema: avro::get_debezium_transaction_schema().canonical_form(),
+                            ccsr_config: ccsr_config.clone(),
+                        },
+                    )),
+                    KafkaSinkFormat::Json => Some((consistency_topic, KafkaSinkFormat::Json)),
+                    KafkaSinkFormat::Protobuf { .. } => bail!(PROTOBUF_CONSISTENCY_FORMAT_ERROR),
                 }
             } else {
                 None
@@ -2216,6 +2763,104 @@ fn persist_sink_builder(
     }))
 }
 
+fn s3_sink_builder(
+    format: Option<Format<Aug>>,
+    envelope: SinkEnvelope,
+    with_options: &mut BTreeMap<String, Value>,
+    bucket: String,
+    prefix: Option<String>,
+    value_desc: RelationDesc,
+    prefix_suffix_nonce: String,
+) -> Result<SinkConnectorBuilder, anyhow::Error> {
+    if envelope != SinkEnvelope::DifferentialRow {
+        bail!("CREATE SINK ... S3 does not support specifying an ENVELOPE");
+    }
+
+    let format = match format {
+        Some(Format::Csv {
+            columns: CsvColumns::Header { names },
+            delimiter,
+        }) => {
+            if !names.is_empty() {
+                bail!(
+                    "CSV WITH HEADER for S3 sinks derives the header from the sinked relation; \
+                     do not specify header columns"
+                );
+            }
+            S3SinkFormat::Csv {
+                delimiter: match delimiter as u32 {
+                    0..=127 => delimiter as u8,
+                    _ => bail!("CSV delimiter must be an ASCII character"),
+                },
+            }
+        }
+        Some(Format::Csv {
+            columns: CsvColumns::Count(_),
+            ..
+        }) => bail!("S3 sinks require FORMAT CSV WITH HEADER"),
+        Some(Format::Parquet { columns }) => {
+            if !columns.is_empty() {
+                bail!(
+                    "FORMAT PARQUET for S3 sinks derives the columns from the sinked relation; \
+                     do not specify columns"
+                );
+            }
+            S3SinkFormat::Parquet
+        }
+        Some(format) => bail_unsupported!(format!("S3 sink format {}", format)),
+        None => bail!("S3 sinks require FORMAT CSV WITH HEADER or FORMAT PARQUET"),
+    };
+
+    let aws = normalize::aws_config(with_options, None)?;
+
+    Ok(SinkConnectorBuilder::S3(S3SinkConnectorBuilder {
+        bucket,
+        prefix: prefix.unwrap_or_default(),
+        prefix_suffix_nonce,
+        aws,
+        format,
+        value_desc,
+    }))
+}
+
+fn postgres_sink_builder(
+    format: Option<Format<Aug>>,
+    envelope: SinkEnvelope,
+    conn: String,
+    table: String,
+    relation_key_indices: Option<Vec<usize>>,
+    key_desc_and_indices: Option<(RelationDesc, Vec<usize>)>,
+    value_desc: RelationDesc,
+) -> Result<SinkConnectorBuilder, anyhow::Error> {
+    if envelope != SinkEnvelope::Upsert {
+        bail!("CREATE SINK ... POSTGRES only supports ENVELOPE UPSERT");
+    }
+    if format.is_some() {
+        bail!("CREATE SINK ... POSTGRES does not support specifying a FORMAT");
+    }
+
+    let (key_desc, key_indices) = key_desc_and_indices.expect("upsert sinks have a key");
+    // Rows are matched to upstream rows by key, and NULL keys never match.
+    for (name, typ) in key_desc.iter() {
+        if typ.nullable {
+            bail!(
+                "Postgres sink key column {} must be non-nullable",
+                name.as_str().quoted()
+            );
+        }
+    }
+
+    Ok(SinkConnectorBuilder::Postgres(
+        PostgresSinkConnectorBuilder {
+            conn,
+            table,
+            key_indices,
+            relation_key_indices,
+            value_desc,
+        },
+    ))
+}
+
 pub fn describe_create_sink(
     _: &StatementContext,
     _: &CreateSinkStatement<Raw>,
@@ -2249,21 +2894,28 @@ pub fn plan_create_sink(
     } = stmt;
 
     let envelope = match envelope {
-        // The persist sink only works with its own special envelope, so make that the default.
-        None if matches!(connector, CreateSinkConnector::Persist { .. }) => {
+        // The persist and S3 sinks only work with the special envelope that writes updates with
+        // their original times and diffs, so make that the default.
+        None if matches!(
+            connector,
+            CreateSinkConnector::Persist { .. } | CreateSinkConnector::S3 { .. }
+        ) =>
+        {
             SinkEnvelope::DifferentialRow
         }
+        // Postgres sinks apply changes to a keyed table, so only ENVELOPE UPSERT makes sense.
+        None if matches!(connector, CreateSinkConnector::Postgres { .. }) => SinkEnvelope::Upsert,
         Some(Envelope::DifferentialRow) => unreachable!("not expressable in SQL"),
         // Other sinks default to ENVELOPE DEBEZIUM. Not sure that's good, though...
         None | Some(Envelope::Debezium(mz_sql_parser::ast::DbzMode::Plain)) => {
             SinkEnvelope::Debezium
         }
         Some(Envelope::Upsert) => SinkEnvelope::Upsert,
-        Some(Envelope::CdcV2) => bail_unsupported!("CDCv2 sinks"),
+        Some(Envelope::CdcV2) => SinkEnvelope::CdcV2,
         Some(Envelope::Debezium(mz_sql_parser::ast::DbzMode::Upsert)) => {
             bail_unsupported!("UPSERT doesn't make sense for sinks")
         }
-        Some(Envelope::None) => bail_unsupported!("\"ENVELOPE NONE\" sinks"),
+        Some(Envelope::None) => SinkEnvelope::AppendOnly,
     };
     let name = scx.allocate_qualified_name(normalize::unresolved_object_name(name)?)?;
     let from = scx.get_item_by_resolved_name(&from)?;
@@ -2277,7 +2929,7 @@ pub fn plan_create_sink(
 
     let desc = from.desc(&scx.catalog.resolve_full_name(from.name()))?;
     let key_indices = match &connector {
-        CreateSinkConnector::Kafka { key, .. } => {
+        CreateSinkConnector::Kafka { key, .. } | CreateSinkConnector::Postgres { key, .. } => {
             if let Some(key) = key.clone() {
                 let key_columns = key
                     .key_columns
@@ -2322,7 +2974,7 @@ pub fn plan_create_sink(
                 None
             }
         }
-        CreateSinkConnector::Persist { .. } => None,
+        CreateSinkConnector::Persist { .. } | CreateSinkConnector::S3 { .. } => None,
     };
 
     // pick the first valid natural relation key, if any
@@ -2378,6 +3030,24 @@ pub fn plan_create_sink(
             shard_id,
             desc.clone(),
         )?,
+        CreateSinkConnector::S3 { bucket, prefix } => s3_sink_builder(
+            format,
+            envelope,
+            &mut with_options,
+            bucket,
+            prefix,
+            desc.clone(),
+            suffix_nonce,
+        )?,
+        CreateSinkConnector::Postgres { conn, table, .. } => postgres_sink_builder(
+            format,
+            envelope,
+            conn,
+            table,
+            relation_key_indices,
+            key_desc_and_indices,
+            desc.clone(),
+        )?,
     };
 
     normalize::ensure_empty_options(&with_options, "CREATE SINK")?;
@@ -3571,3 +4241,57 @@ impl DependsOnCollector {
         &self.ids
     }
 }
+
+pub fn describe_alter_source(
+    _: &StatementContext,
+    _: &AlterSourceStatement<Raw>,
+) -> Result<StatementDesc, anyhow::Error> {
+    Ok(StatementDesc::new(None))
+}
+
+pub fn plan_alter_source(
+    scx: &StatementContext,
+    AlterSourceStatement {
+        source_name,
+        if_exists,
+        action,
+    }: AlterSourceStatement<Aug>,
+) -> Result<Plan, anyhow::Error> {
+    let entry = match scx.get_item_by_resolved_name(&source_name) {
+        Ok(source) => source,
+        Err(_) if if_exists => {
+            // TODO(benesch): generate a notice indicating this source does not
+            // exist.
+            return Ok(Plan::AlterNoop(AlterNoopPlan {
+                object_type: ObjectType::Source,
+            }));
+        }
+        Err(e) => return Err(e),
+    };
+    if entry.item_type() != CatalogItemType::Source {
+        bail!(
+            "{} is a {} not a SOURCE",
+            source_name.full_name_str(),
+            entry.item_type()
+        )
+    }
+    let id = entry.id();
+
+    match action {
+        AlterSourceAction::Refresh => {
+            if !matches!(
+                entry.source_connector()?,
+                SourceConnector::External {
+                    connector: ExternalSourceConnector::Postgres(_),
+                    ..
+                }
+            ) {
+                bail!(
+                    "cannot refresh {}: only POSTGRES sources can be refreshed",
+                    source_name.full_name_str()
+                )
+            }
+            Ok(Plan::AlterSourceRefresh(AlterSourceRefreshPlan { id }))
+        }
+    }
+}
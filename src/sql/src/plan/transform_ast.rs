@@ -20,7 +20,7 @@ use mz_ore::stack::{CheckedRecursion, RecursionGuard};
 use mz_sql_parser::ast::visit_mut::{self, VisitMut};
 use mz_sql_parser::ast::{
     Expr, Function, FunctionArgs, Ident, Op, OrderByExpr, Query, Select, SelectItem, TableAlias,
-    TableFactor, TableFunction, TableWithJoins, UnresolvedObjectName, Value,
+    TableFactor, TableFunction, TableWithJoins, UnresolvedObjectName, Value, WindowSpec,
 };
 
 use crate::normalize;
@@ -68,7 +68,9 @@ where
 //   * Rewrites `avg(col)` to `sum(col) / count(col)`, so that we can pretend
 //     the `avg` aggregate function doesn't exist from here on out. This also
 //     has the nice side effect of reusing the division planning logic, which
-//     is not trivial for some types, like decimals.
+//     is not trivial for some types, like decimals. When `avg` is used as a
+//     window function, the `OVER` clause is carried over to both `sum` and
+//     `count`.
 //
 //   * Rewrites the suite of standard deviation and variance functions in a
 //     manner similar to `avg`.
@@ -103,6 +105,7 @@ impl<'a> FuncRewriter<'a> {
         expr: Expr<Aug>,
         order_by: Vec<OrderByExpr<Aug>>,
         filter: Option<Box<Expr<Aug>>>,
+        over: Option<WindowSpec<Aug>>,
         distinct: bool,
     ) -> Expr<Aug> {
         Expr::Function(Function {
@@ -112,17 +115,23 @@ impl<'a> FuncRewriter<'a> {
                 order_by,
             },
             filter,
-            over: None,
+            over,
             distinct,
         })
     }
 
-    fn plan_avg(expr: Expr<Aug>, filter: Option<Box<Expr<Aug>>>, distinct: bool) -> Expr<Aug> {
+    fn plan_avg(
+        expr: Expr<Aug>,
+        filter: Option<Box<Expr<Aug>>>,
+        over: Option<WindowSpec<Aug>>,
+        distinct: bool,
+    ) -> Expr<Aug> {
         let sum = Self::plan_agg(
             UnresolvedObjectName::qualified(&["pg_catalog", "sum"]),
             expr.clone(),
             vec![],
             filter.clone(),
+            over.clone(),
             distinct,
         )
         .call_unary(vec!["mz_internal", "mz_avg_promotion"]);
@@ -131,6 +140,7 @@ impl<'a> FuncRewriter<'a> {
             expr,
             vec![],
             filter,
+            over,
             distinct,
         );
         Self::plan_divide(sum, count)
@@ -139,6 +149,7 @@ impl<'a> FuncRewriter<'a> {
     fn plan_variance(
         expr: Expr<Aug>,
         filter: Option<Box<Expr<Aug>>>,
+        over: Option<WindowSpec<Aug>>,
         distinct: bool,
         sample: bool,
     ) -> Expr<Aug> {
@@ -163,6 +174,7 @@ impl<'a> FuncRewriter<'a> {
             expr_squared,
             vec![],
             filter.clone(),
+            over.clone(),
             distinct,
         );
         let sum = Self::plan_agg(
@@ -170,6 +182,7 @@ impl<'a> FuncRewriter<'a> {
             expr.clone(),
             vec![],
             filter.clone(),
+            over.clone(),
             distinct,
         );
         let sum_squared = sum.clone().multiply(sum);
@@ -178,6 +191,7 @@ impl<'a> FuncRewriter<'a> {
             expr,
             vec![],
             filter,
+            over,
             distinct,
         );
         Self::plan_divide(
@@ -193,10 +207,11 @@ impl<'a> FuncRewriter<'a> {
     fn plan_stddev(
         expr: Expr<Aug>,
         filter: Option<Box<Expr<Aug>>>,
+        over: Option<WindowSpec<Aug>>,
         distinct: bool,
         sample: bool,
     ) -> Expr<Aug> {
-        Self::plan_variance(expr, filter, distinct, sample).call_unary(vec!["sqrt"])
+        Self::plan_variance(expr, filter, over, distinct, sample).call_unary(vec!["sqrt"])
     }
 
     fn rewrite_expr(&mut self, expr: &Expr<Aug>) -> Option<(Ident, Expr<Aug>)> {
@@ -206,7 +221,7 @@ impl<'a> FuncRewriter<'a> {
                 args: FunctionArgs::Args { args, order_by: _ },
                 filter,
                 distinct,
-                over: None,
+                over,
             }) => {
                 let name = normalize::unresolved_object_name(name.clone()).ok()?;
                 if let Some(database) = &name.database {
@@ -221,18 +236,23 @@ impl<'a> FuncRewriter<'a> {
                     return None;
                 }
                 let filter = filter.clone();
+                let over = over.clone();
                 let distinct = *distinct;
                 let expr = if args.len() == 1 {
                     let arg = args[0].clone();
                     match name.item.as_str() {
-                        "avg" => Self::plan_avg(arg, filter, distinct),
-                        "variance" | "var_samp" => Self::plan_variance(arg, filter, distinct, true),
-                        "var_pop" => Self::plan_variance(arg, filter, distinct, false),
-                        "stddev" | "stddev_samp" => Self::plan_stddev(arg, filter, distinct, true),
-                        "stddev_pop" => Self::plan_stddev(arg, filter, distinct, false),
+                        "avg" => Self::plan_avg(arg, filter, over, distinct),
+                        "variance" | "var_samp" => {
+                            Self::plan_variance(arg, filter, over, distinct, true)
+                        }
+                        "var_pop" => Self::plan_variance(arg, filter, over, distinct, false),
+                        "stddev" | "stddev_samp" => {
+                            Self::plan_stddev(arg, filter, over, distinct, true)
+                        }
+                        "stddev_pop" => Self::plan_stddev(arg, filter, over, distinct, false),
                         _ => return None,
                     }
-                } else if args.len() == 2 {
+                } else if args.len() == 2 && over.is_none() {
                     let (lhs, rhs) = (args[0].clone(), args[1].clone());
                     match name.item.as_str() {
                         "mod" => lhs.modulo(rhs),
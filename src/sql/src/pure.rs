@@ -12,12 +12,12 @@
 //! See the [crate-level documentation](crate) for details.
 
 use std::collections::BTreeMap;
-use std::iter;
 use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::{anyhow, bail, Context};
 use aws_arn::ARN;
+use globset::GlobBuilder;
 use mz_sql_parser::ast::{CsrConnector, KafkaConnector, KafkaSourceConnector};
 use prost::Message;
 use protobuf_native::compiler::{SourceTreeDescriptorDatabase, VirtualSourceTree};
@@ -28,16 +28,20 @@ use uuid::Uuid;
 
 use mz_ccsr::{Client, GetBySubjectError};
 use mz_dataflow_types::aws::{AwsConfig, AwsExternalIdPrefix};
+use mz_dataflow_types::mysql_source::MySqlSourceDetails;
 use mz_dataflow_types::postgres_source::PostgresSourceDetails;
 use mz_dataflow_types::ConnectorContext;
+use mz_ore::str::StrExt;
 
-use mz_repr::strconv;
+use mz_repr::adt::numeric::NUMERIC_DATUM_MAX_PRECISION;
+use mz_repr::{strconv, ScalarType};
 
 use crate::ast::{
-    AvroSchema, CreateSourceConnector, CreateSourceFormat, CreateSourceStatement, CsrConnectorAvro,
-    CsrConnectorProto, CsrSeed, CsrSeedCompiled, CsrSeedCompiledEncoding, CsrSeedCompiledOrLegacy,
-    CsvColumns, DbzMode, Envelope, Format, Ident, ProtobufSchema, Raw, Value, WithOption,
-    WithOptionValue,
+    AvroSchema, ColumnDef, Compression, CreateSourceConnector, CreateSourceFormat,
+    CreateSourceStatement, CsrConnectorAvro, CsrConnectorProto, CsrSeed, CsrSeedCompiled,
+    CsrSeedCompiledEncoding, CsrSeedCompiledOrLegacy, CsvColumns, DbzMode, Envelope, Format, Ident,
+    ProtobufSchema, Raw, RawObjectName, S3KeySource, UnresolvedDataType, UnresolvedObjectName,
+    Value, WithOption, WithOptionValue,
 };
 use crate::kafka_util;
 use crate::normalize;
@@ -70,7 +74,10 @@ pub async fn purify_create_source(
 
     match connector {
         CreateSourceConnector::Kafka(KafkaSourceConnector {
-            connector, topic, ..
+            connector,
+            topic,
+            start_timestamp,
+            ..
         }) => {
             let (broker, connector_options) = match connector {
                 KafkaConnector::Reference {
@@ -112,6 +119,32 @@ pub async fn purify_create_source(
             .await
             .map_err(|e| anyhow!("Failed to create and connect Kafka consumer: {}", e))?;
 
+            // Translate `START TIMESTAMP` to `start_offset`, so that the
+            // source starts at the same offsets whenever it is planned.
+            if let Some(value) = start_timestamp.take() {
+                if with_options_map.contains_key("start_offset")
+                    || with_options_map.contains_key("kafka_time_offset")
+                {
+                    bail!(
+                        "START TIMESTAMP cannot be used with `start_offset` or `kafka_time_offset`"
+                    );
+                }
+                let time_offset = kafka_util::resolve_start_timestamp(&value, now)?;
+                let start_offsets =
+                    kafka_util::lookup_offsets_for_time(Arc::clone(&consumer), &topic, time_offset)
+                        .await?;
+                info!("add start_offset {:?}", start_offsets);
+                with_options.push(WithOption {
+                    key: Ident::new("start_offset"),
+                    value: Some(WithOptionValue::Value(Value::Array(
+                        start_offsets
+                            .iter()
+                            .map(|offset| Value::Number(offset.to_string()))
+                            .collect(),
+                    ))),
+                });
+            }
+
             // Translate `kafka_time_offset` to `start_offset`.
             match kafka_util::lookup_start_offsets(
                 Arc::clone(&consumer),
@@ -141,13 +174,33 @@ pub async fn purify_create_source(
                 None => {}
             }
         }
-        CreateSourceConnector::S3 { .. } => {
+        CreateSourceConnector::S3 {
+            key_sources,
+            pattern,
+            compression,
+        } => {
             let aws_config = normalize::aws_config(&mut with_options_map, None)?;
             validate_aws_credentials(
                 &aws_config,
                 connector_context.aws_external_id_prefix.as_ref(),
             )
             .await?;
+            if let CreateSourceFormat::Bare(Format::Parquet { columns }) = format {
+                if columns.is_empty() {
+                    if *compression != Compression::None {
+                        bail!(
+                            "FORMAT PARQUET for compressed S3 objects requires specifying the columns"
+                        );
+                    }
+                    *columns = purify_parquet_columns(
+                        &aws_config,
+                        connector_context.aws_external_id_prefix.as_ref(),
+                        key_sources,
+                        pattern.as_deref(),
+                    )
+                    .await?;
+                }
+            }
         }
         CreateSourceConnector::Kinesis { arn } => {
             let region = arn
@@ -185,8 +238,40 @@ pub async fn purify_create_source(
             };
             *details = Some(hex::encode(details_proto.encode_to_vec()));
         }
+        CreateSourceConnector::MySql {
+            conn,
+            database,
+            details,
+        } => {
+            // verify that the upstream server has a usable binary log and
+            // snapshot the schema of the database's tables
+            let mut client = mz_mysql_util::connect(conn).await?;
+            mz_mysql_util::validate_binlog_config(&mut client).await?;
+            client.disconnect().await?;
+            let tables = mz_mysql_util::table_info(conn, database).await?;
+
+            let details_proto = MySqlSourceDetails {
+                tables: tables.into_iter().map(|t| t.into()).collect(),
+            };
+            *details = Some(hex::encode(details_proto.encode_to_vec()));
+        }
+        CreateSourceConnector::SqlServer { conn, details } => {
+            // verify that change data capture is enabled and snapshot the
+            // schema of the tables that it is enabled for
+            let mut client = mz_sql_server_util::connect(conn).await?;
+            mz_sql_server_util::validate_cdc_config(&mut client).await?;
+            let tables = mz_sql_server_util::table_info(&mut client).await?;
+            client.close().await?;
+
+            let details_proto = PostgresSourceDetails {
+                tables: tables.into_iter().map(|t| t.into()).collect(),
+                slot: String::new(),
+            };
+            *details = Some(hex::encode(details_proto.encode_to_vec()));
+        }
         CreateSourceConnector::PubNub { .. } => (),
         CreateSourceConnector::Persist { .. } => (),
+        CreateSourceConnector::Webhook { .. } => (),
     }
 
     purify_source_format(format, connector, &envelope, &config_options, with_options).await?;
@@ -290,9 +375,18 @@ async fn purify_source_format_single(
             _ => {}
         },
         Format::Protobuf(schema) => match schema {
-            ProtobufSchema::Csr { csr_connector } => {
-                purify_csr_connector_proto(connector, csr_connector, envelope, with_options)
-                    .await?;
+            ProtobufSchema::Csr {
+                csr_connector,
+                message_name,
+            } => {
+                purify_csr_connector_proto(
+                    connector,
+                    csr_connector,
+                    message_name.as_deref(),
+                    envelope,
+                    with_options,
+                )
+                .await?;
             }
             ProtobufSchema::InlineSchema {
                 message_name: _,
@@ -305,6 +399,7 @@ async fn purify_source_format_single(
                     *schema = mz_sql_parser::ast::Schema::Inline(buf);
                 }
             }
+            ProtobufSchema::Derived => {}
         },
         Format::Csv {
             delimiter: _,
@@ -321,7 +416,14 @@ async fn purify_source_format_single(
                 }
             }
         }
-        Format::Bytes | Format::Regex(_) | Format::Json | Format::Text => (),
+        Format::Bytes | Format::Regex(_) | Format::Json { .. } | Format::Text => (),
+        // The columns of `FORMAT PARQUET` sources are derived when purifying
+        // the S3 connector, as that requires the AWS configuration.
+        Format::Parquet { .. } => {
+            if !matches!(connector, CreateSourceConnector::S3 { .. }) {
+                bail!("FORMAT PARQUET is only supported for S3 sources");
+            }
+        }
     }
     Ok(())
 }
@@ -329,6 +431,7 @@ async fn purify_source_format_single(
 async fn purify_csr_connector_proto(
     connector: &mut CreateSourceConnector<Raw>,
     csr_connector: &mut CsrConnectorProto<Raw>,
+    message_name: Option<&str>,
     envelope: &Option<Envelope>,
     with_options: &Vec<WithOption<Raw>>,
 ) -> Result<(), anyhow::Error> {
@@ -360,9 +463,13 @@ async fn purify_csr_connector_proto(
                 &mut normalize::options(&ccsr_options),
             )?;
 
-            let value =
-                compile_proto(&format!("{}-value", topic), ccsr_config.clone().build()?).await?;
-            let key = compile_proto(&format!("{}-key", topic), ccsr_config.build()?)
+            let value = compile_proto(
+                &format!("{}-value", topic),
+                message_name,
+                ccsr_config.clone().build()?,
+            )
+            .await?;
+            let key = compile_proto(&format!("{}-key", topic), None, ccsr_config.build()?)
                 .await
                 .ok();
 
@@ -476,32 +583,49 @@ async fn get_remote_csr_schema(
 }
 
 /// Collect protobuf message descriptor from CSR and compile the descriptor.
+///
+/// If `message_name` is not specified, the schema must define exactly one
+/// top-level message, which is the message to decode.
 async fn compile_proto(
     subject_name: &String,
+    message_name: Option<&str>,
     ccsr_client: Client,
 ) -> Result<CsrSeedCompiledEncoding, anyhow::Error> {
-    let (primary_subject, dependency_subjects) =
-        ccsr_client.get_subject_and_references(subject_name).await?;
+    let (primary_subject, dependency_subjects) = ccsr_client
+        .get_subject_and_named_references(subject_name)
+        .await?;
 
-    // Compile .proto files into a file descriptor set.
+    // Compile .proto files into a file descriptor set. Dependencies are added
+    // under the name by which they are referenced, which is the path that the
+    // importing file uses.
     let mut source_tree = VirtualSourceTree::new();
-    for subject in iter::once(&primary_subject).chain(dependency_subjects.iter()) {
-        source_tree.as_mut().add_file(
-            Path::new(&subject.name),
-            subject.schema.raw.as_bytes().to_vec(),
-        );
+    source_tree.as_mut().add_file(
+        Path::new(&primary_subject.name),
+        primary_subject.schema.raw.as_bytes().to_vec(),
+    );
+    for (name, subject) in &dependency_subjects {
+        source_tree
+            .as_mut()
+            .add_file(Path::new(name), subject.schema.raw.as_bytes().to_vec());
     }
     let mut db = SourceTreeDescriptorDatabase::new(source_tree.as_mut());
     let fds = db
         .as_mut()
         .build_file_descriptor_set(&[Path::new(&primary_subject.name)])?;
 
-    // Ensure there is exactly one message in the file.
+    // Unless a message was named explicitly, ensure there is exactly one
+    // message in the file. A named message that does not exist in the file
+    // descriptor set is reported during planning.
     let primary_fd = fds.file(0);
-    let message_name = match primary_fd.message_type_size() {
-        1 => String::from_utf8_lossy(primary_fd.message_type(0).name()).into_owned(),
-        0 => bail_unsupported!(9598, "Protobuf schemas with no messages"),
-        _ => bail_unsupported!(9598, "Protobuf schemas with multiple messages"),
+    let message_name = match (message_name, primary_fd.message_type_size()) {
+        (Some(message_name), _) => message_name.to_owned(),
+        (None, 1) => String::from_utf8_lossy(primary_fd.message_type(0).name()).into_owned(),
+        (None, 0) => bail_unsupported!(9598, "Protobuf schemas with no messages"),
+        (None, _) => bail!(
+            "Protobuf schema for subject {} defines multiple messages; \
+            use MESSAGE '<name>' to choose the message to decode",
+            subject_name.quoted()
+        ),
     };
 
     // Encode the file descriptor set into a SQL byte string.
@@ -514,6 +638,140 @@ async fn compile_proto(
     })
 }
 
+/// Derives the columns of a `FORMAT PARQUET` S3 source from the footer of the
+/// first nonempty object in its buckets that matches its pattern.
+async fn purify_parquet_columns(
+    config: &AwsConfig,
+    external_id_prefix: Option<&AwsExternalIdPrefix>,
+    key_sources: &[S3KeySource],
+    pattern: Option<&str>,
+) -> Result<Vec<ColumnDef<Raw>>, anyhow::Error> {
+    let glob = pattern
+        .map(|p| {
+            GlobBuilder::new(p)
+                .literal_separator(true)
+                .backslash_escape(true)
+                .build()
+        })
+        .transpose()?
+        .map(|g| g.compile_matcher());
+    // Only keys that start with the literal prefix of the pattern can match.
+    let prefix = pattern
+        .map(|p| &p[..p.find(&['*', '?', '[', '{', '\\'][..]).unwrap_or(p.len())])
+        .filter(|p| !p.is_empty());
+
+    let config = config.load(external_id_prefix, None).await;
+    let client = aws_sdk_s3::Client::new(&config);
+    for key_source in key_sources {
+        let bucket = match key_source {
+            S3KeySource::Scan { bucket } | S3KeySource::Poll { bucket, .. } => bucket,
+            S3KeySource::SqsNotifications { .. } => continue,
+        };
+        let mut continuation_token = None;
+        loop {
+            let response = client
+                .list_objects_v2()
+                .bucket(bucket)
+                .set_prefix(prefix.map(|p| p.to_string()))
+                .set_continuation_token(continuation_token)
+                .send()
+                .await
+                .with_context(|| format!("Unable to list objects in S3 bucket {}", bucket))?;
+            let object = response.contents.unwrap_or_default().into_iter().find(|o| {
+                o.size > 0
+                    && match (&o.key, &glob) {
+                        (Some(key), Some(glob)) => glob.is_match(key),
+                        (key, None) => key.is_some(),
+                        (None, _) => false,
+                    }
+            });
+            if let Some(key) = object.and_then(|o| o.key) {
+                let footer = fetch_parquet_footer(&client, bucket, &key).await?;
+                let columns =
+                    mz_interchange::parquet::infer_columns(&footer).with_context(|| {
+                        format!("Unable to read Parquet schema of {}/{}", bucket, key)
+                    })?;
+                return Ok(columns
+                    .into_iter()
+                    .map(|(name, typ)| parquet_column_def(name, typ))
+                    .collect());
+            }
+            match response.next_continuation_token {
+                Some(token) => continuation_token = Some(token),
+                None => break,
+            }
+        }
+    }
+    bail!(
+        "FORMAT PARQUET for S3 sources requires an object to derive the columns from; \
+         specify the columns or add an object to the bucket"
+    )
+}
+
+/// Fetches the footer of the Parquet object `bucket`/`key`, i.e. its metadata,
+/// followed by the length of the metadata and the magic bytes.
+async fn fetch_parquet_footer(
+    client: &aws_sdk_s3::Client,
+    bucket: &str,
+    key: &str,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let fetch_suffix = |len: usize| async move {
+        let object = client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .range(format!("bytes=-{}", len))
+            .send()
+            .await
+            .with_context(|| format!("Unable to fetch S3 object {}/{}", bucket, key))?;
+        let bytes = object.body.collect().await?.into_bytes();
+        Ok::<_, anyhow::Error>(bytes.to_vec())
+    };
+    let tail = fetch_suffix(8).await?;
+    if tail.len() != 8 || &tail[4..] != mz_interchange::parquet::PARQUET_MAGIC {
+        bail!("S3 object {}/{} is not a Parquet file", bucket, key);
+    }
+    let metadata_len = u32::from_le_bytes(tail[..4].try_into().expect("slice of four bytes"));
+    fetch_suffix(usize::try_from(metadata_len)? + 8).await
+}
+
+/// Returns the definition of a column of a `FORMAT PARQUET` source.
+fn parquet_column_def(name: String, typ: ScalarType) -> ColumnDef<Raw> {
+    let (type_name, typ_mod) = match typ {
+        ScalarType::Bool => ("bool", vec![]),
+        ScalarType::Int16 => ("int2", vec![]),
+        ScalarType::Int32 => ("int4", vec![]),
+        ScalarType::Int64 => ("int8", vec![]),
+        ScalarType::Float32 => ("float4", vec![]),
+        ScalarType::Float64 => ("float8", vec![]),
+        ScalarType::Numeric {
+            max_scale: Some(scale),
+        } => (
+            "numeric",
+            vec![
+                i64::from(NUMERIC_DATUM_MAX_PRECISION),
+                i64::from(scale.into_u8()),
+            ],
+        ),
+        ScalarType::Numeric { max_scale: None } => ("numeric", vec![]),
+        ScalarType::Bytes => ("bytea", vec![]),
+        ScalarType::Date => ("date", vec![]),
+        ScalarType::Time => ("time", vec![]),
+        ScalarType::Timestamp => ("timestamp", vec![]),
+        ScalarType::TimestampTz => ("timestamptz", vec![]),
+        _ => ("text", vec![]),
+    };
+    ColumnDef {
+        name: Ident::new(name),
+        data_type: UnresolvedDataType::Other {
+            name: RawObjectName::Name(UnresolvedObjectName::qualified(&["pg_catalog", type_name])),
+            typ_mod,
+        },
+        collation: None,
+        options: vec![],
+    }
+}
+
 /// Makes an always-valid AWS API call to perform a basic sanity check of
 /// whether the specified AWS configuration is valid.
 async fn validate_aws_credentials(
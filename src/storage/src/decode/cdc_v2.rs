@@ -0,0 +1,169 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Decoding of CDCv2 update batches and progress statements.
+//!
+//! Avro messages are decoded directly by the decoder in
+//! [`mz_interchange::avro::cdc_v2`]. Protobuf messages are first decoded into
+//! a [`Row`] with an `updates` column and a `progress` column, mirroring the
+//! two variants of the Avro union, and then unpacked from that row. JSON
+//! objects are decoded by the [`JsonDecoderState`] with the same two fields.
+
+use differential_dataflow::capture::{Message, Progress};
+use futures::executor::block_on;
+use mz_avro::{AvroDeserializer, GeneralDeserializer};
+
+use mz_dataflow_types::sources::encoding::{AvroEncoding, DataEncoding};
+use mz_interchange::avro::ConfluentAvroResolver;
+use mz_repr::{Datum, DatumList, Diff, Row, Timestamp};
+
+use super::json::JsonDecoderState;
+use super::protobuf::ProtobufDecoderState;
+
+/// Decodes CDCv2 messages in one of the supported encodings.
+pub(crate) enum CdcV2DecoderState {
+    Avro(ConfluentAvroResolver),
+    Protobuf(ProtobufDecoderState),
+    Json(JsonDecoderState),
+}
+
+impl CdcV2DecoderState {
+    pub fn new(encoding: DataEncoding) -> Self {
+        match encoding {
+            DataEncoding::Avro(AvroEncoding {
+                schema,
+                schema_registry_config,
+                confluent_wire_format,
+            }) => {
+                // We will have already checked validity of the schema by now, so this can't fail.
+                let resolver = ConfluentAvroResolver::new(
+                    &schema,
+                    schema_registry_config,
+                    confluent_wire_format,
+                )
+                .unwrap();
+                CdcV2DecoderState::Avro(resolver)
+            }
+            DataEncoding::Protobuf(encoding) => {
+                CdcV2DecoderState::Protobuf(ProtobufDecoderState::new(encoding).expect(
+                    "Failed to create protobuf decoder, even though we validated ccsr \
+                        client creation in purification.",
+                ))
+            }
+            DataEncoding::Json(encoding) => {
+                CdcV2DecoderState::Json(JsonDecoderState::new(encoding))
+            }
+            other => unreachable!("Attempted to create {} CDCv2 source", other.op_name()),
+        }
+    }
+
+    /// Decodes a single CDCv2 message from `bytes`.
+    pub fn decode(&mut self, bytes: &[u8]) -> Result<Message<Row, Timestamp, Diff>, String> {
+        match self {
+            CdcV2DecoderState::Avro(resolver) => {
+                let (mut data, schema, _) = block_on(resolver.resolve(bytes))
+                    .map_err(|e| format!("Failed to get schema info for CDCv2 record: {}", e))?;
+                let d = GeneralDeserializer {
+                    schema: schema.top_node(),
+                };
+                d.deserialize(&mut data, mz_interchange::avro::cdc_v2::Decoder)
+                    .map_err(|e| format!("Failed to deserialize avro message: {}", e))
+            }
+            CdcV2DecoderState::Protobuf(protobuf) => match protobuf.get_value(bytes) {
+                Some(Ok(row)) => unpack_message(&row)
+                    .map_err(|e| format!("Failed to decode protobuf CDCv2 message: {}", e)),
+                Some(Err(e)) => Err(e.to_string()),
+                None => Err("protobuf deserialization returned None".into()),
+            },
+            CdcV2DecoderState::Json(json) => json
+                .decode_cdcv2(bytes)
+                .map_err(|e| format!("Failed to decode JSON CDCv2 message: {}", e)),
+        }
+    }
+}
+
+/// Unpacks a CDCv2 message from a row with an `updates` column and a nullable
+/// `progress` column.
+///
+/// The message is a progress statement if `progress` is present, and an update
+/// batch otherwise.
+fn unpack_message(row: &Row) -> Result<Message<Row, Timestamp, Diff>, String> {
+    let mut datums = row.iter();
+    let updates = datums.next().ok_or("missing updates field")?;
+    let progress = datums.next().ok_or("missing progress field")?;
+    match progress {
+        Datum::Null => {
+            let mut batch = vec![];
+            for update in expect_list(updates, "updates")?.iter() {
+                let mut fields = expect_list(update, "update")?.iter();
+                let data = match fields.next() {
+                    Some(Datum::List(data)) => Row::pack(data.iter()),
+                    _ => return Err("update is missing its data record".into()),
+                };
+                let time = expect_timestamp(fields.next(), "time")?;
+                let diff = expect_i64(fields.next(), "diff")?;
+                batch.push((data, time, diff));
+            }
+            Ok(Message::Updates(batch))
+        }
+        progress => {
+            let mut fields = expect_list(progress, "progress")?.iter();
+            let lower = expect_timestamps(fields.next(), "lower")?;
+            let upper = expect_timestamps(fields.next(), "upper")?;
+            let mut counts = vec![];
+            let counts_list = fields.next().ok_or("progress is missing counts")?;
+            for count in expect_list(counts_list, "counts")?.iter() {
+                let mut fields = expect_list(count, "count")?.iter();
+                let time = expect_timestamp(fields.next(), "time")?;
+                let count = expect_i64(fields.next(), "count")?;
+                let count = usize::try_from(count)
+                    .map_err(|_| format!("count must not be negative: {}", count))?;
+                counts.push((time, count));
+            }
+            Ok(Message::Progress(Progress {
+                lower,
+                upper,
+                counts,
+            }))
+        }
+    }
+}
+
+fn expect_list<'a>(datum: Datum<'a>, name: &str) -> Result<DatumList<'a>, String> {
+    match datum {
+        Datum::List(list) => Ok(list),
+        other => Err(format!(
+            "expected {} to be a record or list, got {:?}",
+            name, other
+        )),
+    }
+}
+
+fn expect_i64(datum: Option<Datum>, name: &str) -> Result<i64, String> {
+    match datum {
+        Some(Datum::Int64(i)) => Ok(i),
+        Some(Datum::Int32(i)) => Ok(i.into()),
+        other => Err(format!(
+            "expected {} to be an integer, got {:?}",
+            name, other
+        )),
+    }
+}
+
+fn expect_timestamp(datum: Option<Datum>, name: &str) -> Result<Timestamp, String> {
+    let ts = expect_i64(datum, name)?;
+    Timestamp::try_from(ts).map_err(|_| format!("{} must not be negative: {}", name, ts))
+}
+
+fn expect_timestamps(datum: Option<Datum>, name: &str) -> Result<Vec<Timestamp>, String> {
+    let list = expect_list(datum.ok_or_else(|| format!("missing {}", name))?, name)?;
+    list.iter()
+        .map(|datum| expect_timestamp(Some(datum), name))
+        .collect()
+}
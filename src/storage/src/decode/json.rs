@@ -0,0 +1,175 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use differential_dataflow::capture::{Message, Progress};
+
+use mz_dataflow_types::{
+    sources::encoding::{JsonColumn, JsonEncoding},
+    DecodeError,
+};
+use mz_repr::adt::jsonb::JsonbPacker;
+use mz_repr::{Datum, Diff, Row, RowArena, Timestamp};
+
+#[derive(Debug)]
+pub struct JsonDecoderState {
+    columns: Vec<JsonColumn>,
+    row_buf: Row,
+    field_buf: Row,
+}
+
+impl JsonDecoderState {
+    pub fn new(JsonEncoding { columns }: JsonEncoding) -> Self {
+        JsonDecoderState {
+            columns,
+            row_buf: Row::default(),
+            field_buf: Row::default(),
+        }
+    }
+
+    pub fn decode(&mut self, bytes: &[u8]) -> Result<Option<Row>, DecodeError> {
+        let result = if self.columns.is_empty() {
+            self.decode_document(bytes)
+        } else {
+            self.decode_columns(bytes)
+        };
+        match result {
+            Ok(()) => Ok(Some(self.row_buf.clone())),
+            Err(err) => Err(DecodeError::Text(format!("JSON decoding error: {}", err))),
+        }
+    }
+
+    /// Packs the entire document into `row_buf` as a single `jsonb` datum.
+    fn decode_document(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let mut packer = self.row_buf.packer();
+        JsonbPacker::new(&mut packer)
+            .pack_slice(bytes)
+            .map_err(|e| format!("{:#}", e))
+    }
+
+    /// Decodes the object in `bytes` into `row_buf` using the declared
+    /// columns.
+    fn decode_columns(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let object = match serde_json::from_slice(bytes).map_err(|e| e.to_string())? {
+            serde_json::Value::Object(object) => object,
+            other => return Err(format!("expected JSON object, got {}", other)),
+        };
+        self.pack_object(&object)
+    }
+
+    /// Decodes a CDCv2 message, which is either an object with an `updates`
+    /// array of `{"data": ..., "time": ..., "diff": ...}` objects, or an object
+    /// with a `progress` object holding `lower`, `upper`, and `counts`.
+    ///
+    /// The `data` of each update is decoded using the declared columns.
+    pub fn decode_cdcv2(&mut self, bytes: &[u8]) -> Result<Message<Row, Timestamp, Diff>, String> {
+        let mut object = match serde_json::from_slice(bytes).map_err(|e| e.to_string())? {
+            serde_json::Value::Object(object) => object,
+            other => return Err(format!("expected JSON object, got {}", other)),
+        };
+        match (object.remove("updates"), object.remove("progress")) {
+            (Some(serde_json::Value::Array(updates)), None) => {
+                let mut batch = Vec::with_capacity(updates.len());
+                for update in updates {
+                    let data = match update.get("data") {
+                        Some(serde_json::Value::Object(data)) => data,
+                        _ => return Err("update is missing its data object".into()),
+                    };
+                    self.pack_object(data)?;
+                    let time = json_timestamp(update.get("time"), "time")?;
+                    let diff = match update.get("diff").and_then(|d| d.as_i64()) {
+                        Some(diff) => diff,
+                        None => return Err("update is missing an integer diff".into()),
+                    };
+                    batch.push((self.row_buf.clone(), time, diff));
+                }
+                Ok(Message::Updates(batch))
+            }
+            (None, Some(progress)) => {
+                let lower = json_timestamps(progress.get("lower"), "lower")?;
+                let upper = json_timestamps(progress.get("upper"), "upper")?;
+                let counts = match progress.get("counts") {
+                    Some(serde_json::Value::Array(counts)) => counts
+                        .iter()
+                        .map(|count| {
+                            let time = json_timestamp(count.get("time"), "time")?;
+                            let count = match count.get("count").and_then(|c| c.as_u64()) {
+                                Some(count) => usize::try_from(count).map_err(|e| e.to_string())?,
+                                None => return Err("count must be a non-negative integer".into()),
+                            };
+                            Ok((time, count))
+                        })
+                        .collect::<Result<_, String>>()?,
+                    _ => return Err("progress is missing its counts array".into()),
+                };
+                Ok(Message::Progress(Progress {
+                    lower,
+                    upper,
+                    counts,
+                }))
+            }
+            _ => Err("expected exactly one of an updates array or a progress object".into()),
+        }
+    }
+
+    /// Packs the declared columns of `object` into `row_buf`, casting each
+    /// field to its declared type.
+    fn pack_object(
+        &mut self,
+        object: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), String> {
+        let temp_storage = RowArena::new();
+        let mut packer = self.row_buf.packer();
+        for column in &self.columns {
+            {
+                let mut field_packer = self.field_buf.packer();
+                match object.get(&column.name) {
+                    None | Some(serde_json::Value::Null) => {
+                        field_packer.push(Datum::Null);
+                        field_packer.push(Datum::Null);
+                    }
+                    Some(value) => {
+                        JsonbPacker::new(&mut field_packer)
+                            .pack_serde_json(value.clone())
+                            .map_err(|e| format!("field {}: {:#}", column.name, e))?;
+                        match value {
+                            serde_json::Value::String(s) => field_packer.push(Datum::String(s)),
+                            value => field_packer.push(Datum::String(&value.to_string())),
+                        }
+                    }
+                }
+            }
+            let datums = self.field_buf.unpack();
+            let datum = column
+                .cast
+                .eval(&datums, &temp_storage)
+                .map_err(|e| format!("field {}: {}", column.name, e))?;
+            packer.push(datum);
+        }
+        Ok(())
+    }
+}
+
+fn json_timestamp(value: Option<&serde_json::Value>, name: &str) -> Result<Timestamp, String> {
+    value
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| format!("{} must be a non-negative integer", name))
+}
+
+fn json_timestamps(
+    value: Option<&serde_json::Value>,
+    name: &str,
+) -> Result<Vec<Timestamp>, String> {
+    match value {
+        Some(serde_json::Value::Array(values)) => values
+            .iter()
+            .map(|v| json_timestamp(Some(v), name))
+            .collect(),
+        _ => Err(format!("{} must be an array of timestamps", name)),
+    }
+}
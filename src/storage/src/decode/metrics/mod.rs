@@ -36,12 +36,14 @@ impl DecodeMetrics {
         let format_label = match decoder {
             DataDecoderInner::Avro(_) => "avro",
             DataDecoderInner::Csv(_) => "csv",
+            DataDecoderInner::Parquet(_) => "parquet",
             DataDecoderInner::DelimitedBytes { format, .. }
             | DataDecoderInner::PreDelimited(format) => match format {
                 PreDelimitedFormat::Bytes => "raw",
                 PreDelimitedFormat::Text => "text",
                 PreDelimitedFormat::Regex(..) => "regex",
                 PreDelimitedFormat::Protobuf(..) => "protobuf",
+                PreDelimitedFormat::Json(..) => "json",
             },
         };
         let success_label = if success { "success" } else { "error" };
@@ -27,8 +27,6 @@ use chrono::NaiveDateTime;
 use differential_dataflow::capture::YieldingIter;
 use differential_dataflow::Hashable;
 use differential_dataflow::{AsCollection, Collection};
-use futures::executor::block_on;
-use mz_avro::{AvroDeserializer, GeneralDeserializer};
 use mz_expr::PartitionId;
 use timely::dataflow::channels::pact::{Exchange, Pipeline};
 use timely::dataflow::operators::Operator;
@@ -42,35 +40,37 @@ use mz_dataflow_types::{
     },
     DecodeError, LinearOperator,
 };
-use mz_interchange::avro::ConfluentAvroResolver;
 use mz_repr::Datum;
 use mz_repr::{Diff, Row, Timestamp};
 use tracing::error;
 
 use self::avro::AvroDecoderState;
+use self::cdc_v2::CdcV2DecoderState;
 use self::csv::CsvDecoderState;
+use self::json::JsonDecoderState;
+use self::parquet::ParquetDecoderState;
 use self::protobuf::ProtobufDecoderState;
 use crate::source::{DecodeResult, SourceOutput};
 use metrics::DecodeMetrics;
 
 mod avro;
+mod cdc_v2;
 mod csv;
+mod json;
 pub mod metrics;
+mod parquet;
 mod protobuf;
 
 /// Decode delimited CDCv2 messages.
 ///
-/// This not only literally decodes the avro-encoded messages, but
-/// also builds a differential dataflow collection that respects the
-/// data and progress messages in the underlying CDCv2 stream.
+/// This not only literally decodes the Avro-, Protobuf-, or JSON-encoded
+/// messages, but also builds a differential dataflow collection that respects
+/// the data and progress messages in the underlying CDCv2 stream.
 pub fn render_decode_cdcv2<G: Scope<Timestamp = Timestamp>>(
     stream: &Stream<G, SourceOutput<Option<Vec<u8>>, Option<Vec<u8>>, ()>>,
-    schema: &str,
-    registry: Option<mz_ccsr::ClientConfig>,
-    confluent_wire_format: bool,
+    encoding: DataEncoding,
 ) -> (Collection<G, Row, Diff>, Box<dyn Any + Send + Sync>) {
-    // We will have already checked validity of the schema by now, so this can't fail.
-    let mut resolver = ConfluentAvroResolver::new(schema, registry, confluent_wire_format).unwrap();
+    let mut decoder = CdcV2DecoderState::new(encoding);
     let channel = Rc::new(RefCell::new(VecDeque::new()));
     let activator: Rc<RefCell<Option<SyncActivator>>> = Rc::new(RefCell::new(None));
     let mut vector = Vec::new();
@@ -88,21 +88,10 @@ pub fn render_decode_cdcv2<G: Scope<Timestamp = Timestamp>>(
                             Some(value) => value,
                             None => continue,
                         };
-                        let (mut data, schema, _) = match block_on(resolver.resolve(&*value)) {
-                            Ok(ok) => ok,
-                            Err(e) => {
-                                error!("Failed to get schema info for CDCv2 record: {}", e);
-                                continue;
-                            }
-                        };
-                        let d = GeneralDeserializer {
-                            schema: schema.top_node(),
-                        };
-                        let dec = mz_interchange::avro::cdc_v2::Decoder;
-                        let message = match d.deserialize(&mut data, dec) {
+                        let message = match decoder.decode(value) {
                             Ok(ok) => ok,
                             Err(e) => {
-                                error!("Failed to deserialize avro message: {}", e);
+                                error!("{}", e);
                                 continue;
                             }
                         };
@@ -140,6 +129,7 @@ pub(crate) enum PreDelimitedFormat {
     Text,
     Regex(Regex, Row),
     Protobuf(ProtobufDecoderState),
+    Json(JsonDecoderState),
 }
 
 impl PreDelimitedFormat {
@@ -167,6 +157,7 @@ impl PreDelimitedFormat {
                 Ok(Some(row_buf.clone()))
             }
             PreDelimitedFormat::Protobuf(pb) => pb.get_value(bytes).transpose(),
+            PreDelimitedFormat::Json(json) => json.decode(bytes),
         }
     }
 }
@@ -179,6 +170,7 @@ pub(crate) enum DataDecoderInner {
         format: PreDelimitedFormat,
     },
     Csv(CsvDecoderState),
+    Parquet(ParquetDecoderState),
 
     PreDelimited(PreDelimitedFormat),
 }
@@ -204,6 +196,7 @@ impl DataDecoder {
             }
             DataDecoderInner::Avro(avro) => avro.decode(bytes),
             DataDecoderInner::Csv(csv) => csv.decode(bytes),
+            DataDecoderInner::Parquet(parquet) => parquet.next(bytes),
             DataDecoderInner::PreDelimited(format) => {
                 let result = format.decode(*bytes);
                 *bytes = &[];
@@ -233,10 +226,22 @@ impl DataDecoder {
                     format.decode(data)
                 }
             }
+            DataDecoderInner::Parquet(parquet) => parquet.eof(),
             _ => Ok(None),
         }
     }
 
+    /// Get the next record that an earlier call to `eof` decoded, if any.
+    ///
+    /// This is only necessary for formats that decode many records at once,
+    /// like Parquet files.
+    pub fn next_buffered(&mut self) -> Option<Row> {
+        match &mut self.inner {
+            DataDecoderInner::Parquet(parquet) => parquet.next_buffered(),
+            _ => None,
+        }
+    }
+
     pub fn log_errors(&self, n: usize) {
         self.metrics.count_errors(&self.inner, n);
     }
@@ -277,7 +282,8 @@ fn get_decoder(
         DataEncoding::Text
         | DataEncoding::Bytes
         | DataEncoding::Protobuf(_)
-        | DataEncoding::Regex(_) => {
+        | DataEncoding::Regex(_)
+        | DataEncoding::Json(_) => {
             let after_delimiting = match encoding {
                 DataEncoding::Regex(RegexEncoding { regex }) => {
                     PreDelimitedFormat::Regex(regex.0, Default::default())
@@ -288,6 +294,9 @@ fn get_decoder(
                                     client creation in purification.",
                     ))
                 }
+                DataEncoding::Json(encoding) => {
+                    PreDelimitedFormat::Json(JsonDecoderState::new(encoding))
+                }
                 DataEncoding::Bytes => PreDelimitedFormat::Bytes,
                 DataEncoding::Text => PreDelimitedFormat::Text,
                 _ => unreachable!(),
@@ -309,9 +318,16 @@ fn get_decoder(
                 metrics,
             }
         }
+        DataEncoding::Parquet(enc) => DataDecoder {
+            inner: DataDecoderInner::Parquet(ParquetDecoderState::new(enc)),
+            metrics,
+        },
         DataEncoding::Postgres => {
             unreachable!("Postgres sources should not go through the general decoding path.")
         }
+        DataEncoding::MySql => {
+            unreachable!("MySQL sources should not go through the general decoding path.")
+        }
         DataEncoding::RowCodec(_) => {
             unreachable!(
                 "Persist (RowCodec) sources should not go through the general decoding path."
@@ -508,33 +524,33 @@ where
                             }
                             value_buf.clear();
 
-                            match result.transpose() {
-                                None => continue,
-                                Some(value) => {
-                                    if value.is_err() {
-                                        n_errors += 1;
-                                    } else if matches!(&value, Ok(_)) {
-                                        n_successes += 1;
-                                    }
-                                    // `RangeFrom` `Iterator`'s never end
-                                    let position = n_seen.next().unwrap();
-                                    let metadata = to_metadata_row(
-                                        &metadata_items,
-                                        partition.clone(),
-                                        position,
-                                        *upstream_time_millis,
-                                        headers.as_deref(),
-                                    );
-
-                                    session.give(DecodeResult {
-                                        key: None,
-                                        value: Some(value.map(|r| (r, 1))),
-                                        position,
-                                        upstream_time_millis: *upstream_time_millis,
-                                        partition: partition.clone(),
-                                        metadata,
-                                    });
+                            // Some formats decode several records at the end of
+                            // an object, which each get their own position.
+                            let buffered = std::iter::from_fn(|| value_decoder.next_buffered());
+                            for value in result.transpose().into_iter().chain(buffered.map(Ok)) {
+                                if value.is_err() {
+                                    n_errors += 1;
+                                } else if matches!(&value, Ok(_)) {
+                                    n_successes += 1;
                                 }
+                                // `RangeFrom` `Iterator`'s never end
+                                let position = n_seen.next().unwrap();
+                                let metadata = to_metadata_row(
+                                    &metadata_items,
+                                    partition.clone(),
+                                    position,
+                                    *upstream_time_millis,
+                                    headers.as_deref(),
+                                );
+
+                                session.give(DecodeResult {
+                                    key: None,
+                                    value: Some(value.map(|r| (r, 1))),
+                                    position,
+                                    upstream_time_millis: *upstream_time_millis,
+                                    partition: partition.clone(),
+                                    metadata,
+                                });
                             }
                             continue;
                         }
@@ -685,7 +701,10 @@ fn to_metadata_row(
                 }
             }
         }
-        PartitionId::None => {
+        PartitionId::Kinesis(_)
+        | PartitionId::MySql(_)
+        | PartitionId::S3(_)
+        | PartitionId::None => {
             for item in metadata_items.iter() {
                 match item {
                     IncludedColumnSource::DefaultPosition => packer.push(Datum::from(position)),
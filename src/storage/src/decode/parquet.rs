@@ -0,0 +1,69 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::VecDeque;
+
+use mz_dataflow_types::{
+    sources::encoding::{ParquetColumn, ParquetEncoding},
+    DecodeError,
+};
+use mz_interchange::parquet::ParquetDecoder;
+use mz_repr::Row;
+
+/// Decodes Parquet files, which can only be decoded once all of their bytes
+/// are present, as their metadata is stored at the end of the file.
+#[derive(Debug)]
+pub struct ParquetDecoderState {
+    decoder: ParquetDecoder,
+    /// The bytes of the current file
+    file: Vec<u8>,
+    /// The decoded rows of the previous file that have not been returned yet
+    rows: VecDeque<Row>,
+}
+
+impl ParquetDecoderState {
+    pub fn new(ParquetEncoding { columns }: ParquetEncoding) -> Self {
+        let columns = columns
+            .into_iter()
+            .map(|ParquetColumn { name, typ }| (name, typ.scalar_type))
+            .collect();
+        ParquetDecoderState {
+            decoder: ParquetDecoder::new(columns),
+            file: vec![],
+            rows: VecDeque::new(),
+        }
+    }
+
+    /// Buffers `bytes` until the end of the file is reached.
+    pub fn next(&mut self, bytes: &mut &[u8]) -> Result<Option<Row>, DecodeError> {
+        self.file.extend_from_slice(bytes);
+        *bytes = &[];
+        Ok(None)
+    }
+
+    /// Decodes the buffered file, returning its first row.
+    ///
+    /// The remaining rows are returned by `next_buffered`.
+    pub fn eof(&mut self) -> Result<Option<Row>, DecodeError> {
+        let file = std::mem::take(&mut self.file);
+        if file.is_empty() {
+            return Ok(None);
+        }
+        let rows = self
+            .decoder
+            .decode_file(file)
+            .map_err(|e| DecodeError::Text(format!("Parquet decoding error: {:#}", e)))?;
+        self.rows = rows.into();
+        Ok(self.rows.pop_front())
+    }
+
+    pub fn next_buffered(&mut self) -> Option<Row> {
+        self.rows.pop_front()
+    }
+}
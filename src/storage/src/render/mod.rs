@@ -119,11 +119,15 @@ mod upsert;
 ///
 /// This method creates a new dataflow to host the implementations of sources for the `dataflow`
 /// argument, and returns assets for each source that can import the results into a new dataflow.
+///
+/// If `restart` is set, the source replaces a previous ingestion into the same collection and
+/// retracts its contents.
 pub fn build_storage_dataflow<A: Allocate>(
     timely_worker: &mut TimelyWorker<A>,
     storage_state: &mut StorageState,
     debug_name: &str,
     source: CreateSourceCommand<mz_repr::Timestamp>,
+    restart: bool,
 ) {
     let worker_logging = timely_worker.log_register().get("timely");
     let name = format!("Source dataflow: {debug_name}");
@@ -156,6 +160,7 @@ pub fn build_storage_dataflow<A: Allocate>(
                 source_data,
                 storage_state,
                 Arc::clone(&token),
+                restart,
             );
 
             storage_state.source_tokens.insert(source.id, token);
@@ -16,6 +16,7 @@ use differential_dataflow::{Collection, Hashable};
 use timely::dataflow::channels::pact::Exchange;
 use timely::dataflow::operators::generic::builder_rc::OperatorBuilder;
 use timely::dataflow::Scope;
+use timely::progress::Antichain;
 use timely::PartialOrder;
 
 use mz_dataflow_types::client::controller::storage::CollectionMetadata;
@@ -33,6 +34,7 @@ pub fn render<G>(
     source_data: Collection<G, Result<Row, DataflowError>, Diff>,
     storage_state: &mut StorageState,
     token: Arc<dyn Any + Send + Sync>,
+    restart: bool,
 ) where
     G: Scope<Timestamp = Timestamp>,
 {
@@ -62,6 +64,12 @@ pub fn render<G>(
             capabilities.clear();
             let mut buffer = Vec::new();
             let mut stash: HashMap<_, Vec<_>> = HashMap::new();
+            // A restarted source replaces the contents written by its predecessor. They are
+            // retracted in the first append of the restarted source, at the time of its first
+            // updates, so that readers never observe the collection without either of them. If
+            // the restarted source has no updates, the contents are retracted as soon as its
+            // frontier advances.
+            let mut retract_existing = restart;
 
             let mut write = crate::persist_cache::open_writer::<
                 SourceData,
@@ -69,7 +77,7 @@ pub fn render<G>(
                 mz_repr::Timestamp,
                 mz_repr::Diff,
             >(
-                storage_metadata.persist_location,
+                storage_metadata.persist_location.clone(),
                 storage_metadata.persist_shard,
             )
             .await
@@ -97,15 +105,41 @@ pub fn render<G>(
                 }
 
                 let empty = Vec::new();
-                let mut updates = stash
-                    .iter()
-                    .flat_map(|(ts, updates)| {
-                        if !input_frontier.less_equal(ts) {
-                            updates.iter()
-                        } else {
-                            empty.iter()
-                        }
-                    })
+                let updates = stash.iter().flat_map(|(ts, updates)| {
+                    if !input_frontier.less_equal(ts) {
+                        updates.iter()
+                    } else {
+                        empty.iter()
+                    }
+                });
+
+                let mut retractions = Vec::new();
+                if retract_existing
+                    && PartialOrder::less_than(&*shared_frontier.borrow(), &input_frontier)
+                {
+                    let expected_upper = shared_frontier.borrow().clone();
+                    let retract_ts = stash
+                        .keys()
+                        .filter(|ts| !input_frontier.less_equal(ts))
+                        .min()
+                        .or_else(|| expected_upper.as_option())
+                        .copied()
+                        .expect("upper is not empty");
+                    retractions = read_contents(&storage_metadata, &expected_upper)
+                        .await
+                        .into_iter()
+                        .map(|(data, diff)| (data, retract_ts, -diff))
+                        .collect();
+                    // The predecessor may have completed an append while the contents were
+                    // read. They are read again once it has stopped writing.
+                    if *shared_frontier.borrow() != expected_upper {
+                        continue;
+                    }
+                    retract_existing = false;
+                }
+
+                let mut updates = updates
+                    .chain(retractions.iter())
                     .map(|&(ref row, ref ts, ref diff)| ((row, &()), ts, diff));
 
                 if PartialOrder::less_than(&*shared_frontier.borrow(), &input_frontier) {
@@ -131,3 +165,37 @@ pub fn render<G>(
         },
     )
 }
+
+/// Reads the contents of the shard described by `storage_metadata` as of the time just before
+/// `upper`.
+async fn read_contents(
+    storage_metadata: &CollectionMetadata,
+    upper: &Antichain<Timestamp>,
+) -> Vec<(SourceData, Diff)> {
+    let as_of = match upper.as_option() {
+        Some(&upper) if upper > 0 => Antichain::from_elem(upper - 1),
+        // Nothing has been written yet.
+        _ => return vec![],
+    };
+
+    let read = crate::persist_cache::open_reader::<SourceData, (), Timestamp, Diff>(
+        storage_metadata.persist_location.clone(),
+        storage_metadata.persist_shard,
+    )
+    .await
+    .expect("could not open persist shard");
+
+    let mut contents = vec![];
+    let mut snapshot = read
+        .snapshot(as_of)
+        .await
+        .expect("cannot serve requested as_of");
+    while let Some(updates) = snapshot.next().await {
+        for ((data, _), _ts, diff) in updates {
+            contents.push((data.expect("decoding failed"), diff));
+        }
+    }
+    read.expire().await;
+
+    contents
+}
@@ -30,8 +30,8 @@ use mz_repr::{Diff, GlobalId, Row, RowPacker, Timestamp};
 use crate::decode::{render_decode, render_decode_cdcv2, render_decode_delimited};
 use crate::source::{
     self, DecodeResult, DelimitedValueSource, KafkaSourceReader, KinesisSourceReader,
-    PostgresSourceReader, PubNubSourceReader, RawSourceCreationConfig, S3SourceReader,
-    SourceOutput,
+    MySqlSourceReader, PostgresSourceReader, PubNubSourceReader, RawSourceCreationConfig,
+    S3SourceReader, SourceOutput, SqlServerSourceReader,
 };
 use mz_timely_util::operator::{CollectionExt, StreamExt};
 
@@ -203,9 +203,28 @@ where
                     );
                     ((SourceType::Row(ok), err), cap)
                 }
+                ExternalSourceConnector::MySql(_) => {
+                    let ((ok, err), cap) = source::create_raw_source::<_, MySqlSourceReader>(
+                        base_source_config,
+                        &connector,
+                        storage_state.connector_context.clone(),
+                    );
+                    ((SourceType::Row(ok), err), cap)
+                }
+                ExternalSourceConnector::SqlServer(_) => {
+                    let ((ok, err), cap) = source::create_raw_source::<_, SqlServerSourceReader>(
+                        base_source_config,
+                        &connector,
+                        storage_state.connector_context.clone(),
+                    );
+                    ((SourceType::Row(ok), err), cap)
+                }
                 ExternalSourceConnector::Persist(_) => {
                     unreachable!("persist/STORAGE sources cannot be rendered in a storage instance")
                 }
+                ExternalSourceConnector::Webhook(_) => {
+                    unreachable!("webhook sources are appended to by the coordinator")
+                }
             };
 
             // Include any source errors.
@@ -228,26 +247,13 @@ where
                 // `render_decode_cdcv2` accomplishes what decoding and envelope-processing
                 // below do for other `SourceEnvelope`s
                 if let SourceEnvelope::CdcV2 = &envelope {
-                    let AvroEncoding {
-                        schema,
-                        schema_registry_config,
-                        confluent_wire_format,
-                    } = match value_encoding {
-                        DataEncoding::Avro(enc) => enc,
-                        _ => unreachable!("Attempted to create non-Avro CDCv2 source"),
-                    };
                     let ok_source = match ok_source {
                         SourceType::Delimited(s) => s,
                         _ => unreachable!("Attempted to create non-delimited CDCv2 source"),
                     };
                     // TODO(petrosagg): this should move to the envelope section below and
-                    // made to work with a stream of Rows instead of decoding Avro directly
-                    let (oks, token) = render_decode_cdcv2(
-                        &ok_source,
-                        &schema,
-                        schema_registry_config,
-                        confluent_wire_format,
-                    );
+                    // made to work with a stream of Rows instead of decoding directly
+                    let (oks, token) = render_decode_cdcv2(&ok_source, value_encoding);
                     needed_tokens.push(Arc::new(token));
                     (oks, None)
                 } else {
@@ -89,6 +89,7 @@ impl SourceReader for KafkaSourceReader {
         consumer_activator: SyncActivator,
         connector: ExternalSourceConnector,
         restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        _restored_positions: HashMap<PartitionId, String>,
         _: SourceDataEncoding,
         metrics: crate::source::metrics::SourceBaseMetrics,
         connector_context: ConnectorContext,
@@ -585,6 +586,7 @@ fn construct_source_message(
     SourceMessage {
         partition: PartitionId::Kafka(msg.partition()),
         offset: kafka_offset.into(),
+        resume_position: None,
         upstream_time_millis: msg.timestamp().to_millis(),
         key: msg.key().map(|k| k.to_vec()),
         value: msg.payload().map(|p| p.to_vec()),
@@ -7,11 +7,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 use std::time::Instant;
 
-use anyhow::anyhow;
 use aws_sdk_kinesis::error::GetRecordsError;
 use aws_sdk_kinesis::output::GetRecordsOutput;
 use aws_sdk_kinesis::types::SdkError;
@@ -47,22 +46,57 @@ pub struct KinesisSourceReader {
     kinesis_client: KinesisClient,
     /// The name of the stream
     stream_name: String,
-    /// The set of active shards
-    shard_set: HashMap<String, ShardMetrics>,
-    /// A queue representing the next shard to read from. This is necessary
-    /// to ensure that all shards are read from uniformly
-    shard_queue: VecDeque<(String, Option<String>)>,
+    /// All shards of the stream that we know about, including shards that are
+    /// waiting for their parents and shards that have been read to completion
+    shards: HashMap<String, ShardState>,
+    /// A queue representing the next shard to read from, along with the
+    /// iterator for its next `GetRecords` call. This is necessary to ensure
+    /// that all shards are read from uniformly
+    shard_queue: VecDeque<(String, String)>,
+    /// The offsets and sequence numbers restored for shards that have not
+    /// been discovered yet
+    restored_offsets: HashMap<String, (MzOffset, Option<String>)>,
     /// The time at which we last refreshed metadata
     /// TODO(natacha): this should be moved to timestamper
     last_checked_shards: Instant,
     /// Storage for messages that have not yet been timestamped
     buffered_messages: VecDeque<SourceMessage<(), Option<Vec<u8>>, ()>>,
-    /// Count of processed message
-    processed_message_count: i64,
     /// Metrics from which per-shard metrics get created.
     base_metrics: KinesisMetrics,
 }
 
+/// The read state of a single shard.
+///
+/// The offset of a record is the number of records that the source has read
+/// from the shard. Kinesis sequence numbers are too wide to be represented as
+/// an [`MzOffset`], so each record instead carries its sequence number as the
+/// position to resume from, which is persisted along with the shard's offset.
+/// After a restart, the shard is read again after that sequence number.
+struct ShardState {
+    /// The shard's parent and, for shards created by a merge, its adjacent
+    /// parent. A shard is not read until all of its parents have been read to
+    /// completion, so that records with the same partition key are emitted in
+    /// order across a reshard.
+    parents: Vec<String>,
+    status: ShardStatus,
+    /// The offset of the last record read from the shard
+    offset: MzOffset,
+    /// The sequence number of the last record read from the shard, which is
+    /// used to resume the shard after a restart and to replace an expired
+    /// shard iterator
+    last_sequence_number: Option<String>,
+    metrics: ShardMetrics,
+}
+
+enum ShardStatus {
+    /// Waiting for the shard's parents to be read to completion.
+    Blocked,
+    /// Being read; the shard's iterator is in the shard queue.
+    Reading,
+    /// Closed by a reshard and read to completion.
+    Finished,
+}
+
 struct ShardMetrics {
     millis_behind_latest: DeleteOnDropGauge<'static, AtomicI64, Vec<String>>,
 }
@@ -79,30 +113,106 @@ impl ShardMetrics {
 
 impl KinesisSourceReader {
     async fn update_shard_information(&mut self) -> Result<(), anyhow::Error> {
-        let current_shards: HashSet<_> =
-            mz_kinesis_util::get_shard_ids(&self.kinesis_client, &self.stream_name)
-                .await?
+        for shard in mz_kinesis_util::list_shards(&self.kinesis_client, &self.stream_name).await? {
+            let shard_id = shard.shard_id.unwrap_or_else(|| "".into());
+            if self.shards.contains_key(&shard_id) {
+                continue;
+            }
+            let parents = shard
+                .parent_shard_id
+                .into_iter()
+                .chain(shard.adjacent_parent_shard_id)
                 .collect();
-        let known_shards: HashSet<_> = self.shard_set.keys().cloned().collect();
-        let new_shards = current_shards
-            .difference(&known_shards)
-            .map(|shard_id| shard_id.to_owned());
-        for shard_id in new_shards {
-            self.shard_set.insert(
-                shard_id.to_string(),
-                ShardMetrics::new(&self.base_metrics, &self.stream_name, &shard_id),
-            );
-            self.shard_queue.push_back((
-                shard_id.to_string(),
+            let (offset, last_sequence_number) =
+                self.restored_offsets.remove(&shard_id).unwrap_or_default();
+            let state = ShardState {
+                parents,
+                status: ShardStatus::Blocked,
+                offset,
+                last_sequence_number,
+                metrics: ShardMetrics::new(&self.base_metrics, &self.stream_name, &shard_id),
+            };
+            self.shards.insert(shard_id, state);
+        }
+
+        // Start reading the shards whose parents have been read to completion.
+        // Parents that are no longer listed have aged out of the stream's
+        // retention period, so there is nothing left to read from them.
+        loop {
+            let unblocked: Vec<_> = self
+                .shards
+                .iter()
+                .filter(|(_, shard)| {
+                    matches!(shard.status, ShardStatus::Blocked)
+                        && shard.parents.iter().all(|parent| {
+                            self.shards.get(parent).map_or(true, |parent| {
+                                matches!(parent.status, ShardStatus::Finished)
+                            })
+                        })
+                })
+                .map(|(shard_id, _)| shard_id.clone())
+                .collect();
+            if unblocked.is_empty() {
+                return Ok(());
+            }
+            for shard_id in unblocked {
+                let iterator = self.shard_iterator(&shard_id).await?;
+                self.enqueue_shard(shard_id, iterator);
+            }
+        }
+    }
+
+    /// Queues the shard to be read with the given iterator, or marks it as
+    /// finished if there is no iterator because the shard is closed and has
+    /// been read to completion.
+    fn enqueue_shard(&mut self, shard_id: String, iterator: Option<String>) {
+        let shard = self.shards.get_mut(&shard_id).expect("shard must be known");
+        match iterator {
+            Some(iterator) => {
+                shard.status = ShardStatus::Reading;
+                self.shard_queue.push_back((shard_id, iterator));
+            }
+            None => shard.status = ShardStatus::Finished,
+        }
+    }
+
+    /// Refreshes the set of shards, starting to read any shards that have
+    /// become readable.
+    fn refresh_shards(&mut self) -> Result<(), SourceReaderError> {
+        if let Err(e) = block_on(self.update_shard_information()) {
+            error!("{:#?}", e);
+            return Err(e.into());
+        }
+        self.last_checked_shards = Instant::now();
+        Ok(())
+    }
+
+    /// Obtains an iterator for a shard that starts just after the last record
+    /// read from the shard, or at its oldest record if none has been read.
+    ///
+    /// This is used to start reading a shard, possibly after a restart, and to
+    /// replace an expired iterator.
+    async fn shard_iterator(&self, shard_id: &str) -> Result<Option<String>, anyhow::Error> {
+        let iterator = match &self.shards[shard_id].last_sequence_number {
+            Some(sequence_number) => {
+                mz_kinesis_util::get_shard_iterator_after(
+                    &self.kinesis_client,
+                    &self.stream_name,
+                    shard_id,
+                    sequence_number,
+                )
+                .await?
+            }
+            None => {
                 mz_kinesis_util::get_shard_iterator(
                     &self.kinesis_client,
                     &self.stream_name,
-                    &shard_id,
+                    shard_id,
                 )
-                .await?,
-            ));
-        }
-        Ok(())
+                .await?
+            }
+        };
+        Ok(iterator)
     }
 
     /// Obtains the next record for this shard given a shard iterator
@@ -130,7 +240,8 @@ impl SourceReader for KinesisSourceReader {
         _worker_count: usize,
         _consumer_activator: SyncActivator,
         connector: ExternalSourceConnector,
-        _restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        restored_positions: HashMap<PartitionId, String>,
         _encoding: SourceDataEncoding,
         metrics: crate::source::metrics::SourceBaseMetrics,
         connector_context: ConnectorContext,
@@ -140,169 +251,147 @@ impl SourceReader for KinesisSourceReader {
             _ => unreachable!(),
         };
 
-        let state = block_on(create_state(
-            &metrics.kinesis,
-            kc,
+        // Resume each shard after the sequence number of the last record that
+        // was ingested from it.
+        let mut shard_offsets = HashMap::new();
+        for (pid, offset) in restored_offsets {
+            let position = restored_positions.get(&pid).cloned();
+            match (pid, offset) {
+                (PartitionId::Kinesis(shard_id), Some(offset)) => {
+                    shard_offsets.insert(shard_id, (offset, position));
+                }
+                (PartitionId::Kinesis(_), None) => (),
+                _ => panic!("unexpected partition id type"),
+            }
+        }
+
+        let kinesis_client = block_on(create_client(
+            &kc,
             connector_context.aws_external_id_prefix.as_ref(),
             source_id,
         ));
-        match state {
-            Ok((kinesis_client, stream_name, shard_set, shard_queue)) => Ok(KinesisSourceReader {
-                kinesis_client,
-                shard_queue,
-                last_checked_shards: Instant::now(),
-                buffered_messages: VecDeque::new(),
-                shard_set,
-                stream_name,
-                processed_message_count: 0,
-                base_metrics: metrics.kinesis,
-            }),
-            Err(e) => Err(anyhow!("{}", e)),
-        }
+        let mut reader = KinesisSourceReader {
+            kinesis_client,
+            stream_name: kc.stream_name,
+            shards: HashMap::new(),
+            shard_queue: VecDeque::new(),
+            restored_offsets: shard_offsets,
+            last_checked_shards: Instant::now(),
+            buffered_messages: VecDeque::new(),
+            base_metrics: metrics.kinesis,
+        };
+        block_on(reader.update_shard_information())?;
+        Ok(reader)
     }
     fn get_next_message(
         &mut self,
     ) -> Result<NextMessage<Self::Key, Self::Value, Self::Diff>, SourceReaderError> {
-        assert_eq!(self.shard_queue.len(), self.shard_set.len());
-
         //TODO move to timestamper
         if self.last_checked_shards.elapsed() >= KINESIS_SHARD_REFRESH_RATE {
-            if let Err(e) = block_on(self.update_shard_information()) {
-                error!("{:#?}", e);
-                return Err(e.into());
-            }
-            self.last_checked_shards = std::time::Instant::now();
+            self.refresh_shards()?;
         }
 
         if let Some(message) = self.buffered_messages.pop_front() {
-            Ok(NextMessage::Ready(SourceMessageType::Finalized(message)))
-        } else {
-            // Rotate through all of a stream's shards, start with a new shard on each activation.
-            if let Some((shard_id, mut shard_iterator)) = self.shard_queue.pop_front() {
-                if let Some(iterator) = &shard_iterator {
-                    let get_records_output = match block_on(self.get_records(&iterator)) {
-                        Ok(output) => {
-                            shard_iterator = output.next_shard_iterator.clone();
-                            if let Some(millis) = output.millis_behind_latest {
-                                self.shard_set
-                                    .get(&shard_id)
-                                    .unwrap()
-                                    .millis_behind_latest
-                                    .set(millis);
-                            }
-                            output
-                        }
-                        Err(SdkError::DispatchFailure(e)) => {
-                            // todo@jldlaughlin: Parse this to determine fatal/retriable?
-                            error!("{}", e);
-                            self.shard_queue.push_back((shard_id, shard_iterator));
-                            // Do not send error message as this would cause source to terminate
-                            return Ok(NextMessage::TransientDelay);
-                        }
-                        Err(SdkError::ServiceError { err, .. })
-                            if err.is_expired_iterator_exception() =>
-                        {
-                            // todo@jldlaughlin: Will need track source offsets to grab a new iterator.
-                            error!("{}", err);
-                            return Err(SourceReaderError {
-                                inner: SourceErrorDetails::Other(err.to_string()),
-                            });
-                        }
-                        Err(SdkError::ServiceError { err, .. })
-                            if err.is_provisioned_throughput_exceeded_exception() =>
-                        {
-                            self.shard_queue.push_back((shard_id, shard_iterator));
-                            // Do not send error message as this would cause source to terminate
-                            return Ok(NextMessage::Pending);
-                        }
-                        Err(e) => {
-                            // Fatal service errors:
-                            //  - InvalidArgument
-                            //  - KMSAccessDenied, KMSDisabled, KMSInvalidState, KMSNotFound,
-                            //    KMSOptInRequired, KMSThrottling
-                            //  - ResourceNotFound
-                            //
-                            // Other fatal Rusoto errors:
-                            // - Credentials
-                            // - Validation
-                            // - ParseError
-                            // - Unknown (raw HTTP provided)
-                            // - Blocking
-                            error!("{}", e);
-                            return Err(SourceReaderError {
-                                inner: SourceErrorDetails::Other(e.to_string()),
-                            });
-                        }
-                    };
+            return Ok(NextMessage::Ready(SourceMessageType::Finalized(message)));
+        }
 
-                    for record in get_records_output.records.unwrap_or_default() {
-                        let data = record
-                            .data
-                            .map(|blob| blob.into_inner())
-                            .unwrap_or_else(Vec::new);
-                        self.processed_message_count += 1;
-                        let source_message = SourceMessage {
-                            partition: PartitionId::None,
-                            offset: MzOffset {
-                                //TODO: should MzOffset be modified to be a string?
-                                offset: self.processed_message_count,
-                            },
-                            upstream_time_millis: None,
-                            key: (),
-                            value: Some(data),
-                            headers: None,
-                            specific_diff: (),
-                        };
-                        self.buffered_messages.push_back(source_message);
-                    }
+        // Rotate through all of a stream's shards, start with a new shard on each activation.
+        if let Some((shard_id, shard_iterator)) = self.shard_queue.pop_front() {
+            let get_records_output = match block_on(self.get_records(&shard_iterator)) {
+                Ok(output) => output,
+                Err(SdkError::DispatchFailure(e)) => {
+                    // todo@jldlaughlin: Parse this to determine fatal/retriable?
+                    error!("{}", e);
                     self.shard_queue.push_back((shard_id, shard_iterator));
+                    // Do not send error message as this would cause source to terminate
+                    return Ok(NextMessage::TransientDelay);
+                }
+                Err(SdkError::ServiceError { err, .. }) if err.is_expired_iterator_exception() => {
+                    // Shard iterators expire five minutes after they are
+                    // issued, which can happen if the source is paused for a
+                    // while. Pick up where we left off with a new iterator.
+                    let iterator = block_on(self.shard_iterator(&shard_id))?;
+                    self.enqueue_shard(shard_id, iterator);
+                    return Ok(NextMessage::Pending);
+                }
+                Err(SdkError::ServiceError { err, .. })
+                    if err.is_provisioned_throughput_exceeded_exception() =>
+                {
+                    self.shard_queue.push_back((shard_id, shard_iterator));
+                    // Do not send error message as this would cause source to terminate
+                    return Ok(NextMessage::Pending);
+                }
+                Err(e) => {
+                    // Fatal service errors:
+                    //  - InvalidArgument
+                    //  - KMSAccessDenied, KMSDisabled, KMSInvalidState, KMSNotFound,
+                    //    KMSOptInRequired, KMSThrottling
+                    //  - ResourceNotFound
+                    //
+                    // Other fatal Rusoto errors:
+                    // - Credentials
+                    // - Validation
+                    // - ParseError
+                    // - Unknown (raw HTTP provided)
+                    // - Blocking
+                    error!("{}", e);
+                    return Err(SourceReaderError {
+                        inner: SourceErrorDetails::Other(e.to_string()),
+                    });
+                }
+            };
+
+            let shard = self
+                .shards
+                .get_mut(&shard_id)
+                .expect("queued shards must be known");
+            if let Some(millis) = get_records_output.millis_behind_latest {
+                shard.metrics.millis_behind_latest.set(millis);
+            }
+            for record in get_records_output.records.unwrap_or_default() {
+                shard.offset += 1;
+                shard.last_sequence_number = record.sequence_number;
+                let data = record
+                    .data
+                    .map(|blob| blob.into_inner())
+                    .unwrap_or_else(Vec::new);
+                let source_message = SourceMessage {
+                    partition: PartitionId::Kinesis(shard_id.clone()),
+                    offset: shard.offset,
+                    resume_position: shard.last_sequence_number.clone(),
+                    upstream_time_millis: None,
+                    key: (),
+                    value: Some(data),
+                    headers: None,
+                    specific_diff: (),
+                };
+                self.buffered_messages.push_back(source_message);
+            }
+
+            match get_records_output.next_shard_iterator {
+                Some(iterator) => self.shard_queue.push_back((shard_id, iterator)),
+                None => {
+                    // The shard was closed by a reshard and we have read all
+                    // of its records. Its children may now be read, so look
+                    // for them right away rather than at the next refresh.
+                    shard.status = ShardStatus::Finished;
+                    self.refresh_shards()?;
                 }
             }
-            Ok(match self.buffered_messages.pop_front() {
-                Some(message) => NextMessage::Ready(SourceMessageType::Finalized(message)),
-                None => NextMessage::Pending,
-            })
         }
+        Ok(match self.buffered_messages.pop_front() {
+            Some(message) => NextMessage::Ready(SourceMessageType::Finalized(message)),
+            None => NextMessage::Pending,
+        })
     }
 }
 
-/// Creates the necessary data-structures for shard management
-// todo: Better error handling here! Not all errors mean we're done/can't progress.
-async fn create_state(
-    base_metrics: &KinesisMetrics,
-    c: KinesisSourceConnector,
+/// Creates a Kinesis client for the connector's stream
+async fn create_client(
+    c: &KinesisSourceConnector,
     aws_external_id_prefix: Option<&AwsExternalIdPrefix>,
     source_id: GlobalId,
-) -> Result<
-    (
-        KinesisClient,
-        String,
-        HashMap<String, ShardMetrics>,
-        VecDeque<(String, Option<String>)>,
-    ),
-    anyhow::Error,
-> {
+) -> KinesisClient {
     let config = c.aws.load(aws_external_id_prefix, Some(&source_id)).await;
-    let kinesis_client = aws_sdk_kinesis::Client::new(&config);
-
-    let shard_set = mz_kinesis_util::get_shard_ids(&kinesis_client, &c.stream_name).await?;
-    let mut shard_queue: VecDeque<(String, Option<String>)> = VecDeque::new();
-    let mut shard_map = HashMap::new();
-    for shard_id in shard_set {
-        shard_queue.push_back((
-            shard_id.clone(),
-            mz_kinesis_util::get_shard_iterator(&kinesis_client, &c.stream_name, &shard_id).await?,
-        ));
-        shard_map.insert(
-            shard_id.clone(),
-            ShardMetrics::new(base_metrics, &c.stream_name, &shard_id),
-        );
-    }
-
-    Ok((
-        kinesis_client,
-        c.stream_name.clone(),
-        shard_map,
-        shard_queue,
-    ))
+    aws_sdk_kinesis::Client::new(&config)
 }
@@ -70,18 +70,22 @@ use crate::source::util::source;
 mod kafka;
 mod kinesis;
 pub mod metrics;
+mod mysql;
 pub mod persist_source;
 mod postgres;
 mod pubnub;
 mod reclock;
 mod s3;
+mod sql_server;
 pub mod util;
 
 pub use kafka::KafkaSourceReader;
 pub use kinesis::KinesisSourceReader;
+pub use mysql::MySqlSourceReader;
 pub use postgres::PostgresSourceReader;
 pub use pubnub::PubNubSourceReader;
 pub use s3::S3SourceReader;
+pub use sql_server::SqlServerSourceReader;
 
 include!(concat!(env!("OUT_DIR"), "/mz_storage.source.rs"));
 
@@ -170,6 +174,7 @@ where
         consumer_activator: SyncActivator,
         connector: ExternalSourceConnector,
         restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        restored_positions: HashMap<PartitionId, String>,
         encoding: SourceDataEncoding,
         metrics: crate::source::metrics::SourceBaseMetrics,
         connector_context: ConnectorContext,
@@ -182,6 +187,7 @@ where
             consumer_activator,
             connector,
             restored_offsets,
+            restored_positions,
             encoding,
             metrics,
             connector_context,
@@ -198,6 +204,7 @@ where
                 value,
                 partition,
                 offset,
+                resume_position,
                 upstream_time_millis,
                 headers,
                 specific_diff,
@@ -207,6 +214,7 @@ where
                     value,
                     partition,
                     offset,
+                    resume_position,
                     upstream_time_millis,
                     headers,
                     specific_diff,
@@ -217,6 +225,7 @@ where
                 value,
                 partition,
                 offset,
+                resume_position,
                 upstream_time_millis,
                 headers,
                 specific_diff,
@@ -226,6 +235,7 @@ where
                     value,
                     partition,
                     offset,
+                    resume_position,
                     upstream_time_millis,
                     headers,
                     specific_diff,
@@ -419,6 +429,7 @@ pub trait SourceReader {
         consumer_activator: SyncActivator,
         connector: ExternalSourceConnector,
         restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        restored_positions: HashMap<PartitionId, String>,
         encoding: SourceDataEncoding,
         metrics: crate::source::metrics::SourceBaseMetrics,
         connector_context: ConnectorContext,
@@ -515,6 +526,10 @@ pub struct SourceMessage<Key, Value, Diff> {
     pub partition: PartitionId,
     /// Materialize offset of the message (1-indexed)
     pub offset: MzOffset,
+    /// The position from which a restarted source resumes reading the partition once this
+    /// message has been ingested, for sources whose offsets cannot express where to resume
+    /// (e.g., a Kinesis sequence number)
+    pub resume_position: Option<String>,
     /// The time that an external system first observed the message
     ///
     /// Milliseconds since the unix epoch
@@ -1024,8 +1039,22 @@ where
                 }
             };
 
-            // TODO: Use the persisted partition offsets to skip forward
-            let start_offsets = vec![];
+            // TODO: Use the persisted partition offsets to skip forward for all sources. Kinesis
+            // sources are append-only, so unlike sources with stateful envelopes they do not need
+            // to replay their input from the beginning to rebuild their state. Kinesis shards
+            // resume after the sequence numbers they have produced, MySQL sources resume
+            // replicating the binary log after the transactions they have produced, and S3
+            // sources resume scanning each bucket after the objects they have read to completion.
+            let (start_offsets, start_positions) = match &source_connector {
+                ExternalSourceConnector::Kinesis(_) | ExternalSourceConnector::S3(_) => (
+                    timestamper.persisted_offsets(),
+                    timestamper.persisted_positions(),
+                ),
+                ExternalSourceConnector::MySql(_) => {
+                    (timestamper.persisted_offsets(), HashMap::new())
+                }
+                _ => (vec![], HashMap::new()),
+            };
 
             let source_reader = S::new(
                 name.clone(),
@@ -1035,6 +1064,7 @@ where
                 sync_activator,
                 source_connector.clone(),
                 start_offsets,
+                start_positions,
                 encoding,
                 base_metrics,
                 connector_context.clone(),
@@ -1100,11 +1130,17 @@ where
                     // It's time to timestamp a batch
                     _ = timestamp_interval.tick() => {
                         let mut max_offsets = HashMap::new();
+                        let mut resume_positions = HashMap::new();
                         for message in pending_messages.iter().filter_map(|m| m.as_ref().ok()) {
                             let entry = max_offsets.entry(message.partition.clone()).or_default();
                             *entry = std::cmp::max(*entry, message.offset);
+                            // Messages are in ascending offset order within each partition, so
+                            // the last position of a partition is the one to resume from.
+                            if let Some(position) = &message.resume_position {
+                                resume_positions.insert(message.partition.clone(), position.clone());
+                            }
                         }
-                        let (bindings, progress) = match timestamper.timestamp_offsets(&max_offsets).await {
+                        let (bindings, progress) = match timestamper.timestamp_offsets(&max_offsets, &resume_positions).await {
                             Ok((bindings, progress)) => (bindings, progress),
                             Err(e) => {
                                 error!("Error timestamping offsets: {}", e);
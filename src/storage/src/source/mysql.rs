@@ -0,0 +1,756 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use chrono::NaiveDateTime;
+use futures::{FutureExt, StreamExt};
+use mysql_async::binlog::events::EventData;
+use mysql_async::binlog::value::BinlogValue;
+use mysql_async::prelude::Queryable;
+use mysql_async::{BinlogRequest, GnoInterval, Sid, Value};
+use timely::scheduling::SyncActivator;
+use tokio::sync::mpsc::{Receiver, Sender};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use mz_dataflow_types::mysql_source::{MySqlColumn, MySqlTable};
+use mz_dataflow_types::sources::{
+    encoding::SourceDataEncoding, ExternalSourceConnector, MySqlSourceConnector, MzOffset,
+};
+use mz_dataflow_types::ConnectorContext;
+use mz_dataflow_types::SourceErrorDetails;
+use mz_expr::PartitionId;
+use mz_mysql_util::{DdlTarget, GtidSet, MySqlTableInfo};
+use mz_ore::task;
+use mz_repr::{Datum, Diff, GlobalId, Row};
+
+use super::metrics::SourceBaseMetrics;
+use crate::source::{
+    NextMessage, SourceMessage, SourceMessageType, SourceReader, SourceReaderError,
+};
+
+enum ReplicationError {
+    Recoverable(anyhow::Error),
+    Fatal(anyhow::Error),
+}
+
+impl From<mysql_async::Error> for ReplicationError {
+    fn from(err: mysql_async::Error) -> Self {
+        // Errors reported by the server, like a purged binary log or missing
+        // privileges, will not go away by retrying. Connection and I/O errors
+        // might.
+        match err {
+            mysql_async::Error::Server(_) => Self::Fatal(err.into()),
+            _ => Self::Recoverable(err.into()),
+        }
+    }
+}
+
+macro_rules! try_fatal {
+    ($expr:expr $(,)?) => {
+        match $expr {
+            Ok(val) => val,
+            Err(err) => return Err(ReplicationError::Fatal(err.into())),
+        }
+    };
+}
+macro_rules! try_recoverable {
+    ($expr:expr $(,)?) => {
+        match $expr {
+            Ok(val) => val,
+            Err(err) => return Err(ReplicationError::Recoverable(err.into())),
+        }
+    };
+}
+
+// Message used to communicate between `get_next_message` and the tokio task
+enum InternalMessage {
+    Err(SourceReaderError),
+    Value {
+        value: Row,
+        partition: PartitionId,
+        offset: MzOffset,
+        diff: Diff,
+        end: bool,
+    },
+}
+
+/// Information required to sync data from MySQL
+pub struct MySqlSourceReader {
+    receiver_stream: Receiver<InternalMessage>,
+}
+
+/// An internal struct held by the spawned tokio task
+struct MySqlTaskInfo {
+    source_id: GlobalId,
+    connector: MySqlSourceConnector,
+    /// The ID under which the source registers with the upstream server as a
+    /// replica
+    server_id: u32,
+    /// The transactions whose changes the source has produced
+    ///
+    /// The updates of each transaction are produced in the partition of the
+    /// server that it originated on, at the offset of its transaction number.
+    /// A restarted source resumes after all transactions up to the persisted
+    /// offset of each server.
+    gtids: GtidSet,
+    /// The position of each source table in the source's details, by its
+    /// database and name
+    source_tables: HashMap<(String, String), usize>,
+    sender: Sender<InternalMessage>,
+    activator: SyncActivator,
+}
+
+impl SourceReader for MySqlSourceReader {
+    type Key = ();
+    type Value = Row;
+    // MySQL can produce deletes that cause retractions
+    type Diff = Diff;
+
+    fn new(
+        _source_name: String,
+        source_id: GlobalId,
+        _worker_id: usize,
+        _worker_count: usize,
+        consumer_activator: SyncActivator,
+        connector: ExternalSourceConnector,
+        restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        _restored_positions: HashMap<PartitionId, String>,
+        _encoding: SourceDataEncoding,
+        _metrics: SourceBaseMetrics,
+        _connector_context: ConnectorContext,
+    ) -> Result<Self, anyhow::Error> {
+        let connector = match connector {
+            ExternalSourceConnector::MySql(mysql) => mysql,
+            _ => {
+                panic!("MySQL is the only legitimate ExternalSourceConnector for MySqlSourceReader")
+            }
+        };
+
+        let (dataflow_tx, dataflow_rx) = tokio::sync::mpsc::channel(10_000);
+
+        // Server IDs need only be unique among the replicas of the upstream
+        // server. The high bit keeps them clear of the small IDs that are
+        // conventionally assigned by hand.
+        let mut hasher = DefaultHasher::new();
+        source_id.hash(&mut hasher);
+        let server_id = (hasher.finish() as u32) | 1 << 31;
+
+        let mut gtids = GtidSet::default();
+        for (partition, offset) in restored_offsets {
+            if let (PartitionId::MySql(sid), Some(offset)) = (partition, offset) {
+                let gno = u64::try_from(offset.offset)?;
+                gtids.insert_through(Uuid::parse_str(&sid)?, gno);
+            }
+        }
+
+        let task_info = MySqlTaskInfo {
+            source_id,
+            server_id,
+            gtids,
+            source_tables: connector
+                .details
+                .tables
+                .iter()
+                .enumerate()
+                .map(|(i, t)| ((t.database.clone(), t.name.clone()), i))
+                .collect(),
+            connector,
+            sender: dataflow_tx,
+            activator: consumer_activator,
+        };
+
+        task::spawn(
+            || format!("mysql_source:{}", source_id),
+            mysql_replication_loop(task_info),
+        );
+        Ok(Self {
+            receiver_stream: dataflow_rx,
+        })
+    }
+
+    fn get_next_message(
+        &mut self,
+    ) -> Result<NextMessage<Self::Key, Self::Value, Self::Diff>, SourceReaderError> {
+        match self.receiver_stream.recv().now_or_never() {
+            Some(Some(InternalMessage::Value {
+                value,
+                diff,
+                partition,
+                offset,
+                end,
+            })) => {
+                let message = SourceMessage {
+                    partition,
+                    offset,
+                    resume_position: None,
+                    upstream_time_millis: None,
+                    key: (),
+                    value,
+                    headers: None,
+                    specific_diff: diff,
+                };
+                if end {
+                    Ok(NextMessage::Ready(SourceMessageType::Finalized(message)))
+                } else {
+                    Ok(NextMessage::Ready(SourceMessageType::InProgress(message)))
+                }
+            }
+            Some(Some(InternalMessage::Err(e))) => Err(e),
+            None => Ok(NextMessage::Pending),
+            Some(None) => Ok(NextMessage::Finished),
+        }
+    }
+}
+
+/// Defers to `mysql_replication_loop_inner` and sends errors through the channel if they occur
+async fn mysql_replication_loop(mut task_info: MySqlTaskInfo) {
+    if let Err(e) = mysql_replication_loop_inner(&mut task_info).await {
+        // Drop the send error, as we have no way of communicating back to the
+        // source operator if the channel is gone.
+        let _ = task_info.sender.send(InternalMessage::Err(e)).await;
+        task_info
+            .activator
+            .activate()
+            .expect("mysql reader activation failed");
+    }
+}
+
+/// Core logic
+async fn mysql_replication_loop_inner(
+    task_info: &mut MySqlTaskInfo,
+) -> Result<(), SourceReaderError> {
+    let source_id = task_info.source_id;
+    // Rows of the snapshot are only sent once the whole snapshot has been
+    // read, so a failed attempt at it need not be retracted.
+    let mut snapshot_tx = Transaction::new();
+    // A restarted source has already produced the snapshot.
+    while task_info.gtids.is_empty() {
+        match task_info.produce_snapshot(&mut snapshot_tx).await {
+            Ok(()) => {
+                info!("replication snapshot for source {} succeeded", source_id);
+                task_info.close_snapshot(snapshot_tx).await;
+                break;
+            }
+            Err(ReplicationError::Recoverable(e)) => {
+                snapshot_tx.clear();
+                warn!(
+                    "replication snapshot for source {} failed, retrying: {}",
+                    source_id, e
+                );
+            }
+            Err(ReplicationError::Fatal(e)) => {
+                return Err(SourceReaderError {
+                    inner: SourceErrorDetails::Initialization(e.to_string()),
+                })
+            }
+        }
+
+        // TODO(petrosagg): implement exponential back-off
+        tokio::time::sleep(Duration::from_secs(3)).await;
+    }
+
+    info!(
+        "source {} replicates transactions after {}",
+        source_id, task_info.gtids
+    );
+    loop {
+        match task_info.produce_replication().await {
+            Err(ReplicationError::Recoverable(e)) => {
+                warn!(
+                    "replication for source {} interrupted, retrying: {}",
+                    source_id, e
+                )
+            }
+            Err(ReplicationError::Fatal(e)) => {
+                return Err(SourceReaderError {
+                    inner: SourceErrorDetails::Other(e.to_string()),
+                })
+            }
+            Ok(()) => {
+                // shutdown iniated elsewhere
+                return Ok(());
+            }
+        }
+
+        // TODO(petrosagg): implement exponential back-off
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        info!("resuming replication for source {}", source_id);
+    }
+}
+
+/// A helper struct to build and produce transactions
+struct Transaction {
+    rows: Vec<(Row, Diff)>,
+}
+
+impl Transaction {
+    fn new() -> Self {
+        Transaction { rows: vec![] }
+    }
+
+    /// Record an insertion of a row in the current transaction
+    fn insert(&mut self, row: Row) {
+        self.rows.push((row, 1));
+    }
+
+    /// Record a deletion of a row in the current transaction
+    fn delete(&mut self, row: Row) {
+        self.rows.push((row, -1));
+    }
+
+    /// Discard the rows of the current transaction
+    fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    /// Finalize a transaction and send it off through the channel
+    async fn close(
+        &mut self,
+        partition: &PartitionId,
+        offset: i64,
+        sender: &Sender<InternalMessage>,
+        activator: &SyncActivator,
+    ) {
+        let num = self.rows.len();
+        for (i, (row, diff)) in self.rows.drain(..).enumerate() {
+            // a closed receiver means the source has been shutdown
+            // (dropped or the process is dying), so just continue on
+            // without activation
+            if let Ok(_) = sender
+                .send(InternalMessage::Value {
+                    value: row,
+                    partition: partition.clone(),
+                    offset: MzOffset { offset },
+                    diff,
+                    end: i == (num - 1),
+                })
+                .await
+            {
+                activator
+                    .activate()
+                    .expect("mysql reader activation failed");
+            }
+        }
+    }
+}
+
+impl MySqlTaskInfo {
+    /// Validates that all source tables still exist upstream with the schema
+    /// that the source was created with
+    fn validate_tables(&self, tables: Vec<MySqlTableInfo>) -> Result<(), anyhow::Error> {
+        let upstream: HashMap<String, MySqlTable> = tables
+            .into_iter()
+            .map(|t| (t.name.clone(), MySqlTable::from(t)))
+            .collect();
+        for table in &self.connector.details.tables {
+            match upstream.get(&table.name) {
+                Some(upstream) if has_same_columns(table, upstream) => (),
+                Some(_) => bail!(
+                    "Schema for table {} differs, recreate materialized source to use new schema",
+                    table.name
+                ),
+                None => bail!(
+                    "Database {} is missing expected table {}",
+                    table.database,
+                    table.name
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Produces the initial snapshot of the source tables and records the
+    /// transactions that it reflects, from which the binary log is replicated.
+    async fn produce_snapshot(
+        &mut self,
+        snapshot_tx: &mut Transaction,
+    ) -> Result<(), ReplicationError> {
+        let tables = try_recoverable!(
+            mz_mysql_util::table_info(&self.connector.conn, &self.connector.database).await
+        );
+        try_fatal!(self.validate_tables(tables));
+
+        let mut lock_conn = try_recoverable!(mz_mysql_util::connect(&self.connector.conn).await);
+        let mut conn = try_recoverable!(mz_mysql_util::connect(&self.connector.conn).await);
+        // Timestamps are read in UTC.
+        conn.query_drop("SET SESSION time_zone = '+00:00'").await?;
+
+        // The source tables are locked while the snapshot transaction starts,
+        // so that no transaction that writes to them commits between the
+        // snapshot and the reading of the executed transactions.
+        let tables = &self.connector.details.tables;
+        if !tables.is_empty() {
+            let locks = tables
+                .iter()
+                .map(|t| format!("{}.{} READ", quote(&t.database), quote(&t.name)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            lock_conn
+                .query_drop(format!("LOCK TABLES {}", locks))
+                .await?;
+        }
+        conn.query_drop("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .await?;
+        conn.query_drop("START TRANSACTION WITH CONSISTENT SNAPSHOT, READ ONLY")
+            .await?;
+        let gtids: Option<String> = conn.query_first("SELECT @@GLOBAL.gtid_executed").await?;
+        lock_conn.query_drop("UNLOCK TABLES").await?;
+        lock_conn.disconnect().await?;
+        self.gtids = try_fatal!(gtids.unwrap_or_default().parse::<GtidSet>());
+
+        for (table_id, table) in tables.iter().enumerate() {
+            let columns = table
+                .columns
+                .iter()
+                .map(|c| quote(&c.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let rows: Vec<mysql_async::Row> = conn
+                .query(format!(
+                    "SELECT {} FROM {}.{}",
+                    columns,
+                    quote(&table.database),
+                    quote(&table.name)
+                ))
+                .await?;
+            for row in rows {
+                let values = row.unwrap().into_iter().map(BinlogValue::Value);
+                snapshot_tx.insert(try_fatal!(pack_row(table_id, table, values)));
+            }
+        }
+        conn.query_drop("COMMIT").await?;
+        conn.disconnect().await?;
+        Ok(())
+    }
+
+    /// Produces the snapshot in the partition of the first server that the
+    /// transactions it reflects originated on.
+    ///
+    /// The offsets of the other servers are recorded by producing and
+    /// retracting a placeholder row in their partitions, so that a restarted
+    /// source does not replicate their transactions again.
+    async fn close_snapshot(&mut self, mut snapshot_tx: Transaction) {
+        let last_gnos: Vec<_> = self
+            .gtids
+            .intervals()
+            .filter_map(|(sid, intervals)| {
+                let (_, end) = intervals.last()?;
+                Some((PartitionId::MySql(sid.to_string()), (end - 1) as i64))
+            })
+            .collect();
+        for (i, (partition, gno)) in last_gnos.iter().enumerate() {
+            if i > 0 || snapshot_tx.rows.is_empty() {
+                snapshot_tx.delete(placeholder_row());
+                snapshot_tx.insert(placeholder_row());
+            }
+            snapshot_tx
+                .close(partition, *gno, &self.sender, &self.activator)
+                .await;
+        }
+        // The snapshot of a server without transactions reflects none, and is
+        // taken again by a restarted source.
+        snapshot_tx
+            .close(&PartitionId::None, 1, &self.sender, &self.activator)
+            .await;
+    }
+
+    /// Streams the binary log from the end of the transactions that the source
+    /// has produced, and produces the changes of the source tables in each
+    /// subsequent transaction.
+    async fn produce_replication(&mut self) -> Result<(), ReplicationError> {
+        let conn = try_recoverable!(mz_mysql_util::connect(&self.connector.conn).await);
+        let sids = self
+            .gtids
+            .intervals()
+            .map(|(sid, intervals)| {
+                intervals
+                    .iter()
+                    .fold(Sid::new(*sid.as_bytes()), |sid, (start, end)| {
+                        sid.with_interval(GnoInterval::new(*start, *end))
+                    })
+            })
+            .collect::<Vec<_>>();
+        let request = BinlogRequest::new(self.server_id)
+            .with_gtid()
+            .with_sids(sids);
+        let mut stream = conn.get_binlog_stream(request).await?;
+
+        let mut tx = Transaction::new();
+        let mut gtid = None;
+        while let Some(event) = stream.next().await {
+            let event = event?;
+            match try_fatal!(event.read_data()) {
+                Some(EventData::GtidEvent(event)) => {
+                    gtid = Some((Uuid::from_bytes(event.sid()), event.gno()));
+                }
+                Some(EventData::RowsEvent(event)) => {
+                    let tme = stream.get_tme(event.table_id()).ok_or_else(|| {
+                        ReplicationError::Fatal(anyhow!(
+                            "rows event for unknown table {}",
+                            event.table_id()
+                        ))
+                    })?;
+                    let key = (
+                        tme.database_name().into_owned(),
+                        tme.table_name().into_owned(),
+                    );
+                    let table_id = match self.source_tables.get(&key) {
+                        Some(table_id) => *table_id,
+                        None => continue,
+                    };
+                    let table = &self.connector.details.tables[table_id];
+                    if tme.columns_count() as usize != table.columns.len() {
+                        return Err(ReplicationError::Fatal(anyhow!(
+                            "table {} has been altered upstream, recreate the source",
+                            table.name
+                        )));
+                    }
+                    for row in event.rows(tme) {
+                        let (before, after) = try_fatal!(row);
+                        if let Some(before) = before {
+                            let values = before.unwrap();
+                            tx.delete(try_fatal!(pack_row(table_id, table, values)));
+                        }
+                        if let Some(after) = after {
+                            let values = after.unwrap();
+                            tx.insert(try_fatal!(pack_row(table_id, table, values)));
+                        }
+                    }
+                }
+                Some(EventData::XidEvent(_)) => self.commit(&mut tx, &mut gtid).await?,
+                Some(EventData::QueryEvent(event)) => {
+                    let query = event.query();
+                    try_fatal!(self.validate_ddl(&event.schema(), &query));
+                    // Statements other than BEGIN either are COMMIT or are
+                    // DDL, which commits implicitly.
+                    if !query.trim().eq_ignore_ascii_case("BEGIN") {
+                        self.commit(&mut tx, &mut gtid).await?;
+                    }
+                }
+                _ => (),
+            }
+        }
+        Err(ReplicationError::Recoverable(anyhow!(
+            "binlog stream ended unexpectedly"
+        )))
+    }
+
+    /// Produces the changes of the current transaction and records it as
+    /// replicated.
+    async fn commit(
+        &mut self,
+        tx: &mut Transaction,
+        gtid: &mut Option<(Uuid, u64)>,
+    ) -> Result<(), ReplicationError> {
+        let (sid, gno) = match gtid.take() {
+            Some(gtid) => gtid,
+            None if tx.rows.is_empty() => return Ok(()),
+            None => {
+                return Err(ReplicationError::Fatal(anyhow!(
+                    "transaction without a GTID in the binary log"
+                )))
+            }
+        };
+        self.gtids.insert(sid, gno);
+        if !tx.rows.is_empty() {
+            let partition = PartitionId::MySql(sid.to_string());
+            let offset = try_fatal!(i64::try_from(gno));
+            tx.close(&partition, offset, &self.sender, &self.activator)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Fails if a statement in the binary log alters, drops, renames or
+    /// truncates a source table, as the source can no longer decode the table's
+    /// rows afterwards.
+    ///
+    /// Unqualified table names refer to tables in `schema`, the default
+    /// database of the statement.
+    fn validate_ddl(&self, schema: &str, query: &str) -> Result<(), anyhow::Error> {
+        let targets = mz_mysql_util::ddl_targets(query)
+            .map_err(|e| anyhow!("unable to parse statement {:?}: {}", query, e))?;
+        for table in &self.connector.details.tables {
+            let affected = targets.iter().any(|target| match target {
+                DdlTarget::Table { database, name } => {
+                    database.as_deref().unwrap_or(schema) == table.database && *name == table.name
+                }
+                DdlTarget::Database(database) => *database == table.database,
+            });
+            if affected {
+                bail!(
+                    "table {} has been altered, dropped, or truncated upstream, recreate the \
+                     source to replicate it",
+                    table.name
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reports whether two versions of a table have the same columns with the same
+/// types. Changes to the nullability or keys of columns do not affect how their
+/// values are decoded, so they are ignored.
+fn has_same_columns(table: &MySqlTable, upstream: &MySqlTable) -> bool {
+    table.columns.len() == upstream.columns.len()
+        && table
+            .columns
+            .iter()
+            .zip(&upstream.columns)
+            .all(|(column, upstream)| {
+                column.name == upstream.name && column.column_type == upstream.column_type
+            })
+}
+
+/// A row of no source table, see [`MySqlTaskInfo::close_snapshot`]
+fn placeholder_row() -> Row {
+    let mut row = Row::default();
+    let mut packer = row.packer();
+    packer.push(Datum::Int32(-1));
+    packer.push_list(std::iter::empty::<Datum>());
+    row
+}
+
+/// Quotes a MySQL identifier
+fn quote(ident: &str) -> String {
+    format!("`{}`", ident.replace('`', "``"))
+}
+
+/// Packs the values of a row of the source table at position `table_id` into a
+/// Row of the table's ID and the text of each value, e.g.
+/// `Row{ 3, ["1", "2"] }`.
+fn pack_row<'a, I>(table_id: usize, table: &MySqlTable, values: I) -> Result<Row, anyhow::Error>
+where
+    I: IntoIterator<Item = BinlogValue<'a>>,
+{
+    let mut row = Row::default();
+    let mut packer = row.packer();
+    packer.push(Datum::Int32(i32::try_from(table_id)?));
+    packer.push_list_with(|rp| -> Result<(), anyhow::Error> {
+        for (column, value) in table.columns.iter().zip(values) {
+            match value_to_text(column, value)? {
+                Some(text) => rp.push(Datum::String(&text)),
+                None => rp.push(Datum::Null),
+            }
+        }
+        Ok(())
+    })?;
+    Ok(row)
+}
+
+/// Converts a value of `column` into the text that views over the source cast
+/// to the column's type, or `None` for nulls.
+///
+/// Values read by the snapshot are in MySQL's text format, while values in the
+/// binary log are decoded into their natural representation.
+fn value_to_text(
+    column: &MySqlColumn,
+    value: BinlogValue,
+) -> Result<Option<String>, anyhow::Error> {
+    let value = match value {
+        BinlogValue::Value(value) => value,
+        BinlogValue::Jsonb(jsonb) => {
+            let json = serde_json::Value::try_from(jsonb)?;
+            return Ok(Some(json.to_string()));
+        }
+        BinlogValue::JsonDiff(_) => bail!(
+            "column {} has a partial JSON update, which is not supported; \
+             set binlog_row_value_options to ''",
+            column.name
+        ),
+    };
+    let data_type = column.data_type.as_str();
+    let text = match value {
+        Value::NULL => return Ok(None),
+        Value::Bytes(bytes) => match data_type {
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => {
+                let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                format!("\\x{}", hex)
+            }
+            "bit" => bytes
+                .iter()
+                .fold(0u64, |n, b| n << 8 | u64::from(*b))
+                .to_string(),
+            "timestamp" => {
+                let text = String::from_utf8(bytes)?;
+                // The binary log records timestamps as seconds since the epoch.
+                match text.parse::<f64>() {
+                    Ok(secs) => epoch_to_text(secs)?,
+                    Err(_) => format!("{}+00", text),
+                }
+            }
+            _ => String::from_utf8(bytes)?,
+        },
+        Value::Int(n) => match data_type {
+            "enum" => match usize::try_from(n)? {
+                // Invalid values are stored as the empty string, with index 0.
+                0 => String::new(),
+                i => column.enum_values().get(i - 1).cloned().ok_or_else(|| {
+                    anyhow!("invalid value {} for enum column {}", n, column.name)
+                })?,
+            },
+            "set" => column
+                .enum_values()
+                .into_iter()
+                .enumerate()
+                .filter(|(i, _)| n & (1 << i) != 0)
+                .map(|(_, value)| value)
+                .collect::<Vec<_>>()
+                .join(","),
+            "timestamp" => epoch_to_text(n as f64)?,
+            // The binary log does not distinguish unsigned integers.
+            "tinyint" if column.is_unsigned() => (n as u8).to_string(),
+            "smallint" if column.is_unsigned() => (n as u16).to_string(),
+            "mediumint" if column.is_unsigned() => ((n as u32) & 0xff_ffff).to_string(),
+            "int" if column.is_unsigned() => (n as u32).to_string(),
+            "bigint" if column.is_unsigned() => (n as u64).to_string(),
+            _ => n.to_string(),
+        },
+        Value::UInt(n) => n.to_string(),
+        Value::Float(n) => n.to_string(),
+        Value::Double(n) => n.to_string(),
+        Value::Date(year, month, day, hour, minute, second, micros) => {
+            let date = format!("{:04}-{:02}-{:02}", year, month, day);
+            let time = format!("{:02}:{:02}:{:02}.{:06}", hour, minute, second, micros);
+            match data_type {
+                "date" => date,
+                "timestamp" => format!("{} {}+00", date, time),
+                _ => format!("{} {}", date, time),
+            }
+        }
+        Value::Time(negative, days, hours, minutes, seconds, micros) => format!(
+            "{}{}:{:02}:{:02}.{:06}",
+            if negative { "-" } else { "" },
+            days * 24 + u32::from(hours),
+            minutes,
+            seconds,
+            micros
+        ),
+    };
+    Ok(Some(text))
+}
+
+/// Formats a number of seconds since the Unix epoch as the text of a
+/// `timestamptz`
+fn epoch_to_text(secs: f64) -> Result<String, anyhow::Error> {
+    let micros = (secs * 1_000_000.0).round() as i64;
+    let timestamp = NaiveDateTime::from_timestamp_opt(
+        micros.div_euclid(1_000_000),
+        (micros.rem_euclid(1_000_000) * 1_000) as u32,
+    )
+    .ok_or_else(|| anyhow!("timestamp out of range: {}", secs))?;
+    Ok(format!("{}+00", timestamp.format("%Y-%m-%d %H:%M:%S%.6f")))
+}
@@ -28,7 +28,7 @@ use tokio_postgres::types::PgLsn;
 use tokio_postgres::SimpleQueryMessage;
 use tracing::{error, info, warn};
 
-use mz_dataflow_types::postgres_source::PostgresTable;
+use mz_dataflow_types::postgres_source::{PostgresColumn, PostgresTable};
 use mz_dataflow_types::sources::{
     encoding::SourceDataEncoding, ExternalSourceConnector, MzOffset, PostgresSourceConnector,
 };
@@ -150,6 +150,13 @@ struct PostgresTaskInfo {
     lsn: PgLsn,
     metrics: PgSourceMetrics,
     source_tables: HashMap<u32, PostgresTable>,
+    /// The current contents of each source table, which are only tracked if
+    /// truncating a table retracts its contents, and only for tables whose contents are
+    /// not already tracked in `keyed_rows`
+    table_contents: HashMap<u32, HashMap<Row, Diff>>,
+    /// The latest version of each row of the source tables whose replica identity is their
+    /// primary key, which are only tracked if the source was created to do so
+    keyed_rows: HashMap<u32, KeyedRows>,
     sender: Sender<InternalMessage>,
     activator: SyncActivator,
 }
@@ -168,6 +175,7 @@ impl SourceReader for PostgresSourceReader {
         consumer_activator: SyncActivator,
         connector: ExternalSourceConnector,
         _restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        _restored_positions: HashMap<PartitionId, String>,
         _encoding: SourceDataEncoding,
         metrics: SourceBaseMetrics,
         _connector_context: ConnectorContext,
@@ -194,6 +202,8 @@ impl SourceReader for PostgresSourceReader {
                     .iter()
                     .map(|t| (t.relation_id, t.clone())),
             ),
+            table_contents: HashMap::new(),
+            keyed_rows: HashMap::new(),
             sender: dataflow_tx,
             activator: consumer_activator,
         };
@@ -224,6 +234,7 @@ impl SourceReader for PostgresSourceReader {
                         SourceMessage {
                             partition: PartitionId::None,
                             offset: lsn.into(),
+                            resume_position: None,
                             upstream_time_millis: None,
                             key: (),
                             value,
@@ -236,6 +247,7 @@ impl SourceReader for PostgresSourceReader {
                         SourceMessage {
                             partition: PartitionId::None,
                             offset: lsn.into(),
+                            resume_position: None,
                             upstream_time_millis: None,
                             key: (),
                             value,
@@ -407,9 +419,143 @@ impl Transaction {
     }
 }
 
+/// The latest version of each row of a table whose replica identity is its primary key, keyed by
+/// the values of its primary key columns
+///
+/// For such tables the replication stream only carries the primary key of deleted rows and of the
+/// previous version of updated rows, and omits the TOASTed values that an update leaves unchanged.
+/// The source recovers the missing values from the rows tracked here.
+struct KeyedRows {
+    /// The positions of the primary key columns
+    key_indices: Vec<usize>,
+    rows: HashMap<Row, Row>,
+    /// The previous versions of the rows changed by the current transaction, in the order they
+    /// were changed
+    undo_log: Vec<(Row, Option<Row>)>,
+}
+
+impl KeyedRows {
+    /// Returns an empty set of rows for `table`, unless it has no primary key
+    fn new(table: &PostgresTable) -> Option<Self> {
+        let key_indices: Vec<_> = table
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| column.primary_key)
+            .map(|(i, _)| i)
+            .collect();
+        if key_indices.is_empty() {
+            return None;
+        }
+        Some(KeyedRows {
+            key_indices,
+            rows: HashMap::new(),
+            undo_log: vec![],
+        })
+    }
+
+    /// Returns the primary key of a row produced by the source
+    fn key_of_row(&self, row: &Row) -> Row {
+        let values = row
+            .iter()
+            .nth(1)
+            .expect("rows end with their values")
+            .unwrap_list();
+        let values: Vec<_> = values.iter().collect();
+        Row::pack(self.key_indices.iter().map(|i| values[*i]))
+    }
+
+    /// Returns the primary key of a tuple received in the replication stream
+    fn key_of_tuple(&self, tuple_data: &[TupleData]) -> Result<Row, anyhow::Error> {
+        let mut key = Row::default();
+        let mut packer = key.packer();
+        for i in &self.key_indices {
+            match tuple_data.get(*i) {
+                Some(TupleData::Null) => packer.push(Datum::Null),
+                Some(TupleData::Text(b)) => packer.push(Datum::from(std::str::from_utf8(&b)?)),
+                Some(TupleData::UnchangedToast) | None => {
+                    bail!("primary key value missing from replication stream")
+                }
+            }
+        }
+        Ok(key)
+    }
+
+    /// Returns the row with the primary key of `tuple_data`
+    fn get(&self, tuple_data: &[TupleData]) -> Result<Row, anyhow::Error> {
+        let key = self.key_of_tuple(tuple_data)?;
+        self.rows
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| anyhow!("no row found for primary key {:?}", key))
+    }
+
+    /// Records a row of the initial snapshot
+    fn load(&mut self, row: Row) {
+        self.rows.insert(self.key_of_row(&row), row);
+    }
+
+    fn insert(&mut self, row: Row) {
+        let key = self.key_of_row(&row);
+        let previous = self.rows.insert(key.clone(), row);
+        self.undo_log.push((key, previous));
+    }
+
+    fn remove(&mut self, row: &Row) {
+        let key = self.key_of_row(row);
+        let previous = self.rows.remove(&key);
+        self.undo_log.push((key, previous));
+    }
+
+    fn truncate(&mut self) {
+        self.undo_log
+            .extend(self.rows.drain().map(|(key, row)| (key, Some(row))));
+    }
+
+    /// Makes the changes of the current transaction permanent
+    fn commit(&mut self) {
+        self.undo_log.clear();
+    }
+
+    /// Reverts the changes of the current transaction, which will be received again when
+    /// replication resumes
+    fn rollback(&mut self) {
+        for (key, previous) in self.undo_log.drain(..).rev() {
+            match previous {
+                Some(row) => self.rows.insert(key, row),
+                None => self.rows.remove(&key),
+            };
+        }
+    }
+}
+
+/// Returns the OID of the table that a row produced by the source belongs to
+fn row_rel_id(row: &Row) -> u32 {
+    row.iter()
+        .next()
+        .expect("rows start with their relation id")
+        .unwrap_int32() as u32
+}
+
 // implement the core pg logic in this impl block
 impl PostgresTaskInfo {
-    /// Validates that all expected tables exist in the publication tables and they have the same schema
+    /// Records an update of a source table in its tracked contents, if truncating the table
+    /// retracts its contents and its rows are not tracked by primary key
+    fn track_update(&mut self, row: &Row, diff: Diff) {
+        let rel_id = row_rel_id(row);
+        if !self.connector.retract_on_truncate || self.keyed_rows.contains_key(&rel_id) {
+            return;
+        }
+        let contents = self.table_contents.entry(rel_id).or_default();
+        let count = contents.entry(row.clone()).or_default();
+        *count += diff;
+        if *count == 0 {
+            contents.remove(row);
+        }
+    }
+
+    /// Validates that all expected tables exist in the publication tables and that their schemas
+    /// have at most been extended with new columns
     fn validate_tables(&self, tables: Vec<TableInfo>) -> Result<(), anyhow::Error> {
         let pub_tables: HashMap<u32, PostgresTable> = tables
             .into_iter()
@@ -418,7 +564,7 @@ impl PostgresTaskInfo {
         for (id, schema) in self.source_tables.iter() {
             match pub_tables.get(id) {
                 Some(pub_schema) => {
-                    if pub_schema != schema {
+                    if !schema.is_extended_by(pub_schema) {
                         error!(
                             "Error validating table in publication. Expected: {:?} Actual: {:?}",
                             schema, pub_schema
@@ -468,6 +614,10 @@ impl PostgresTaskInfo {
         // Validate publication tables against the state snapshot
         try_fatal!(self.validate_tables(publication_tables));
 
+        // Any rows of a previous attempt at the snapshot have been retracted
+        self.table_contents.clear();
+        self.keyed_rows.clear();
+
         // Start a transaction and immediately create a replication slot with the USE SNAPSHOT
         // directive. This makes the starting point of the slot and the snapshot of the transaction
         // identical.
@@ -501,13 +651,45 @@ impl PostgresTaskInfo {
         self.lsn = try_fatal!(consistent_point
             .parse()
             .or_else(|_| Err(anyhow!("invalid lsn"))));
-        for info in self.source_tables.values() {
+        // The tables are cloned so that their tracked contents can be updated while copying them
+        let source_tables: Vec<_> = self.source_tables.values().cloned().collect();
+        for info in &source_tables {
             let relation_id: Datum = (i32::try_from(info.relation_id).unwrap()).into();
+
+            // If requested, rows of tables whose replica identity is their primary key are
+            // tracked, as the replication stream does not include the full previous version of
+            // their rows
+            let mut keyed_rows = None;
+            if self.connector.track_primary_keys {
+                let replica_identity = client
+                    .simple_query(&format!(
+                        "SELECT relreplident FROM pg_catalog.pg_class WHERE oid = {}",
+                        info.relation_id
+                    ))
+                    .await?
+                    .into_iter()
+                    .find_map(|msg| match msg {
+                        SimpleQueryMessage::Row(row) => row.get(0).map(String::from),
+                        _ => None,
+                    });
+                if replica_identity.as_deref() == Some("d") {
+                    keyed_rows = KeyedRows::new(info);
+                }
+            }
+
+            // Only the columns that the source was created with are copied, as
+            // columns may have been appended to the table since.
+            let columns = info
+                .columns
+                .iter()
+                .map(|c| format!("{:?}", c.name))
+                .collect::<Vec<_>>()
+                .join(", ");
             let reader = client
                 .copy_out_simple(
                     format!(
-                        "COPY {:?}.{:?} TO STDOUT (FORMAT TEXT, DELIMITER '\t')",
-                        info.namespace, info.name
+                        "COPY {:?}.{:?} ({}) TO STDOUT (FORMAT TEXT, DELIMITER '\t')",
+                        info.namespace, info.name, columns
                     )
                     .as_str(),
                 )
@@ -538,6 +720,10 @@ impl PostgresTaskInfo {
                     Ok(())
                 }));
                 snapshot_tx.insert(mz_row.clone());
+                match &mut keyed_rows {
+                    Some(keyed_rows) => keyed_rows.load(mz_row.clone()),
+                    None => self.track_update(&mz_row, 1),
+                }
                 try_fatal!(
                     buffer
                         .write_all(&try_fatal!(bincode::serialize(&mz_row)))
@@ -545,6 +731,9 @@ impl PostgresTaskInfo {
                 );
             }
 
+            if let Some(keyed_rows) = keyed_rows {
+                self.keyed_rows.insert(info.relation_id, keyed_rows);
+            }
             self.metrics.tables.inc();
         }
         self.metrics.lsn.set(self.lsn.into());
@@ -573,26 +762,46 @@ impl PostgresTaskInfo {
     /// replication protocol doesn't use the binary encoding for column values so contrary to the
     /// initial snapshot here we need to parse the textual form of each column.
     ///
-    /// The `old_tuple` argument can be used as a source of data to use when encountering unchanged
-    /// TOAST values.
-    fn row_from_tuple<'a, T>(rel_id: u32, tuple_data: T) -> Result<Row, anyhow::Error>
+    /// Only the values of the columns that `table` was created with are kept, as columns may have
+    /// been appended to the upstream table since. Unchanged TOASTed values are taken from
+    /// `old_row`, the previous version of the row, if it is known.
+    fn row_from_tuple<'a, T>(
+        table: &PostgresTable,
+        tuple_data: T,
+        old_row: Option<&Row>,
+    ) -> Result<Row, anyhow::Error>
     where
         T: IntoIterator<Item = &'a TupleData>,
     {
         let mut row = Row::default();
         let mut packer = row.packer();
 
-        let rel_id: Datum = (rel_id as i32).into();
-        packer.push(rel_id);
+        let rel_id = table.relation_id;
+        let old_values: Vec<_> = match old_row {
+            Some(old_row) => old_row
+                .iter()
+                .nth(1)
+                .expect("rows end with their values")
+                .unwrap_list()
+                .iter()
+                .collect(),
+            None => vec![],
+        };
+        packer.push(Datum::from(rel_id as i32));
         packer.push_list_with(move |packer| {
-            for val in tuple_data.into_iter() {
+            for (i, val) in tuple_data.into_iter().take(table.columns.len()).enumerate() {
                 let datum = match val {
                     TupleData::Null => Datum::Null,
-                    TupleData::UnchangedToast => bail!(
-                        "Missing TOASTed value from table with OID = {}. \
-                        Did you forget to set REPLICA IDENTITY to FULL for your table?",
-                        rel_id
-                    ),
+                    TupleData::UnchangedToast => match old_values.get(i) {
+                        Some(datum) => *datum,
+                        None => bail!(
+                            "Missing TOASTed value from table with OID = {}. \
+                            Did you forget to set REPLICA IDENTITY to FULL for your table, \
+                            or to give it a primary key and create the source with \
+                            default_replica_identity = 'track'?",
+                            rel_id
+                        ),
+                    },
                     TupleData::Text(b) => std::str::from_utf8(&b)?.into(),
                 };
                 packer.push(datum);
@@ -603,9 +812,38 @@ impl PostgresTaskInfo {
         Ok(row)
     }
 
+    /// Returns the previous version of an updated or deleted row.
+    ///
+    /// The row is decoded from the old tuple if the table's replica identity is `FULL`. Otherwise
+    /// it is looked up in the tracked rows of the table by the primary key in `key_tuple`.
+    fn old_row(
+        table: &PostgresTable,
+        keyed_rows: Option<&KeyedRows>,
+        old_tuple: Option<&[TupleData]>,
+        key_tuple: Option<&[TupleData]>,
+    ) -> Result<Row, anyhow::Error> {
+        match (old_tuple, keyed_rows, key_tuple) {
+            (Some(old_tuple), _, _) => PostgresTaskInfo::row_from_tuple(table, old_tuple, None),
+            (None, Some(keyed_rows), Some(key_tuple)) => keyed_rows.get(key_tuple),
+            _ => bail!(
+                "Old row missing from replication stream for table with OID = {}. \
+                Did you forget to set REPLICA IDENTITY to FULL for your table, \
+                or to give it a primary key and create the source with \
+                default_replica_identity = 'track'?",
+                table.relation_id
+            ),
+        }
+    }
+
     async fn produce_replication(&mut self) -> Result<(), ReplicationError> {
         use ReplicationError::*;
 
+        // Replication resumes after the last committed transaction, so the changes of any
+        // transaction that was interrupted will be received again
+        for keyed_rows in self.keyed_rows.values_mut() {
+            keyed_rows.rollback();
+        }
+
         let client =
             try_recoverable!(mz_postgres_util::connect_replication(&self.connector.conn).await);
 
@@ -682,55 +920,63 @@ impl PostgresTaskInfo {
                                     Insert(insert) => {
                                         self.metrics.inserts.inc();
                                         let rel_id = insert.rel_id();
-                                        if !self.source_tables.contains_key(&rel_id) {
-                                            continue;
-                                        }
+                                        let table = match self.source_tables.get(&rel_id) {
+                                            Some(table) => table,
+                                            None => continue,
+                                        };
                                         let new_tuple = insert.tuple().tuple_data();
-                                        let row = try_fatal!(PostgresTaskInfo::row_from_tuple(rel_id, new_tuple));
+                                        let row = try_fatal!(PostgresTaskInfo::row_from_tuple(table, new_tuple, None));
+                                        if let Some(keyed_rows) = self.keyed_rows.get_mut(&rel_id) {
+                                            keyed_rows.insert(row.clone());
+                                        }
                                         inserts.push(row);
                                     }
                                     Update(update) => {
                                         self.metrics.updates.inc();
                                         let rel_id = update.rel_id();
-                                        if !self.source_tables.contains_key(&rel_id) {
-                                            continue;
-                                        }
-                                        let old_tuple = try_fatal!(update
-                                            .old_tuple()
-                                            .ok_or_else(|| anyhow!("Old row missing from replication stream for table with OID = {}. \
-                                                    Did you forget to set REPLICA IDENTITY to FULL for your table?", rel_id)))
-                                        .tuple_data();
-                                        let old_row =
-                                            try_fatal!(PostgresTaskInfo::row_from_tuple(rel_id, old_tuple));
-                                        deletes.push(old_row);
+                                        let table = match self.source_tables.get(&rel_id) {
+                                            Some(table) => table,
+                                            None => continue,
+                                        };
+                                        let keyed_rows = self.keyed_rows.get_mut(&rel_id);
+                                        let new_tuple = update.new_tuple().tuple_data();
+                                        // The key tuple is only present if the update changed the primary key
+                                        let key_tuple = update.key_tuple().map_or(new_tuple, |t| t.tuple_data());
+                                        let old_row = try_fatal!(PostgresTaskInfo::old_row(
+                                            table,
+                                            keyed_rows.as_deref(),
+                                            update.old_tuple().map(|t| t.tuple_data()),
+                                            Some(key_tuple),
+                                        ));
 
                                         // If the new tuple contains unchanged toast values, reuse the ones
-                                        // from the old tuple
-                                        let new_tuple = update
-                                            .new_tuple()
-                                            .tuple_data()
-                                            .iter()
-                                            .zip(old_tuple.iter())
-                                            .map(|(new, old)| match new {
-                                                TupleData::UnchangedToast => old,
-                                                _ => new,
-                                            });
+                                        // from the old row
                                         let new_row =
-                                            try_fatal!(PostgresTaskInfo::row_from_tuple(rel_id, new_tuple));
+                                            try_fatal!(PostgresTaskInfo::row_from_tuple(table, new_tuple, Some(&old_row)));
+                                        if let Some(keyed_rows) = keyed_rows {
+                                            keyed_rows.remove(&old_row);
+                                            keyed_rows.insert(new_row.clone());
+                                        }
+                                        deletes.push(old_row);
                                         inserts.push(new_row);
                                     }
                                     Delete(delete) => {
                                         self.metrics.deletes.inc();
                                         let rel_id = delete.rel_id();
-                                        if !self.source_tables.contains_key(&rel_id) {
-                                            continue;
+                                        let table = match self.source_tables.get(&rel_id) {
+                                            Some(table) => table,
+                                            None => continue,
+                                        };
+                                        let keyed_rows = self.keyed_rows.get_mut(&rel_id);
+                                        let row = try_fatal!(PostgresTaskInfo::old_row(
+                                            table,
+                                            keyed_rows.as_deref(),
+                                            delete.old_tuple().map(|t| t.tuple_data()),
+                                            delete.key_tuple().map(|t| t.tuple_data()),
+                                        ));
+                                        if let Some(keyed_rows) = keyed_rows {
+                                            keyed_rows.remove(&row);
                                         }
-                                        let old_tuple = try_fatal!(delete
-                                            .old_tuple()
-                                            .ok_or_else(|| anyhow!("Old row missing from replication stream for table with OID = {}. \
-                                                    Did you forget to set REPLICA IDENTITY to FULL for your table?", rel_id)))
-                                        .tuple_data();
-                                        let row = try_fatal!(PostgresTaskInfo::row_from_tuple(rel_id, old_tuple));
                                         deletes.push(row);
                                     }
                                     Commit(commit) => {
@@ -740,72 +986,94 @@ impl PostgresTaskInfo {
                                         let mut tx = Transaction::new();
 
                                         for row in deletes.drain(..) {
+                                            self.track_update(&row, -1);
                                             tx.delete(row);
                                         }
                                         for row in inserts.drain(..) {
+                                            self.track_update(&row, 1);
                                             tx.insert(row);
                                         }
+                                        for keyed_rows in self.keyed_rows.values_mut() {
+                                            keyed_rows.commit();
+                                        }
 
                                         tx.close(self.lsn, &self.sender, &self.activator).await;
                                         self.metrics.lsn.set(self.lsn.into());
                                     }
                                     Relation(relation) => {
                                         let rel_id = relation.rel_id();
-                                        match self.source_tables.get(&rel_id) {
-                                            Some(source_table) => {
-                                                // Start with the cheapest check first, this will catch the majority of alters
-                                                if source_table.columns.len() != relation.columns().len() {
-                                                    error!(
-                                                        "alter table detected on {} with id {}",
-                                                        source_table.name, source_table.relation_id
-                                                    );
-                                                    return Err(Fatal(anyhow!(
-                                                        "source table {} with oid {} has been altered",
-                                                        source_table.name,
-                                                        source_table.relation_id
-                                                    )));
-                                                }
-                                                if source_table.name.ne(relation.name().unwrap())
-                                                    || source_table.namespace.ne(relation.namespace().unwrap())
-                                                {
-                                                    error!(
-                                                        "table name changed on {}.{} with id {} to {}.{}",
-                                                        source_table.namespace,
-                                                        source_table.name,
-                                                        source_table.relation_id,
-                                                        relation.namespace().unwrap(),
-                                                        relation.name().unwrap()
-                                                    );
-                                                    return Err(Fatal(anyhow!(
-                                                        "source table {} with oid {} has been altered",
-                                                        source_table.name,
-                                                        source_table.relation_id
-                                                    )));
-                                                }
-                                                // Relation messages do not include nullability/primary_key data
-                                                // so we check the name, type_oid, and type_mod explicitly and error if any of them differ
-                                                if !source_table.columns.iter().zip(relation.columns()).all(
-                                                    |(src, rel)| {
-                                                        src.name == rel.name().unwrap()
-                                                            && src.type_oid == rel.type_id()
-                                                            && src.type_mod == rel.type_modifier()
-                                                    },
-                                                ) {
-                                                    error!("alter table error: name {}, oid {}, old_schema {:?}, new_schema {:?}", source_table.name, source_table.relation_id, source_table.columns, relation.columns());
-                                                    return Err(Fatal(anyhow!(
-                                                        "source table {} with oid {} has been altered",
-                                                        source_table.name,
-                                                        source_table.relation_id
-                                                    )));
-                                                }
-                                            }
+                                        let source_table = match self.source_tables.get(&rel_id) {
+                                            Some(source_table) => source_table,
                                             // Ignore messages for tables we do not know about
                                             None => continue,
+                                        };
+                                        // Relation messages do not include nullability/primary_key data,
+                                        // which is ignored when comparing the tables below
+                                        let upstream_table = PostgresTable {
+                                            name: relation.name().unwrap().into(),
+                                            namespace: relation.namespace().unwrap().into(),
+                                            relation_id: rel_id,
+                                            columns: relation
+                                                .columns()
+                                                .iter()
+                                                .map(|column| PostgresColumn {
+                                                    name: column.name().unwrap().into(),
+                                                    type_oid: column.type_id(),
+                                                    type_mod: column.type_modifier(),
+                                                    nullable: true,
+                                                    primary_key: false,
+                                                })
+                                                .collect(),
+                                        };
+                                        // Appending columns is the only alteration that the source can
+                                        // survive, by continuing to ingest the original columns only
+                                        if !source_table.is_extended_by(&upstream_table) {
+                                            error!("alter table error: name {}, oid {}, old_schema {:?}, new_schema {:?}", source_table.name, source_table.relation_id, source_table.columns, upstream_table.columns);
+                                            return Err(Fatal(anyhow!(
+                                                "source table {} with oid {} has been altered",
+                                                source_table.name,
+                                                source_table.relation_id
+                                            )));
+                                        }
+                                        if upstream_table.columns.len() > source_table.columns.len() {
+                                            info!(
+                                                "columns appended to source table {} with oid {} are ignored until the source is refreshed",
+                                                source_table.name, source_table.relation_id
+                                            );
                                         }
                                     }
                                     Origin(_) | Type(_) => {
                                         self.metrics.ignored.inc();
                                     }
+                                    Truncate(truncate) if self.connector.retract_on_truncate => {
+                                        for rel_id in truncate.rel_ids() {
+                                            if !self.source_tables.contains_key(rel_id) {
+                                                continue;
+                                            }
+                                            match self.keyed_rows.get_mut(rel_id) {
+                                                // The rows tracked by primary key include the changes to the
+                                                // table earlier in this transaction, which are kept, so
+                                                // retracting them retracts both those changes and the contents
+                                                // of the table as of the last transaction
+                                                Some(keyed_rows) => {
+                                                    deletes.extend(keyed_rows.rows.values().cloned());
+                                                    keyed_rows.truncate();
+                                                }
+                                                // The truncation supersedes the changes to the table earlier in
+                                                // this transaction, so the contents of the table as of the last
+                                                // transaction are retracted instead
+                                                None => {
+                                                    inserts.retain(|row| row_rel_id(row) != *rel_id);
+                                                    deletes.retain(|row| row_rel_id(row) != *rel_id);
+                                                    for (row, count) in self.table_contents.get(rel_id).into_iter().flatten() {
+                                                        for _ in 0..*count {
+                                                            deletes.push(row.clone());
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
                                     Truncate(truncate) => {
                                         let tables = truncate
                                             .rel_ids()
@@ -7,6 +7,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::time::Duration;
 
@@ -45,6 +46,7 @@ impl SourceReader for PubNubSourceReader {
         _consumer_activator: SyncActivator,
         connector: ExternalSourceConnector,
         _restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        _restored_positions: HashMap<PartitionId, String>,
         _encoding: SourceDataEncoding,
         _: crate::source::metrics::SourceBaseMetrics,
         _: ConnectorContext,
@@ -120,6 +122,7 @@ impl SourceReader for PubNubSourceReader {
                                 // timetokens.
                                 offset: msg.timetoken.t.try_into().unwrap(),
                             },
+                            resume_position: None,
                             upstream_time_millis: None,
                             key: (),
                             value: row,
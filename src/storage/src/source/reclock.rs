@@ -36,11 +36,14 @@ pub struct ReclockOperator {
     write_upper: Antichain<Timestamp>,
     // If a binding is here, it has been persisted.  Previously returned bindings are compacted.
     persisted_timestamp_bindings: HashMap<PartitionId, VecDeque<(Timestamp, MzOffset)>>,
+    // The accumulated position of each partition, keyed by the position a source resumes from.
+    // Once the updates of a timestamp have been applied, each partition has at most one position.
+    persisted_positions: HashMap<PartitionId, HashMap<String, Diff>>,
     // Max offset we've returned (or have committed to returning at the end of the current invocation).
     read_cursors: HashMap<PartitionId, MzOffset>,
-    write_handle: WriteHandle<(), PartitionId, Timestamp, Diff>,
+    write_handle: WriteHandle<String, PartitionId, Timestamp, Diff>,
     timestamp_bindings_listener:
-        Pin<Box<dyn Stream<Item = ListenEvent<(), PartitionId, Timestamp, Diff>>>>,
+        Pin<Box<dyn Stream<Item = ListenEvent<String, PartitionId, Timestamp, Diff>>>>,
     now: NowFn,
     update_interval: Duration,
     last_timestamp: Timestamp,
@@ -98,6 +101,7 @@ impl ReclockOperator {
             as_of.meet_assign(&write_upper);
         }
 
+        let mut persisted_positions = HashMap::new();
         let persisted_timestamp_bindings = if read_progress != write_upper {
             let mut snapshot_map = HashMap::new();
             let mut snap_iter = read_handle
@@ -107,8 +111,9 @@ impl ReclockOperator {
 
             while let Some(v) = snap_iter.next().await {
                 for ((key, value), timestamp, diff) in v {
-                    let _: () = key.unwrap();
+                    let position = key.unwrap();
                     let partition = value.unwrap();
+                    update_position(&mut persisted_positions, &partition, position, diff);
                     let (current_ts, current_offset) = snapshot_map
                         .entry(partition)
                         .or_insert((0, MzOffset::default()));
@@ -143,6 +148,7 @@ impl ReclockOperator {
             read_progress,
             write_upper,
             persisted_timestamp_bindings,
+            persisted_positions,
             read_cursors,
             write_handle,
             timestamp_bindings_listener,
@@ -152,6 +158,37 @@ impl ReclockOperator {
         })
     }
 
+    /// Returns, for each partition, the offset up to which messages were bound to timestamps
+    /// as of the `as_of` frontier this operator was created with.
+    ///
+    /// This must be called before any new bindings are minted.
+    pub fn persisted_offsets(&self) -> Vec<(PartitionId, Option<MzOffset>)> {
+        self.persisted_timestamp_bindings
+            .iter()
+            .map(|(partition, bindings)| {
+                (
+                    partition.clone(),
+                    bindings.front().map(|(_timestamp, offset)| *offset),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns, for each partition, the position that a source resumes reading the partition from,
+    /// as of the `as_of` frontier this operator was created with.
+    ///
+    /// Only partitions whose bindings were minted along with a position are included. This must be
+    /// called before any new bindings are minted.
+    pub fn persisted_positions(&self) -> HashMap<PartitionId, String> {
+        self.persisted_positions
+            .iter()
+            .filter_map(|(partition, positions)| {
+                let position = positions.keys().find(|position| !position.is_empty())?;
+                Some((partition.clone(), position.clone()))
+            })
+            .collect()
+    }
+
     // TODO: require NowFn to be monotonic so we can simplify this function
     fn get_time(&mut self) -> Option<Timestamp> {
         let update_interval: u64 = self
@@ -202,6 +239,10 @@ impl ReclockOperator {
     /// function.  If `observed_max_offsets` is empty, one attempt will be made to increase the upper of underlying
     /// persist collection.  After that attempt, thre progress will be computed and returned.
     ///
+    /// `observed_positions` optionally provides, for partitions in `observed_max_offsets`, the position that a
+    /// restarted source resumes reading the partition from once the message at the highest observed offset has been
+    /// ingested. New bindings atomically replace the persisted position of their partition with it.
+    ///
     /// Return values:
     /// - The `Timestamp` returned is a persisted `Timestamp` that will be returned by any replica after any number of
     ///   restarts
@@ -212,6 +253,7 @@ impl ReclockOperator {
     pub async fn timestamp_offsets<'a>(
         &mut self,
         observed_max_offsets: &'a HashMap<PartitionId, MzOffset>,
+        observed_positions: &HashMap<PartitionId, String>,
     ) -> anyhow::Result<(
         HashMap<&'a PartitionId, (Timestamp, &'a MzOffset)>,
         Antichain<Timestamp>,
@@ -367,12 +409,13 @@ impl ReclockOperator {
                 tokio::time::sleep(self.update_interval).await;
                 continue;
             }
+            let updates = self.position_updates(&new_bindings, observed_positions);
             let compare_and_append_result = self
                 .write_handle
                 .compare_and_append(
-                    new_bindings
+                    updates
                         .iter()
-                        .map(|(partition, diff)| ((&(), *partition), &new_ts, diff)),
+                        .map(|(position, partition, diff)| ((position, *partition), &new_ts, diff)),
                     self.write_upper.clone(),
                     new_upper.clone(),
                 )
@@ -405,12 +448,25 @@ impl ReclockOperator {
                         self.read_progress = progress;
                     }
                     ListenEvent::Updates(updates) => {
-                        for ((_, value), timestamp, diff) in updates {
+                        for ((key, value), timestamp, diff) in updates {
+                            let position = key.expect("Unable to decode position");
                             let partition = value.expect("Unable to decode partition id");
-                            self.persisted_timestamp_bindings
+                            update_position(
+                                &mut self.persisted_positions,
+                                &partition,
+                                position,
+                                diff,
+                            );
+                            // The updates of a binding that moves its partition to a new position
+                            // share a timestamp, so they add up to a single offset increment.
+                            let bindings = self
+                                .persisted_timestamp_bindings
                                 .entry(partition)
-                                .or_insert_with(VecDeque::new)
-                                .push_back((timestamp, MzOffset { offset: diff }));
+                                .or_insert_with(VecDeque::new);
+                            match bindings.back_mut() {
+                                Some((ts, offset)) if *ts == timestamp => *offset += diff,
+                                _ => bindings.push_back((timestamp, MzOffset { offset: diff })),
+                            }
                         }
                     }
                 }
@@ -419,4 +475,55 @@ impl ReclockOperator {
             debug_assert!(self.validate_persisted_bindings());
         }
     }
+
+    /// Returns the updates that persist `new_bindings`, which are pairs of a partition and the
+    /// increment of its offset.
+    ///
+    /// A binding for a partition with an observed position retracts the partition's current
+    /// position and moves its accumulated offset to the observed one. Other bindings add to the
+    /// current position, or to the empty position if there is none.
+    fn position_updates<'a>(
+        &self,
+        new_bindings: &[(&'a PartitionId, i64)],
+        observed_positions: &HashMap<PartitionId, String>,
+    ) -> Vec<(String, &'a PartitionId, Diff)> {
+        let mut updates = vec![];
+        for (partition, diff) in new_bindings {
+            let current = self.persisted_positions.get(*partition);
+            let position = match observed_positions.get(*partition) {
+                Some(position) => position.clone(),
+                None => current
+                    .and_then(|positions| positions.keys().next().cloned())
+                    .unwrap_or_default(),
+            };
+            let mut moved = 0;
+            for (old_position, old_diff) in current.into_iter().flatten() {
+                if *old_position != position {
+                    updates.push((old_position.clone(), *partition, -old_diff));
+                    moved += old_diff;
+                }
+            }
+            updates.push((position, *partition, diff + moved));
+        }
+        updates
+    }
+}
+
+/// Adds `diff` to the accumulated count of `position` for `partition`, forgetting positions whose
+/// count drops to zero.
+fn update_position(
+    positions: &mut HashMap<PartitionId, HashMap<String, Diff>>,
+    partition: &PartitionId,
+    position: String,
+    diff: Diff,
+) {
+    let partition_positions = positions.entry(partition.clone()).or_default();
+    let count = partition_positions.entry(position.clone()).or_default();
+    *count += diff;
+    if *count == 0 {
+        partition_positions.remove(&position);
+        if partition_positions.is_empty() {
+            positions.remove(partition);
+        }
+    }
 }
@@ -30,18 +30,19 @@
 use std::collections::{HashMap, HashSet};
 use std::convert::{From, TryInto};
 use std::default::Default;
+use std::io::{self, Cursor, ErrorKind};
 use std::ops::AddAssign;
 
-use async_compression::tokio::bufread::GzipDecoder;
+use async_compression::tokio::bufread::{GzipDecoder, ZstdDecoder};
 use aws_sdk_s3::error::{GetObjectError, ListObjectsV2Error};
 use aws_sdk_s3::types::SdkError;
 use aws_sdk_s3::Client as S3Client;
 use aws_sdk_sqs::model::{ChangeMessageVisibilityBatchRequestEntry, Message as SqsMessage};
 use aws_sdk_sqs::Client as SqsClient;
-use futures::{FutureExt, StreamExt, TryStreamExt};
+use futures::{FutureExt, Stream, StreamExt, TryStreamExt};
 use globset::GlobMatcher;
 use timely::scheduling::SyncActivator;
-use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::time::{self, Duration};
 use tokio_util::io::{ReaderStream, StreamReader};
@@ -69,6 +70,11 @@ mod metrics;
 mod notifications;
 
 struct InternalMessage {
+    /// `PartitionId::None` for the chunks of an object, and for the end of an object that does
+    /// not advance its bucket's partition
+    partition: PartitionId,
+    /// The key of the object, for the end of an object that advances its bucket's partition
+    resume_position: Option<String>,
     record: Option<Vec<u8>>,
 }
 /// Size of data chunks we send to dataflow
@@ -87,6 +93,8 @@ pub struct S3SourceReader {
     dataflow_status: tokio::sync::watch::Sender<DataflowStatus>,
     /// Total number of records that this source has read
     offset: S3Offset,
+    /// Number of objects of each scanned bucket that have been read to completion in key order
+    bucket_offsets: HashMap<String, MzOffset>,
 }
 
 /// Current dataflow status
@@ -98,10 +106,13 @@ enum DataflowStatus {
     Stopped,
 }
 
-/// Number of records This source has downloaded
+/// Number of chunks this source has downloaded
 ///
-/// Possibly this should be per-bucket or per-object, depending on the needs
-/// for deterministic timestamping on restarts: issue #5715
+/// Chunks are not aligned with records, so the chunks of all objects share a single partition.
+/// The objects of a scanned bucket are additionally counted in a partition of the bucket, which
+/// only advances past an object once it and every object listed before it have been read to
+/// completion. The key of the latest such object is persisted with the partition's offset, and a
+/// restarted source resumes scanning the bucket after it.
 #[derive(Clone, Copy, Debug)]
 struct S3Offset(i64);
 
@@ -120,6 +131,8 @@ impl From<S3Offset> for MzOffset {
 struct KeyInfo {
     bucket: String,
     key: String,
+    /// Whether the key was listed by a bucket scan, which lists keys in lexicographic order
+    scanned: bool,
 }
 
 async fn download_objects_task(
@@ -142,6 +155,10 @@ async fn download_objects_task(
 
     struct BucketInfo {
         keys: HashSet<String>,
+        /// Whether every scanned object of the bucket has been read to completion. Once an
+        /// object fails, the bucket's partition stops advancing so that a restarted source
+        /// reads the object again.
+        contiguous: bool,
         metrics: BucketMetrics,
     }
     let mut seen_buckets: HashMap<String, BucketInfo> = HashMap::new();
@@ -180,11 +197,27 @@ async fn download_objects_task(
                 } else {
                     let bi = BucketInfo {
                         keys: HashSet::new(),
+                        contiguous: true,
                         metrics: BucketMetrics::new(&metrics, &source_id, &msg.bucket),
                     };
                     seen_buckets.insert(msg.bucket.clone(), bi);
                 };
 
+                let advances_bucket = msg.scanned && seen_buckets[&msg.bucket].contiguous;
+                let end_of_object = if advances_bucket {
+                    InternalMessage {
+                        partition: PartitionId::S3(msg.bucket.clone()),
+                        resume_position: Some(msg.key.clone()),
+                        record: None,
+                    }
+                } else {
+                    InternalMessage {
+                        partition: PartitionId::None,
+                        resume_position: None,
+                        record: None,
+                    }
+                };
+
                 let (tx, activator, client, msg_ref, sid) =
                     (&tx, &activator, &client, &msg, &source_id);
 
@@ -195,6 +228,7 @@ async fn download_objects_task(
                     &msg_ref.bucket,
                     &msg_ref.key,
                     compression,
+                    end_of_object,
                     sid,
                 )
                 .await;
@@ -211,6 +245,11 @@ async fn download_objects_task(
                         bucket_info.keys.insert(msg.key);
                     }
                     Err(DownloadError::Failed { err }) => {
+                        if msg.scanned {
+                            let bucket_info =
+                                seen_buckets.get_mut(&msg.bucket).expect("just inserted");
+                            bucket_info.contiguous = false;
+                        }
                         if tx
                             .send(Err(S3Error::IoError {
                                 bucket: msg_ref.bucket.clone(),
@@ -240,13 +279,26 @@ async fn download_objects_task(
     debug!("source_id={} exiting download objects task", source_id);
 }
 
+/// Lists the keys in `bucket` that match `glob` and sends them to the downloader.
+///
+/// If `poll_interval` is set, the bucket is listed again every `poll_interval` until the source
+/// is shut down. S3 lists keys in lexicographic order, so each listing starts after the greatest
+/// key of the previous one, and only objects whose keys sort after every key that has already
+/// been listed are discovered. If listing the bucket fails, the failure is reported and the
+/// bucket is listed again after the next interval.
+///
+/// The first listing starts after `start_after`, the key up to which the source had read the
+/// bucket's objects to completion before it was restarted.
 async fn scan_bucket_task(
     bucket: String,
     source_id: GlobalId,
     glob: Option<GlobMatcher>,
+    poll_interval: Option<Duration>,
+    start_after: Option<String>,
     aws_config: AwsConfig,
     aws_external_id_prefix: Option<AwsExternalIdPrefix>,
     tx: Sender<S3Result<KeyInfo>>,
+    mut shutdown_rx: tokio::sync::watch::Receiver<DataflowStatus>,
     base_metrics: SourceBaseMetrics,
 ) {
     let config = aws_config
@@ -265,8 +317,15 @@ async fn scan_bucket_task(
     // This isn't a meaningful performance optimization, it just makes it easy for folks to import a
     // single object without granting materialized the ListObjects IAM permission
     let is_literal_object = glob.is_some() && prefix.as_deref() == glob.map(|g| g.glob().glob());
-    if is_literal_object {
+    if is_literal_object && poll_interval.is_none() {
         let key = glob.unwrap().glob().glob();
+        if start_after.as_deref() >= Some(key) {
+            debug!(
+                "source_id={} single object was already read from s3 bucket={} key={}",
+                source_id, bucket, key
+            );
+            return;
+        }
         debug!(
             "source_id={} downloading single object from s3 bucket={} key={}",
             source_id, bucket, key
@@ -275,6 +334,7 @@ async fn scan_bucket_task(
             .send(Ok(KeyInfo {
                 bucket,
                 key: key.to_string(),
+                scanned: true,
             }))
             .await
         {
@@ -294,60 +354,103 @@ async fn scan_bucket_task(
 
     let scan_metrics = ScanBucketMetrics::new(&base_metrics, &source_id, &bucket);
 
-    let mut continuation_token = None;
-    loop {
-        let response = Retry::default()
-            .max_duration(Duration::from_secs(30))
-            .retry_async(|_| {
-                client
-                    .list_objects_v2()
-                    .bucket(&bucket)
-                    .set_prefix(prefix.clone())
-                    .set_continuation_token(continuation_token.clone())
-                    .send()
-            })
-            .await;
+    // The greatest key listed so far
+    let mut high_water_mark = start_after;
+    'poll: loop {
+        let mut continuation_token = None;
+        loop {
+            let response = Retry::default()
+                .max_duration(Duration::from_secs(30))
+                .retry_async(|_| {
+                    client
+                        .list_objects_v2()
+                        .bucket(&bucket)
+                        .set_prefix(prefix.clone())
+                        .set_start_after(high_water_mark.clone())
+                        .set_continuation_token(continuation_token.clone())
+                        .send()
+                })
+                .await;
 
-        match response {
-            Ok(response) => {
-                if let Some(c) = response.contents {
-                    let keys = c
-                        .into_iter()
-                        .filter_map(|obj| obj.key)
-                        .filter(|k| glob.map(|g| g.is_match(k)).unwrap_or(true));
-
-                    for key in keys {
-                        let res = tx
-                            .send(Ok(KeyInfo {
-                                bucket: bucket.clone(),
-                                key,
-                            }))
-                            .await;
+            match response {
+                Ok(response) => {
+                    if let Some(c) = response.contents {
+                        for key in c.into_iter().filter_map(|obj| obj.key) {
+                            high_water_mark = Some(key.clone());
+                            if !glob.map(|g| g.is_match(&key)).unwrap_or(true) {
+                                continue;
+                            }
 
-                        match res {
-                            Ok(_) => scan_metrics.objects_discovered.inc(),
-                            Err(e) => {
-                                debug!("unable to send keys to downloader: {}", e);
-                                break;
+                            let res = tx
+                                .send(Ok(KeyInfo {
+                                    bucket: bucket.clone(),
+                                    key,
+                                    scanned: true,
+                                }))
+                                .await;
+
+                            match res {
+                                Ok(_) => scan_metrics.objects_discovered.inc(),
+                                Err(e) => {
+                                    debug!("unable to send keys to downloader: {}", e);
+                                    break 'poll;
+                                }
                             }
                         }
                     }
+
+                    if response.next_continuation_token.is_none() {
+                        break;
+                    }
+                    continuation_token = response.next_continuation_token;
                 }
+                Err(err) if poll_interval.is_some() => {
+                    let res = tx
+                        .send(Err(S3Error::PollFailed {
+                            bucket: bucket.clone(),
+                            err,
+                        }))
+                        .await;
+                    if let Err(e) = res {
+                        debug!("Source queue has been shut down: {}", e);
+                        break 'poll;
+                    }
 
-                if response.next_continuation_token.is_none() {
+                    // Keys that were listed before the failure have already been sent, so the
+                    // next poll resumes after them
                     break;
                 }
-                continuation_token = response.next_continuation_token;
+                Err(err) => {
+                    tx.send(Err(S3Error::ListObjectsFailed {
+                        bucket: bucket.clone(),
+                        err,
+                    }))
+                    .await
+                    .unwrap_or_else(|e| debug!("Source queue has been shut down: {}", e));
+
+                    break 'poll;
+                }
             }
-            Err(err) => {
-                tx.send(Err(S3Error::ListObjectsFailed {
-                    bucket: bucket.clone(),
-                    err,
-                }))
-                .await
-                .unwrap_or_else(|e| debug!("Source queue has been shut down: {}", e));
+        }
 
-                break;
+        let poll_interval = match poll_interval {
+            Some(poll_interval) => poll_interval,
+            None => break,
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(poll_interval) => {
+                debug!(
+                    "source_id={} polling bucket for new objects bucket={} after={}",
+                    source_id,
+                    bucket,
+                    high_water_mark.as_deref().unwrap_or("<none>")
+                );
+            }
+            status = shutdown_rx.changed() => {
+                if status.is_err() || *shutdown_rx.borrow() == DataflowStatus::Stopped {
+                    debug!("source_id={} bucket scan received dataflow shutdown message", source_id);
+                    break;
+                }
             }
         }
     }
@@ -551,6 +654,7 @@ async fn process_message(
                             let ki = Ok(KeyInfo {
                                 bucket: record.s3.bucket.name,
                                 key: key.clone(),
+                                scanned: false,
                             });
                             if tx.send(ki).await.is_err() {
                                 debug!(
@@ -630,6 +734,12 @@ enum S3Error {
         #[source]
         err: SdkError<ListObjectsV2Error>,
     },
+    #[error("Unable to poll S3 bucket {}: {}", bucket, err)]
+    PollFailed {
+        bucket: String,
+        #[source]
+        err: SdkError<ListObjectsV2Error>,
+    },
     #[error("IO Error for S3 bucket {}: {}", bucket, err)]
     IoError {
         bucket: String,
@@ -653,6 +763,7 @@ async fn download_object(
     bucket: &str,
     key: &str,
     compression: Compression,
+    end_of_object: InternalMessage,
     source_id: &str,
 ) -> Result<DownloadMetricUpdate, DownloadError> {
     let retry_reader: RetryReader<_, _, _> = RetryReader::new(|state, offset| async move {
@@ -686,8 +797,9 @@ async fn download_object(
         if let Some(s) = obj.content_encoding.as_deref() {
             match (s, compression) {
                 ("gzip", Compression::Gzip) => (),
+                ("zstd", Compression::Zstd) => (),
                 ("identity", Compression::None) => (),
-                ("identity" | "gzip", _) => {
+                ("identity" | "gzip" | "zstd", _) => {
                     debug!("object {} has mismatched Content-Encoding: {}", key, s)
                 }
                 _ => debug!("object {} has unrecognized Content-Encoding: {}", key, s),
@@ -718,6 +830,14 @@ async fn download_object(
             let decoder = GzipDecoder::new(reader);
             read_object_chunked(source_id, decoder, tx).await
         }
+        Compression::Zstd => {
+            let decoder = ZstdDecoder::new(reader);
+            read_object_chunked(source_id, decoder, tx).await
+        }
+        Compression::Snappy => {
+            let decoder = StreamReader::new(Box::pin(snappy_frames(reader)));
+            read_object_chunked(source_id, decoder, tx).await
+        }
     };
 
     debug!(
@@ -726,8 +846,7 @@ async fn download_object(
     );
 
     if download_result.is_ok() {
-        let sent = tx.send(Ok(InternalMessage { record: None }));
-        if sent.await.is_err() {
+        if tx.send(Ok(end_of_object)).await.is_err() {
             download_result = Err(DownloadError::SendFailed);
         }
     };
@@ -735,6 +854,51 @@ async fn download_object(
     download_result
 }
 
+/// Decompresses data in the Snappy framing format, yielding the contents of
+/// each chunk.
+///
+/// The checksums of the chunks are not verified, as S3 already verifies the
+/// integrity of downloaded objects.
+fn snappy_frames<R>(mut reader: R) -> impl Stream<Item = Result<Cursor<Vec<u8>>, io::Error>>
+where
+    R: Unpin + AsyncRead,
+{
+    async_stream::try_stream! {
+        let mut decoder = snap::raw::Decoder::new();
+        // The type of a chunk, followed by the 24-bit length of its data
+        let mut header = [0; 4];
+        loop {
+            match reader.read_exact(&mut header).await {
+                Ok(_) => (),
+                Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+                Err(err) => Err(err)?,
+            }
+            let len = u32::from_le_bytes([header[1], header[2], header[3], 0]);
+            let mut data = vec![0; usize::try_from(len).expect("u32 fits in usize")];
+            reader.read_exact(&mut data).await?;
+            match header[0] {
+                // Compressed and uncompressed data, which start with a checksum
+                0x00 if data.len() >= 4 => {
+                    let data = decoder
+                        .decompress_vec(&data[4..])
+                        .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+                    yield Cursor::new(data);
+                }
+                0x01 if data.len() >= 4 => {
+                    data.drain(..4);
+                    yield Cursor::new(data);
+                }
+                // The stream identifier, padding, and other skippable chunks
+                0x80..=0xff => (),
+                chunk_type => Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid Snappy chunk of type {:#04x}", chunk_type),
+                ))?,
+            }
+        }
+    }
+}
+
 async fn read_object_chunked<R>(
     source_id: &str,
     reader: R,
@@ -754,6 +918,8 @@ where
                 chunks += 1;
                 if tx
                     .send(Ok(InternalMessage {
+                        partition: PartitionId::None,
+                        resume_position: None,
                         // ReaderStream return's `None` if the underlying `AsyncRead`
                         // gives out 0 bytes, so the chunk is always !empty.
                         // See https://github.com/tokio-rs/tokio/blob/e8f19e771f501408427f7f9ee6ba4f54b2d4094c/tokio-util/src/io/reader_stream.rs#L102-L108
@@ -793,7 +959,8 @@ impl SourceReader for S3SourceReader {
         _worker_count: usize,
         consumer_activator: SyncActivator,
         connector: ExternalSourceConnector,
-        _restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        restored_positions: HashMap<PartitionId, String>,
         _encoding: SourceDataEncoding,
         metrics: crate::source::metrics::SourceBaseMetrics,
         connector_context: ConnectorContext,
@@ -805,6 +972,27 @@ impl SourceReader for S3SourceReader {
             }
         };
 
+        let mut offset = S3Offset(0);
+        let mut bucket_offsets = HashMap::new();
+        for (partition, restored_offset) in restored_offsets {
+            match (partition, restored_offset) {
+                (PartitionId::None, Some(restored_offset)) => {
+                    offset = S3Offset(restored_offset.offset)
+                }
+                (PartitionId::S3(bucket), Some(restored_offset)) => {
+                    bucket_offsets.insert(bucket, restored_offset);
+                }
+                _ => (),
+            }
+        }
+        let start_after: HashMap<_, _> = restored_positions
+            .into_iter()
+            .filter_map(|(partition, position)| match partition {
+                PartitionId::S3(bucket) => Some((bucket, position)),
+                _ => None,
+            })
+            .collect();
+
         // a single arbitrary worker is responsible for scanning the bucket
         let (receiver, shutdowner) = {
             let (dataflow_tx, dataflow_rx) = tokio::sync::mpsc::channel(10_000);
@@ -841,9 +1029,34 @@ impl SourceReader for S3SourceReader {
                                 bucket,
                                 source_id,
                                 glob.clone(),
+                                None,
+                                start_after.get(&bucket).cloned(),
                                 s3_conn.aws.clone(),
                                 connector_context.aws_external_id_prefix.clone(),
                                 keys_tx.clone(),
+                                shutdown_rx.clone(),
+                                metrics.clone(),
+                            ),
+                        );
+                    }
+                    S3KeySource::Poll { bucket, interval } => {
+                        debug!(
+                            "source_id={} polling s3 bucket={} interval={:?} worker={}",
+                            source_id, bucket, interval, worker_id
+                        );
+                        let task_name = format!("s3_poll:{}:{}", source_id, bucket);
+                        task::spawn(
+                            || task_name,
+                            scan_bucket_task(
+                                bucket,
+                                source_id,
+                                glob.clone(),
+                                Some(interval),
+                                start_after.get(&bucket).cloned(),
+                                s3_conn.aws.clone(),
+                                connector_context.aws_external_id_prefix.clone(),
+                                keys_tx.clone(),
+                                shutdown_rx.clone(),
                                 metrics.clone(),
                             ),
                         );
@@ -877,7 +1090,8 @@ impl SourceReader for S3SourceReader {
             id: source_id,
             receiver_stream: receiver,
             dataflow_status: shutdowner,
-            offset: S3Offset(0),
+            offset,
+            bucket_offsets,
         })
     }
 
@@ -885,12 +1099,27 @@ impl SourceReader for S3SourceReader {
         &mut self,
     ) -> Result<NextMessage<Self::Key, Self::Value, Self::Diff>, SourceReaderError> {
         match self.receiver_stream.recv().now_or_never() {
-            Some(Some(Ok(InternalMessage { record }))) => {
-                self.offset += 1;
+            Some(Some(Ok(InternalMessage {
+                partition,
+                resume_position,
+                record,
+            }))) => {
+                let offset = match &partition {
+                    PartitionId::S3(bucket) => {
+                        let offset = self.bucket_offsets.entry(bucket.clone()).or_default();
+                        *offset += 1;
+                        *offset
+                    }
+                    _ => {
+                        self.offset += 1;
+                        self.offset.into()
+                    }
+                };
                 Ok(NextMessage::Ready(SourceMessageType::Finalized(
                     SourceMessage {
-                        partition: PartitionId::None,
-                        offset: self.offset.into(),
+                        partition,
+                        offset,
+                        resume_position,
                         upstream_time_millis: None,
                         key: (),
                         value: record,
@@ -900,7 +1129,7 @@ impl SourceReader for S3SourceReader {
                 )))
             }
             Some(Some(Err(e))) => match e {
-                S3Error::GetObjectError { .. } => {
+                S3Error::GetObjectError { .. } | S3Error::PollFailed { .. } => {
                     warn!(
                         "when reading source '{}' ({}): {}",
                         self.source_name, self.id, e
@@ -0,0 +1,602 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use futures::{FutureExt, TryStreamExt};
+use timely::scheduling::SyncActivator;
+use tokio::sync::mpsc::{Receiver, Sender};
+use tracing::{info, warn};
+
+use mz_dataflow_types::postgres_source::PostgresTable;
+use mz_dataflow_types::sources::{
+    encoding::SourceDataEncoding, ExternalSourceConnector, MzOffset, SqlServerSourceConnector,
+};
+use mz_dataflow_types::ConnectorContext;
+use mz_dataflow_types::SourceErrorDetails;
+use mz_expr::PartitionId;
+use mz_ore::task;
+use mz_repr::{Datum, Diff, GlobalId, Row};
+use mz_sql_server_util::{quote_identifier, Lsn, SqlServerClient, SqlServerTableInfo};
+
+use super::metrics::SourceBaseMetrics;
+use crate::source::{
+    NextMessage, SourceMessage, SourceMessageType, SourceReader, SourceReaderError,
+};
+
+/// How often the change tables are checked for new changes
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The number of the error that SQL Server reports to the victim of a deadlock
+const DEADLOCK_VICTIM_ERROR: u32 = 1205;
+
+enum ReplicationError {
+    Recoverable(anyhow::Error),
+    Fatal(anyhow::Error),
+}
+
+impl From<tiberius::error::Error> for ReplicationError {
+    fn from(err: tiberius::error::Error) -> Self {
+        // Errors reported by the server, like missing privileges or objects,
+        // will not go away by retrying, unless the server picked us to resolve
+        // a deadlock. Connection and I/O errors might.
+        match &err {
+            tiberius::error::Error::Server(e) if e.code() != DEADLOCK_VICTIM_ERROR => {
+                Self::Fatal(err.into())
+            }
+            _ => Self::Recoverable(err.into()),
+        }
+    }
+}
+
+macro_rules! try_fatal {
+    ($expr:expr $(,)?) => {
+        match $expr {
+            Ok(val) => val,
+            Err(err) => return Err(ReplicationError::Fatal(err.into())),
+        }
+    };
+}
+macro_rules! try_recoverable {
+    ($expr:expr $(,)?) => {
+        match $expr {
+            Ok(val) => val,
+            Err(err) => return Err(ReplicationError::Recoverable(err.into())),
+        }
+    };
+}
+
+// Message used to communicate between `get_next_message` and the tokio task
+enum InternalMessage {
+    Err(SourceReaderError),
+    Value {
+        value: Row,
+        offset: MzOffset,
+        diff: Diff,
+        end: bool,
+    },
+}
+
+/// Information required to sync data from SQL Server
+pub struct SqlServerSourceReader {
+    receiver_stream: Receiver<InternalMessage>,
+}
+
+/// An internal struct held by the spawned tokio task
+struct SqlServerTaskInfo {
+    source_id: GlobalId,
+    connector: SqlServerSourceConnector,
+    /// The LSN up to which the source has produced the changes of the source
+    /// tables
+    lsn: Lsn,
+    /// The number of transactions that the source has produced, which is the
+    /// offset of their updates
+    offset: i64,
+    /// The capture instance whose change table records the changes of each
+    /// source table, by the table's object ID
+    capture_instances: HashMap<u32, String>,
+    /// The T-SQL expressions that select the text of each column of each source
+    /// table, by the table's object ID
+    text_exprs: HashMap<u32, String>,
+    sender: Sender<InternalMessage>,
+    activator: SyncActivator,
+}
+
+impl SourceReader for SqlServerSourceReader {
+    type Key = ();
+    type Value = Row;
+    // SQL Server can produce deletes that cause retractions
+    type Diff = Diff;
+
+    fn new(
+        _source_name: String,
+        source_id: GlobalId,
+        _worker_id: usize,
+        _worker_count: usize,
+        consumer_activator: SyncActivator,
+        connector: ExternalSourceConnector,
+        _restored_offsets: Vec<(PartitionId, Option<MzOffset>)>,
+        _restored_positions: HashMap<PartitionId, String>,
+        _encoding: SourceDataEncoding,
+        _metrics: SourceBaseMetrics,
+        _connector_context: ConnectorContext,
+    ) -> Result<Self, anyhow::Error> {
+        let connector = match connector {
+            ExternalSourceConnector::SqlServer(sql_server) => sql_server,
+            _ => {
+                panic!(
+                    "SQL Server is the only legitimate ExternalSourceConnector for \
+                     SqlServerSourceReader"
+                )
+            }
+        };
+
+        let (dataflow_tx, dataflow_rx) = tokio::sync::mpsc::channel(10_000);
+
+        let task_info = SqlServerTaskInfo {
+            source_id,
+            connector,
+            lsn: Lsn::default(),
+            offset: 0,
+            capture_instances: HashMap::new(),
+            text_exprs: HashMap::new(),
+            sender: dataflow_tx,
+            activator: consumer_activator,
+        };
+
+        task::spawn(
+            || format!("sql_server_source:{}", source_id),
+            sql_server_replication_loop(task_info),
+        );
+        Ok(Self {
+            receiver_stream: dataflow_rx,
+        })
+    }
+
+    fn get_next_message(
+        &mut self,
+    ) -> Result<NextMessage<Self::Key, Self::Value, Self::Diff>, SourceReaderError> {
+        match self.receiver_stream.recv().now_or_never() {
+            Some(Some(InternalMessage::Value {
+                value,
+                diff,
+                offset,
+                end,
+            })) => {
+                let message = SourceMessage {
+                    partition: PartitionId::None,
+                    offset,
+                    resume_position: None,
+                    upstream_time_millis: None,
+                    key: (),
+                    value,
+                    headers: None,
+                    specific_diff: diff,
+                };
+                if end {
+                    Ok(NextMessage::Ready(SourceMessageType::Finalized(message)))
+                } else {
+                    Ok(NextMessage::Ready(SourceMessageType::InProgress(message)))
+                }
+            }
+            Some(Some(InternalMessage::Err(e))) => Err(e),
+            None => Ok(NextMessage::Pending),
+            Some(None) => Ok(NextMessage::Finished),
+        }
+    }
+}
+
+/// Defers to `sql_server_replication_loop_inner` and sends errors through the
+/// channel if they occur
+async fn sql_server_replication_loop(mut task_info: SqlServerTaskInfo) {
+    if let Err(e) = sql_server_replication_loop_inner(&mut task_info).await {
+        // Drop the send error, as we have no way of communicating back to the
+        // source operator if the channel is gone.
+        let _ = task_info.sender.send(InternalMessage::Err(e)).await;
+        task_info
+            .activator
+            .activate()
+            .expect("sql server reader activation failed");
+    }
+}
+
+/// Core logic
+async fn sql_server_replication_loop_inner(
+    task_info: &mut SqlServerTaskInfo,
+) -> Result<(), SourceReaderError> {
+    let source_id = task_info.source_id;
+    // Rows of the snapshot are only sent once the whole snapshot has been
+    // read, so a failed attempt at it need not be retracted.
+    let mut snapshot_tx = Transaction::new();
+    loop {
+        match task_info.produce_snapshot(&mut snapshot_tx).await {
+            Ok(()) => {
+                info!("replication snapshot for source {} succeeded", source_id);
+                task_info.offset += 1;
+                snapshot_tx
+                    .close(task_info.offset, &task_info.sender, &task_info.activator)
+                    .await;
+                break;
+            }
+            Err(ReplicationError::Recoverable(e)) => {
+                snapshot_tx.clear();
+                warn!(
+                    "replication snapshot for source {} failed, retrying: {}",
+                    source_id, e
+                );
+            }
+            Err(ReplicationError::Fatal(e)) => {
+                return Err(SourceReaderError {
+                    inner: SourceErrorDetails::Initialization(e.to_string()),
+                })
+            }
+        }
+
+        // TODO(petrosagg): implement exponential back-off
+        tokio::time::sleep(Duration::from_secs(3)).await;
+    }
+
+    loop {
+        match task_info.produce_replication().await {
+            Err(ReplicationError::Recoverable(e)) => {
+                warn!(
+                    "replication for source {} interrupted, retrying: {}",
+                    source_id, e
+                )
+            }
+            Err(ReplicationError::Fatal(e)) => {
+                return Err(SourceReaderError {
+                    inner: SourceErrorDetails::Other(e.to_string()),
+                })
+            }
+            Ok(()) => {
+                // shutdown iniated elsewhere
+                return Ok(());
+            }
+        }
+
+        // TODO(petrosagg): implement exponential back-off
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        info!("resuming replication for source {}", source_id);
+    }
+}
+
+/// A helper struct to build and produce transactions
+#[derive(Default)]
+struct Transaction {
+    rows: Vec<(Row, Diff)>,
+}
+
+impl Transaction {
+    fn new() -> Self {
+        Transaction { rows: vec![] }
+    }
+
+    /// Record an insertion of a row in the current transaction
+    fn insert(&mut self, row: Row) {
+        self.rows.push((row, 1));
+    }
+
+    /// Record a deletion of a row in the current transaction
+    fn delete(&mut self, row: Row) {
+        self.rows.push((row, -1));
+    }
+
+    /// Discard the rows of the current transaction
+    fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    /// Finalize a transaction and send it off through the channel
+    async fn close(
+        &mut self,
+        offset: i64,
+        sender: &Sender<InternalMessage>,
+        activator: &SyncActivator,
+    ) {
+        let num = self.rows.len();
+        for (i, (row, diff)) in self.rows.drain(..).enumerate() {
+            // a closed receiver means the source has been shutdown
+            // (dropped or the process is dying), so just continue on
+            // without activation
+            if let Ok(_) = sender
+                .send(InternalMessage::Value {
+                    value: row,
+                    offset: MzOffset { offset },
+                    diff,
+                    end: i == (num - 1),
+                })
+                .await
+            {
+                activator
+                    .activate()
+                    .expect("sql server reader activation failed");
+            }
+        }
+    }
+}
+
+impl SqlServerTaskInfo {
+    /// Validates that all source tables still have change data capture
+    /// enabled upstream, with a schema that extends the one that the source
+    /// was created with, and records how to select their columns.
+    fn validate_tables(&mut self, tables: Vec<SqlServerTableInfo>) -> Result<(), anyhow::Error> {
+        let mut upstream: HashMap<u32, SqlServerTableInfo> =
+            tables.into_iter().map(|t| (t.object_id, t)).collect();
+        for table in &self.connector.details.tables {
+            let info = match upstream.remove(&table.relation_id) {
+                Some(info) => info,
+                None => bail!(
+                    "table {}.{} has been dropped or no longer has change data capture enabled",
+                    table.namespace,
+                    table.name
+                ),
+            };
+            // Columns that were added after the source was created are not
+            // replicated, and capture instances do not record them anyway.
+            let text_exprs = info
+                .columns
+                .iter()
+                .take(table.columns.len())
+                .map(|c| c.text_expr())
+                .collect::<Vec<_>>()
+                .join(", ");
+            if !table.is_extended_by(&PostgresTable::from(info)) {
+                bail!(
+                    "table {}.{} has been altered in ways other than appending columns; \
+                     recreate the source to use its new schema",
+                    table.namespace,
+                    table.name
+                );
+            }
+            self.text_exprs.insert(table.relation_id, text_exprs);
+        }
+        Ok(())
+    }
+
+    /// Produces the initial snapshot of the source tables and records the LSN
+    /// that it reflects, from which changes are replicated.
+    async fn produce_snapshot(
+        &mut self,
+        snapshot_tx: &mut Transaction,
+    ) -> Result<(), ReplicationError> {
+        let mut client = try_recoverable!(mz_sql_server_util::connect(&self.connector.conn).await);
+        let tables = try_recoverable!(mz_sql_server_util::table_info(&mut client).await);
+        try_fatal!(self.validate_tables(tables));
+
+        // A table may have two capture instances while its schema changes, of
+        // which the older one has recorded its changes for longer.
+        let rows = client
+            .simple_query(
+                "SELECT source_object_id, capture_instance
+                FROM cdc.change_tables
+                ORDER BY create_date DESC",
+            )
+            .await?
+            .into_first_result()
+            .await?;
+        for row in rows {
+            let object_id = try_fatal!(get::<i32>(&row, 0));
+            let capture_instance = try_fatal!(get::<&str>(&row, 1));
+            self.capture_instances
+                .insert(object_id as u32, capture_instance.into());
+        }
+
+        // Locking the source tables waits for all transactions that write to
+        // them to end, so their changes are reflected by both the snapshot and
+        // the end of the transaction log.
+        client
+            .simple_query("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE; BEGIN TRANSACTION")
+            .await?
+            .into_results()
+            .await?;
+        let tables = &self.connector.details.tables;
+        for table in tables {
+            client
+                .simple_query(format!(
+                    "SELECT TOP 0 * FROM {} WITH (TABLOCK, HOLDLOCK)",
+                    qualified_name(table)
+                ))
+                .await?
+                .into_results()
+                .await?;
+        }
+        self.lsn = try_recoverable!(mz_sql_server_util::log_end_lsn(&mut client).await);
+
+        for table in tables {
+            let mut rows = client
+                .simple_query(format!(
+                    "SELECT {} FROM {}",
+                    self.text_exprs[&table.relation_id],
+                    qualified_name(table)
+                ))
+                .await?
+                .into_row_stream();
+            while let Some(row) = rows.try_next().await? {
+                snapshot_tx.insert(try_fatal!(pack_row(table, &row, 0)));
+            }
+        }
+        client.simple_query("COMMIT").await?.into_results().await?;
+        Ok(())
+    }
+
+    /// Polls the change tables of the source tables, and produces the changes
+    /// of each transaction that committed since the last poll.
+    async fn produce_replication(&mut self) -> Result<(), ReplicationError> {
+        let mut client = try_recoverable!(mz_sql_server_util::connect(&self.connector.conn).await);
+        loop {
+            let max_lsn = try_recoverable!(mz_sql_server_util::max_lsn(&mut client).await);
+            if max_lsn > self.lsn {
+                self.validate_ddl(&mut client, max_lsn).await?;
+
+                // Changes are recorded at the LSN of the commit of their
+                // transaction.
+                let mut txs: BTreeMap<Lsn, Transaction> = BTreeMap::new();
+                for table in &self.connector.details.tables {
+                    let capture_instance = try_fatal!(self
+                        .capture_instances
+                        .get(&table.relation_id)
+                        .ok_or_else(|| anyhow!(
+                            "table {}.{} no longer has change data capture enabled",
+                            table.namespace,
+                            table.name
+                        )));
+                    self.validate_capture_instance(&mut client, table, capture_instance)
+                        .await?;
+                    let function = format!("fn_cdc_get_all_changes_{}", capture_instance);
+                    let mut rows = client
+                        .simple_query(format!(
+                            "SELECT __$start_lsn, __$operation, {}
+                            FROM cdc.{}(sys.fn_cdc_increment_lsn({}), {}, N'all update old')",
+                            self.text_exprs[&table.relation_id],
+                            quote_identifier(&function),
+                            self.lsn,
+                            max_lsn
+                        ))
+                        .await?
+                        .into_row_stream();
+                    while let Some(row) = rows.try_next().await? {
+                        let lsn = try_fatal!(Lsn::try_from(try_fatal!(get::<&[u8]>(&row, 0))));
+                        let tx = txs.entry(lsn).or_default();
+                        let values = try_fatal!(pack_row(table, &row, 2));
+                        // Updates are recorded as their old values, with
+                        // operation 3, followed by their new values, with
+                        // operation 4.
+                        match try_fatal!(get::<i32>(&row, 1)) {
+                            1 | 3 => tx.delete(values),
+                            2 | 4 => tx.insert(values),
+                            op => {
+                                return Err(ReplicationError::Fatal(anyhow!(
+                                    "unknown change operation {} for table {}.{}",
+                                    op,
+                                    table.namespace,
+                                    table.name
+                                )))
+                            }
+                        }
+                    }
+                }
+                for (_, mut tx) in txs {
+                    self.offset += 1;
+                    tx.close(self.offset, &self.sender, &self.activator).await;
+                }
+                self.lsn = max_lsn;
+            }
+
+            if self.sender.is_closed() {
+                return Ok(());
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Fails if a statement since the last poll has altered a source table in
+    /// ways other than adding columns, as the source can no longer decode the
+    /// table's changes afterwards.
+    async fn validate_ddl(
+        &mut self,
+        client: &mut SqlServerClient,
+        max_lsn: Lsn,
+    ) -> Result<(), ReplicationError> {
+        let rows = client
+            .simple_query(format!(
+                "SELECT 1 FROM cdc.ddl_history WHERE ddl_lsn > {} AND ddl_lsn <= {}",
+                self.lsn, max_lsn
+            ))
+            .await?
+            .into_first_result()
+            .await?;
+        if !rows.is_empty() {
+            let tables = try_recoverable!(mz_sql_server_util::table_info(client).await);
+            try_fatal!(self.validate_tables(tables));
+        }
+        Ok(())
+    }
+
+    /// Fails if the capture instance of `table` has been dropped, or has
+    /// cleaned up changes that the source has not replicated yet.
+    async fn validate_capture_instance(
+        &self,
+        client: &mut SqlServerClient,
+        table: &PostgresTable,
+        capture_instance: &str,
+    ) -> Result<(), ReplicationError> {
+        let row = client
+            .simple_query(format!(
+                "SELECT sys.fn_cdc_get_min_lsn(N'{}'), sys.fn_cdc_increment_lsn({})",
+                capture_instance.replace('\'', "''"),
+                self.lsn
+            ))
+            .await?
+            .into_row()
+            .await?;
+        let row = try_fatal!(row.ok_or_else(|| anyhow!("query for LSN returned no rows")));
+        let min_lsn = try_fatal!(Lsn::try_from(try_fatal!(get::<&[u8]>(&row, 0))));
+        let from_lsn = try_fatal!(Lsn::try_from(try_fatal!(get::<&[u8]>(&row, 1))));
+        if min_lsn == Lsn::default() {
+            Err(ReplicationError::Fatal(anyhow!(
+                "table {}.{} has been dropped or no longer has change data capture enabled",
+                table.namespace,
+                table.name
+            )))
+        } else if min_lsn > from_lsn {
+            Err(ReplicationError::Fatal(anyhow!(
+                "changes of table {}.{} have been cleaned up before they were replicated; \
+                 recreate the source",
+                table.namespace,
+                table.name
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Returns the quoted, schema-qualified name of a source table
+fn qualified_name(table: &PostgresTable) -> String {
+    format!(
+        "{}.{}",
+        quote_identifier(&table.namespace),
+        quote_identifier(&table.name)
+    )
+}
+
+/// Gets the non-null value of the column at `idx` in `row`
+fn get<'a, T>(row: &'a tiberius::Row, idx: usize) -> Result<T, anyhow::Error>
+where
+    T: tiberius::FromSql<'a>,
+{
+    row.try_get(idx)?
+        .ok_or_else(|| anyhow!("unexpected null in column {}", idx))
+}
+
+/// Packs the text of the columns of `table`, which start at position `start`
+/// in `row`, into a Row of the table's object ID and the text, e.g.
+/// `Row{ 245575913, ["1", "2"] }`.
+fn pack_row(
+    table: &PostgresTable,
+    row: &tiberius::Row,
+    start: usize,
+) -> Result<Row, anyhow::Error> {
+    let mut packed = Row::default();
+    let mut packer = packed.packer();
+    packer.push(Datum::Int32(i32::try_from(table.relation_id)?));
+    packer.push_list_with(|rp| -> Result<(), anyhow::Error> {
+        for i in start..start + table.columns.len() {
+            match row.try_get::<&str, _>(i)? {
+                Some(text) => rp.push(Datum::String(text)),
+                None => rp.push(Datum::Null),
+            }
+        }
+        Ok(())
+    })?;
+    Ok(packed)
+}
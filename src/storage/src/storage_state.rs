@@ -110,7 +110,14 @@ impl<'a, A: Allocate> ActiveStorageState<'a, A> {
             StorageCommand::CreateSources(sources) => {
                 for source in sources {
                     match &source.desc.connector {
-                        SourceConnector::Local { .. } | SourceConnector::Log => {
+                        // The coordinator appends the data of webhook sources
+                        // directly, as it does for tables.
+                        SourceConnector::Local { .. }
+                        | SourceConnector::Log
+                        | SourceConnector::External {
+                            connector: ExternalSourceConnector::Webhook(_),
+                            ..
+                        } => {
                             self.storage_state.table_state.insert(
                                 source.id,
                                 TableState {
@@ -166,6 +173,7 @@ impl<'a, A: Allocate> ActiveStorageState<'a, A> {
                                 self.storage_state,
                                 &source.id.to_string(),
                                 source.clone(),
+                                false,
                             );
                         }
                     }
@@ -185,6 +193,25 @@ impl<'a, A: Allocate> ActiveStorageState<'a, A> {
                         .insert(source.id, source.storage_metadata);
                 }
             }
+            StorageCommand::AlterSources(sources) => {
+                for source in sources {
+                    // Dropping the token shuts down the running ingestion. The restarted one
+                    // continues from the write frontier its predecessor reached.
+                    self.storage_state.source_tokens.remove(&source.id);
+
+                    crate::render::build_storage_dataflow(
+                        self.timely_worker,
+                        self.storage_state,
+                        &source.id.to_string(),
+                        source.clone(),
+                        true,
+                    );
+
+                    self.storage_state
+                        .source_descriptions
+                        .insert(source.id, source.desc);
+                }
+            }
             StorageCommand::AllowCompaction(list) => {
                 for (id, frontier) in list {
                     if frontier.is_empty() {
@@ -593,6 +593,7 @@ pub(crate) async fn build(
                     "s3-add-notifications" => {
                         Box::new(s3::build_add_notifications(builtin).map_err(wrap_err)?)
                     }
+                    "s3-verify-sink" => Box::new(s3::build_verify_sink(builtin).map_err(wrap_err)?),
                     "set-regex" => Box::new(set::build_regex_set(builtin).map_err(wrap_err)?),
                     "unset-regex" => Box::new(set::build_regex_unset(builtin).map_err(wrap_err)?),
                     "set-sql-timeout" => {
@@ -11,7 +11,7 @@ use std::path;
 use std::str::FromStr;
 
 use anyhow::bail;
-use async_compression::tokio::write::GzipEncoder;
+use async_compression::tokio::write::{GzipEncoder, ZstdEncoder};
 use async_trait::async_trait;
 use tokio::fs::{self, OpenOptions};
 use tokio::io::{AsyncWrite, AsyncWriteExt};
@@ -29,6 +29,9 @@ pub struct AppendAction {
 
 pub enum Compression {
     Gzip,
+    Zstd,
+    /// The Snappy framing format
+    Snappy,
     None,
 }
 
@@ -38,6 +41,8 @@ impl FromStr for Compression {
     fn from_str(s: &str) -> Result<Self, anyhow::Error> {
         match s {
             "gzip" => Ok(Compression::Gzip),
+            "zstd" => Ok(Compression::Zstd),
+            "snappy" => Ok(Compression::Snappy),
             "none" => Ok(Compression::None),
             f => bail!("unknown compression format: {}", f),
         }
@@ -64,6 +69,9 @@ fn build_path(cmd: &mut BuiltinCommand) -> Result<String, anyhow::Error> {
 pub fn build_append(mut cmd: BuiltinCommand) -> Result<AppendAction, anyhow::Error> {
     let path = build_path(&mut cmd)?;
     let compression = build_compression(&mut cmd)?;
+    if let Compression::Snappy = compression {
+        bail!("snappy compression is not supported for file appends");
+    }
     let trailing_newline = cmd.args.opt_bool("trailing-newline")?.unwrap_or(true);
     let repeat = cmd.args.opt_parse("repeat")?.unwrap_or(1);
     cmd.args.done()?;
@@ -102,6 +110,8 @@ impl Action for AppendAction {
 
         let mut file: Box<dyn AsyncWrite + Unpin + Send> = match self.compression {
             Compression::Gzip => Box::new(GzipEncoder::new(file)),
+            Compression::Zstd => Box::new(ZstdEncoder::new(file)),
+            Compression::Snappy => unreachable!("rejected when building the action"),
             Compression::None => Box::new(file),
         };
 
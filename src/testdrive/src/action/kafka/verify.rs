@@ -11,11 +11,13 @@ use std::cmp;
 use std::str;
 use std::time::Duration;
 
-use anyhow::{bail, Context};
+use anyhow::{anyhow, bail, Context};
 use async_trait::async_trait;
 use byteorder::{BigEndian, ByteOrder};
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor};
 use rdkafka::consumer::{Consumer, StreamConsumer};
 use rdkafka::message::Message;
+use tokio::fs;
 use tokio::pin;
 use tokio_stream::StreamExt;
 
@@ -25,7 +27,15 @@ use crate::parser::BuiltinCommand;
 
 pub enum SinkFormat {
     Avro,
-    Json { key: bool },
+    Json {
+        key: bool,
+    },
+    Protobuf {
+        descriptor_file: String,
+        key_message: Option<String>,
+        value_message: String,
+        confluent_wire_format: bool,
+    },
 }
 
 pub enum SinkConsistencyFormat {
@@ -47,6 +57,12 @@ pub fn build_verify(mut cmd: BuiltinCommand) -> Result<VerifyAction, anyhow::Err
         "json" => SinkFormat::Json {
             key: cmd.args.parse("key")?,
         },
+        "protobuf" => SinkFormat::Protobuf {
+            descriptor_file: cmd.args.string("descriptor-file")?,
+            key_message: cmd.args.opt_string("key-message"),
+            value_message: cmd.args.string("message")?,
+            confluent_wire_format: cmd.args.opt_bool("confluent-wire-format")?.unwrap_or(false),
+        },
         f => bail!("unknown format: {}", f),
     };
     let sink = cmd.args.string("sink")?;
@@ -100,6 +116,27 @@ fn avro_from_bytes(
     Ok(datum)
 }
 
+fn protobuf_to_json(
+    message: &MessageDescriptor,
+    mut bytes: &[u8],
+    confluent_wire_format: bool,
+) -> Result<serde_json::Value, anyhow::Error> {
+    if confluent_wire_format {
+        // Skip the magic byte, the schema ID, and the message indexes, which
+        // sinks always write as a single zero.
+        if bytes.len() < 6 {
+            bail!(
+                "protobuf datum is too few bytes: expected at least 6 bytes, got {}",
+                bytes.len()
+            );
+        }
+        bytes = &bytes[6..];
+    }
+    let message =
+        DynamicMessage::decode(message.clone(), bytes).context("decoding protobuf message")?;
+    serde_json::to_value(&message).context("converting protobuf message to json")
+}
+
 async fn get_topic(
     sink: &str,
     topic_field: &str,
@@ -241,11 +278,13 @@ impl Action for VerifyAction {
                 );
                 let mut actual_messages = vec![];
                 for (key, value) in actual_bytes {
+                    // Consistency records are keyed by the sink's topic name,
+                    // which is not JSON-encoded.
                     let key_datum = match key {
-                        None => None,
-                        Some(bytes) => {
+                        Some(bytes) if self.consistency.is_none() => {
                             Some(serde_json::from_slice(&bytes).context("decoding json")?)
                         }
+                        _ => None,
                     };
                     let value_datum = match value {
                         None => None,
@@ -269,6 +308,59 @@ impl Action for VerifyAction {
                     &state.regex_replacement,
                 )?
             }
+            SinkFormat::Protobuf {
+                descriptor_file,
+                key_message,
+                value_message,
+                confluent_wire_format,
+            } => {
+                assert!(
+                    self.partial_search.is_none(),
+                    "partial search not yet implemented for protobuf formatted sinks"
+                );
+                let bytes = fs::read(state.temp_path.join(descriptor_file))
+                    .await
+                    .context("reading protobuf descriptor file")?;
+                let pool =
+                    DescriptorPool::decode(&*bytes).context("parsing protobuf descriptor file")?;
+                let get_message = |name: &str| {
+                    pool.get_message_by_name(name)
+                        .ok_or_else(|| anyhow!("unknown message name {}", name))
+                };
+                let key_message = key_message.as_deref().map(get_message).transpose()?;
+                let value_message = get_message(value_message)?;
+
+                let mut actual_messages = vec![];
+                for (key, value) in actual_bytes {
+                    let key_datum = match (&key_message, key) {
+                        (Some(message), Some(bytes)) => {
+                            Some(protobuf_to_json(message, &bytes, *confluent_wire_format)?)
+                        }
+                        _ => None,
+                    };
+                    let value_datum = match value {
+                        None => None,
+                        Some(bytes) => Some(protobuf_to_json(
+                            &value_message,
+                            &bytes,
+                            *confluent_wire_format,
+                        )?),
+                    };
+                    actual_messages.push((key_datum, value_datum));
+                }
+
+                if self.sort_messages {
+                    actual_messages.sort_by_key(|k| format!("{:?}", k.1));
+                }
+
+                json::validate_sink(
+                    key_message.is_some(),
+                    &self.expected_messages,
+                    &actual_messages,
+                    &state.regex,
+                    &state.regex_replacement,
+                )?
+            }
         }
         Ok(ControlFlow::Continue)
     }
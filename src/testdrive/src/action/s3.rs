@@ -12,6 +12,7 @@ use std::io::Write;
 use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, bail, Context};
+use async_compression::tokio::write::ZstdEncoder;
 use async_trait::async_trait;
 use aws_sdk_s3::error::{CreateBucketError, CreateBucketErrorKind};
 use aws_sdk_s3::model::{
@@ -22,6 +23,10 @@ use aws_sdk_s3::types::{ByteStream, SdkError};
 use aws_sdk_sqs::model::{DeleteMessageBatchRequestEntry, QueueAttributeName};
 use flate2::write::GzEncoder;
 use flate2::Compression as Flate2Compression;
+use snap::write::FrameEncoder;
+use tokio::io::AsyncWriteExt;
+
+use mz_ore::retry::Retry;
 
 use crate::action::file::{build_compression, Compression};
 use crate::action::{Action, ControlFlow, State};
@@ -129,6 +134,27 @@ impl Action for PutObjectAction {
                     .context("writing to gzip encoder")?;
                 encoder.finish().context("writing to gzip encoder")
             }
+            Compression::Zstd => {
+                let mut encoder = ZstdEncoder::new(Vec::new());
+                encoder
+                    .write_all(buffer.as_ref())
+                    .await
+                    .context("writing to zstd encoder")?;
+                encoder
+                    .shutdown()
+                    .await
+                    .context("writing to zstd encoder")?;
+                Ok(encoder.into_inner())
+            }
+            Compression::Snappy => {
+                let mut encoder = FrameEncoder::new(Vec::new());
+                encoder
+                    .write_all(buffer.as_ref())
+                    .context("writing to snappy encoder")?;
+                encoder
+                    .into_inner()
+                    .map_err(|e| anyhow!("writing to snappy encoder: {}", e.error()))
+            }
         }?;
 
         state
@@ -140,6 +166,8 @@ impl Action for PutObjectAction {
             .set_content_encoding(match self.compression {
                 Compression::None => None,
                 Compression::Gzip => Some("gzip".to_string()),
+                Compression::Zstd => Some("zstd".to_string()),
+                Compression::Snappy => None,
             })
             .key(&self.key)
             .send()
@@ -446,3 +474,142 @@ fn allow_s3_policy(queue_arn: &str, bucket: &str, self_account: &str) -> String
         self_account = self_account
     )
 }
+
+pub struct VerifySinkAction {
+    bucket_prefix: String,
+    key_prefix: String,
+    expected: Vec<Vec<String>>,
+}
+
+pub fn build_verify_sink(mut cmd: BuiltinCommand) -> Result<VerifySinkAction, anyhow::Error> {
+    let bucket_prefix = format!("testdrive-{}", cmd.args.string("bucket")?);
+    let key_prefix = cmd.args.opt_string("prefix").unwrap_or_default();
+    let expected = parse_csv_records(cmd.input.join("\n").as_bytes())?;
+    cmd.args.done()?;
+    Ok(VerifySinkAction {
+        bucket_prefix,
+        key_prefix,
+        expected,
+    })
+}
+
+#[async_trait]
+impl Action for VerifySinkAction {
+    async fn undo(&self, _state: &mut State) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    async fn redo(&self, state: &mut State) -> Result<ControlFlow, anyhow::Error> {
+        let bucket = format!("{}-{}", self.bucket_prefix, state.seed);
+        println!("Verifying S3 sink output in {}/{}", bucket, self.key_prefix);
+
+        let mut expected = self.expected.clone();
+        expected.sort();
+
+        Retry::default()
+            .initial_backoff(Duration::from_millis(50))
+            .factor(1.5)
+            .max_duration(state.timeout)
+            .retry_async_canceling(|_| async {
+                let mut actual = self.read_sink_records(state, &bucket).await?;
+                actual.sort();
+                if actual != expected {
+                    bail!(
+                        "S3 sink records did not match\nexpected:\n{:?}\nactual:\n{:?}",
+                        expected,
+                        actual
+                    );
+                }
+                Ok(())
+            })
+            .await?;
+        Ok(ControlFlow::Continue)
+    }
+}
+
+impl VerifySinkAction {
+    /// Reads the records of every data file announced by a manifest under the
+    /// key prefix, without their `mz_timestamp` column.
+    async fn read_sink_records(
+        &self,
+        state: &State,
+        bucket: &str,
+    ) -> Result<Vec<Vec<String>>, anyhow::Error> {
+        let mut manifest_keys = vec![];
+        let mut continuation_token = None;
+        loop {
+            let response = state
+                .s3_client
+                .list_objects_v2()
+                .bucket(bucket)
+                .prefix(&self.key_prefix)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await
+                .with_context(|| format!("listing objects for bucket {}", bucket))?;
+            for obj in response.contents.unwrap_or_default() {
+                let key = obj.key.unwrap_or_default();
+                if key.contains("/manifests/") && key.ends_with(".json") {
+                    manifest_keys.push(key);
+                }
+            }
+            if response.next_continuation_token.is_none() {
+                break;
+            }
+            continuation_token = response.next_continuation_token;
+        }
+        manifest_keys.sort();
+
+        let mut records = vec![];
+        for manifest_key in manifest_keys {
+            let manifest: serde_json::Value =
+                serde_json::from_slice(&get_object(state, bucket, &manifest_key).await?)
+                    .with_context(|| format!("parsing manifest {}", manifest_key))?;
+            let files = manifest["files"]
+                .as_array()
+                .ok_or_else(|| anyhow!("manifest {} has no files array", manifest_key))?;
+            for file in files {
+                let key = file["key"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("manifest {} has a file without a key", manifest_key))?;
+                let contents = get_object(state, bucket, key).await?;
+                // Skip the header, and drop the timestamp, which is not
+                // deterministic, from each record.
+                for mut record in parse_csv_records(&contents)?.into_iter().skip(1) {
+                    if record.len() < 2 {
+                        bail!("record in {} is missing its mz_timestamp column", key);
+                    }
+                    record.remove(record.len() - 2);
+                    records.push(record);
+                }
+            }
+        }
+        Ok(records)
+    }
+}
+
+async fn get_object(state: &State, bucket: &str, key: &str) -> Result<Vec<u8>, anyhow::Error> {
+    let object = state
+        .s3_client
+        .get_object()
+        .bucket(bucket)
+        .key(key)
+        .send()
+        .await
+        .with_context(|| format!("getting object {}/{}", bucket, key))?;
+    let body = object
+        .body
+        .collect()
+        .await
+        .with_context(|| format!("reading object {}/{}", bucket, key))?;
+    Ok(body.into_bytes().to_vec())
+}
+
+fn parse_csv_records(contents: &[u8]) -> Result<Vec<Vec<String>>, anyhow::Error> {
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(contents)
+        .records()
+        .map(|record| Ok(record?.iter().map(String::from).collect()))
+        .collect()
+}
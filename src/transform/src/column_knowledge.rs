@@ -100,6 +100,9 @@ impl ColumnKnowledge {
                     }
                     Ok(body_knowledge)
                 }
+                MirRelationExpr::LetRec { .. } => Err(crate::TransformError::Internal(
+                    "column knowledge does not support recursive bindings".to_string(),
+                )),
                 MirRelationExpr::Project { input, outputs } => {
                     let input_knowledge = self.harvest(input, knowledge, knowledge_stack)?;
                     Ok(outputs
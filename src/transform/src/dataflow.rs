@@ -32,20 +32,30 @@ pub fn optimize_dataflow(
     dataflow: &mut DataflowDesc,
     indexes: &dyn IndexOracle,
 ) -> Result<(), TransformError> {
+    // Recursive views are not yet optimized across view boundaries.
+    let recursive = dataflow
+        .objects_to_build
+        .iter()
+        .any(|build_desc| build_desc.plan.is_recursive());
+
     // Inline views that are used in only one other view.
-    inline_views(dataflow)?;
+    if !recursive {
+        inline_views(dataflow)?;
+    }
 
     // Logical optimization pass after view inlining
     optimize_dataflow_relations(dataflow, indexes, &Optimizer::logical_optimizer())?;
 
-    optimize_dataflow_filters(dataflow)?;
-    // TODO: when the linear operator contract ensures that propagated
-    // predicates are always applied, projections and filters can be removed
-    // from where they come from. Once projections and filters can be removed,
-    // TODO: it would be useful for demand to be optimized after filters
-    // that way demand only includes the columns that are still necessary after
-    // the filters are applied.
-    optimize_dataflow_demand(dataflow)?;
+    if !recursive {
+        optimize_dataflow_filters(dataflow)?;
+        // TODO: when the linear operator contract ensures that propagated
+        // predicates are always applied, projections and filters can be removed
+        // from where they come from. Once projections and filters can be removed,
+        // TODO: it would be useful for demand to be optimized after filters
+        // that way demand only includes the columns that are still necessary after
+        // the filters are applied.
+        optimize_dataflow_demand(dataflow)?;
+    }
 
     // A smaller logical optimization pass after projections and filters are
     // pushed down across views.
@@ -104,6 +104,9 @@ impl Demand {
 
                     self.action(value, needs, gets)
                 }
+                MirRelationExpr::LetRec { .. } => Err(crate::TransformError::Internal(
+                    "demand does not support recursive bindings".to_string(),
+                )),
                 MirRelationExpr::Project { input, outputs } => self.action(
                     input,
                     columns.into_iter().map(|c| outputs[c]).collect(),
@@ -37,6 +37,7 @@ impl TopK {
             order_key,
             limit,
             offset,
+            with_ties,
             monotonic,
         } = relation
         {
@@ -46,12 +47,18 @@ impl TopK {
                 order_key: inner_order_key,
                 limit: inner_limit,
                 offset: inner_offset,
+                with_ties: inner_with_ties,
                 monotonic: inner_monotonic,
             } = &mut **input
             {
                 // We can fuse two chained TopK operators as long as they share the
-                // same grouping and ordering key.
-                if *group_key == *inner_group_key && *order_key == *inner_order_key {
+                // same grouping and ordering key, and neither retains ties, as the
+                // number of records produced by those is not bounded by their limits.
+                if *group_key == *inner_group_key
+                    && *order_key == *inner_order_key
+                    && !*with_ties
+                    && !*inner_with_ties
+                {
                     // Given the following limit/offset pairs:
                     //
                     // inner_offset          inner_limit
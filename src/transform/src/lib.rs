@@ -415,6 +415,19 @@ impl Optimizer {
         indexes: &dyn IndexOracle,
     ) -> Result<(), TransformError> {
         let mut id_gen = Default::default();
+        if relation.is_recursive() {
+            // Most transforms assume that each local `Get` is bound by an
+            // enclosing `Let`, which does not hold within recursive bindings,
+            // and report an error when they encounter one. We only plan the
+            // joins of recursive expressions.
+            return crate::join_implementation::JoinImplementation::default().transform(
+                relation,
+                TransformArgs {
+                    id_gen: &mut id_gen,
+                    indexes,
+                },
+            );
+        }
         for transform in self.transforms.iter() {
             transform.transform(
                 relation,
@@ -255,6 +255,9 @@ impl LiteralLifting {
                     gets.remove(&id);
                     result
                 }
+                MirRelationExpr::LetRec { .. } => Err(crate::TransformError::Internal(
+                    "literal lifting does not support recursive bindings".to_string(),
+                )),
                 MirRelationExpr::Project { input, outputs } => {
                     // We do not want to lift literals around projections.
                     // Projections are the highest lifted operator and lifting
@@ -608,6 +611,7 @@ impl LiteralLifting {
                     order_key,
                     limit: _,
                     offset: _,
+                    with_ties: _,
                     monotonic: _,
                 } => {
                     let literals = self.action(input, gets)?;
@@ -115,6 +115,17 @@ impl MonotonicFlag {
                     }
                     result
                 }
+                MirRelationExpr::LetRec { id, value, body } => {
+                    // Recursive bindings may retract records as the iteration
+                    // progresses, and so are never considered monotonic.
+                    let prior = locals.remove(id);
+                    self.apply(value, sources, locals)?;
+                    let result = self.apply(body, sources, locals)?;
+                    if prior {
+                        locals.insert(*id);
+                    }
+                    result
+                }
                 // The default behavior.
                 // TODO: check that this is the behavior we want.
                 MirRelationExpr::Negate { .. } | MirRelationExpr::Constant { rows: Err(_), .. } => {
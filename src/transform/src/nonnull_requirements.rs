@@ -102,6 +102,9 @@ impl NonNullRequirements {
                     }
                     Ok(())
                 }
+                MirRelationExpr::LetRec { .. } => Err(crate::TransformError::Internal(
+                    "non-null requirements does not support recursive bindings".to_string(),
+                )),
                 MirRelationExpr::Project { input, outputs } => self.action(
                     input,
                     columns.into_iter().map(|c| outputs[c]).collect(),
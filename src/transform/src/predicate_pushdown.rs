@@ -490,6 +490,9 @@ impl PredicatePushdown {
                     // Continue recursively on the value.
                     self.action(value, get_predicates)
                 }
+                MirRelationExpr::LetRec { .. } => Err(crate::TransformError::Internal(
+                    "predicate pushdown does not support recursive bindings".to_string(),
+                )),
                 MirRelationExpr::Join {
                     inputs,
                     equivalences,
@@ -83,6 +83,9 @@ impl ProjectionLifting {
                     gets.remove(&id);
                     Ok(())
                 }
+                MirRelationExpr::LetRec { .. } => Err(crate::TransformError::Internal(
+                    "projection lifting does not support recursive bindings".to_string(),
+                )),
                 MirRelationExpr::Project { input, outputs } => {
                     self.action(input, gets)?;
                     if let MirRelationExpr::Project {
@@ -235,6 +238,7 @@ impl ProjectionLifting {
                     order_key,
                     limit,
                     offset,
+                    with_ties,
                     monotonic: _,
                 } => {
                     self.action(input, gets)?;
@@ -256,6 +260,7 @@ impl ProjectionLifting {
                                 order_key.clone(),
                                 limit.clone(),
                                 offset.clone(),
+                                *with_ties,
                             )
                             .project(outputs.clone());
                     }
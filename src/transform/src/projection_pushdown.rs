@@ -48,6 +48,11 @@ impl crate::Transform for ProjectionPushdown {
         relation: &mut MirRelationExpr,
         _: TransformArgs,
     ) -> Result<(), crate::TransformError> {
+        if relation.is_recursive() {
+            return Err(crate::TransformError::Internal(
+                "projection pushdown does not support recursive bindings".to_string(),
+            ));
+        }
         self.action(
             relation,
             &(0..relation.arity()).collect(),
@@ -111,6 +116,11 @@ impl ProjectionPushdown {
                 );
                 desired_projection.clone()
             }
+            // Recursive expressions are rejected by `transform`, and are not
+            // pushed through by `optimize_dataflow`.
+            MirRelationExpr::LetRec { .. } => {
+                unreachable!("projection pushdown does not support recursive bindings")
+            }
             MirRelationExpr::Join {
                 inputs,
                 equivalences,
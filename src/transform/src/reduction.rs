@@ -65,7 +65,9 @@ impl FoldConstants {
         match relation {
             MirRelationExpr::Constant { .. } => { /* handled after match */ }
             MirRelationExpr::Get { .. } => {}
-            MirRelationExpr::Let { .. } => { /* constant prop done in InlineLet */ }
+            MirRelationExpr::Let { .. } | MirRelationExpr::LetRec { .. } => {
+                /* constant prop done in InlineLet */
+            }
             MirRelationExpr::Reduce {
                 input,
                 group_key,
@@ -109,11 +111,14 @@ impl FoldConstants {
                 order_key,
                 limit,
                 offset,
+                with_ties,
                 ..
             } => {
                 if let MirRelationExpr::Constant { rows, .. } = &mut **input {
                     if let Ok(rows) = rows {
-                        Self::fold_topk_constant(group_key, order_key, limit, offset, rows);
+                        Self::fold_topk_constant(
+                            group_key, order_key, limit, offset, with_ties, rows,
+                        );
                     }
                     *relation = input.take_dangerous();
                 }
@@ -503,6 +508,7 @@ impl FoldConstants {
         order_key: &[ColumnOrder],
         limit: &Option<usize>,
         offset: &usize,
+        with_ties: &bool,
         rows: &'a mut [(Row, Diff)],
     ) {
         // helper functions for comparing elements by order_key and group_key
@@ -540,12 +546,20 @@ impl FoldConstants {
 
         let mut same_group_key =
             |lhs: &(Row, Diff), rhs: &(Row, Diff)| cmp_group_key(lhs, rhs) == Ordering::Equal;
+        let mut same_order_key = |lhs: &(Row, Diff), rhs: &(Row, Diff)| {
+            let lhs_datums = &lhs_datum_vec.borrow_with(&lhs.0);
+            let rhs_datums = &rhs_datum_vec.borrow_with(&rhs.0);
+            mz_expr::compare_columns(order_key, lhs_datums, rhs_datums, || Ordering::Equal)
+                == Ordering::Equal
+        };
 
         let mut cursor = 0;
         while cursor < rows.len() {
             // first, reset the remaining limit and offset for the current group
             let mut offset_rem: Diff = offset.clone().try_into().unwrap();
             let mut limit_rem: Option<Diff> = limit.clone().map(|x| x.try_into().unwrap());
+            // the last row retained for the current group, if any
+            let mut last_retained: Option<usize> = None;
 
             let mut finger = cursor;
             while finger < rows.len() && same_group_key(&rows[cursor], &rows[finger]) {
@@ -561,9 +575,24 @@ impl FoldConstants {
                     // determine how many of the remaining rows to retain,
                     // then update the diff and decrement the remaining limit by that number
                     if let Some(limit_rem) = &mut limit_rem {
-                        let rows_to_retain = std::cmp::min(*limit_rem, rows[finger].1);
-                        rows[finger].1 = rows_to_retain;
-                        *limit_rem -= rows_to_retain;
+                        if *limit_rem > 0 || !with_ties {
+                            let rows_to_retain = std::cmp::min(*limit_rem, rows[finger].1);
+                            // with ties, the remaining copies tie with the retained ones
+                            if !with_ties {
+                                rows[finger].1 = rows_to_retain;
+                            }
+                            *limit_rem -= rows_to_retain;
+                        } else if !last_retained
+                            .map(|last| same_order_key(&rows[last], &rows[finger]))
+                            .unwrap_or(false)
+                        {
+                            // with ties, only rows that tie with the last retained row
+                            // are retained once the limit has been exhausted
+                            rows[finger].1 = 0;
+                        }
+                    }
+                    if rows[finger].1 > 0 {
+                        last_retained = Some(finger);
                     }
                 }
                 finger += 1;
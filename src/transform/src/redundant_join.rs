@@ -94,6 +94,9 @@ impl RedundantJoin {
                     }
                     Ok(result)
                 }
+                MirRelationExpr::LetRec { .. } => Err(crate::TransformError::Internal(
+                    "redundant join elimination does not support recursive bindings".to_string(),
+                )),
                 MirRelationExpr::Get { id, typ } => {
                     // Extract the value provenance, or an empty list if unavailable.
                     let mut val_info = lets.get(id).cloned().unwrap_or_default();
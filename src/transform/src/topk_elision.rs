@@ -36,6 +36,7 @@ impl TopKElision {
             order_key: _,
             limit,
             offset,
+            with_ties: _,
             monotonic: _,
         } = relation
         {
@@ -0,0 +1,38 @@
+# Copyright Materialize, Inc. and contributors. All rights reserved.
+#
+# Use of this software is governed by the Business Source License
+# included in the LICENSE file at the root of this repository.
+#
+# As of the Change Date specified in that file, in accordance with
+# the Business Source License, use of this software will be governed
+# by the Apache License, Version 2.0.
+
+from materialize.mzcompose import Composition
+from materialize.mzcompose.services import Localstack, Materialized, Testdrive
+
+SERVICES = [
+    Localstack(),
+    Materialized(),
+    Testdrive(no_reset=True, consistent_seed=True),
+]
+
+
+def workflow_default(c: Composition) -> None:
+    """Test that Kinesis sources resume from their persisted per-shard offsets
+    across restarts, including after the stream has been resharded
+    """
+
+    c.up("localstack", "materialized")
+    c.wait_for_materialized()
+
+    c.run("testdrive", "create-source.td")
+    restart_mz(c)
+    c.run("testdrive", "verify-after-restart.td", "reshard.td")
+    restart_mz(c)
+    c.run("testdrive", "verify-after-reshard.td")
+
+
+def restart_mz(c: Composition) -> None:
+    c.kill("materialized")
+    c.up("materialized")
+    c.wait_for_materialized()
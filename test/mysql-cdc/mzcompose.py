@@ -0,0 +1,47 @@
+# Copyright Materialize, Inc. and contributors. All rights reserved.
+#
+# Use of this software is governed by the Business Source License
+# included in the LICENSE file at the root of this repository.
+#
+# As of the Change Date specified in that file, in accordance with
+# the Business Source License, use of this software will be governed
+# by the Apache License, Version 2.0.
+
+from materialize.mzcompose import Composition, WorkflowArgumentParser
+from materialize.mzcompose.services import Materialized, MySql, Testdrive
+
+MYSQL_ROOT_PASSWORD = "rootpw"
+
+SERVICES = [
+    Materialized(),
+    Testdrive(),
+    MySql(
+        mysql_root_password=MYSQL_ROOT_PASSWORD,
+        command="\n".join(
+            [
+                "--default-authentication-plugin=mysql_native_password",
+                "--secure-file-priv=/var/lib/mysql-files",
+                "--gtid-mode=ON",
+                "--enforce-gtid-consistency=ON",
+            ]
+        ),
+    ),
+]
+
+
+def workflow_default(c: Composition, parser: WorkflowArgumentParser) -> None:
+    parser.add_argument(
+        "filter",
+        nargs="*",
+        default=["*.td"],
+        help="limit to only the files matching filter",
+    )
+    args = parser.parse_args()
+
+    c.start_and_wait_for_tcp(services=["materialized", "mysql"])
+    c.wait_for_materialized()
+    c.run(
+        "testdrive",
+        f"--var=mysql-root-password={MYSQL_ROOT_PASSWORD}",
+        *args.filter,
+    )
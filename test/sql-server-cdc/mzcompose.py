@@ -0,0 +1,39 @@
+# Copyright Materialize, Inc. and contributors. All rights reserved.
+#
+# Use of this software is governed by the Business Source License
+# included in the LICENSE file at the root of this repository.
+#
+# As of the Change Date specified in that file, in accordance with
+# the Business Source License, use of this software will be governed
+# by the Apache License, Version 2.0.
+
+from materialize.mzcompose import Composition, WorkflowArgumentParser
+from materialize.mzcompose.services import Materialized, SqlServer, Testdrive
+
+# At least 8 characters, including uppercase and lowercase letters, digits and
+# symbols.
+SA_PASSWORD = "AAbb!@12345"
+
+SERVICES = [
+    Materialized(),
+    Testdrive(),
+    SqlServer(sa_password=SA_PASSWORD),
+]
+
+
+def workflow_default(c: Composition, parser: WorkflowArgumentParser) -> None:
+    parser.add_argument(
+        "filter",
+        nargs="*",
+        default=["*.td"],
+        help="limit to only the files matching filter",
+    )
+    args = parser.parse_args()
+
+    c.start_and_wait_for_tcp(services=["materialized", "sql-server"])
+    c.wait_for_materialized()
+    c.run(
+        "testdrive",
+        f"--var=sa-password={SA_PASSWORD}",
+        *args.filter,
+    )